@@ -0,0 +1,97 @@
+use crate::{
+    config::AnimationsConfig,
+    services::{
+        ReadOnlyService, ServiceEvent,
+        upower::{BatteryStatus, PowerProfile, UPowerService},
+    },
+};
+use iced::Subscription;
+use log::info;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<UPowerService>),
+}
+
+/// Decides whether the non-essential animations run, off with reduced motion,
+/// on battery or with the power-saver profile
+pub struct Animations {
+    config: AnimationsConfig,
+    service: Option<UPowerService>,
+    enabled: bool,
+}
+
+impl Animations {
+    pub fn new(config: AnimationsConfig) -> Self {
+        let mut animations = Self {
+            config,
+            service: None,
+            enabled: true,
+        };
+        animations.check();
+
+        animations
+    }
+
+    pub fn reload(&mut self, config: AnimationsConfig) {
+        self.config = config;
+        self.check();
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn check(&mut self) {
+        let on_battery = self
+            .service
+            .as_ref()
+            .and_then(|s| s.system_battery)
+            .is_some_and(|b| matches!(b.status, BatteryStatus::Discharging(_)));
+        let power_saver = self
+            .service
+            .as_ref()
+            .is_some_and(|s| s.power_profile == PowerProfile::PowerSaver);
+
+        let enabled = !(self.config.reduced_motion
+            || (self.config.disable_on_battery && on_battery)
+            || (self.config.disable_on_power_saver && power_saver));
+
+        if enabled != self.enabled {
+            info!(
+                "Animations {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+            self.enabled = enabled;
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+        }
+
+        self.check();
+    }
+
+    /// The power state only matters when it can turn the animations off
+    pub fn subscription(&self) -> Subscription<Message> {
+        if !self.config.reduced_motion
+            && (self.config.disable_on_battery || self.config.disable_on_power_saver)
+        {
+            UPowerService::subscribe().map(Message::Event)
+        } else {
+            Subscription::none()
+        }
+    }
+}
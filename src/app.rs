@@ -1,42 +1,141 @@
 use crate::{
-    HEIGHT, centerbox,
-    config::{self, AppearanceStyle, Config, Modules, Position},
+    HEIGHT,
+    animations::{self, Animations},
+    battery_saver::{self, BatterySaver},
+    centerbox,
+    config::{self, AppearanceStyle, Config, ModuleName, Modules, Position, SpecialWorkspaceBar},
     get_log_spec,
+    hooks::{self, Hooks},
+    ipc::{self, IpcCommand, ModuleAction, PinAction},
     menu::{MenuSize, MenuType},
     modules::{
         self,
-        app_launcher::{self, AppLauncher},
-        clipboard::{self, Clipboard},
-        clock::Clock,
         custom_module::{self, Custom},
-        media_player::MediaPlayer,
-        privacy::Privacy,
         settings::Settings,
-        system_info::SystemInfo,
-        tray::TrayModule,
-        updates::Updates,
         window_title::WindowTitle,
         workspaces::Workspaces,
     },
     outputs::{HasOutput, Outputs},
+    pinned_popups::PinnedPopups,
     position_button::ButtonUIRef,
+    privacy_mode::{self, PrivacyMode},
+    redraw::{self, RedrawScheduler},
+    scheduler::{self, Scheduler},
+    sounds::{SoundEvent, Sounds},
+    state::State,
     theme::{AshellTheme, backdrop_color, darken_color},
+    utils::launcher::execute_command,
+    zen_mode::{self, ZenMode},
 };
 
-#[cfg(feature = "hyprland")]
+#[cfg(feature = "metrics")]
+use crate::metrics::{self, Metrics};
+#[cfg(feature = "acpi")]
+use crate::modules::acpi::Acpi;
+#[cfg(feature = "app-launcher")]
+use crate::modules::app_launcher::{self, AppLauncher};
+#[cfg(feature = "audio")]
+use crate::modules::audio::Audio;
+#[cfg(feature = "autostart")]
+use crate::modules::autostart::Autostart;
+#[cfg(feature = "backup")]
+use crate::modules::backup::Backup;
+#[cfg(feature = "battery")]
+use crate::modules::battery::Battery;
+#[cfg(feature = "bluetooth")]
+use crate::modules::bluetooth::Bluetooth;
+#[cfg(feature = "clipboard")]
+use crate::modules::clipboard::{self, Clipboard};
+#[cfg(feature = "clock")]
+use crate::modules::clock::Clock;
+#[cfg(feature = "dashboard")]
+use crate::modules::dashboard::Dashboard;
+#[cfg(feature = "encrypted-volumes")]
+use crate::modules::encrypted_volumes::EncryptedVolumes;
+#[cfg(feature = "gpu")]
+use crate::modules::gpu::Gpu;
+#[cfg(feature = "idle-inhibitor")]
+use crate::modules::idle_inhibitor::IdleInhibitor;
+#[cfg(feature = "media-player")]
+use crate::modules::media_player::MediaPlayer;
+#[cfg(feature = "audio")]
+use crate::modules::microphone::Microphone;
+#[cfg(feature = "modem")]
+use crate::modules::modem::Modem;
+#[cfg(feature = "network")]
+use crate::modules::network::Network;
+#[cfg(feature = "notes")]
+use crate::modules::notes::Notes;
+#[cfg(feature = "notifications")]
+use crate::modules::notifications::Notifications;
+#[cfg(feature = "passwords")]
+use crate::modules::passwords::Passwords;
+#[cfg(feature = "power-profiles")]
+use crate::modules::power_profiles::PowerProfiles;
+#[cfg(feature = "privacy")]
+use crate::modules::privacy::Privacy;
+#[cfg(feature = "qr-code")]
+use crate::modules::qr_code::QrCode;
+#[cfg(feature = "system-info")]
+use crate::modules::system_info::SystemInfo;
+#[cfg(feature = "totp")]
+use crate::modules::totp::Totp;
+#[cfg(feature = "tray")]
+use crate::modules::tray::TrayModule;
+#[cfg(feature = "updates")]
+use crate::modules::updates::Updates;
+#[cfg(feature = "usbguard")]
+use crate::modules::usbguard::UsbGuard;
+#[cfg(feature = "wallpaper-colors")]
+use crate::wallpaper_colors::{self, WallpaperColors};
+
+#[cfg(feature = "keyboard-layout")]
 use crate::modules::keyboard_layout::KeyboardLayout;
+
 #[cfg(feature = "hyprland")]
+use crate::modules::keyboard_layout::HyprlandKeyboardLayoutManager;
+#[cfg(feature = "keyboard-submap")]
 use crate::modules::keyboard_submap::KeyboardSubmap;
+#[cfg(feature = "window-group")]
+use crate::modules::window_group::WindowGroup;
+#[cfg(feature = "window-rules")]
+use crate::modules::window_rules::WindowRules;
 #[cfg(feature = "hyprland")]
 use crate::modules::window_title::HyprlandWindowManager;
 #[cfg(feature = "hyprland")]
 use crate::modules::workspaces::HyprlandWorkspaceManager;
 
+#[cfg(feature = "niri")]
+use crate::modules::keyboard_layout::NiriKeyboardLayoutManager;
 #[cfg(feature = "niri")]
 use crate::modules::window_title::NiriWindowManager;
 #[cfg(feature = "niri")]
 use crate::modules::workspaces::NiriWorkspaceManager;
 
+#[cfg(feature = "sway")]
+use crate::modules::keyboard_layout::SwayKeyboardLayoutManager;
+#[cfg(feature = "sway")]
+use crate::modules::window_title::SwayWindowManager;
+#[cfg(feature = "sway")]
+use crate::modules::workspaces::SwayWorkspaceManager;
+
+#[cfg(feature = "wlroots")]
+use crate::modules::window_title::ForeignToplevelWindowManager;
+#[cfg(feature = "wlroots")]
+use crate::modules::workspaces::ExtWorkspaceManager;
+
+#[cfg(feature = "river")]
+use crate::modules::window_title::ForeignToplevelWindowManager;
+#[cfg(feature = "river")]
+use crate::modules::workspaces::RiverWorkspaceManager;
+
+#[cfg(feature = "kwin")]
+use crate::modules::keyboard_layout::KwinKeyboardLayoutManager;
+#[cfg(feature = "kwin")]
+use crate::modules::window_title::ForeignToplevelWindowManager;
+#[cfg(feature = "kwin")]
+use crate::modules::workspaces::KwinWorkspaceManager;
+
 use flexi_logger::LoggerHandle;
 use iced::{
     Alignment, Color, Element, Gradient, Length, Radians, Subscription, Task, Theme,
@@ -46,9 +145,9 @@ use iced::{
         wayland::{Event as WaylandEvent, OutputEvent},
     },
     gradient::Linear,
-    keyboard,
+    keyboard, mouse,
     widget::{Row, container, mouse_area},
-    window::Id,
+    window::{self, Id},
 };
 use log::{debug, info, warn};
 use std::{collections::HashMap, f32::consts::PI, path::PathBuf};
@@ -58,6 +157,11 @@ pub struct GeneralConfig {
     outputs: config::Outputs,
     pub modules: Modules,
     enable_esc_key: bool,
+    enable_context_menu: bool,
+    enable_keyboard_navigation: bool,
+    /// only read with Hyprland
+    hyprland_reserved_area: bool,
+    editor_cmd: Option<String>,
 }
 
 pub struct App {
@@ -65,33 +169,141 @@ pub struct App {
     pub theme: AshellTheme,
     logger: LoggerHandle,
     pub general_config: GeneralConfig,
+    pub state: State,
     pub outputs: Outputs,
+    pub pinned_popups: PinnedPopups,
+    /// window dragged from the window title, dropped on a workspace
+    dragged_window: Option<String>,
+    /// bar holding the keyboard grabbed through IPC for the workspace navigation
+    keyboard_grab: Option<Id>,
+    #[cfg(feature = "app-launcher")]
     pub app_launcher: Option<AppLauncher>,
     pub custom: HashMap<String, Custom>,
+    #[cfg(feature = "updates")]
     pub updates: Option<Updates>,
+    #[cfg(feature = "clipboard")]
     pub clipboard: Option<Clipboard>,
+    #[cfg(feature = "system-info")]
     pub system_info: SystemInfo,
 
     #[cfg(feature = "niri")]
     pub workspaces: Workspaces<NiriWorkspaceManager>,
     #[cfg(feature = "niri")]
+    pub workspaces_instances: HashMap<String, Workspaces<NiriWorkspaceManager>>,
+    #[cfg(feature = "niri")]
     pub window_title: WindowTitle<NiriWindowManager>,
+    #[cfg(feature = "niri")]
+    pub keyboard_layout: KeyboardLayout<NiriKeyboardLayoutManager>,
 
     #[cfg(feature = "hyprland")]
     pub workspaces: Workspaces<HyprlandWorkspaceManager>,
     #[cfg(feature = "hyprland")]
-    pub window_title: WindowTitle<HyprlandWindowManager>,
-
+    pub workspaces_instances: HashMap<String, Workspaces<HyprlandWorkspaceManager>>,
     #[cfg(feature = "hyprland")]
-    pub keyboard_layout: KeyboardLayout,
+    pub window_title: WindowTitle<HyprlandWindowManager>,
     #[cfg(feature = "hyprland")]
+    pub keyboard_layout: KeyboardLayout<HyprlandKeyboardLayoutManager>,
+
+    #[cfg(feature = "sway")]
+    pub workspaces: Workspaces<SwayWorkspaceManager>,
+    #[cfg(feature = "sway")]
+    pub workspaces_instances: HashMap<String, Workspaces<SwayWorkspaceManager>>,
+    #[cfg(feature = "sway")]
+    pub window_title: WindowTitle<SwayWindowManager>,
+    #[cfg(feature = "sway")]
+    pub keyboard_layout: KeyboardLayout<SwayKeyboardLayoutManager>,
+
+    #[cfg(feature = "wlroots")]
+    pub workspaces: Workspaces<ExtWorkspaceManager>,
+    #[cfg(feature = "wlroots")]
+    pub workspaces_instances: HashMap<String, Workspaces<ExtWorkspaceManager>>,
+    #[cfg(feature = "wlroots")]
+    pub window_title: WindowTitle<ForeignToplevelWindowManager>,
+
+    #[cfg(feature = "river")]
+    pub workspaces: Workspaces<RiverWorkspaceManager>,
+    #[cfg(feature = "river")]
+    pub workspaces_instances: HashMap<String, Workspaces<RiverWorkspaceManager>>,
+    #[cfg(feature = "river")]
+    pub window_title: WindowTitle<ForeignToplevelWindowManager>,
+
+    #[cfg(feature = "kwin")]
+    pub workspaces: Workspaces<KwinWorkspaceManager>,
+    #[cfg(feature = "kwin")]
+    pub workspaces_instances: HashMap<String, Workspaces<KwinWorkspaceManager>>,
+    #[cfg(feature = "kwin")]
+    pub window_title: WindowTitle<ForeignToplevelWindowManager>,
+    #[cfg(feature = "kwin")]
+    pub keyboard_layout: KeyboardLayout<KwinKeyboardLayoutManager>,
+
+    #[cfg(feature = "keyboard-submap")]
     pub keyboard_submap: KeyboardSubmap,
+    #[cfg(feature = "window-group")]
+    pub window_group: WindowGroup,
+    #[cfg(feature = "window-rules")]
+    pub window_rules: WindowRules,
 
+    #[cfg(feature = "tray")]
     pub tray: TrayModule,
+    #[cfg(feature = "clock")]
     pub clock: Clock,
+    #[cfg(feature = "privacy")]
     pub privacy: Privacy,
     pub settings: Settings,
+    #[cfg(feature = "media-player")]
     pub media_player: MediaPlayer,
+    pub hooks: Hooks,
+    pub sounds: Sounds,
+    #[cfg(feature = "autostart")]
+    pub autostart: Autostart,
+    #[cfg(feature = "notes")]
+    pub notes: Notes,
+    pub scheduler: Scheduler,
+    #[cfg(feature = "metrics")]
+    pub metrics: Metrics,
+    pub zen_mode: ZenMode,
+    pub privacy_mode: PrivacyMode,
+    pub battery_saver: BatterySaver,
+    pub animations: Animations,
+    #[cfg(feature = "wallpaper-colors")]
+    pub wallpaper_colors: WallpaperColors,
+    pub redraw: RedrawScheduler,
+    #[cfg(feature = "qr-code")]
+    pub qr_code: QrCode,
+    #[cfg(feature = "totp")]
+    pub totp: Totp,
+    #[cfg(feature = "passwords")]
+    pub passwords: Passwords,
+    #[cfg(feature = "encrypted-volumes")]
+    pub encrypted_volumes: EncryptedVolumes,
+    #[cfg(feature = "usbguard")]
+    pub usbguard: UsbGuard,
+    #[cfg(feature = "backup")]
+    pub backup: Backup,
+    #[cfg(feature = "modem")]
+    pub modem: Modem,
+    #[cfg(feature = "acpi")]
+    pub acpi: Acpi,
+    #[cfg(feature = "gpu")]
+    pub gpu: Gpu,
+    #[cfg(feature = "dashboard")]
+    pub dashboard: Dashboard,
+    #[cfg(feature = "battery")]
+    pub battery: Battery,
+    #[cfg(feature = "audio")]
+    pub audio: Audio,
+    #[cfg(feature = "audio")]
+    pub microphone: Microphone,
+    #[cfg(feature = "network")]
+    pub network: Network,
+    #[cfg(feature = "bluetooth")]
+    pub bluetooth: Bluetooth,
+    #[cfg(feature = "notifications")]
+    pub notifications: Notifications,
+    #[cfg(feature = "idle-inhibitor")]
+    pub idle_inhibitor: IdleInhibitor,
+    #[cfg(feature = "power-profiles")]
+    pub power_profiles: PowerProfiles,
 }
 
 #[derive(Debug, Clone)]
@@ -100,23 +312,96 @@ pub enum Message {
     ConfigChanged(Box<Config>),
     ToggleMenu(MenuType, Id, ButtonUIRef),
     CloseMenu(Id),
+    #[cfg(feature = "clipboard")]
     Clipboard(clipboard::Message),
+    #[cfg(feature = "app-launcher")]
     AppLauncher(app_launcher::Message),
     Custom(String, custom_module::Message),
+    #[cfg(feature = "updates")]
     Updates(modules::updates::Message),
     Workspaces(modules::workspaces::Message),
+    WorkspacesInstance(String, modules::workspaces::Message),
     WindowTitle(modules::window_title::Message),
+    #[cfg(feature = "system-info")]
     SystemInfo(modules::system_info::Message),
-    #[cfg(feature = "hyprland")]
+    #[cfg(feature = "keyboard-layout")]
     KeyboardLayout(modules::keyboard_layout::Message),
-    #[cfg(feature = "hyprland")]
+    #[cfg(feature = "keyboard-submap")]
     KeyboardSubmap(modules::keyboard_submap::Message),
+    #[cfg(feature = "window-group")]
+    WindowGroup(modules::window_group::Message),
+    #[cfg(feature = "window-rules")]
+    WindowRules(modules::window_rules::Message),
+    #[cfg(feature = "tray")]
     Tray(modules::tray::Message),
+    #[cfg(feature = "clock")]
     Clock(modules::clock::Message),
+    #[cfg(feature = "privacy")]
     Privacy(modules::privacy::Message),
     Settings(modules::settings::Message),
+    #[cfg(feature = "media-player")]
     MediaPlayer(modules::media_player::Message),
+    #[cfg(feature = "autostart")]
+    Autostart(modules::autostart::Message),
+    #[cfg(feature = "notes")]
+    Notes(modules::notes::Message),
+    #[cfg(feature = "qr-code")]
+    QrCode(modules::qr_code::Message),
+    #[cfg(feature = "totp")]
+    Totp(modules::totp::Message),
+    #[cfg(feature = "passwords")]
+    Passwords(modules::passwords::Message),
+    #[cfg(feature = "encrypted-volumes")]
+    EncryptedVolumes(modules::encrypted_volumes::Message),
+    #[cfg(feature = "usbguard")]
+    UsbGuard(modules::usbguard::Message),
+    #[cfg(feature = "backup")]
+    Backup(modules::backup::Message),
+    #[cfg(feature = "modem")]
+    Modem(modules::modem::Message),
+    #[cfg(feature = "acpi")]
+    Acpi(modules::acpi::Message),
+    #[cfg(feature = "gpu")]
+    Gpu(modules::gpu::Message),
+    #[cfg(feature = "dashboard")]
+    Dashboard(modules::dashboard::Message),
+    #[cfg(feature = "battery")]
+    Battery(modules::battery::Message),
+    #[cfg(feature = "audio")]
+    Audio(modules::audio::Message),
+    #[cfg(feature = "audio")]
+    Microphone(modules::microphone::Message),
+    #[cfg(feature = "network")]
+    Network(modules::network::Message),
+    #[cfg(feature = "bluetooth")]
+    Bluetooth(modules::bluetooth::Message),
+    #[cfg(feature = "notifications")]
+    Notifications(modules::notifications::Message),
+    #[cfg(feature = "idle-inhibitor")]
+    IdleInhibitor(modules::idle_inhibitor::Message),
+    #[cfg(feature = "power-profiles")]
+    PowerProfiles(modules::power_profiles::Message),
     OutputEvent((OutputEvent, WlOutput)),
+    Hooks(hooks::Message),
+    Scheduler(scheduler::Message),
+    #[cfg(feature = "metrics")]
+    Metrics(metrics::Message),
+    ZenMode(zen_mode::Message),
+    PrivacyMode(privacy_mode::Message),
+    BatterySaver(battery_saver::Message),
+    Animations(animations::Message),
+    #[cfg(feature = "wallpaper-colors")]
+    WallpaperColors(wallpaper_colors::Message),
+    Redraw(redraw::Message),
+    Ipc(IpcCommand),
+    ModuleVisibility(String, ModuleAction),
+    PinPopup(String, PinAction),
+    WindowDragEnded,
+    /// a navigation key pressed on a bar, with the shift state
+    BarKey(Id, keyboard::key::Named, bool),
+    ReloadConfig,
+    OpenConfig,
+    Quit,
     CloseAllMenus,
 }
 
@@ -129,6 +414,8 @@ impl App {
                 config.appearance.style,
                 config.position,
                 config.appearance.scale_factor,
+                config.enable_keyboard_navigation,
+                cfg!(feature = "hyprland") && config.hyprland_reserved_area,
             );
 
             let custom = config
@@ -138,87 +425,550 @@ impl App {
                 .map(|o| (o.name.clone(), Custom::new(o)))
                 .collect();
 
-            (
-                App {
-                    config_path,
-                    theme: AshellTheme::new(config.position, &config.appearance),
-                    logger,
-                    general_config: GeneralConfig {
-                        outputs: config.outputs,
-                        modules: config.modules,
-                        enable_esc_key: config.enable_esc_key,
-                    },
-                    outputs,
-                    app_launcher: config.app_launcher_cmd.map(AppLauncher::new),
-                    custom,
-                    updates: config.updates.map(Updates::new),
-                    clipboard: config.clipboard_cmd.map(Clipboard::new),
-                    workspaces: Workspaces::new(config.workspaces),
-                    system_info: SystemInfo::new(config.system_info),
-
-                    #[cfg(feature = "hyprland")]
-                    window_title: WindowTitle::<HyprlandWindowManager>::new(config.window_title),
-                    #[cfg(feature = "hyprland")]
-                    keyboard_layout: KeyboardLayout::new(config.keyboard_layout),
-                    #[cfg(feature = "hyprland")]
-                    keyboard_submap: KeyboardSubmap::default(),
-
-                    #[cfg(feature = "niri")]
-                    window_title: WindowTitle::<NiriWindowManager>::new(config.window_title),
-
-                    tray: TrayModule::default(),
-                    clock: Clock::new(config.clock),
-                    privacy: Privacy::default(),
-                    settings: Settings::new(config.settings),
-                    media_player: MediaPlayer::new(config.media_player),
+            #[cfg(feature = "autostart")]
+            let mut autostart = Autostart::new(config.autostart);
+            #[cfg(feature = "autostart")]
+            let autostart_task = autostart.start().map(Message::Autostart);
+            #[cfg(not(feature = "autostart"))]
+            let autostart_task = Task::none();
+
+            #[cfg(feature = "wallpaper-colors")]
+            let (wallpaper_colors, wallpaper_colors_task) =
+                WallpaperColors::new(config.wallpaper_colors, config.position);
+            #[cfg(feature = "wallpaper-colors")]
+            let wallpaper_colors_task = wallpaper_colors_task.map(Message::WallpaperColors);
+            #[cfg(not(feature = "wallpaper-colors"))]
+            let wallpaper_colors_task = Task::none();
+
+            let state = State::load();
+            #[cfg(feature = "notifications")]
+            let notifications = Notifications::new(config.notifications, state.do_not_disturb);
+
+            let mut app = App {
+                config_path,
+                theme: AshellTheme::new(config.position, &config.appearance),
+                logger,
+                general_config: GeneralConfig {
+                    outputs: config.outputs,
+                    modules: config.modules,
+                    enable_esc_key: config.enable_esc_key,
+                    enable_context_menu: config.enable_context_menu,
+                    enable_keyboard_navigation: config.enable_keyboard_navigation,
+                    hyprland_reserved_area: cfg!(feature = "hyprland")
+                        && config.hyprland_reserved_area,
+                    editor_cmd: config.editor_cmd,
                 },
-                task,
+                state,
+                outputs,
+                pinned_popups: PinnedPopups::new(config.pinned_popups),
+                dragged_window: None,
+                keyboard_grab: None,
+                #[cfg(feature = "app-launcher")]
+                app_launcher: config.app_launcher_cmd.map(AppLauncher::new),
+                custom,
+                #[cfg(feature = "updates")]
+                updates: config.updates.map(Updates::new),
+                #[cfg(feature = "clipboard")]
+                clipboard: config.clipboard_cmd.map(Clipboard::new),
+                workspaces_instances: config
+                    .workspaces
+                    .instances
+                    .clone()
+                    .into_iter()
+                    .map(|(name, config)| (name, Workspaces::new(config)))
+                    .collect(),
+                workspaces: Workspaces::new(config.workspaces),
+                #[cfg(feature = "system-info")]
+                system_info: SystemInfo::new(config.system_info),
+
+                #[cfg(feature = "hyprland")]
+                window_title: WindowTitle::<HyprlandWindowManager>::new(config.window_title),
+                #[cfg(feature = "keyboard-layout")]
+                keyboard_layout: KeyboardLayout::new(config.keyboard_layout),
+                #[cfg(feature = "keyboard-submap")]
+                keyboard_submap: KeyboardSubmap::default(),
+                #[cfg(feature = "window-group")]
+                window_group: WindowGroup::default(),
+                #[cfg(feature = "window-rules")]
+                window_rules: WindowRules::default(),
+
+                #[cfg(feature = "niri")]
+                window_title: WindowTitle::<NiriWindowManager>::new(config.window_title),
+
+                #[cfg(feature = "sway")]
+                window_title: WindowTitle::<SwayWindowManager>::new(config.window_title),
+
+                #[cfg(feature = "wlroots")]
+                window_title: WindowTitle::<ForeignToplevelWindowManager>::new(config.window_title),
+
+                #[cfg(feature = "river")]
+                window_title: WindowTitle::<ForeignToplevelWindowManager>::new(config.window_title),
+
+                #[cfg(feature = "kwin")]
+                window_title: WindowTitle::<ForeignToplevelWindowManager>::new(config.window_title),
+
+                #[cfg(feature = "tray")]
+                tray: TrayModule::default(),
+                #[cfg(feature = "clock")]
+                clock: Clock::new(config.clock),
+                #[cfg(feature = "privacy")]
+                privacy: Privacy::default(),
+                settings: Settings::new(config.settings),
+                #[cfg(feature = "media-player")]
+                media_player: MediaPlayer::new(config.media_player),
+                hooks: Hooks::new(config.hooks),
+                sounds: Sounds::new(config.sounds),
+                #[cfg(feature = "autostart")]
+                autostart,
+                #[cfg(feature = "notes")]
+                notes: Notes::new(config.notes),
+                scheduler: Scheduler::new(config.scheduler),
+                #[cfg(feature = "metrics")]
+                metrics: Metrics::new(config.metrics),
+                zen_mode: ZenMode::new(config.zen_mode),
+                privacy_mode: PrivacyMode::new(config.privacy_mode),
+                battery_saver: BatterySaver::new(config.battery_saver),
+                animations: Animations::new(config.animations),
+                #[cfg(feature = "wallpaper-colors")]
+                wallpaper_colors,
+                redraw: RedrawScheduler::default(),
+                #[cfg(feature = "qr-code")]
+                qr_code: QrCode::new(config.qr_code),
+                #[cfg(feature = "totp")]
+                totp: Totp::new(config.totp),
+                #[cfg(feature = "passwords")]
+                passwords: Passwords::new(config.passwords),
+                #[cfg(feature = "encrypted-volumes")]
+                encrypted_volumes: EncryptedVolumes::new(config.encrypted_volumes),
+                #[cfg(feature = "usbguard")]
+                usbguard: UsbGuard::default(),
+                #[cfg(feature = "backup")]
+                backup: Backup::new(config.backup),
+                #[cfg(feature = "modem")]
+                modem: Modem::new(config.modem),
+                #[cfg(feature = "acpi")]
+                acpi: Acpi::default(),
+                #[cfg(feature = "gpu")]
+                gpu: Gpu::new(config.gpu),
+                #[cfg(feature = "dashboard")]
+                dashboard: Dashboard::new(config.dashboard),
+                #[cfg(feature = "battery")]
+                battery: Battery::new(config.battery),
+                #[cfg(feature = "audio")]
+                audio: Audio::new(config.audio),
+                #[cfg(feature = "audio")]
+                microphone: Microphone::new(config.microphone),
+                #[cfg(feature = "network")]
+                network: Network::new(config.network),
+                #[cfg(feature = "bluetooth")]
+                bluetooth: Bluetooth::new(config.bluetooth),
+                #[cfg(feature = "notifications")]
+                notifications,
+                #[cfg(feature = "idle-inhibitor")]
+                idle_inhibitor: IdleInhibitor::new(config.idle_inhibitor),
+                #[cfg(feature = "power-profiles")]
+                power_profiles: PowerProfiles::new(config.power_profiles),
+            };
+            app.sync_animations();
+            let pinned_popups_task = app.restore_pinned_popups();
+
+            (
+                app,
+                Task::batch(vec![
+                    task,
+                    autostart_task,
+                    pinned_popups_task,
+                    wallpaper_colors_task,
+                ]),
             )
         }
     }
 
+    /// Both the animation policy and the battery saver can turn the animations off
+    fn sync_animations(&mut self) {
+        self.zen_mode
+            .set_animations(self.animations.enabled() && self.battery_saver.animations());
+    }
+
+    fn update_privacy_mode(&mut self, message: privacy_mode::Message) -> Task<Message> {
+        match self.privacy_mode.update(message) {
+            // the open menus could show the blanked content
+            privacy_mode::Action::Enabled if self.outputs.menu_is_open() => self
+                .outputs
+                .close_all_menus(self.general_config.enable_esc_key),
+            _ => Task::none(),
+        }
+    }
+
+    fn set_module_visibility(&mut self, name: String, action: ModuleAction) {
+        if !self
+            .configured_modules()
+            .any(|module| module.to_string() == name)
+        {
+            warn!("Module {name} is not in the configuration");
+
+            return;
+        }
+
+        let hidden = action.hidden(self.state.is_hidden(&name));
+
+        if self.state.set_hidden(&name, hidden) {
+            info!("Module {name} {}", if hidden { "hidden" } else { "shown" });
+
+            self.state.save();
+        }
+    }
+
+    /// Refreshes the module owning the menu, when a menu is opened or pinned
+    fn menu_opened(&mut self, menu_type: &MenuType) -> Task<Message> {
+        let mut cmd = vec![];
+        match menu_type {
+            #[cfg(feature = "updates")]
+            MenuType::Updates => {
+                if let Some(updates) = self.updates.as_mut() {
+                    updates.update(modules::updates::Message::MenuOpened);
+                }
+            }
+            #[cfg(feature = "tray")]
+            MenuType::Tray(name) => {
+                self.tray
+                    .update(modules::tray::Message::MenuOpened(name.clone()));
+            }
+            #[cfg(feature = "notes")]
+            MenuType::Notes => {
+                self.notes.update(modules::notes::Message::MenuOpened);
+            }
+            #[cfg(feature = "qr-code")]
+            MenuType::QrCode => {
+                if let modules::qr_code::Action::Command(task) =
+                    self.qr_code.update(modules::qr_code::Message::MenuOpened)
+                {
+                    cmd.push(task.map(Message::QrCode));
+                }
+            }
+            #[cfg(feature = "totp")]
+            MenuType::Totp => {
+                if let modules::totp::Action::Command(task) =
+                    self.totp.update(modules::totp::Message::MenuOpened)
+                {
+                    cmd.push(task.map(Message::Totp));
+                }
+            }
+            #[cfg(feature = "encrypted-volumes")]
+            MenuType::EncryptedVolumes => {
+                self.encrypted_volumes
+                    .update(modules::encrypted_volumes::Message::MenuOpened);
+            }
+            #[cfg(feature = "modem")]
+            MenuType::Modem => {
+                if let modules::modem::Action::Command(task) =
+                    self.modem.update(modules::modem::Message::MenuOpened)
+                {
+                    cmd.push(task.map(Message::Modem));
+                }
+            }
+            #[cfg(feature = "clock")]
+            MenuType::Clock => {
+                if let modules::clock::Action::Command(task) =
+                    self.clock.update(modules::clock::Message::MenuOpened)
+                {
+                    cmd.push(task.map(Message::Clock));
+                }
+            }
+            #[cfg(feature = "passwords")]
+            MenuType::Passwords => {
+                if let modules::passwords::Action::Command(task) = self
+                    .passwords
+                    .update(modules::passwords::Message::MenuOpened)
+                {
+                    cmd.push(task.map(Message::Passwords));
+                }
+            }
+            #[cfg(feature = "window-rules")]
+            MenuType::WindowRules => {
+                self.window_rules
+                    .update(modules::window_rules::Message::MenuOpened);
+            }
+            #[cfg(feature = "network")]
+            MenuType::Network => {
+                if let modules::network::Action::Command(task) =
+                    self.network.update(modules::network::Message::MenuOpened)
+                {
+                    cmd.push(task.map(Message::Network));
+                }
+            }
+            #[cfg(feature = "notifications")]
+            MenuType::Notifications => {
+                self.notifications
+                    .update(modules::notifications::Message::MenuOpened);
+            }
+            #[cfg(feature = "system-info")]
+            MenuType::SystemInfo => {
+                if let modules::system_info::Action::Command(task) = self
+                    .system_info
+                    .update(modules::system_info::Message::MenuOpened)
+                {
+                    cmd.push(task.map(Message::SystemInfo));
+                }
+            }
+            MenuType::Settings => {
+                cmd.push(
+                    match self.settings.update(modules::settings::Message::MenuOpened) {
+                        modules::settings::Action::Command(task) => task.map(Message::Settings),
+                        _ => Task::none(),
+                    },
+                );
+            }
+            _ => {}
+        };
+
+        Task::batch(cmd)
+    }
+
+    /// Pins the popups kept in the state file
+    fn restore_pinned_popups(&mut self) -> Task<Message> {
+        let mut tasks = Vec::new();
+
+        for name in self.state.pinned_popups.clone() {
+            if let Some(menu_type) = MenuType::from_name(&name) {
+                tasks.push(self.menu_opened(&menu_type));
+                tasks.push(self.pinned_popups.pin(menu_type));
+            }
+        }
+
+        Task::batch(tasks)
+    }
+
+    fn set_popup_pinned(&mut self, name: String, action: PinAction) -> Task<Message> {
+        let Some(menu_type) = MenuType::from_name(&name) else {
+            warn!("Module {name} has no popup to pin");
+
+            return Task::none();
+        };
+
+        if !self
+            .configured_modules()
+            .any(|module| module.to_string() == name)
+        {
+            warn!("Module {name} is not in the configuration");
+
+            return Task::none();
+        }
+
+        let pinned = self.pinned_popups.is_pinned(&menu_type);
+
+        let task = match (pinned, action.pinned(pinned)) {
+            (false, true) => Task::batch(vec![
+                self.menu_opened(&menu_type),
+                self.pinned_popups.pin(menu_type),
+            ]),
+            (true, false) => self.pinned_popups.unpin(&menu_type),
+            _ => return Task::none(),
+        };
+
+        self.state.pinned_popups = self.pinned_popups.names();
+        self.state.save();
+
+        task
+    }
+
     fn refesh_config(&mut self, config: Box<Config>) {
         self.general_config = GeneralConfig {
             outputs: config.outputs,
             modules: config.modules,
             enable_esc_key: config.enable_esc_key,
+            enable_context_menu: config.enable_context_menu,
+            enable_keyboard_navigation: config.enable_keyboard_navigation,
+            hyprland_reserved_area: cfg!(feature = "hyprland") && config.hyprland_reserved_area,
+            editor_cmd: config.editor_cmd,
         };
-        self.theme = AshellTheme::new(config.position, &config.appearance);
+        self.scheduler.reload(config.scheduler);
+        self.sounds.reload(config.sounds);
+        #[cfg(feature = "metrics")]
+        self.metrics.reload(config.metrics);
+        self.zen_mode.reload(config.zen_mode);
+        self.animations.reload(config.animations);
+        self.sync_animations();
+        self.privacy_mode.reload(config.privacy_mode);
+        self.theme = AshellTheme::new(
+            config.position,
+            &self
+                .scheduler
+                .appearance(config.appearance.style, config.appearance.scale_factor)
+                .unwrap_or(config.appearance),
+        );
         let custom = config
             .custom_modules
             .into_iter()
             .map(|o| (o.name.clone(), Custom::new(o)))
             .collect();
 
-        self.app_launcher = config.app_launcher_cmd.map(AppLauncher::new);
+        #[cfg(feature = "app-launcher")]
+        {
+            self.app_launcher = config.app_launcher_cmd.map(AppLauncher::new);
+        }
         self.custom = custom;
-        self.updates = config.updates.map(Updates::new);
-        self.clipboard = config.clipboard_cmd.map(Clipboard::new);
+        #[cfg(feature = "updates")]
+        {
+            self.updates = config.updates.map(Updates::new);
+        }
+        #[cfg(feature = "clipboard")]
+        {
+            self.clipboard = config.clipboard_cmd.map(Clipboard::new);
+        }
+        self.workspaces_instances = config
+            .workspaces
+            .instances
+            .clone()
+            .into_iter()
+            .map(|(name, config)| (name, Workspaces::new(config)))
+            .collect();
         self.workspaces = Workspaces::new(config.workspaces);
+        #[cfg(feature = "keyboard-layout")]
+        {
+            self.keyboard_layout = KeyboardLayout::new(config.keyboard_layout);
+        }
 
         #[cfg(feature = "hyprland")]
         {
             self.window_title = WindowTitle::<HyprlandWindowManager>::new(config.window_title);
-            self.keyboard_layout = KeyboardLayout::new(config.keyboard_layout);
+        }
+        #[cfg(feature = "keyboard-submap")]
+        {
             self.keyboard_submap = KeyboardSubmap::default();
         }
+        #[cfg(feature = "window-group")]
+        {
+            self.window_group = WindowGroup::default();
+        }
+
+        #[cfg(feature = "sway")]
+        {
+            self.window_title = WindowTitle::<SwayWindowManager>::new(config.window_title);
+        }
+
+        #[cfg(feature = "wlroots")]
+        {
+            self.window_title =
+                WindowTitle::<ForeignToplevelWindowManager>::new(config.window_title);
+        }
 
-        self.system_info = SystemInfo::new(config.system_info);
-        self.clock = Clock::new(config.clock);
+        #[cfg(feature = "river")]
+        {
+            self.window_title =
+                WindowTitle::<ForeignToplevelWindowManager>::new(config.window_title);
+        }
+
+        #[cfg(feature = "kwin")]
+        {
+            self.window_title =
+                WindowTitle::<ForeignToplevelWindowManager>::new(config.window_title);
+        }
+
+        #[cfg(feature = "system-info")]
+        {
+            self.system_info = SystemInfo::new(config.system_info);
+        }
+        #[cfg(feature = "clock")]
+        self.clock
+            .update(modules::clock::Message::ConfigReloaded(config.clock));
         self.settings
             .update(modules::settings::Message::ConfigReloaded(config.settings));
-        self.media_player
-            .update(modules::media_player::Message::ConfigReloaded(
-                config.media_player,
+        #[cfg(feature = "notes")]
+        self.notes
+            .update(modules::notes::Message::ConfigReloaded(config.notes));
+        #[cfg(feature = "qr-code")]
+        {
+            self.qr_code = QrCode::new(config.qr_code);
+        }
+        #[cfg(feature = "totp")]
+        {
+            self.totp = Totp::new(config.totp);
+        }
+        #[cfg(feature = "passwords")]
+        {
+            self.passwords = Passwords::new(config.passwords);
+        }
+        #[cfg(feature = "encrypted-volumes")]
+        self.encrypted_volumes
+            .update(modules::encrypted_volumes::Message::ConfigReloaded(
+                config.encrypted_volumes,
+            ));
+        #[cfg(feature = "backup")]
+        self.backup
+            .update(modules::backup::Message::ConfigReloaded(config.backup));
+        #[cfg(feature = "modem")]
+        self.modem
+            .update(modules::modem::Message::ConfigReloaded(config.modem));
+        #[cfg(feature = "gpu")]
+        self.gpu
+            .update(modules::gpu::Message::ConfigReloaded(config.gpu));
+        #[cfg(feature = "dashboard")]
+        self.dashboard
+            .update(modules::dashboard::Message::ConfigReloaded(
+                config.dashboard,
+            ));
+        #[cfg(feature = "battery")]
+        self.battery
+            .update(modules::battery::Message::ConfigReloaded(config.battery));
+        #[cfg(feature = "audio")]
+        self.audio
+            .update(modules::audio::Message::ConfigReloaded(config.audio));
+        #[cfg(feature = "audio")]
+        self.microphone
+            .update(modules::microphone::Message::ConfigReloaded(
+                config.microphone,
             ));
+        #[cfg(feature = "network")]
+        self.network
+            .update(modules::network::Message::ConfigReloaded(config.network));
+        #[cfg(feature = "bluetooth")]
+        self.bluetooth
+            .update(modules::bluetooth::Message::ConfigReloaded(
+                config.bluetooth,
+            ));
+        #[cfg(feature = "notifications")]
+        self.notifications
+            .update(modules::notifications::Message::ConfigReloaded(
+                config.notifications,
+            ));
+        #[cfg(feature = "idle-inhibitor")]
+        self.idle_inhibitor
+            .update(modules::idle_inhibitor::Message::ConfigReloaded(
+                config.idle_inhibitor,
+            ));
+        #[cfg(feature = "power-profiles")]
+        self.power_profiles
+            .update(modules::power_profiles::Message::ConfigReloaded(
+                config.power_profiles,
+            ));
+        self.hooks = Hooks::new(config.hooks);
     }
 
     pub fn title(&self, _id: Id) -> String {
         String::from("ashell")
     }
 
-    pub fn theme(&self, _id: Id) -> Theme {
-        self.theme.get_theme().clone()
+    pub fn theme(&self, id: Id) -> Theme {
+        self.wallpaper_theme(id)
+            .unwrap_or_else(|| self.theme.get_theme().clone())
+    }
+
+    /// The theme of a bar colored after its wallpaper, the menus keep the configured colors
+    #[cfg(feature = "wallpaper-colors")]
+    fn wallpaper_theme(&self, id: Id) -> Option<Theme> {
+        if !matches!(self.outputs.has(id), Some(HasOutput::Main)) {
+            return None;
+        }
+
+        self.wallpaper_colors
+            .colors(self.outputs.get_monitor_name(id))
+            .map(|colors| self.theme.bar_theme(colors))
+    }
+
+    #[cfg(not(feature = "wallpaper-colors"))]
+    fn wallpaper_theme(&self, _id: Id) -> Option<Theme> {
+        None
     }
 
     pub fn style(&self, theme: &Theme) -> Appearance {
@@ -239,6 +989,8 @@ impl App {
             Message::ConfigChanged(config) => {
                 info!("New config: {config:?}");
                 let mut tasks = Vec::new();
+                let hyprland_reserved_area =
+                    cfg!(feature = "hyprland") && config.hyprland_reserved_area;
                 info!(
                     "Current outputs: {:?}, new outputs: {:?}",
                     self.general_config.outputs, config.outputs
@@ -247,6 +999,9 @@ impl App {
                     || self.theme.bar_position != config.position
                     || self.theme.bar_style != config.appearance.style
                     || self.theme.scale_factor != config.appearance.scale_factor
+                    || self.general_config.enable_keyboard_navigation
+                        != config.enable_keyboard_navigation
+                    || self.general_config.hyprland_reserved_area != hyprland_reserved_area
                 {
                     warn!("Outputs changed, syncing");
                     tasks.push(self.outputs.sync(
@@ -254,50 +1009,72 @@ impl App {
                         &config.outputs,
                         config.position,
                         config.appearance.scale_factor,
+                        config.enable_keyboard_navigation,
+                        hyprland_reserved_area,
                     ));
                 }
 
+                #[cfg(feature = "autostart")]
+                if let modules::autostart::Action::Command(task) =
+                    self.autostart
+                        .update(modules::autostart::Message::ConfigReloaded(
+                            config.autostart.clone(),
+                        ))
+                {
+                    tasks.push(task.map(Message::Autostart));
+                }
+
+                #[cfg(feature = "media-player")]
+                if let modules::media_player::Action::Command(task) =
+                    self.media_player
+                        .update(modules::media_player::Message::ConfigReloaded(
+                            config.media_player.clone(),
+                        ))
+                {
+                    tasks.push(task.map(Message::MediaPlayer));
+                }
+
+                tasks.push(self.pinned_popups.reload(config.pinned_popups.clone()));
+                tasks.push(
+                    self.battery_saver
+                        .reload(config.battery_saver.clone())
+                        .map(Message::BatterySaver),
+                );
+                #[cfg(feature = "wallpaper-colors")]
+                tasks.push(
+                    self.wallpaper_colors
+                        .reload(config.wallpaper_colors.clone(), config.position)
+                        .map(Message::WallpaperColors),
+                );
+
                 self.logger.set_new_spec(get_log_spec(&config.log_level));
                 self.refesh_config(config);
 
                 Task::batch(tasks)
             }
             Message::ToggleMenu(menu_type, id, button_ui_ref) => {
-                let mut cmd = vec![];
-                match &menu_type {
-                    MenuType::Updates => {
-                        if let Some(updates) = self.updates.as_mut() {
-                            updates.update(modules::updates::Message::MenuOpened);
-                        }
-                    }
-                    MenuType::Tray(name) => {
-                        self.tray
-                            .update(modules::tray::Message::MenuOpened(name.clone()));
-                    }
-                    MenuType::Settings => {
-                        cmd.push(
-                            match self.settings.update(modules::settings::Message::MenuOpened) {
-                                modules::settings::Action::Command(task) => {
-                                    task.map(Message::Settings)
-                                }
-                                _ => Task::none(),
-                            },
-                        );
-                    }
-                    _ => {}
-                };
+                let mut cmd = vec![self.menu_opened(&menu_type)];
+                // the search field needs the keyboard as soon as the menu is open
+                #[cfg(feature = "passwords")]
+                let request_keyboard = menu_type == MenuType::Passwords;
+                #[cfg(not(feature = "passwords"))]
+                let request_keyboard = false;
                 cmd.push(self.outputs.toggle_menu(
                     id,
                     menu_type,
                     button_ui_ref,
                     self.general_config.enable_esc_key,
                 ));
+                if request_keyboard && self.outputs.menu_is_open() {
+                    cmd.push(self.outputs.request_keyboard(id));
+                }
 
                 Task::batch(cmd)
             }
             Message::CloseMenu(id) => self
                 .outputs
                 .close_menu(id, self.general_config.enable_esc_key),
+            #[cfg(feature = "app-launcher")]
             Message::AppLauncher(msg) => {
                 if let Some(app_launcher) = self.app_launcher.as_mut() {
                     app_launcher.update(msg);
@@ -312,6 +1089,7 @@ impl App {
 
                 Task::none()
             }
+            #[cfg(feature = "updates")]
             Message::Updates(msg) => {
                 if let Some(updates) = self.updates.as_mut() {
                     match updates.update(msg) {
@@ -332,6 +1110,7 @@ impl App {
                     Task::none()
                 }
             }
+            #[cfg(feature = "clipboard")]
             Message::Clipboard(msg) => {
                 if let Some(clipboard) = self.clipboard.as_mut() {
                     clipboard.update(msg);
@@ -339,36 +1118,132 @@ impl App {
 
                 Task::none()
             }
+            Message::Workspaces(modules::workspaces::Message::WindowDropped(workspace_id)) => {
+                if let Some(window_id) = self.dragged_window.take() {
+                    self.workspaces
+                        .update(modules::workspaces::Message::MoveWindow(
+                            window_id,
+                            workspace_id,
+                        ));
+                }
+                Task::none()
+            }
+            Message::Workspaces(modules::workspaces::Message::ModifiersChanged(modifiers)) => {
+                for workspaces in self.workspaces_instances.values_mut() {
+                    workspaces.update(modules::workspaces::Message::ModifiersChanged(modifiers));
+                }
+                self.workspaces
+                    .update(modules::workspaces::Message::ModifiersChanged(modifiers));
+                Task::none()
+            }
             Message::Workspaces(msg) => {
+                let active = self.workspaces.active();
                 self.workspaces.update(msg);
+                if self.workspaces.active() != active {
+                    self.sounds.play(SoundEvent::WorkspaceSwitch);
+                }
+                let workspaces = &self.workspaces;
+                self.outputs
+                    .set_hidden(|name| workspaces.special_bar(name) == SpecialWorkspaceBar::Hide)
+            }
+            Message::WorkspacesInstance(
+                name,
+                modules::workspaces::Message::WindowDropped(workspace_id),
+            ) => {
+                if let (Some(window_id), Some(workspaces)) = (
+                    self.dragged_window.take(),
+                    self.workspaces_instances.get_mut(&name),
+                ) {
+                    workspaces.update(modules::workspaces::Message::MoveWindow(
+                        window_id,
+                        workspace_id,
+                    ));
+                }
                 Task::none()
             }
-            Message::WindowTitle(msg) => {
-                #[cfg(feature = "hyprland")]
-                {
-                    self.window_title.update(msg);
+            Message::WorkspacesInstance(name, msg) => {
+                if let Some(workspaces) = self.workspaces_instances.get_mut(&name) {
+                    workspaces.update(msg);
+                }
+                Task::none()
+            }
+            Message::BarKey(id, key, shift) => {
+                if !matches!(self.outputs.has(id), Some(HasOutput::Main)) {
+                    return Task::none();
                 }
 
-                #[cfg(feature = "niri")]
-                {
-                    self.window_title.update(msg);
+                let monitor = self.outputs.get_monitor_name(id).map(str::to_owned);
+                let direction = match key {
+                    keyboard::key::Named::ArrowLeft => -1,
+                    keyboard::key::Named::ArrowRight => 1,
+                    keyboard::key::Named::Tab if shift => -1,
+                    keyboard::key::Named::Tab => 1,
+                    keyboard::key::Named::Enter | keyboard::key::Named::Space => {
+                        self.workspaces
+                            .update(modules::workspaces::Message::ActivateFocused);
+
+                        return self.release_keyboard_grab();
+                    }
+                    _ => return Task::none(),
+                };
+
+                self.workspaces
+                    .update(modules::workspaces::Message::MoveFocus(direction, monitor));
+
+                Task::none()
+            }
+            Message::WindowTitle(msg) => {
+                match self.window_title.update(msg) {
+                    modules::window_title::Action::None => {}
+                    modules::window_title::Action::DragStarted(id) => {
+                        self.dragged_window = Some(id);
+                    }
+                    // a press released on the same window is a click
+                    modules::window_title::Action::Released(id) => {
+                        if self
+                            .dragged_window
+                            .take()
+                            .is_some_and(|dragged| dragged == id)
+                        {
+                            self.window_title
+                                .update(modules::window_title::Message::FocusWindow(id));
+                        }
+                    }
                 }
                 Task::none()
             }
-            Message::SystemInfo(msg) => {
-                self.system_info.update(msg);
+            Message::WindowDragEnded => {
+                self.dragged_window = None;
                 Task::none()
             }
-            #[cfg(feature = "hyprland")]
+            #[cfg(feature = "system-info")]
+            Message::SystemInfo(msg) => match self.system_info.update(msg) {
+                modules::system_info::Action::None => Task::none(),
+                modules::system_info::Action::Command(task) => task.map(Message::SystemInfo),
+            },
+            #[cfg(feature = "keyboard-layout")]
             Message::KeyboardLayout(message) => {
                 self.keyboard_layout.update(message);
                 Task::none()
             }
-            #[cfg(feature = "hyprland")]
+            #[cfg(feature = "keyboard-submap")]
             Message::KeyboardSubmap(message) => {
                 self.keyboard_submap.update(message);
                 Task::none()
             }
+            #[cfg(feature = "window-group")]
+            Message::WindowGroup(message) => {
+                self.window_group.update(message);
+                Task::none()
+            }
+            #[cfg(feature = "window-rules")]
+            Message::WindowRules(message) => match self.window_rules.update(message) {
+                modules::window_rules::Action::None => Task::none(),
+                modules::window_rules::Action::CloseMenu => self
+                    .outputs
+                    .close_all_menu_if(MenuType::WindowRules, self.general_config.enable_esc_key),
+            },
+            #[cfg(feature = "tray")]
             Message::Tray(msg) => match self.tray.update(msg) {
                 modules::tray::Action::None => Task::none(),
                 modules::tray::Action::ToggleMenu(name, id, button_ui_ref) => {
@@ -384,10 +1259,12 @@ impl App {
                     .outputs
                     .close_all_menu_if(MenuType::Tray(name), self.general_config.enable_esc_key),
             },
-            Message::Clock(message) => {
-                self.clock.update(message);
-                Task::none()
-            }
+            #[cfg(feature = "clock")]
+            Message::Clock(message) => match self.clock.update(message) {
+                modules::clock::Action::None => Task::none(),
+                modules::clock::Action::Command(task) => task.map(Message::Clock),
+            },
+            #[cfg(feature = "privacy")]
             Message::Privacy(msg) => {
                 self.privacy.update(msg);
                 Task::none()
@@ -415,6 +1292,9 @@ impl App {
                         .and_then(|info| info.description.as_deref())
                         .unwrap_or("");
 
+                    self.hooks.output_added(name);
+                    self.sounds.play(SoundEvent::DeviceAdded);
+
                     self.outputs.add(
                         self.theme.bar_style,
                         &self.general_config.outputs,
@@ -422,40 +1302,525 @@ impl App {
                         name,
                         wl_output,
                         self.theme.scale_factor,
+                        self.general_config.enable_keyboard_navigation,
                     )
                 }
                 iced::event::wayland::OutputEvent::Removed => {
                     info!("Output destroyed");
+                    if let Some(name) = self.outputs.get_output_name(&wl_output) {
+                        self.hooks.output_removed(name);
+                        self.sounds.play(SoundEvent::DeviceRemoved);
+                    }
+
                     self.outputs.remove(
                         self.theme.bar_style,
                         self.theme.bar_position,
                         wl_output,
                         self.theme.scale_factor,
+                        self.general_config.enable_keyboard_navigation,
                     )
                 }
                 _ => Task::none(),
             },
+            #[cfg(feature = "autostart")]
+            Message::Autostart(msg) => match self.autostart.update(msg) {
+                modules::autostart::Action::None => Task::none(),
+                modules::autostart::Action::Command(task) => task.map(Message::Autostart),
+            },
+            #[cfg(feature = "notes")]
+            Message::Notes(msg) => match self.notes.update(msg) {
+                modules::notes::Action::None => Task::none(),
+                modules::notes::Action::Command(task) => task.map(Message::Notes),
+                modules::notes::Action::RequestKeyboard(id) => self.outputs.request_keyboard(id),
+                modules::notes::Action::ReleaseKeyboard(id) => self.outputs.release_keyboard(id),
+            },
+            Message::Scheduler(msg) => match self.scheduler.update(msg) {
+                scheduler::Action::None => Task::none(),
+                scheduler::Action::AppearanceChanged => {
+                    if let Some(appearance) = self
+                        .scheduler
+                        .appearance(self.theme.bar_style, self.theme.scale_factor)
+                    {
+                        self.theme = AshellTheme::new(self.theme.bar_position, &appearance);
+                    }
+
+                    Task::none()
+                }
+                scheduler::Action::Command(task) => task.map(Message::Scheduler),
+            },
+            Message::ZenMode(msg) => {
+                self.zen_mode.update(msg);
+
+                Task::none()
+            }
+            #[cfg(feature = "metrics")]
+            Message::Metrics(msg) => {
+                self.metrics.update(msg);
+
+                Task::none()
+            }
+            Message::PrivacyMode(msg) => self.update_privacy_mode(msg),
+            Message::BatterySaver(msg) => {
+                let task = self.battery_saver.update(msg);
+                self.sync_animations();
+
+                task.map(Message::BatterySaver)
+            }
+            Message::Animations(msg) => {
+                self.animations.update(msg);
+                self.sync_animations();
+
+                Task::none()
+            }
+            #[cfg(feature = "wallpaper-colors")]
+            Message::WallpaperColors(msg) => self
+                .wallpaper_colors
+                .update(msg)
+                .map(Message::WallpaperColors),
+            Message::Redraw(redraw::Message::Tick(now)) => {
+                let intervals = self.redraw_intervals();
+
+                for module in self.redraw.due(now, &intervals) {
+                    match module {
+                        #[cfg(feature = "clock")]
+                        ModuleName::Clock => {
+                            self.clock.update(modules::clock::Message::Update);
+                        }
+                        #[cfg(feature = "media-player")]
+                        ModuleName::MediaPlayer => {
+                            self.media_player
+                                .update(modules::media_player::Message::Tick);
+                        }
+                        _ => {}
+                    }
+                }
+
+                Task::none()
+            }
+            Message::Ipc(command) => match command {
+                IpcCommand::Zen { action } => {
+                    self.zen_mode.update(zen_mode::Message::Set(action));
+
+                    Task::none()
+                }
+                IpcCommand::Privacy { action } => {
+                    self.update_privacy_mode(privacy_mode::Message::Set(action))
+                }
+                IpcCommand::Module { action, name } => {
+                    self.set_module_visibility(name, action);
+
+                    Task::none()
+                }
+                IpcCommand::Popup { action, name } => self.set_popup_pinned(name, action),
+                // answered by the listener with the geometry published by the outputs
+                IpcCommand::Geometry => Task::none(),
+                // answered by the listener with the history published by the notifications
+                IpcCommand::Notifications => Task::none(),
+                IpcCommand::FocusWorkspaces { output } => {
+                    let release_task = self.release_keyboard_grab();
+
+                    match self.outputs.grab_bar_keyboard(output.as_deref()) {
+                        Some((id, task)) => {
+                            self.keyboard_grab = Some(id);
+                            // the navigation starts from the active workspace
+                            let monitor = self.outputs.get_monitor_name(id).map(str::to_owned);
+                            self.workspaces
+                                .update(modules::workspaces::Message::MoveFocus(0, monitor));
+
+                            Task::batch(vec![release_task, task])
+                        }
+                        None => release_task,
+                    }
+                }
+            },
+            Message::ModuleVisibility(name, action) => {
+                self.set_module_visibility(name, action);
+
+                Task::none()
+            }
+            Message::PinPopup(name, action) => self.set_popup_pinned(name, action),
+            Message::ReloadConfig => {
+                info!("Reload config file");
+
+                let config = config::read_config(&self.config_path).unwrap_or_default();
+
+                Task::batch(vec![
+                    self.outputs
+                        .close_all_menus(self.general_config.enable_esc_key),
+                    self.update(Message::ConfigChanged(Box::new(config))),
+                ])
+            }
+            Message::OpenConfig => {
+                let editor = self
+                    .general_config
+                    .editor_cmd
+                    .as_deref()
+                    .unwrap_or("xdg-open");
+
+                execute_command(format!(
+                    "{editor} '{}'",
+                    self.config_path.to_string_lossy().replace('\'', "'\\''")
+                ));
+
+                self.outputs
+                    .close_all_menus(self.general_config.enable_esc_key)
+            }
+            Message::Quit => {
+                info!("Quit requested from the context menu");
+
+                iced::exit()
+            }
+            #[cfg(feature = "qr-code")]
+            Message::QrCode(msg) => match self.qr_code.update(msg) {
+                modules::qr_code::Action::None => Task::none(),
+                modules::qr_code::Action::Command(task) => task.map(Message::QrCode),
+                modules::qr_code::Action::CloseMenu(id, task) => Task::batch(vec![
+                    task.map(Message::QrCode),
+                    self.outputs.close_menu_if(
+                        id,
+                        MenuType::QrCode,
+                        self.general_config.enable_esc_key,
+                    ),
+                ]),
+            },
+            #[cfg(feature = "totp")]
+            Message::Totp(msg) => match self.totp.update(msg) {
+                modules::totp::Action::None => Task::none(),
+                modules::totp::Action::Command(task) => task.map(Message::Totp),
+            },
+            #[cfg(feature = "usbguard")]
+            Message::UsbGuard(msg) => match self.usbguard.update(msg) {
+                modules::usbguard::Action::None => Task::none(),
+                modules::usbguard::Action::Command(task) => task.map(Message::UsbGuard),
+                // the popup of the blocked device, unless the menu is shown already
+                modules::usbguard::Action::Prompt => {
+                    if self.outputs.is_menu_open(&MenuType::UsbGuard)
+                        || self.pinned_popups.is_pinned(&MenuType::UsbGuard)
+                    {
+                        Task::none()
+                    } else {
+                        Task::batch(vec![
+                            self.menu_opened(&MenuType::UsbGuard),
+                            self.pinned_popups.pin(MenuType::UsbGuard),
+                        ])
+                    }
+                }
+                // a popup pinned by the user stays
+                modules::usbguard::Action::ClosePrompt => {
+                    if self
+                        .state
+                        .pinned_popups
+                        .iter()
+                        .any(|name| name == "UsbGuard")
+                    {
+                        Task::none()
+                    } else {
+                        self.pinned_popups.unpin(&MenuType::UsbGuard)
+                    }
+                }
+            },
+            #[cfg(feature = "backup")]
+            Message::Backup(msg) => match self.backup.update(msg) {
+                modules::backup::Action::None => Task::none(),
+                modules::backup::Action::Command(task) => task.map(Message::Backup),
+            },
+            #[cfg(feature = "modem")]
+            Message::Modem(msg) => match self.modem.update(msg) {
+                modules::modem::Action::None => Task::none(),
+                modules::modem::Action::Command(task) => task.map(Message::Modem),
+            },
+            #[cfg(feature = "acpi")]
+            Message::Acpi(msg) => match self.acpi.update(msg) {
+                modules::acpi::Action::None => Task::none(),
+                modules::acpi::Action::Command(task) => task.map(Message::Acpi),
+            },
+            #[cfg(feature = "gpu")]
+            Message::Gpu(msg) => match self.gpu.update(msg) {
+                modules::gpu::Action::None => Task::none(),
+                modules::gpu::Action::Command(task) => task.map(Message::Gpu),
+            },
+            #[cfg(feature = "dashboard")]
+            Message::Dashboard(msg) => {
+                self.dashboard.update(msg);
+                Task::none()
+            }
+            #[cfg(feature = "battery")]
+            Message::Battery(msg) => {
+                if let modules::battery::Action::AlertReached = self.battery.update(msg) {
+                    self.sounds.play(SoundEvent::BatteryCritical);
+                }
+                Task::none()
+            }
+            #[cfg(feature = "audio")]
+            Message::Audio(msg) => {
+                self.audio.update(msg);
+                Task::none()
+            }
+            #[cfg(feature = "audio")]
+            Message::Microphone(msg) => {
+                self.microphone.update(msg);
+                Task::none()
+            }
+            #[cfg(feature = "network")]
+            Message::Network(msg) => match self.network.update(msg) {
+                modules::network::Action::None => Task::none(),
+                modules::network::Action::Command(task) => task.map(Message::Network),
+                modules::network::Action::RequestKeyboard(id) => self.outputs.request_keyboard(id),
+                modules::network::Action::ReleaseKeyboard(id) => self.outputs.release_keyboard(id),
+                modules::network::Action::ReleaseKeyboardWithCommand(id, task) => {
+                    Task::batch(vec![
+                        task.map(Message::Network),
+                        self.outputs.release_keyboard(id),
+                    ])
+                }
+            },
+            #[cfg(feature = "bluetooth")]
+            Message::Bluetooth(msg) => match self.bluetooth.update(msg) {
+                modules::bluetooth::Action::None => Task::none(),
+                modules::bluetooth::Action::Command(task) => task.map(Message::Bluetooth),
+            },
+            #[cfg(feature = "notifications")]
+            Message::Notifications(msg) => match self.notifications.update(msg) {
+                modules::notifications::Action::None => Task::none(),
+                modules::notifications::Action::Command(task) => task.map(Message::Notifications),
+                modules::notifications::Action::Notified(task) => {
+                    self.sounds.play(SoundEvent::Notification);
+
+                    task.map(Message::Notifications)
+                }
+                modules::notifications::Action::DoNotDisturbChanged(do_not_disturb, task) => {
+                    self.state.do_not_disturb = do_not_disturb;
+                    self.state.save();
+                    self.hooks.do_not_disturb_changed(do_not_disturb);
+
+                    task.map(Message::Notifications)
+                }
+            },
+            #[cfg(feature = "idle-inhibitor")]
+            Message::IdleInhibitor(msg) => {
+                self.idle_inhibitor.update(msg);
+                Task::none()
+            }
+            #[cfg(feature = "power-profiles")]
+            Message::PowerProfiles(msg) => match self.power_profiles.update(msg) {
+                modules::power_profiles::Action::None => Task::none(),
+                modules::power_profiles::Action::Command(task) => task.map(Message::PowerProfiles),
+            },
+            #[cfg(feature = "encrypted-volumes")]
+            Message::EncryptedVolumes(msg) => match self.encrypted_volumes.update(msg) {
+                modules::encrypted_volumes::Action::None => Task::none(),
+                modules::encrypted_volumes::Action::Command(task) => {
+                    task.map(Message::EncryptedVolumes)
+                }
+                modules::encrypted_volumes::Action::RequestKeyboard(id, task) => Task::batch(vec![
+                    task.map(Message::EncryptedVolumes),
+                    self.outputs.request_keyboard(id),
+                ]),
+                modules::encrypted_volumes::Action::ReleaseKeyboard(id, task) => Task::batch(vec![
+                    task.map(Message::EncryptedVolumes),
+                    self.outputs.release_keyboard(id),
+                ]),
+            },
+            #[cfg(feature = "passwords")]
+            Message::Passwords(msg) => match self.passwords.update(msg) {
+                modules::passwords::Action::None => Task::none(),
+                modules::passwords::Action::Command(task) => task.map(Message::Passwords),
+            },
+            Message::Hooks(msg) => {
+                self.hooks.update(msg);
+                Task::none()
+            }
+            #[cfg(feature = "media-player")]
             Message::MediaPlayer(msg) => match self.media_player.update(msg) {
                 modules::media_player::Action::None => Task::none(),
                 modules::media_player::Action::Command(task) => task.map(Message::MediaPlayer),
             },
             Message::CloseAllMenus => {
+                self.workspaces
+                    .update(modules::workspaces::Message::ClearFocus);
+                let release_task = self.release_keyboard_grab();
+
                 if self.outputs.menu_is_open() {
-                    self.outputs
-                        .close_all_menus(self.general_config.enable_esc_key)
+                    Task::batch(vec![
+                        release_task,
+                        self.outputs
+                            .close_all_menus(self.general_config.enable_esc_key),
+                    ])
                 } else {
-                    Task::none()
+                    release_task
                 }
             }
         }
     }
 
+    fn release_keyboard_grab(&mut self) -> Task<Message> {
+        match self.keyboard_grab.take() {
+            Some(id) => self.outputs.release_bar_keyboard(id),
+            None => Task::none(),
+        }
+    }
+
+    /// Content of a menu, shown either from the bar or as a pinned popup
+    fn menu_content(
+        &'_ self,
+        id: Id,
+        menu_type: &MenuType,
+    ) -> Option<(Element<'_, Message>, MenuSize)> {
+        match menu_type {
+            #[cfg(feature = "updates")]
+            MenuType::Updates => self.updates.as_ref().map(|updates| {
+                (
+                    updates.menu_view(id, &self.theme).map(Message::Updates),
+                    MenuSize::Small,
+                )
+            }),
+            #[cfg(feature = "tray")]
+            MenuType::Tray(name) => Some((
+                self.tray.menu_view(&self.theme, name).map(Message::Tray),
+                MenuSize::Medium,
+            )),
+            MenuType::Settings => Some((
+                self.settings
+                    .menu_view(id, &self.theme, self.theme.bar_position)
+                    .map(Message::Settings),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "media-player")]
+            MenuType::MediaPlayer => Some((
+                self.media_player
+                    .menu_view(&self.theme)
+                    .map(Message::MediaPlayer),
+                MenuSize::Large,
+            )),
+            #[cfg(feature = "system-info")]
+            MenuType::SystemInfo => Some((
+                self.system_info
+                    .menu_view(&self.theme)
+                    .map(Message::SystemInfo),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "autostart")]
+            MenuType::Autostart => Some((
+                self.autostart
+                    .menu_view(&self.theme)
+                    .map(Message::Autostart),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "notes")]
+            MenuType::Notes => Some((
+                self.notes.menu_view(id, &self.theme).map(Message::Notes),
+                MenuSize::Large,
+            )),
+            #[cfg(feature = "qr-code")]
+            MenuType::QrCode => Some((
+                self.qr_code.menu_view(id, &self.theme).map(Message::QrCode),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "usbguard")]
+            MenuType::UsbGuard => Some((
+                self.usbguard.menu_view(&self.theme).map(Message::UsbGuard),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "backup")]
+            MenuType::Backup => Some((
+                self.backup.menu_view(&self.theme).map(Message::Backup),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "modem")]
+            MenuType::Modem => Some((
+                self.modem.menu_view(&self.theme).map(Message::Modem),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "gpu")]
+            MenuType::Gpu => Some((
+                self.gpu.menu_view(&self.theme).map(Message::Gpu),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "dashboard")]
+            MenuType::Dashboard => Some((
+                self.dashboard
+                    .menu_view(&self.theme)
+                    .map(Message::Dashboard),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "audio")]
+            MenuType::Audio => Some((
+                self.audio.menu_view(&self.theme).map(Message::Audio),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "network")]
+            MenuType::Network => Some((
+                self.network
+                    .menu_view(id, &self.theme)
+                    .map(Message::Network),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "bluetooth")]
+            MenuType::Bluetooth => Some((
+                self.bluetooth
+                    .menu_view(&self.theme)
+                    .map(Message::Bluetooth),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "notifications")]
+            MenuType::Notifications => Some((
+                self.notifications
+                    .menu_view(&self.theme)
+                    .map(Message::Notifications),
+                MenuSize::Large,
+            )),
+            #[cfg(feature = "clock")]
+            MenuType::Clock => Some((
+                self.clock.menu_view(&self.theme).map(Message::Clock),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "encrypted-volumes")]
+            MenuType::EncryptedVolumes => Some((
+                self.encrypted_volumes
+                    .menu_view(id, &self.theme)
+                    .map(Message::EncryptedVolumes),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "passwords")]
+            MenuType::Passwords => Some((
+                self.passwords
+                    .menu_view(&self.theme)
+                    .map(Message::Passwords),
+                MenuSize::Medium,
+            )),
+            #[cfg(feature = "window-rules")]
+            MenuType::WindowRules => Some((
+                self.window_rules
+                    .menu_view(&self.theme)
+                    .map(Message::WindowRules),
+                MenuSize::Small,
+            )),
+            MenuType::Context => Some((self.context_menu_view(), MenuSize::Small)),
+            #[cfg(feature = "totp")]
+            MenuType::Totp => Some((
+                self.totp.menu_view(&self.theme).map(Message::Totp),
+                MenuSize::Medium,
+            )),
+        }
+    }
+
     pub fn view(&'_ self, id: Id) -> Element<'_, Message> {
         match self.outputs.has(id) {
             Some(HasOutput::Main) => {
+                let special_bar = self
+                    .outputs
+                    .get_monitor_name(id)
+                    .map_or(SpecialWorkspaceBar::Show, |name| {
+                        self.workspaces.special_bar(name)
+                    });
+                // the surface is shrunk to a line, it stays empty
+                if special_bar == SpecialWorkspaceBar::Hide {
+                    return Row::new().into();
+                }
+
                 let [left, center, right] = self.modules_section(id, &self.theme);
 
-                let centerbox = centerbox::Centerbox::new([left, center, right])
+                let mut centerbox = centerbox::Centerbox::new([left, center, right])
                     .spacing(self.theme.space.xxs)
                     .width(Length::Fill)
                     .align_items(Alignment::Center)
@@ -469,9 +1834,16 @@ impl App {
                     } else {
                         [0, 0]
                     });
+                // the right clicks on the modules are left to them
+                if self.general_config.enable_context_menu && !self.outputs.menu_is_open() {
+                    centerbox = centerbox.on_right_press_with_position(move |button_ui_ref| {
+                        Message::ToggleMenu(MenuType::Context, id, button_ui_ref)
+                    });
+                }
 
-                let status_bar = container(centerbox).style(|t: &Theme| container::Style {
+                let status_bar = container(centerbox).style(move |t: &Theme| container::Style {
                     background: match self.theme.bar_style {
+                        _ if special_bar == SpecialWorkspaceBar::Transparent => None,
                         AppearanceStyle::Gradient => Some({
                             let start_color =
                                 t.palette().background.scale_alpha(self.theme.opacity);
@@ -535,79 +1907,99 @@ impl App {
                     status_bar.into()
                 }
             }
-            Some(HasOutput::Menu(menu_info)) => match menu_info {
-                Some((MenuType::Updates, button_ui_ref)) => {
-                    if let Some(updates) = self.updates.as_ref() {
-                        self.menu_wrapper(
-                            id,
-                            updates.menu_view(id, &self.theme).map(Message::Updates),
-                            MenuSize::Small,
-                            *button_ui_ref,
-                        )
-                    } else {
-                        Row::new().into()
+            Some(HasOutput::Menu(Some((menu_type, button_ui_ref)))) => {
+                match self.menu_content(id, menu_type) {
+                    Some((content, menu_size)) => {
+                        self.menu_wrapper(id, content, menu_size, *button_ui_ref)
                     }
+                    None => Row::new().into(),
                 }
-                Some((MenuType::Tray(name), button_ui_ref)) => self.menu_wrapper(
-                    id,
-                    self.tray.menu_view(&self.theme, name).map(Message::Tray),
-                    MenuSize::Medium,
-                    *button_ui_ref,
-                ),
-                Some((MenuType::Settings, button_ui_ref)) => self.menu_wrapper(
-                    id,
-                    self.settings
-                        .menu_view(id, &self.theme, self.theme.bar_position)
-                        .map(Message::Settings),
-                    MenuSize::Medium,
-                    *button_ui_ref,
-                ),
-                Some((MenuType::MediaPlayer, button_ui_ref)) => self.menu_wrapper(
-                    id,
-                    self.media_player
-                        .menu_view(&self.theme)
-                        .map(Message::MediaPlayer),
-                    MenuSize::Large,
-                    *button_ui_ref,
-                ),
-                Some((MenuType::SystemInfo, button_ui_ref)) => self.menu_wrapper(
-                    id,
-                    self.system_info
-                        .menu_view(&self.theme)
-                        .map(Message::SystemInfo),
-                    MenuSize::Medium,
-                    *button_ui_ref,
-                ),
+            }
+            Some(HasOutput::Menu(None)) => Row::new().into(),
+            #[cfg(feature = "notifications")]
+            None if self.notifications.is_popup_surface(id) => self
+                .notifications
+                .popup_view(&self.theme)
+                .map(Message::Notifications),
+            None => match self
+                .pinned_popups
+                .menu_type(id)
+                .and_then(|menu_type| self.menu_content(id, menu_type))
+            {
+                Some((content, menu_size)) => self.menu_panel(content, menu_size).into(),
                 None => Row::new().into(),
             },
-            None => Row::new().into(),
         }
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             Subscription::batch(self.modules_subscriptions(&self.general_config.modules.left)),
             Subscription::batch(self.modules_subscriptions(&self.general_config.modules.center)),
             Subscription::batch(self.modules_subscriptions(&self.general_config.modules.right)),
             config::subscription(&self.config_path),
-            listen_with(move |evt, _, _| match evt {
+            self.hooks.subscription().map(Message::Hooks),
+            self.scheduler.subscription().map(Message::Scheduler),
+            self.zen_mode.subscription().map(Message::ZenMode),
+            self.privacy_mode.subscription().map(Message::PrivacyMode),
+            self.battery_saver.subscription().map(Message::BatterySaver),
+            self.animations.subscription().map(Message::Animations),
+            self.redraw
+                .subscription(&self.redraw_intervals())
+                .map(Message::Redraw),
+            ipc::subscription().map(Message::Ipc),
+            listen_with(move |evt, status, id| match evt {
                 iced::Event::PlatformSpecific(iced::event::PlatformSpecific::Wayland(
                     WaylandEvent::Output(event, wl_output),
                 )) => {
                     debug!("Wayland event: {event:?}");
                     Some(Message::OutputEvent((event, wl_output)))
                 }
-                iced::Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
+                // the widgets handle the release first, dropping the dragged window
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::WindowDragEnded)
+                }
+                // scrolling on the workspaces with a modifier moves the focused window
+                iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => Some(
+                    Message::Workspaces(modules::workspaces::Message::ModifiersChanged(modifiers)),
+                ),
+                // the releases aren't reported once the keyboard or the pointer is gone
+                iced::Event::Window(window::Event::Unfocused)
+                | iced::Event::Mouse(mouse::Event::CursorLeft) => Some(Message::Workspaces(
+                    modules::workspaces::Message::ModifiersChanged(keyboard::Modifiers::default()),
+                )),
+                iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
                     debug!("Keyboard event received: {key:?}");
-                    if matches!(key, keyboard::Key::Named(keyboard::key::Named::Escape)) {
-                        debug!("ESC key pressed, closing all menus");
-                        Some(Message::CloseAllMenus)
-                    } else {
-                        None
+                    match key {
+                        keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                            debug!("ESC key pressed, closing all menus");
+                            Some(Message::CloseAllMenus)
+                        }
+                        // the keys used by the focused widgets, like a text input, are captured
+                        keyboard::Key::Named(
+                            named @ (keyboard::key::Named::ArrowLeft
+                            | keyboard::key::Named::ArrowRight
+                            | keyboard::key::Named::Tab
+                            | keyboard::key::Named::Enter
+                            | keyboard::key::Named::Space),
+                        ) if status == iced::event::Status::Ignored => {
+                            Some(Message::BarKey(id, named, modifiers.shift()))
+                        }
+                        _ => None,
                     }
                 }
                 _ => None,
             }),
-        ])
+        ];
+        #[cfg(feature = "metrics")]
+        subscriptions.push(self.metrics.subscription().map(Message::Metrics));
+        #[cfg(feature = "wallpaper-colors")]
+        subscriptions.push(
+            self.wallpaper_colors
+                .subscription()
+                .map(Message::WallpaperColors),
+        );
+
+        Subscription::batch(subscriptions)
     }
 }
@@ -0,0 +1,143 @@
+use crate::{
+    config::BatterySaverConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        upower::{BatteryStatus, PowerProfile, PowerProfileCommand, UPowerService},
+    },
+    utils::launcher::execute_command,
+};
+use iced::{Subscription, Task};
+use log::info;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<UPowerService>),
+}
+
+/// Saves battery below a charge threshold, until the charger is plugged again
+pub struct BatterySaver {
+    config: BatterySaverConfig,
+    service: Option<UPowerService>,
+    active: bool,
+    /// power profile restored when the saver stops
+    previous_profile: Option<PowerProfile>,
+}
+
+impl BatterySaver {
+    pub fn new(config: BatterySaverConfig) -> Self {
+        Self {
+            config,
+            service: None,
+            active: false,
+            previous_profile: None,
+        }
+    }
+
+    pub fn reload(&mut self, config: BatterySaverConfig) -> Task<Message> {
+        self.config = config;
+
+        self.check()
+    }
+
+    /// The polling intervals of the modules are multiplied by this factor
+    pub fn polling_factor(&self) -> u32 {
+        if self.active {
+            self.config.polling_factor.max(1)
+        } else {
+            1
+        }
+    }
+
+    pub fn animations(&self) -> bool {
+        !(self.active && self.config.disable_animations)
+    }
+
+    fn start(&mut self) -> Task<Message> {
+        info!("Battery saver started");
+        self.active = true;
+
+        if let Some(cmd) = &self.config.start_cmd {
+            execute_command(cmd.clone());
+        }
+
+        match self.service.as_mut() {
+            Some(service)
+                if self.config.power_saver_profile
+                    && !matches!(
+                        service.power_profile,
+                        PowerProfile::PowerSaver | PowerProfile::Unknown
+                    ) =>
+            {
+                self.previous_profile = Some(service.power_profile);
+
+                service
+                    .command(PowerProfileCommand::Set(PowerProfile::PowerSaver))
+                    .map(Message::Event)
+            }
+            _ => Task::none(),
+        }
+    }
+
+    fn stop(&mut self) -> Task<Message> {
+        info!("Battery saver stopped");
+        self.active = false;
+
+        if let Some(cmd) = &self.config.stop_cmd {
+            execute_command(cmd.clone());
+        }
+
+        match (self.previous_profile.take(), self.service.as_mut()) {
+            // the profile changed by hand while saving is kept
+            (Some(profile), Some(service)) if service.power_profile == PowerProfile::PowerSaver => {
+                service
+                    .command(PowerProfileCommand::Set(profile))
+                    .map(Message::Event)
+            }
+            _ => Task::none(),
+        }
+    }
+
+    fn check(&mut self) -> Task<Message> {
+        let battery = self.service.as_ref().and_then(|s| s.system_battery);
+        let discharging =
+            battery.is_some_and(|b| matches!(b.status, BatteryStatus::Discharging(_)));
+        let low = discharging
+            && battery
+                .zip(self.config.threshold)
+                .is_some_and(|(b, threshold)| b.capacity <= threshold);
+
+        if !self.active && low {
+            self.start()
+        } else if self.active && (!discharging || self.config.threshold.is_none()) {
+            self.stop()
+        } else {
+            Task::none()
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+        }
+
+        self.check()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.config.threshold.is_some() || self.active {
+            UPowerService::subscribe().map(Message::Event)
+        } else {
+            Subscription::none()
+        }
+    }
+}
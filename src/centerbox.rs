@@ -1,4 +1,5 @@
 //! Distribute content horizontally.
+use crate::position_button::ButtonUIRef;
 use iced::advanced::layout::{self, Layout, Limits, Node};
 use iced::advanced::overlay;
 use iced::advanced::renderer;
@@ -17,6 +18,7 @@ pub struct Centerbox<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer
     height: Length,
     align_items: Alignment,
     children: [Element<'a, Message, Theme, Renderer>; 3],
+    on_right_press: Option<Box<dyn Fn(ButtonUIRef) -> Message + 'a>>,
 }
 
 impl<'a, Message, Theme, Renderer> Centerbox<'a, Message, Theme, Renderer>
@@ -32,6 +34,7 @@ where
             height: Length::Shrink,
             align_items: Alignment::Start,
             children,
+            on_right_press: None,
         }
     }
 
@@ -68,6 +71,16 @@ where
         self.align_items = align;
         self
     }
+
+    /// Sets the message produced by a right click on the empty space
+    /// around the contents, the position is the one of the cursor.
+    pub fn on_right_press_with_position(
+        mut self,
+        on_right_press: impl Fn(ButtonUIRef) -> Message + 'a,
+    ) -> Self {
+        self.on_right_press = Some(Box::new(on_right_press));
+        self
+    }
 }
 
 impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
@@ -223,7 +236,8 @@ where
         shell: &mut Shell<'_, Message>,
         viewport: &Rectangle,
     ) -> event::Status {
-        self.children
+        let status = self
+            .children
             .iter_mut()
             .zip(&mut tree.children)
             .zip(layout.children())
@@ -239,7 +253,25 @@ where
                     viewport,
                 )
             })
-            .fold(event::Status::Ignored, event::Status::merge)
+            .fold(event::Status::Ignored, event::Status::merge);
+
+        if status == event::Status::Ignored
+            && let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) = event
+            && let Some(on_right_press) = self.on_right_press.as_ref()
+            && let Some(position) = cursor.position_over(layout.bounds())
+            && !layout
+                .children()
+                .any(|child| child.bounds().contains(position))
+        {
+            shell.publish(on_right_press(ButtonUIRef {
+                position,
+                viewport: (viewport.width, viewport.height),
+            }));
+
+            return event::Status::Captured;
+        }
+
+        status
     }
 
     fn mouse_interaction(
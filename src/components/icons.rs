@@ -61,6 +61,7 @@ pub enum StaticIcon {
     Vpn,
     Bluetooth,
     BluetoothConnected,
+    BluetoothOff,
     PowerSaver,
     Balanced,
     Performance,
@@ -113,6 +114,44 @@ pub enum StaticIcon {
     GamepadBatteryAlert,
     GamepadBatteryCharging,
     Remove,
+    Rocket,
+    Note,
+    Edit,
+    CheckboxBlank,
+    CheckboxChecked,
+    QrCode,
+    QrCodeScan,
+    ShieldKey,
+    Key,
+    Account,
+    LockOpen,
+    Usb,
+    Alert,
+    TrashCan,
+    ChartDonut,
+    Backup,
+    UpChevron,
+    DownChevron,
+    Cellular0,
+    Cellular1,
+    Cellular2,
+    Cellular3,
+    CellularOff,
+    Sms,
+    ClockCheck,
+    ClockAlert,
+    LeftChevron,
+    Cast,
+    Cellphone,
+    Tablet,
+    Gpu,
+    MonitorMultiple,
+    ChartLine,
+    Bell,
+    BellOff,
+    BellBadge,
+    Coffee,
+    CoffeeOutline,
 }
 
 impl StaticIcon {
@@ -161,6 +200,7 @@ impl StaticIcon {
             StaticIcon::Vpn => "\u{f0582}",
             StaticIcon::Bluetooth => "\u{f00af}",
             StaticIcon::BluetoothConnected => "\u{f00b1}",
+            StaticIcon::BluetoothOff => "\u{f00b2}",
             StaticIcon::PowerSaver => "\u{f0f86}",
             StaticIcon::Balanced => "\u{f0f85}",
             StaticIcon::Performance => "\u{f04c5}",
@@ -213,6 +253,44 @@ impl StaticIcon {
             StaticIcon::GamepadBatteryAlert => "\u{f074b}",
             StaticIcon::GamepadBatteryCharging => "\u{f0a22}",
             StaticIcon::Remove => "\u{f0377}",
+            StaticIcon::Rocket => "\u{f0463}",
+            StaticIcon::Note => "\u{f039e}",
+            StaticIcon::Edit => "\u{f03eb}",
+            StaticIcon::CheckboxBlank => "\u{f0131}",
+            StaticIcon::CheckboxChecked => "\u{f0135}",
+            StaticIcon::QrCode => "\u{f0432}",
+            StaticIcon::QrCodeScan => "\u{f0433}",
+            StaticIcon::ShieldKey => "\u{f0bc4}",
+            StaticIcon::Key => "\u{f030b}",
+            StaticIcon::Account => "\u{f0004}",
+            StaticIcon::LockOpen => "\u{f0fc6}",
+            StaticIcon::Usb => "\u{f0553}",
+            StaticIcon::Alert => "\u{f0026}",
+            StaticIcon::TrashCan => "\u{f0a79}",
+            StaticIcon::ChartDonut => "\u{f07af}",
+            StaticIcon::Backup => "\u{f006f}",
+            StaticIcon::UpChevron => "\u{f0143}",
+            StaticIcon::DownChevron => "\u{f0140}",
+            StaticIcon::Cellular0 => "\u{f08bf}",
+            StaticIcon::Cellular1 => "\u{f08bc}",
+            StaticIcon::Cellular2 => "\u{f08bd}",
+            StaticIcon::Cellular3 => "\u{f08be}",
+            StaticIcon::CellularOff => "\u{f0783}",
+            StaticIcon::Sms => "\u{f0369}",
+            StaticIcon::ClockCheck => "\u{f0fa9}",
+            StaticIcon::ClockAlert => "\u{f05ce}",
+            StaticIcon::LeftChevron => "\u{f0141}",
+            StaticIcon::Cast => "\u{f0118}",
+            StaticIcon::Cellphone => "\u{f011c}",
+            StaticIcon::Tablet => "\u{f04f6}",
+            StaticIcon::Gpu => "\u{f08ae}",
+            StaticIcon::MonitorMultiple => "\u{f037a}",
+            StaticIcon::ChartLine => "\u{f012a}",
+            StaticIcon::Bell => "\u{f009a}",
+            StaticIcon::BellOff => "\u{f009b}",
+            StaticIcon::BellBadge => "\u{f116b}",
+            StaticIcon::Coffee => "\u{f0176}",
+            StaticIcon::CoffeeOutline => "\u{f06ca}",
         }
     }
 
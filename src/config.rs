@@ -1,5 +1,6 @@
 use crate::app::Message;
 use crate::services::upower::PeripheralDeviceKind;
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
 use hex_color::HexColor;
 use iced::futures::StreamExt;
 use iced::{Color, Subscription, futures::SinkExt, stream::channel, theme::palette};
@@ -8,19 +9,29 @@ use inotify::Inotify;
 use inotify::WatchMask;
 use log::{debug, error, info, warn};
 use regex::Regex;
-use serde::{Deserialize, Deserializer, de::Visitor};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Visitor};
 use serde_with::DisplayFromStr;
 use serde_with::serde_as;
 use std::path::PathBuf;
 use std::time::Duration;
 use std::{
-    any::TypeId, collections::HashMap, error::Error, fs::File, io::Read, ops::Deref, path::Path,
+    any::TypeId, borrow::Cow, collections::HashMap, error::Error, fs::File, io::Read, ops::Deref,
+    path::Path,
 };
 use tokio::time::sleep;
 
 pub const DEFAULT_CONFIG_FILE_PATH: &str = "~/.config/ashell/config.toml";
 
-#[derive(Deserialize, Clone, Debug)]
+/// Schema of the options parsed from a string
+fn string_schema(description: &str) -> Schema {
+    json_schema!({
+        "type": "string",
+        "description": description,
+    })
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct Config {
     pub log_level: String,
@@ -30,7 +41,11 @@ pub struct Config {
     pub app_launcher_cmd: Option<String>,
     #[serde(rename = "CustomModule")]
     pub custom_modules: Vec<CustomModuleDef>,
+    #[serde(rename = "Autostart")]
+    pub autostart: Vec<AutostartDef>,
     pub clipboard_cmd: Option<String>,
+    /// command opening the configuration file, `xdg-open` when not set
+    pub editor_cmd: Option<String>,
     pub updates: Option<UpdatesModuleConfig>,
     pub workspaces: WorkspacesModuleConfig,
     pub window_title: WindowTitleConfig,
@@ -40,7 +55,43 @@ pub struct Config {
     pub appearance: Appearance,
     pub media_player: MediaPlayerModuleConfig,
     pub keyboard_layout: KeyboardLayoutModuleConfig,
+    pub notes: NotesModuleConfig,
+    pub qr_code: QrCodeModuleConfig,
+    pub totp: TotpModuleConfig,
+    pub passwords: PasswordsModuleConfig,
+    pub encrypted_volumes: EncryptedVolumesModuleConfig,
+    pub backup: BackupModuleConfig,
+    pub modem: ModemModuleConfig,
+    pub gpu: GpuModuleConfig,
+    pub dashboard: DashboardModuleConfig,
+    pub battery: BatteryModuleConfig,
+    pub audio: AudioModuleConfig,
+    pub microphone: MicrophoneModuleConfig,
+    pub network: NetworkModuleConfig,
+    pub bluetooth: BluetoothModuleConfig,
+    pub notifications: NotificationsModuleConfig,
+    pub idle_inhibitor: IdleInhibitorModuleConfig,
+    pub power_profiles: PowerProfilesModuleConfig,
+    pub metrics: MetricsConfig,
+    pub sounds: SoundsConfig,
+    pub hooks: HooksConfig,
+    pub scheduler: SchedulerConfig,
+    pub zen_mode: ZenModeConfig,
+    pub privacy_mode: PrivacyModeConfig,
+    pub battery_saver: BatterySaverConfig,
+    pub animations: AnimationsConfig,
+    pub wallpaper_colors: WallpaperColorsConfig,
+    /// position of the pinned popups by module name
+    pub pinned_popups: HashMap<String, PinnedPopupConfig>,
     pub enable_esc_key: bool,
+    pub enable_context_menu: bool,
+    /// the bar takes the keyboard when clicked, to switch the workspaces with the arrow keys
+    pub enable_keyboard_navigation: bool,
+    /// reserve the space of the bars with the Hyprland monitor rules instead of the exclusive zone
+    pub hyprland_reserved_area: bool,
+    /// sections merged over the rest of the file when their conditions match,
+    /// next to the settings they override
+    pub when: Vec<ConfigProfile>,
 }
 
 impl Default for Config {
@@ -52,6 +103,7 @@ impl Default for Config {
             modules: Modules::default(),
             app_launcher_cmd: None,
             clipboard_cmd: None,
+            editor_cmd: None,
             updates: None,
             workspaces: WorkspacesModuleConfig::default(),
             window_title: WindowTitleConfig::default(),
@@ -61,19 +113,66 @@ impl Default for Config {
             appearance: Appearance::default(),
             media_player: MediaPlayerModuleConfig::default(),
             keyboard_layout: KeyboardLayoutModuleConfig::default(),
+            notes: NotesModuleConfig::default(),
+            qr_code: QrCodeModuleConfig::default(),
+            totp: TotpModuleConfig::default(),
+            passwords: PasswordsModuleConfig::default(),
+            encrypted_volumes: EncryptedVolumesModuleConfig::default(),
+            backup: BackupModuleConfig::default(),
+            modem: ModemModuleConfig::default(),
+            gpu: GpuModuleConfig::default(),
+            dashboard: DashboardModuleConfig::default(),
+            battery: BatteryModuleConfig::default(),
+            audio: AudioModuleConfig::default(),
+            microphone: MicrophoneModuleConfig::default(),
+            network: NetworkModuleConfig::default(),
+            bluetooth: BluetoothModuleConfig::default(),
+            notifications: NotificationsModuleConfig::default(),
+            idle_inhibitor: IdleInhibitorModuleConfig::default(),
+            power_profiles: PowerProfilesModuleConfig::default(),
+            metrics: MetricsConfig::default(),
+            sounds: SoundsConfig::default(),
+            hooks: HooksConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            zen_mode: ZenModeConfig::default(),
+            privacy_mode: PrivacyModeConfig::default(),
+            battery_saver: BatterySaverConfig::default(),
+            animations: AnimationsConfig::default(),
+            wallpaper_colors: WallpaperColorsConfig::default(),
+            pinned_popups: HashMap::new(),
             custom_modules: vec![],
+            autostart: vec![],
             enable_esc_key: false,
+            enable_context_menu: true,
+            enable_keyboard_navigation: false,
+            hyprland_reserved_area: false,
+            when: Vec::new(),
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+/// The conditions of a `[[when]]` section, they are applied before the rest of the file
+/// is decoded
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug)]
+#[serde(default)]
+pub struct ConfigProfile {
+    /// the name of the machine
+    pub hostname: Option<String>,
+    /// the desktop of the session, as in `XDG_CURRENT_DESKTOP`, e.g. `Hyprland` or `niri`
+    pub session: Option<String>,
+    /// the type of the session, as in `XDG_SESSION_TYPE`, e.g. `wayland` or `x11`
+    pub session_type: Option<String>,
+    /// environment variables and their expected values
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 pub struct UpdatesModuleConfig {
     pub check_cmd: String,
     pub update_cmd: String,
 }
 
-#[derive(Deserialize, Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
 pub enum WorkspaceVisibilityMode {
     #[default]
     All,
@@ -81,28 +180,145 @@ pub enum WorkspaceVisibilityMode {
     MonitorSpecificExclusive,
 }
 
-#[derive(Deserialize, Clone, Default, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum WorkspaceStyle {
+    /// the active workspace is the widest
+    #[default]
+    Pills,
+    /// the buttons fit their labels whatever the state
+    Numbers,
+    /// dots without labels, growing with the state
+    Dots,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+pub enum WorkspaceAction {
+    /// changes to the workspace, toggles the special ones
+    ChangeWorkspace,
+    ToggleSpecialWorkspace,
+    MoveFocusedWindow,
+    /// `{id}` and `{name}` are replaced with the workspace id and name
+    Command(String),
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScrollModifier {
+    Shift,
+    Ctrl,
+    Alt,
+    Super,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct WorkspaceBindings {
+    pub left_click: Option<WorkspaceAction>,
+    pub middle_click: Option<WorkspaceAction>,
+    pub right_click: Option<WorkspaceAction>,
+    pub scroll_left: Option<WorkspaceAction>,
+    pub scroll_right: Option<WorkspaceAction>,
+}
+
+impl Default for WorkspaceBindings {
+    fn default() -> Self {
+        Self {
+            left_click: Some(WorkspaceAction::ChangeWorkspace),
+            middle_click: None,
+            right_click: None,
+            scroll_left: None,
+            scroll_right: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct SpecialWorkspaceConfig {
+    /// name of the special workspace, without the `special:` prefix
+    pub name: String,
+    pub label: Option<String>,
+    pub icon: Option<String>,
+    /// shown even when the workspace doesn't exist, a click creates it
+    #[serde(default)]
+    pub persistent: bool,
+    /// the bar of the output while the workspace is shown on it
+    #[serde(default)]
+    pub bar: SpecialWorkspaceBar,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum SpecialWorkspaceBar {
+    #[default]
+    Show,
+    /// the bar gives its space back to the windows
+    Hide,
+    /// the bar keeps its modules without a background
+    Transparent,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug)]
 #[serde(default)]
 pub struct WorkspacesModuleConfig {
     pub visibility_mode: WorkspaceVisibilityMode,
+    pub style: WorkspaceStyle,
     pub enable_workspace_filling: bool,
     pub max_workspaces: Option<u32>,
+    /// workspaces always shown on a monitor, by monitor name
+    pub persistent_workspaces: HashMap<String, Vec<i32>>,
     pub workspace_names: Vec<String>,
     pub enable_virtual_desktops: bool,
+    /// show the icons of the windows in each workspace
+    pub show_window_icons: bool,
+    /// scrolling past the last workspace goes back to the first one
+    pub scroll_wraparound: bool,
+    /// scrolling jumps over the workspaces without windows
+    pub scroll_skip_empty: bool,
+    /// scrolling with the modifier held moves the focused window along
+    pub scroll_move_window_modifier: Option<ScrollModifier>,
+    /// actions of the mouse buttons and of the horizontal scroll on a workspace
+    pub bindings: WorkspaceBindings,
+    /// label of the workspaces with the `{icon}`, `{name}`, `{id}` and `{windows}` placeholders
+    pub format: Option<String>,
+    /// icons of the workspaces by id or name
+    pub icons: HashMap<String, String>,
+    /// commands run when switching to a workspace from the bar, by id or name
+    pub commands: HashMap<String, String>,
+    /// hide the workspaces without windows, except the shown ones
+    pub hide_empty: bool,
+    /// hide the special workspaces and the scratchpads
+    pub hide_special: bool,
+    /// labels and icons of the special workspaces, in the order they're shown
+    pub special_workspaces: Vec<SpecialWorkspaceConfig>,
+    /// separate the workspaces of each monitor, with the `All` visibility mode
+    pub group_by_monitor: bool,
+    /// show the monitor name before its group
+    pub show_monitor_names: bool,
+    /// named instances, shown with `Workspaces:<name>`, they inherit the fields they don't set
+    pub instances: HashMap<String, WorkspacesModuleConfig>,
 }
 
-#[derive(Deserialize, Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
 pub enum WindowTitleMode {
     #[default]
     Title,
     Class,
 }
 
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct WindowTitleConfig {
     pub mode: WindowTitleMode,
     pub truncate_title_after_length: u32,
+    /// list all the windows of the active workspace instead of the focused one
+    pub taskbar: bool,
+    /// show the application icon before the title
+    pub show_icon: bool,
+    pub icon_size: u16,
+    /// right click on the title opens the window rules popup, Hyprland only
+    pub window_rules: bool,
+    /// minimum time between two updates of the title, in milliseconds
+    pub min_update_interval: u64,
+    /// the windows with a matching title or class are never shown
+    pub blacklist: Vec<RegexCfg>,
 }
 
 impl Default for WindowTitleConfig {
@@ -110,23 +326,665 @@ impl Default for WindowTitleConfig {
         Self {
             mode: Default::default(),
             truncate_title_after_length: 150,
+            taskbar: false,
+            show_icon: false,
+            icon_size: 16,
+            window_rules: false,
+            min_update_interval: 100,
+            blacklist: Vec::new(),
         }
     }
 }
 
-#[derive(Deserialize, Clone, Default, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug)]
 #[serde(default)]
 pub struct KeyboardLayoutModuleConfig {
     pub labels: HashMap<String, String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotesModuleConfig {
+    pub path: String,
+}
+
+impl Default for NotesModuleConfig {
+    fn default() -> Self {
+        Self {
+            path: "~/.local/share/ashell/notes.md".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct QrCodeModuleConfig {
+    pub paste_cmd: String,
+    pub copy_cmd: String,
+    pub scan_cmd: String,
+}
+
+impl Default for QrCodeModuleConfig {
+    fn default() -> Self {
+        Self {
+            paste_cmd: "wl-paste --no-newline".to_string(),
+            copy_cmd: "wl-copy".to_string(),
+            scan_cmd: "grim -g \"$(slurp)\" - | zbarimg -q --raw -".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct TotpAccount {
+    pub name: String,
+    /// command printing the base32 secret or an `otpauth://` uri
+    pub secret_cmd: String,
+    #[serde(default = "default_totp_digits")]
+    pub digits: u32,
+    #[serde(default = "default_totp_period")]
+    pub period: u64,
+}
+
+fn default_totp_digits() -> u32 {
+    6
+}
+
+fn default_totp_period() -> u64 {
+    30
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct TotpModuleConfig {
+    pub copy_cmd: String,
+    pub paste_cmd: String,
+    pub clear_cmd: String,
+    /// seconds before the copied code is removed from the clipboard
+    pub clear_after: u64,
+    #[serde(rename = "Account")]
+    pub accounts: Vec<TotpAccount>,
+}
+
+impl Default for TotpModuleConfig {
+    fn default() -> Self {
+        Self {
+            copy_cmd: "wl-copy".to_string(),
+            paste_cmd: "wl-paste --no-newline".to_string(),
+            clear_cmd: "wl-copy --clear".to_string(),
+            clear_after: 30,
+            accounts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum PasswordsBackend {
+    #[default]
+    Pass,
+    SecretService,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct PasswordsModuleConfig {
+    pub backend: PasswordsBackend,
+    /// commands used by the `Pass` backend, `{entry}` is replaced by the entry name
+    pub list_cmd: String,
+    pub password_cmd: String,
+    pub username_cmd: String,
+    pub copy_cmd: String,
+    pub paste_cmd: String,
+    pub clear_cmd: String,
+    /// seconds before the copied secret is removed from the clipboard
+    pub clear_after: u64,
+}
+
+impl Default for PasswordsModuleConfig {
+    fn default() -> Self {
+        Self {
+            backend: PasswordsBackend::default(),
+            list_cmd: "cd \"${PASSWORD_STORE_DIR:-$HOME/.password-store}\" && find -L . -name '*.gpg' | sed -e 's|^\\./||' -e 's|\\.gpg$||' | sort".to_string(),
+            password_cmd: "pass show {entry} | head -n 1".to_string(),
+            username_cmd: "pass show {entry} | sed -n -E 's/^(login|user|username): *//Ip' | head -n 1".to_string(),
+            copy_cmd: "wl-copy".to_string(),
+            paste_cmd: "wl-paste --no-newline".to_string(),
+            clear_cmd: "wl-copy --clear".to_string(),
+            clear_after: 45,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct EncryptedVolumesModuleConfig {
+    /// lock every volume when the session becomes idle
+    pub lock_on_idle: bool,
+    /// lock every volume when the session is locked
+    pub lock_on_session_lock: bool,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub enum BackupBackend {
+    #[default]
+    StatusFile,
+    Borg,
+    Restic,
+    Timeshift,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct BackupModuleConfig {
+    pub backend: BackupBackend,
+    /// borg or restic repository, the environment of the backend is used when missing
+    pub repository: Option<String>,
+    /// file written after each successful backup, used by the `StatusFile` backend
+    pub status_file: Option<String>,
+    /// directory containing the timeshift snapshots
+    pub snapshots_dir: String,
+    /// hours after which the last backup is overdue
+    pub max_age: u64,
+    /// seconds between two checks of the last backup
+    pub check_interval: u64,
+    pub backup_cmd: Option<String>,
+}
+
+impl Default for BackupModuleConfig {
+    fn default() -> Self {
+        Self {
+            backend: BackupBackend::default(),
+            repository: None,
+            status_file: None,
+            snapshots_dir: "/timeshift/snapshots".to_string(),
+            max_age: 24,
+            check_interval: 600,
+            backup_cmd: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct ModemModuleConfig {
+    /// access point used to connect the mobile data, the existing bearer is used when missing
+    pub apn: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct GpuModuleConfig {
+    /// commands offered to apply a new GPU mode
+    pub reboot_cmd: String,
+    pub logout_cmd: String,
+}
+
+impl Default for GpuModuleConfig {
+    fn default() -> Self {
+        Self {
+            reboot_cmd: "systemctl reboot".to_string(),
+            logout_cmd: "loginctl kill-user $(whoami)".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct DashboardModuleConfig {
+    /// sensor of the temperature chart, like the one of the system info module
+    pub temperature_sensor: String,
+}
+
+impl Default for DashboardModuleConfig {
+    fn default() -> Self {
+        Self {
+            temperature_sensor: "acpitz temp1".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct BatteryModuleConfig {
+    pub format: BatteryFormat,
+    /// charge, in percent, under which the battery is shown as low
+    pub warn_threshold: i64,
+    pub alert_threshold: i64,
+    /// commands run once when the discharging battery goes past a threshold, e.g. `notify-send`
+    pub warn_cmd: Option<String>,
+    pub alert_cmd: Option<String>,
+}
+
+impl Default for BatteryModuleConfig {
+    fn default() -> Self {
+        Self {
+            format: BatteryFormat::default(),
+            warn_threshold: 20,
+            alert_threshold: 10,
+            warn_cmd: None,
+            alert_cmd: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct AudioModuleConfig {
+    /// the volume is shown next to the icon
+    pub show_volume: bool,
+    /// change of the volume, in percent, for a scroll step on the module
+    pub scroll_step: u8,
+}
+
+impl Default for AudioModuleConfig {
+    fn default() -> Self {
+        Self {
+            show_volume: true,
+            scroll_step: 5,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct MicrophoneModuleConfig {
+    /// the input volume is shown next to the icon
+    pub show_volume: bool,
+    /// the module is only shown while an application records
+    pub hide_when_idle: bool,
+    /// change of the volume, in percent, for a scroll step on the module
+    pub scroll_step: u8,
+}
+
+impl Default for MicrophoneModuleConfig {
+    fn default() -> Self {
+        Self {
+            show_volume: true,
+            hide_when_idle: false,
+            scroll_step: 5,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct NetworkModuleConfig {
+    /// the name of the Wi-Fi network, or `Wired`, is shown next to the icon
+    pub show_ssid: bool,
+    /// the signal strength of the Wi-Fi network is shown in percent
+    pub show_strength: bool,
+}
+
+impl Default for NetworkModuleConfig {
+    fn default() -> Self {
+        Self {
+            show_ssid: true,
+            show_strength: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct BluetoothModuleConfig {
+    /// the connected devices are shown instead of the adapter icon
+    pub show_devices: bool,
+    /// the battery level of the connected devices is shown when they report it
+    pub show_battery: bool,
+}
+
+impl Default for BluetoothModuleConfig {
+    fn default() -> Self {
+        Self {
+            show_devices: true,
+            show_battery: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotificationsModuleConfig {
+    /// milliseconds the popups stay on screen when the notification doesn't say
+    pub timeout: u64,
+    /// corner of the screen where the popups are shown
+    pub position: PopupAnchor,
+    /// popups shown at the same time, the oldest ones are hidden first
+    pub max_popups: usize,
+    /// notifications kept in the notification center
+    pub history_size: usize,
+    /// notifications of an application kept in the notification center, by application name
+    pub app_history_size: HashMap<String, usize>,
+}
+
+impl Default for NotificationsModuleConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 5000,
+            position: PopupAnchor::TopRight,
+            max_popups: 3,
+            history_size: 50,
+            app_history_size: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct IdleInhibitorModuleConfig {
+    /// minutes before the inhibitor is released, it stays on until toggled off when not set
+    pub duration: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct PowerProfilesModuleConfig {
+    /// shows the name of the profile next to its icon
+    pub show_label: bool,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub on_lock: Option<String>,
+    pub on_unlock: Option<String>,
+    /// the logind idle hint, set by the idle daemon
+    pub on_idle_enter: Option<String>,
+    pub on_idle_exit: Option<String>,
+    pub on_output_added: Option<String>,
+    pub on_output_removed: Option<String>,
+    pub on_lid_close: Option<String>,
+    pub on_lid_open: Option<String>,
+    pub on_tablet_mode_enter: Option<String>,
+    pub on_tablet_mode_exit: Option<String>,
+    pub on_power_button: Option<String>,
+    pub on_dnd_enable: Option<String>,
+    pub on_dnd_disable: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct ZenModeConfig {
+    /// modules still shown in zen mode
+    pub modules: Vec<ModuleName>,
+    /// milliseconds
+    pub fade_duration: u64,
+}
+
+impl Default for ZenModeConfig {
+    fn default() -> Self {
+        Self {
+            modules: vec![
+                #[cfg(feature = "clock")]
+                ModuleName::Clock,
+            ],
+            fade_duration: 200,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct PrivacyModeConfig {
+    /// modules blanked in privacy mode
+    pub modules: Vec<ModuleName>,
+    pub placeholder: String,
+    /// enable the privacy mode while the screen is shared
+    pub on_screenshare: bool,
+}
+
+impl Default for PrivacyModeConfig {
+    fn default() -> Self {
+        Self {
+            modules: vec![
+                ModuleName::WindowTitle,
+                #[cfg(feature = "notes")]
+                ModuleName::Notes,
+            ],
+            placeholder: "•••".to_string(),
+            on_screenshare: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct BatterySaverConfig {
+    /// battery percentage at which the saver starts, disabled when not set
+    pub threshold: Option<i64>,
+    /// switch to the power-saver profile while saving
+    pub power_saver_profile: bool,
+    /// the polling intervals are multiplied by this factor while saving
+    pub polling_factor: u32,
+    pub disable_animations: bool,
+    pub start_cmd: Option<String>,
+    pub stop_cmd: Option<String>,
+}
+
+impl Default for BatterySaverConfig {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            power_saver_profile: true,
+            polling_factor: 3,
+            disable_animations: true,
+            start_cmd: None,
+            stop_cmd: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct AnimationsConfig {
+    /// turns the animations off, whatever the power state
+    pub reduced_motion: bool,
+    /// turns the animations off while the battery is discharging
+    pub disable_on_battery: bool,
+    /// turns the animations off while the power-saver profile is active
+    pub disable_on_power_saver: bool,
+}
+
+impl Default for AnimationsConfig {
+    fn default() -> Self {
+        Self {
+            reduced_motion: false,
+            disable_on_battery: true,
+            disable_on_power_saver: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct WallpaperColorsConfig {
+    /// image path by output name, `*` for all the outputs
+    pub images: HashMap<String, String>,
+    /// prints a `<output> <path>` line for each output
+    pub query_cmd: Option<String>,
+    /// seconds between the checks for a new wallpaper
+    pub interval: u64,
+}
+
+impl Default for WallpaperColorsConfig {
+    fn default() -> Self {
+        Self {
+            images: HashMap::new(),
+            query_cmd: None,
+            interval: 10,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum PopupAnchor {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Position of a pinned popup on the desktop
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct PinnedPopupConfig {
+    pub anchor: PopupAnchor,
+    /// distance from the anchored edges
+    pub margin: i32,
+}
+
+impl Default for PinnedPopupConfig {
+    fn default() -> Self {
+        Self {
+            anchor: PopupAnchor::default(),
+            margin: 16,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScheduleTime {
+    Sunrise,
+    Sunset,
+    At(NaiveTime),
+}
+
+impl<'de> Deserialize<'de> for ScheduleTime {
+    fn deserialize<D>(deserializer: D) -> Result<ScheduleTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ScheduleTimeVisitor;
+        impl Visitor<'_> for ScheduleTimeVisitor {
+            type Value = ScheduleTime;
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("\"sunrise\", \"sunset\" or a time in the HH:MM format")
+            }
+            fn visit_str<E>(self, value: &str) -> Result<ScheduleTime, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "sunrise" => Ok(ScheduleTime::Sunrise),
+                    "sunset" => Ok(ScheduleTime::Sunset),
+                    other => NaiveTime::parse_from_str(other, "%H:%M")
+                        .map(ScheduleTime::At)
+                        .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(other), &self)),
+                }
+            }
+        }
+        deserializer.deserialize_str(ScheduleTimeVisitor)
+    }
+}
+
+impl Serialize for ScheduleTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ScheduleTime::Sunrise => serializer.serialize_str("sunrise"),
+            ScheduleTime::Sunset => serializer.serialize_str("sunset"),
+            ScheduleTime::At(time) => serializer.collect_str(&time.format("%H:%M")),
+        }
+    }
+}
+
+impl JsonSchema for ScheduleTime {
+    fn schema_name() -> Cow<'static, str> {
+        "ScheduleTime".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema("\"sunrise\", \"sunset\" or a time in the HH:MM format")
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct ScheduleEvent {
+    pub at: ScheduleTime,
+    /// minutes added to the time of the event, can be negative
+    #[serde(default)]
+    pub offset: i64,
+    pub command: Option<String>,
+    pub appearance: Option<String>,
+    /// the color scheme preferred by the apps, set with gsettings
+    pub color_scheme: Option<ColorScheme>,
+    /// the appearance is taken from the colors generated by pywal once the command is done
+    #[serde(default)]
+    pub pywal: bool,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct SoundsConfig {
+    /// name of the freedesktop sound theme
+    pub theme: String,
+    /// a monitor is connected or disconnected
+    pub device_plug: bool,
+    /// the battery goes under the alert threshold of the battery module
+    pub battery_critical: bool,
+    pub workspace_switch: bool,
+    /// a notification shows a popup
+    pub notification: bool,
+}
+
+impl Default for SoundsConfig {
+    fn default() -> Self {
+        Self {
+            theme: "freedesktop".to_string(),
+            device_plug: false,
+            battery_critical: false,
+            workspace_switch: false,
+            notification: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// file written in the Prometheus text format, for the textfile collector of node_exporter
+    pub textfile: Option<String>,
+    /// seconds between two writes of the file
+    pub interval: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            textfile: None,
+            interval: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    #[serde(rename = "Event")]
+    pub events: Vec<ScheduleEvent>,
+    pub appearances: HashMap<String, Appearance>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct SystemInfoCpu {
     #[serde(default)]
     pub warn_threshold: u32,
     #[serde(default)]
     pub alert_threshold: u32,
+    /// shows the usage of each core instead of the global one
+    #[serde(default)]
+    pub per_core: bool,
 }
 
 impl Default for SystemInfoCpu {
@@ -134,11 +992,12 @@ impl Default for SystemInfoCpu {
         Self {
             warn_threshold: 60,
             alert_threshold: 80,
+            per_core: false,
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct SystemInfoMemory {
     pub warn_threshold: u32,
@@ -154,12 +1013,18 @@ impl Default for SystemInfoMemory {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct SystemInfoTemperature {
     pub warn_threshold: i32,
     pub alert_threshold: i32,
     pub sensor: String,
+    /// hwmon label of the GPU sensor, e.g. `amdgpu edge`
+    pub gpu_sensor: Option<String>,
+    /// show when the CPU is throttled by the temperature
+    pub throttle_alert: bool,
+    /// run when the throttling starts
+    pub throttle_cmd: Option<String>,
 }
 
 impl Default for SystemInfoTemperature {
@@ -168,15 +1033,28 @@ impl Default for SystemInfoTemperature {
             warn_threshold: 60,
             alert_threshold: 80,
             sensor: "acpitz temp1".to_string(),
+            gpu_sensor: None,
+            throttle_alert: true,
+            throttle_cmd: Some(
+                "notify-send -u critical 'CPU throttled' 'The CPU is too hot'".to_string(),
+            ),
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct SystemInfoDisk {
     pub warn_threshold: u32,
     pub alert_threshold: u32,
+    /// mount points checked for low free space
+    pub watched_mounts: Vec<String>,
+    /// free space, in GB, under which a watched mount is reported
+    pub min_free_space: u64,
+    /// trash size, in GB, over which the trash is reported
+    pub trash_max_size: Option<u64>,
+    pub empty_trash_cmd: String,
+    pub disk_usage_cmd: String,
 }
 
 impl Default for SystemInfoDisk {
@@ -184,26 +1062,36 @@ impl Default for SystemInfoDisk {
         Self {
             warn_threshold: 80,
             alert_threshold: 90,
+            watched_mounts: Vec::new(),
+            min_free_space: 10,
+            trash_max_size: None,
+            empty_trash_cmd: "gio trash --empty".to_string(),
+            disk_usage_cmd: "baobab".to_string(),
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 pub enum SystemInfoIndicator {
     Cpu,
     Memory,
     MemorySwap,
     Temperature,
+    GpuTemperature,
     Disk(String),
     IpAddress,
     DownloadSpeed,
     UploadSpeed,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct SystemInfoModuleConfig {
     pub indicators: Vec<SystemInfoIndicator>,
+    /// seconds between two updates
+    pub interval: u64,
+    /// processes listed in the menu, by CPU usage
+    pub top_processes: usize,
     pub cpu: SystemInfoCpu,
     pub memory: SystemInfoMemory,
     pub temperature: SystemInfoTemperature,
@@ -218,6 +1106,8 @@ impl Default for SystemInfoModuleConfig {
                 SystemInfoIndicator::Memory,
                 SystemInfoIndicator::Temperature,
             ],
+            interval: 5,
+            top_processes: 5,
             cpu: SystemInfoCpu::default(),
             memory: SystemInfoMemory::default(),
             temperature: SystemInfoTemperature::default(),
@@ -226,20 +1116,138 @@ impl Default for SystemInfoModuleConfig {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum FirstDayOfWeek {
+    #[default]
+    Monday,
+    Saturday,
+    Sunday,
+}
+
+impl FirstDayOfWeek {
+    pub fn weekday(self) -> Weekday {
+        match self {
+            FirstDayOfWeek::Monday => Weekday::Mon,
+            FirstDayOfWeek::Saturday => Weekday::Sat,
+            FirstDayOfWeek::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HolidayDate {
+    /// the same day every year
+    Yearly {
+        month: u32,
+        day: u32,
+    },
+    On(NaiveDate),
+}
+
+impl HolidayDate {
+    pub fn matches(self, date: NaiveDate) -> bool {
+        match self {
+            HolidayDate::Yearly { month, day } => date.month() == month && date.day() == day,
+            HolidayDate::On(on) => on == date,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for HolidayDate {
+    fn deserialize<D>(deserializer: D) -> Result<HolidayDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct HolidayDateVisitor;
+        impl Visitor<'_> for HolidayDateVisitor {
+            type Value = HolidayDate;
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a date in the YYYY-MM-DD or MM-DD format")
+            }
+            fn visit_str<E>(self, value: &str) -> Result<HolidayDate, E>
+            where
+                E: serde::de::Error,
+            {
+                let invalid = || E::invalid_value(serde::de::Unexpected::Str(value), &self);
+
+                match value.split('-').collect::<Vec<_>>().as_slice() {
+                    [month, day] => {
+                        let month = month.parse::<u32>().map_err(|_| invalid())?;
+                        let day = day.parse::<u32>().map_err(|_| invalid())?;
+
+                        // 2000 is a leap year, February 29 is valid
+                        NaiveDate::from_ymd_opt(2000, month, day)
+                            .map(|_| HolidayDate::Yearly { month, day })
+                            .ok_or_else(invalid)
+                    }
+                    _ => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .map(HolidayDate::On)
+                        .map_err(|_| invalid()),
+                }
+            }
+        }
+        deserializer.deserialize_str(HolidayDateVisitor)
+    }
+}
+
+impl Serialize for HolidayDate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            HolidayDate::Yearly { month, day } => {
+                serializer.collect_str(&format_args!("{month:02}-{day:02}"))
+            }
+            HolidayDate::On(date) => serializer.collect_str(&date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+impl JsonSchema for HolidayDate {
+    fn schema_name() -> Cow<'static, str> {
+        "HolidayDate".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema("a date in the YYYY-MM-DD or MM-DD format")
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct Holiday {
+    pub date: HolidayDate,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+#[serde(default)]
 pub struct ClockModuleConfig {
     pub format: String,
+    /// refresh the clock as soon as the system timezone is changed through timedated
+    pub follow_timezone: bool,
+    pub first_day_of_week: FirstDayOfWeek,
+    pub show_week_numbers: bool,
+    #[serde(rename = "Holiday")]
+    pub holidays: Vec<Holiday>,
+    /// other time zones shown in the popup, e.g. `America/New_York`
+    pub timezones: Vec<String>,
 }
 
 impl Default for ClockModuleConfig {
     fn default() -> Self {
         Self {
             format: "%a %d %b %R".to_string(),
+            follow_timezone: false,
+            first_day_of_week: FirstDayOfWeek::default(),
+            show_week_numbers: true,
+            holidays: Vec::new(),
+            timezones: Vec::new(),
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
 pub enum SettingsIndicator {
     IdleInhibitor,
     PowerProfile,
@@ -247,11 +1255,12 @@ pub enum SettingsIndicator {
     Network,
     Vpn,
     Bluetooth,
+    BluetoothDevices,
     Battery,
     PeripheralBattery,
 }
 
-#[derive(Deserialize, Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
 pub enum BatteryFormat {
     Icon,
     Percentage,
@@ -259,14 +1268,14 @@ pub enum BatteryFormat {
     IconAndPercentage,
 }
 
-#[derive(Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, PartialEq, Eq, Debug)]
 pub enum PeripheralIndicators {
     #[default]
     All,
     Specific(Vec<PeripheralDeviceKind>),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct SettingsModuleConfig {
     pub lock_cmd: Option<String>,
@@ -280,9 +1289,15 @@ pub struct SettingsModuleConfig {
     pub peripheral_battery_format: BatteryFormat,
     pub audio_sinks_more_cmd: Option<String>,
     pub audio_sources_more_cmd: Option<String>,
+    /// sinks cycled with a middle click on the audio indicator, in order
+    pub audio_sink_cycle: Vec<String>,
     pub wifi_more_cmd: Option<String>,
     pub vpn_more_cmd: Option<String>,
     pub bluetooth_more_cmd: Option<String>,
+    /// devices shown in the bar, matched by name or address
+    pub bluetooth_pinned_devices: Vec<String>,
+    /// connect the pinned devices when the adapter is powered on
+    pub bluetooth_auto_reconnect: bool,
     pub remove_airplane_btn: bool,
     pub remove_idle_btn: bool,
     pub indicators: Vec<SettingsIndicator>,
@@ -304,9 +1319,12 @@ impl Default for SettingsModuleConfig {
             peripheral_battery_format: BatteryFormat::Icon,
             audio_sinks_more_cmd: Default::default(),
             audio_sources_more_cmd: Default::default(),
+            audio_sink_cycle: Default::default(),
             wifi_more_cmd: Default::default(),
             vpn_more_cmd: Default::default(),
             bluetooth_more_cmd: Default::default(),
+            bluetooth_pinned_devices: Default::default(),
+            bluetooth_auto_reconnect: Default::default(),
             remove_airplane_btn: Default::default(),
             remove_idle_btn: Default::default(),
             indicators: vec![
@@ -314,6 +1332,7 @@ impl Default for SettingsModuleConfig {
                 SettingsIndicator::PowerProfile,
                 SettingsIndicator::Audio,
                 SettingsIndicator::Bluetooth,
+                SettingsIndicator::BluetoothDevices,
                 SettingsIndicator::Network,
                 SettingsIndicator::Vpn,
                 SettingsIndicator::Battery,
@@ -323,7 +1342,7 @@ impl Default for SettingsModuleConfig {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 pub struct SettingsCustomButton {
     pub name: String,
     pub icon: String,
@@ -332,28 +1351,62 @@ pub struct SettingsCustomButton {
     pub tooltip: Option<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct MediaPlayerModuleConfig {
     pub max_title_length: u32,
+    /// players listed first, the first one is shown in the bar
+    pub player_priority: Vec<String>,
+    /// players never shown
+    pub ignored_players: Vec<String>,
+    /// show the position and the duration of the track
+    pub show_position: bool,
+    /// show the album art in the menu
+    pub show_album_art: bool,
+    /// show the blurred album art behind the title
+    pub album_art_backdrop: bool,
+    /// size limit of the downloaded album art, in MB
+    pub album_art_cache_size: u64,
+    /// show the lyrics of the track in the menu
+    pub show_lyrics: bool,
+    /// directory of the `Artist - Title.lrc` files
+    pub lyrics_dir: Option<String>,
+    /// fetch the lyrics missing locally from LRCLIB
+    pub fetch_lyrics: bool,
+    /// list the audio outputs, with the network ones, to move the playback there
+    pub show_cast_targets: bool,
 }
 
 impl Default for MediaPlayerModuleConfig {
     fn default() -> Self {
         MediaPlayerModuleConfig {
             max_title_length: 100,
+            player_priority: Vec::new(),
+            ignored_players: Vec::new(),
+            show_position: false,
+            show_album_art: true,
+            album_art_backdrop: false,
+            album_art_cache_size: 50,
+            show_lyrics: false,
+            lyrics_dir: None,
+            fetch_lyrics: true,
+            show_cast_targets: false,
         }
     }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Copy, Debug)]
 #[serde(untagged)]
 pub enum AppearanceColor {
-    Simple(HexColor),
+    Simple(#[schemars(with = "String")] HexColor),
     Complete {
+        #[schemars(with = "String")]
         base: HexColor,
+        #[schemars(with = "Option<String>")]
         strong: Option<HexColor>,
+        #[schemars(with = "Option<String>")]
         weak: Option<HexColor>,
+        #[schemars(with = "Option<String>")]
         text: Option<HexColor>,
     },
 }
@@ -402,7 +1455,7 @@ impl AppearanceColor {
     }
 }
 
-#[derive(Deserialize, Default, Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Default, Copy, Clone, Eq, PartialEq, Debug)]
 pub enum AppearanceStyle {
     #[default]
     Islands,
@@ -410,7 +1463,7 @@ pub enum AppearanceStyle {
     Gradient,
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Copy, Debug)]
 #[serde(default)]
 pub struct MenuAppearance {
     #[serde(deserialize_with = "opacity_deserializer")]
@@ -427,7 +1480,7 @@ impl Default for MenuAppearance {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(default)]
 pub struct Appearance {
     pub font_name: Option<String>,
@@ -445,6 +1498,8 @@ pub struct Appearance {
     pub text_color: AppearanceColor,
     pub workspace_colors: Vec<AppearanceColor>,
     pub special_workspace_colors: Option<Vec<AppearanceColor>>,
+    /// color of the workspaces with a window demanding attention, the danger color by default
+    pub urgent_workspace_color: Option<AppearanceColor>,
 }
 
 static PRIMARY: HexColor = HexColor::rgb(250, 179, 135);
@@ -533,11 +1588,12 @@ impl Default for Appearance {
                 AppearanceColor::Simple(HexColor::rgb(203, 166, 247)),
             ],
             special_workspace_colors: None,
+            urgent_workspace_color: None,
         }
     }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Position {
     #[default]
     Top,
@@ -546,24 +1602,152 @@ pub enum Position {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ModuleName {
+    #[cfg(feature = "app-launcher")]
     AppLauncher,
+    #[cfg(feature = "updates")]
     Updates,
+    #[cfg(feature = "clipboard")]
     Clipboard,
-    Workspaces,
+    /// the named instances have their own configuration, `Workspaces:<name>` in the bar
+    Workspaces(Option<String>),
     WindowTitle,
+    #[cfg(feature = "system-info")]
     SystemInfo,
-    #[cfg(feature = "hyprland")]
+    #[cfg(feature = "keyboard-layout")]
     KeyboardLayout,
-    #[cfg(feature = "hyprland")]
+    #[cfg(feature = "keyboard-submap")]
     KeyboardSubmap,
+    #[cfg(feature = "window-group")]
+    WindowGroup,
+    #[cfg(feature = "tray")]
     Tray,
+    #[cfg(feature = "clock")]
     Clock,
+    #[cfg(feature = "privacy")]
     Privacy,
     Settings,
+    #[cfg(feature = "media-player")]
     MediaPlayer,
+    #[cfg(feature = "autostart")]
+    Autostart,
+    #[cfg(feature = "notes")]
+    Notes,
+    #[cfg(feature = "qr-code")]
+    QrCode,
+    #[cfg(feature = "totp")]
+    Totp,
+    #[cfg(feature = "passwords")]
+    Passwords,
+    #[cfg(feature = "encrypted-volumes")]
+    EncryptedVolumes,
+    #[cfg(feature = "usbguard")]
+    UsbGuard,
+    #[cfg(feature = "backup")]
+    Backup,
+    #[cfg(feature = "modem")]
+    Modem,
+    #[cfg(feature = "acpi")]
+    Acpi,
+    #[cfg(feature = "gpu")]
+    Gpu,
+    #[cfg(feature = "dashboard")]
+    Dashboard,
+    #[cfg(feature = "battery")]
+    Battery,
+    #[cfg(feature = "audio")]
+    Audio,
+    #[cfg(feature = "audio")]
+    Microphone,
+    #[cfg(feature = "network")]
+    Network,
+    #[cfg(feature = "bluetooth")]
+    Bluetooth,
+    #[cfg(feature = "notifications")]
+    Notifications,
+    #[cfg(feature = "idle-inhibitor")]
+    IdleInhibitor,
+    #[cfg(feature = "power-profiles")]
+    PowerProfiles,
     Custom(String),
 }
 
+impl std::fmt::Display for ModuleName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let ModuleName::Workspaces(Some(instance)) = self {
+            return write!(f, "Workspaces:{instance}");
+        }
+
+        f.write_str(match self {
+            #[cfg(feature = "app-launcher")]
+            ModuleName::AppLauncher => "AppLauncher",
+            #[cfg(feature = "updates")]
+            ModuleName::Updates => "Updates",
+            #[cfg(feature = "clipboard")]
+            ModuleName::Clipboard => "Clipboard",
+            ModuleName::Workspaces(_) => "Workspaces",
+            ModuleName::WindowTitle => "WindowTitle",
+            #[cfg(feature = "system-info")]
+            ModuleName::SystemInfo => "SystemInfo",
+            #[cfg(feature = "keyboard-layout")]
+            ModuleName::KeyboardLayout => "KeyboardLayout",
+            #[cfg(feature = "keyboard-submap")]
+            ModuleName::KeyboardSubmap => "KeyboardSubmap",
+            #[cfg(feature = "window-group")]
+            ModuleName::WindowGroup => "WindowGroup",
+            #[cfg(feature = "tray")]
+            ModuleName::Tray => "Tray",
+            #[cfg(feature = "clock")]
+            ModuleName::Clock => "Clock",
+            #[cfg(feature = "privacy")]
+            ModuleName::Privacy => "Privacy",
+            ModuleName::Settings => "Settings",
+            #[cfg(feature = "media-player")]
+            ModuleName::MediaPlayer => "MediaPlayer",
+            #[cfg(feature = "autostart")]
+            ModuleName::Autostart => "Autostart",
+            #[cfg(feature = "notes")]
+            ModuleName::Notes => "Notes",
+            #[cfg(feature = "qr-code")]
+            ModuleName::QrCode => "QrCode",
+            #[cfg(feature = "totp")]
+            ModuleName::Totp => "Totp",
+            #[cfg(feature = "passwords")]
+            ModuleName::Passwords => "Passwords",
+            #[cfg(feature = "encrypted-volumes")]
+            ModuleName::EncryptedVolumes => "EncryptedVolumes",
+            #[cfg(feature = "usbguard")]
+            ModuleName::UsbGuard => "UsbGuard",
+            #[cfg(feature = "backup")]
+            ModuleName::Backup => "Backup",
+            #[cfg(feature = "modem")]
+            ModuleName::Modem => "Modem",
+            #[cfg(feature = "acpi")]
+            ModuleName::Acpi => "Acpi",
+            #[cfg(feature = "gpu")]
+            ModuleName::Gpu => "Gpu",
+            #[cfg(feature = "dashboard")]
+            ModuleName::Dashboard => "Dashboard",
+            #[cfg(feature = "battery")]
+            ModuleName::Battery => "Battery",
+            #[cfg(feature = "audio")]
+            ModuleName::Audio => "Audio",
+            #[cfg(feature = "audio")]
+            ModuleName::Microphone => "Microphone",
+            #[cfg(feature = "network")]
+            ModuleName::Network => "Network",
+            #[cfg(feature = "bluetooth")]
+            ModuleName::Bluetooth => "Bluetooth",
+            #[cfg(feature = "notifications")]
+            ModuleName::Notifications => "Notifications",
+            #[cfg(feature = "idle-inhibitor")]
+            ModuleName::IdleInhibitor => "IdleInhibitor",
+            #[cfg(feature = "power-profiles")]
+            ModuleName::PowerProfiles => "PowerProfiles",
+            ModuleName::Custom(name) => name,
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for ModuleName {
     fn deserialize<D>(deserializer: D) -> Result<ModuleName, D::Error>
     where
@@ -579,22 +1763,76 @@ impl<'de> Deserialize<'de> for ModuleName {
             where
                 E: serde::de::Error,
             {
+                if let Some(instance) = value.strip_prefix("Workspaces:") {
+                    return Ok(ModuleName::Workspaces(Some(instance.to_string())));
+                }
+
                 Ok(match value {
+                    #[cfg(feature = "app-launcher")]
                     "AppLauncher" => ModuleName::AppLauncher,
+                    #[cfg(feature = "updates")]
                     "Updates" => ModuleName::Updates,
+                    #[cfg(feature = "clipboard")]
                     "Clipboard" => ModuleName::Clipboard,
-                    "Workspaces" => ModuleName::Workspaces,
+                    "Workspaces" => ModuleName::Workspaces(None),
                     "WindowTitle" => ModuleName::WindowTitle,
+                    #[cfg(feature = "system-info")]
                     "SystemInfo" => ModuleName::SystemInfo,
-                    #[cfg(feature = "hyprland")]
+                    #[cfg(feature = "keyboard-layout")]
                     "KeyboardLayout" => ModuleName::KeyboardLayout,
-                    #[cfg(feature = "hyprland")]
+                    #[cfg(feature = "keyboard-submap")]
                     "KeyboardSubmap" => ModuleName::KeyboardSubmap,
+                    #[cfg(feature = "window-group")]
+                    "WindowGroup" => ModuleName::WindowGroup,
+                    #[cfg(feature = "tray")]
                     "Tray" => ModuleName::Tray,
+                    #[cfg(feature = "clock")]
                     "Clock" => ModuleName::Clock,
+                    #[cfg(feature = "privacy")]
                     "Privacy" => ModuleName::Privacy,
                     "Settings" => ModuleName::Settings,
+                    #[cfg(feature = "media-player")]
                     "MediaPlayer" => ModuleName::MediaPlayer,
+                    #[cfg(feature = "autostart")]
+                    "Autostart" => ModuleName::Autostart,
+                    #[cfg(feature = "notes")]
+                    "Notes" => ModuleName::Notes,
+                    #[cfg(feature = "qr-code")]
+                    "QrCode" => ModuleName::QrCode,
+                    #[cfg(feature = "totp")]
+                    "Totp" => ModuleName::Totp,
+                    #[cfg(feature = "passwords")]
+                    "Passwords" => ModuleName::Passwords,
+                    #[cfg(feature = "encrypted-volumes")]
+                    "EncryptedVolumes" => ModuleName::EncryptedVolumes,
+                    #[cfg(feature = "usbguard")]
+                    "UsbGuard" => ModuleName::UsbGuard,
+                    #[cfg(feature = "backup")]
+                    "Backup" => ModuleName::Backup,
+                    #[cfg(feature = "modem")]
+                    "Modem" => ModuleName::Modem,
+                    #[cfg(feature = "acpi")]
+                    "Acpi" => ModuleName::Acpi,
+                    #[cfg(feature = "gpu")]
+                    "Gpu" => ModuleName::Gpu,
+                    #[cfg(feature = "dashboard")]
+                    "Dashboard" => ModuleName::Dashboard,
+                    #[cfg(feature = "battery")]
+                    "Battery" => ModuleName::Battery,
+                    #[cfg(feature = "audio")]
+                    "Audio" => ModuleName::Audio,
+                    #[cfg(feature = "audio")]
+                    "Microphone" => ModuleName::Microphone,
+                    #[cfg(feature = "network")]
+                    "Network" => ModuleName::Network,
+                    #[cfg(feature = "bluetooth")]
+                    "Bluetooth" => ModuleName::Bluetooth,
+                    #[cfg(feature = "notifications")]
+                    "Notifications" => ModuleName::Notifications,
+                    #[cfg(feature = "idle-inhibitor")]
+                    "IdleInhibitor" => ModuleName::IdleInhibitor,
+                    #[cfg(feature = "power-profiles")]
+                    "PowerProfiles" => ModuleName::PowerProfiles,
                     other => ModuleName::Custom(other.to_string()),
                 })
             }
@@ -603,14 +1841,33 @@ impl<'de> Deserialize<'de> for ModuleName {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl Serialize for ModuleName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl JsonSchema for ModuleName {
+    fn schema_name() -> Cow<'static, str> {
+        "ModuleName".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema("name of a module or of a custom module")
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 #[serde(untagged)]
 pub enum ModuleDef {
     Single(ModuleName),
     Group(Vec<ModuleName>),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 pub struct Modules {
     #[serde(default)]
     pub left: Vec<ModuleDef>,
@@ -623,10 +1880,12 @@ pub struct Modules {
 impl Default for Modules {
     fn default() -> Self {
         Self {
-            left: vec![ModuleDef::Single(ModuleName::Workspaces)],
+            left: vec![ModuleDef::Single(ModuleName::Workspaces(None))],
             center: vec![ModuleDef::Single(ModuleName::WindowTitle)],
             right: vec![ModuleDef::Group(vec![
+                #[cfg(feature = "clock")]
                 ModuleName::Clock,
+                #[cfg(feature = "privacy")]
                 ModuleName::Privacy,
                 ModuleName::Settings,
             ])],
@@ -634,7 +1893,7 @@ impl Default for Modules {
     }
 }
 
-#[derive(Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Default, Debug, PartialEq, Eq)]
 pub enum Outputs {
     #[default]
     All,
@@ -660,9 +1919,13 @@ where
 
 /// Newtype wrapper around `Regex`to be deserializable and usable as a hashmap key
 #[serde_as]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(transparent)]
-pub struct RegexCfg(#[serde_as(as = "DisplayFromStr")] pub Regex);
+pub struct RegexCfg(
+    #[serde_as(as = "DisplayFromStr")]
+    #[schemars(with = "String")]
+    pub Regex,
+);
 
 impl PartialEq for RegexCfg {
     fn eq(&self, other: &Self) -> bool {
@@ -687,7 +1950,7 @@ impl Deref for RegexCfg {
 }
 
 #[serde_as]
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
 pub struct CustomModuleDef {
     pub name: String,
     pub command: String,
@@ -700,9 +1963,38 @@ pub struct CustomModuleDef {
     pub icons: Option<HashMap<RegexCfg, String>>,
     /// regex to show alert
     pub alert: Option<RegexCfg>,
+    /// height of the icons and images sent by the listen_cmd
+    #[serde(default = "default_custom_image_size")]
+    pub image_size: u16,
     // .. appearance etc
 }
 
+fn default_custom_image_size() -> u16 {
+    16
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum AutostartRestart {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+pub struct AutostartDef {
+    pub name: String,
+    pub command: String,
+    /// names of the entries that must be started before this one
+    #[serde(default)]
+    pub after: Vec<String>,
+    #[serde(default)]
+    pub restart: AutostartRestart,
+    /// max number of automatic restarts, unlimited if not set
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
+}
+
 pub fn get_config(path: Option<PathBuf>) -> Result<(Config, PathBuf), Box<dyn Error + Send>> {
     match path {
         Some(p) => {
@@ -741,7 +2033,7 @@ fn expand_path(path: PathBuf) -> Result<PathBuf, Box<dyn Error + Send>> {
     Ok(PathBuf::from(expanded.to_string()))
 }
 
-fn read_config(path: &Path) -> Result<Config, Box<dyn Error + Send>> {
+pub fn read_config(path: &Path) -> Result<Config, Box<dyn Error + Send>> {
     let mut content = String::new();
     let read_result = File::open(path).and_then(|mut file| file.read_to_string(&mut content));
 
@@ -749,11 +2041,14 @@ fn read_config(path: &Path) -> Result<Config, Box<dyn Error + Send>> {
         Ok(_) => {
             info!("Decoding config file {path:?}");
 
-            let res = toml::from_str(&content);
+            let res = toml::from_str::<toml::Table>(&content).and_then(|table| {
+                toml::Value::Table(inherit_workspaces_instances(apply_profiles(table))).try_into()
+            });
 
             match res {
                 Ok(config) => {
                     info!("Config file loaded successfully");
+                    check_config(&config);
                     Ok(config)
                 }
                 Err(e) => {
@@ -770,6 +2065,133 @@ fn read_config(path: &Path) -> Result<Config, Box<dyn Error + Send>> {
     }
 }
 
+/// Warns about the options that can't work with the rest of the configuration
+fn check_config(config: &Config) {
+    let workspaces = &config.workspaces;
+    let scroll_modifier = workspaces.scroll_move_window_modifier.is_some()
+        || workspaces
+            .instances
+            .values()
+            .any(|instance| instance.scroll_move_window_modifier.is_some());
+
+    // the modifiers are only reported to the surface with the keyboard
+    if scroll_modifier && !config.enable_keyboard_navigation {
+        warn!(
+            "scroll_move_window_modifier needs enable_keyboard_navigation, \
+            the bar never gets the keyboard to know the modifiers"
+        );
+    }
+}
+
+/// Merges the `[[when]]` sections matching this machine over the base config, in order
+fn apply_profiles(mut table: toml::Table) -> toml::Table {
+    let Some(toml::Value::Array(profiles)) = table.remove("when") else {
+        return table;
+    };
+
+    for profile in profiles {
+        let toml::Value::Table(mut profile) = profile else {
+            warn!("Ignoring a `when` section that is not a table");
+            continue;
+        };
+
+        let hostname = profile.remove("hostname");
+        let session = profile.remove("session");
+        let session_type = profile.remove("session_type");
+        let env = profile.remove("env");
+
+        if profile_matches(hostname, session, session_type, env) {
+            debug!("Applying the config profile {profile:?}");
+            merge_table(&mut table, profile);
+        }
+    }
+
+    table
+}
+
+/// The instances of the workspaces start from the `[workspaces]` section
+fn inherit_workspaces_instances(mut table: toml::Table) -> toml::Table {
+    let Some(toml::Value::Table(workspaces)) = table.get_mut("workspaces") else {
+        return table;
+    };
+    let Some(toml::Value::Table(instances)) = workspaces.remove("instances") else {
+        return table;
+    };
+
+    let instances = instances
+        .into_iter()
+        .map(|(name, instance)| match instance {
+            toml::Value::Table(instance) => {
+                let mut merged = workspaces.clone();
+                merge_table(&mut merged, instance);
+
+                (name, toml::Value::Table(merged))
+            }
+            other => (name, other),
+        })
+        .collect();
+    workspaces.insert("instances".to_string(), toml::Value::Table(instances));
+
+    table
+}
+
+/// Every condition set must match, a section without conditions always applies
+fn profile_matches(
+    hostname: Option<toml::Value>,
+    session: Option<toml::Value>,
+    session_type: Option<toml::Value>,
+    env: Option<toml::Value>,
+) -> bool {
+    let hostname_matches = hostname.is_none_or(|expected| {
+        let current = std::fs::read_to_string("/proc/sys/kernel/hostname").unwrap_or_default();
+
+        expected.as_str() == Some(current.trim())
+    });
+
+    // the desktop names, e.g. `Hyprland` or `niri`, XDG_CURRENT_DESKTOP can list several
+    let session_matches = session.is_none_or(|expected| {
+        ["XDG_CURRENT_DESKTOP", "XDG_SESSION_DESKTOP"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .any(|value| {
+                value.split(':').any(|name| {
+                    expected
+                        .as_str()
+                        .is_some_and(|e| e.eq_ignore_ascii_case(name))
+                })
+            })
+    });
+
+    let session_type_matches = session_type.is_none_or(|expected| {
+        std::env::var("XDG_SESSION_TYPE").is_ok_and(|value| {
+            expected
+                .as_str()
+                .is_some_and(|e| e.eq_ignore_ascii_case(&value))
+        })
+    });
+
+    let env_matches = env.is_none_or(|expected| match expected {
+        toml::Value::Table(vars) => vars
+            .iter()
+            .all(|(var, value)| std::env::var(var).ok().as_deref() == value.as_str()),
+        _ => false,
+    });
+
+    hostname_matches && session_matches && session_type_matches && env_matches
+}
+
+/// The tables are merged key by key, any other value is replaced
+fn merge_table(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge_table(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 enum Event {
     Changed,
     Removed,
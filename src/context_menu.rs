@@ -0,0 +1,118 @@
+use crate::{
+    app::{App, Message},
+    components::icons::{StaticIcon, icon},
+    ipc::{ModuleAction, PinAction},
+    menu::MenuType,
+};
+use iced::{
+    Alignment, Element, Length,
+    widget::{Column, button, column, horizontal_rule, row, text, toggler},
+};
+
+impl App {
+    /// Menu opened with a right click on the bar
+    pub fn context_menu_view(&'_ self) -> Element<'_, Message> {
+        let action = |action_icon, label, message| {
+            button(
+                row!(icon(action_icon), text(label))
+                    .align_y(Alignment::Center)
+                    .spacing(self.theme.space.xs),
+            )
+            .style(self.theme.ghost_button_style())
+            .padding([self.theme.space.xxs, self.theme.space.xs])
+            .width(Length::Fill)
+            .on_press(message)
+        };
+
+        column!(
+            action(
+                StaticIcon::Refresh,
+                "Reload configuration",
+                Message::ReloadConfig
+            ),
+            action(StaticIcon::Edit, "Open configuration", Message::OpenConfig),
+            action(StaticIcon::Power, "Quit", Message::Quit),
+            horizontal_rule(1),
+            text("Modules").size(self.theme.font_size.lg),
+            Column::with_children(
+                self.configured_modules()
+                    .map(|module| {
+                        let name = module.to_string();
+
+                        toggler(!self.state.is_hidden(&name))
+                            .label(name.clone())
+                            .on_toggle(move |visible| {
+                                Message::ModuleVisibility(
+                                    name.clone(),
+                                    if visible {
+                                        ModuleAction::Show
+                                    } else {
+                                        ModuleAction::Hide
+                                    },
+                                )
+                            })
+                            .width(Length::Fill)
+                            .into()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .spacing(self.theme.space.xs),
+            horizontal_rule(1),
+            text("Pinned popups").size(self.theme.font_size.lg),
+            Column::with_children(
+                self.configured_modules()
+                    .filter_map(|module| {
+                        let name = module.to_string();
+                        let menu_type = MenuType::from_name(&name)?;
+
+                        Some(
+                            toggler(self.pinned_popups.is_pinned(&menu_type))
+                                .label(name.clone())
+                                .on_toggle(move |pinned| {
+                                    Message::PinPopup(
+                                        name.clone(),
+                                        if pinned {
+                                            PinAction::Pin
+                                        } else {
+                                            PinAction::Unpin
+                                        },
+                                    )
+                                })
+                                .width(Length::Fill)
+                                .into(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .spacing(self.theme.space.xs),
+        )
+        .push_maybe(self.listener_restarts_view())
+        .spacing(self.theme.space.sm)
+        .into()
+    }
+
+    /// The listeners restarted by the watchdogs, only shown after a restart
+    fn listener_restarts_view(&'_ self) -> Option<Element<'_, Message>> {
+        let restarts = [
+            ("Workspaces", self.workspaces.restarts()),
+            ("WindowTitle", self.window_title.restarts()),
+        ]
+        .into_iter()
+        .filter(|(_, restarts)| *restarts > 0)
+        .collect::<Vec<_>>();
+
+        (!restarts.is_empty()).then(|| {
+            column!(
+                horizontal_rule(1),
+                text("Listener restarts").size(self.theme.font_size.lg),
+            )
+            .extend(restarts.into_iter().map(|(name, restarts)| {
+                row!(text(name).width(Length::Fill), text(restarts))
+                    .align_y(Alignment::Center)
+                    .into()
+            }))
+            .spacing(self.theme.space.xs)
+            .into()
+        })
+    }
+}
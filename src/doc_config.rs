@@ -0,0 +1,165 @@
+use crate::config::Config;
+use serde_json::Value;
+
+/// JSON Schema of the configuration file, for the editor completion
+pub fn schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(Config)).unwrap_or_default()
+}
+
+/// The options of the configuration file, with their types and defaults
+pub fn options() -> String {
+    let schema = serde_json::to_value(schemars::schema_for!(Config)).unwrap_or_default();
+    let mut lines = Vec::new();
+
+    document(&schema, &schema, "", &mut lines);
+
+    lines.join("\n")
+}
+
+/// Follows the reference to a definition, like `#/$defs/Appearance`
+fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => reference
+            .strip_prefix("#/")
+            .map(|path| {
+                path.split('/')
+                    .try_fold(root, |value, key| value.get(key))
+                    .unwrap_or(schema)
+            })
+            .unwrap_or(schema),
+        None => schema,
+    }
+}
+
+/// The schema without the null of the optional values
+fn non_null<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    let variants = schema
+        .get("anyOf")
+        .and_then(Value::as_array)
+        .map(|variants| {
+            variants
+                .iter()
+                .filter(|variant| variant.get("type").and_then(Value::as_str) != Some("null"))
+                .collect::<Vec<_>>()
+        });
+
+    match variants.as_deref() {
+        Some([variant]) => resolve(variant, root),
+        _ => resolve(schema, root),
+    }
+}
+
+fn type_name(schema: &Value, root: &Value) -> String {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let resolved = resolve(schema, root);
+
+        // the enums are more useful with their values
+        return if ["enum", "oneOf", "anyOf"]
+            .iter()
+            .any(|key| resolved.get(key).is_some())
+        {
+            type_name(resolved, root)
+        } else {
+            reference
+                .rsplit('/')
+                .next()
+                .unwrap_or(reference)
+                .to_string()
+        };
+    }
+
+    if let Some(variants) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        return variants
+            .iter()
+            .map(|variant| type_name(variant, root))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    match schema.get("type") {
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|name| type_of(schema, name, root))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Some(Value::String(name)) => type_of(schema, name, root),
+        _ => "any".to_string(),
+    }
+}
+
+fn type_of(schema: &Value, name: &str, root: &Value) -> String {
+    match name {
+        "array" => format!(
+            "[{}]",
+            schema
+                .get("items")
+                .map(|items| type_name(items, root))
+                .unwrap_or_else(|| "any".to_string())
+        ),
+        "object" => match schema.get("additionalProperties") {
+            Some(value @ Value::Object(_)) => format!("{{ name = {} }}", type_name(value, root)),
+            _ => "table".to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+fn document(schema: &Value, root: &Value, prefix: &str, lines: &mut Vec<String>) {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+
+    let mut sections = Vec::new();
+
+    for (name, property) in properties {
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        let resolved = non_null(property, root);
+
+        // the nested sections are listed after the options
+        if resolved.get("properties").is_some() {
+            sections.push((path, resolved));
+            continue;
+        }
+
+        let mut line = format!("{path}: {}", type_name(property, root));
+        if let Some(default) = property.get("default") {
+            line.push_str(&format!(" = {default}"));
+        }
+        lines.push(line);
+
+        if let Some(description) = property
+            .get("description")
+            .or_else(|| resolved.get("description"))
+            .and_then(Value::as_str)
+        {
+            lines.extend(description.lines().map(|line| format!("    {line}")));
+        }
+    }
+
+    for (path, section) in sections {
+        lines.push(String::new());
+        lines.push(format!("[{path}]"));
+        document(section, root, &path, lines);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    config::HooksConfig,
+    services::{
+        ReadOnlyService, ServiceEvent,
+        acpi::{AcpiEvent, AcpiService},
+        logind::{LogindEvent, LogindService},
+    },
+    utils::launcher::{execute_command, execute_command_with_env},
+};
+use iced::Subscription;
+use log::debug;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Logind(ServiceEvent<LogindService>),
+    Acpi(ServiceEvent<AcpiService>),
+}
+
+pub struct Hooks {
+    config: HooksConfig,
+}
+
+impl Hooks {
+    pub fn new(config: HooksConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Logind(ServiceEvent::Update(event)) => {
+                let command = match event {
+                    LogindEvent::Lock => self.config.on_lock.clone(),
+                    LogindEvent::Unlock => self.config.on_unlock.clone(),
+                    LogindEvent::Idle(true) => self.config.on_idle_enter.clone(),
+                    LogindEvent::Idle(false) => self.config.on_idle_exit.clone(),
+                };
+
+                if let Some(command) = command {
+                    debug!("Running {event:?} hook: {command}");
+                    execute_command(command);
+                }
+            }
+            Message::Logind(_) => {}
+            Message::Acpi(ServiceEvent::Update(event)) => {
+                let command = match event {
+                    AcpiEvent::Lid(true) => self.config.on_lid_close.clone(),
+                    AcpiEvent::Lid(false) => self.config.on_lid_open.clone(),
+                    AcpiEvent::TabletMode(true) => self.config.on_tablet_mode_enter.clone(),
+                    AcpiEvent::TabletMode(false) => self.config.on_tablet_mode_exit.clone(),
+                    AcpiEvent::PowerButton => self.config.on_power_button.clone(),
+                };
+
+                if let Some(command) = command {
+                    debug!("Running {event:?} hook: {command}");
+                    execute_command(command);
+                }
+            }
+            Message::Acpi(_) => {}
+        }
+    }
+
+    pub fn output_added(&self, name: &str) {
+        if let Some(command) = self.config.on_output_added.clone() {
+            debug!("Running output added hook for {name}: {command}");
+            execute_command_with_env(command, vec![("ASHELL_OUTPUT", name.to_owned())]);
+        }
+    }
+
+    pub fn output_removed(&self, name: &str) {
+        if let Some(command) = self.config.on_output_removed.clone() {
+            debug!("Running output removed hook for {name}: {command}");
+            execute_command_with_env(command, vec![("ASHELL_OUTPUT", name.to_owned())]);
+        }
+    }
+
+    #[cfg(feature = "notifications")]
+    pub fn do_not_disturb_changed(&self, enabled: bool) {
+        let command = if enabled {
+            self.config.on_dnd_enable.clone()
+        } else {
+            self.config.on_dnd_disable.clone()
+        };
+
+        if let Some(command) = command {
+            debug!("Running do not disturb hook: {command}");
+            execute_command(command);
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let logind_hooks = [
+            &self.config.on_lock,
+            &self.config.on_unlock,
+            &self.config.on_idle_enter,
+            &self.config.on_idle_exit,
+        ];
+        let acpi_hooks = [
+            &self.config.on_lid_close,
+            &self.config.on_lid_open,
+            &self.config.on_tablet_mode_enter,
+            &self.config.on_tablet_mode_exit,
+            &self.config.on_power_button,
+        ];
+
+        Subscription::batch(vec![
+            if logind_hooks.iter().any(|hook| hook.is_some()) {
+                LogindService::subscribe().map(Message::Logind)
+            } else {
+                Subscription::none()
+            },
+            if acpi_hooks.iter().any(|hook| hook.is_some()) {
+                AcpiService::subscribe().map(Message::Acpi)
+            } else {
+                Subscription::none()
+            },
+        ])
+    }
+}
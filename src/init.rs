@@ -0,0 +1,314 @@
+use crate::config::DEFAULT_CONFIG_FILE_PATH;
+use std::{
+    env, fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+use zbus::fdo::DBusProxy;
+
+/// What `ashell init` found on this machine
+#[derive(Debug)]
+struct Detected {
+    compositor: Option<&'static str>,
+    monitors: Vec<String>,
+    upower: bool,
+    battery: bool,
+    power_profiles: bool,
+    network_manager: bool,
+    bluez: bool,
+    audio: bool,
+    pipewire: bool,
+}
+
+/// The compositor of the session, from the environment it exports
+fn detect_compositor() -> Option<&'static str> {
+    if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        Some("hyprland")
+    } else if env::var_os("NIRI_SOCKET").is_some() {
+        Some("niri")
+    } else if env::var_os("SWAYSOCK").is_some() {
+        Some("sway")
+    } else if env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop == "river") {
+        Some("river")
+    } else if env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop == "KDE") {
+        Some("kwin")
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        Some("wlroots")
+    } else {
+        None
+    }
+}
+
+/// The connected monitors, named like the compositors do, e.g. `eDP-1`
+fn detect_monitors() -> Vec<String> {
+    let mut monitors = fs::read_dir("/sys/class/drm")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    fs::read_to_string(entry.path().join("status"))
+                        .is_ok_and(|status| status.trim() == "connected")
+                })
+                .filter_map(|entry| {
+                    // the connectors are named after their card, e.g. `card1-eDP-1`
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    name.split_once('-').map(|(_, name)| name.to_string())
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    monitors.sort();
+    monitors.dedup();
+
+    monitors
+}
+
+/// The workspaces and window title work only with the compositor of the build
+fn built_for(compositor: &str) -> bool {
+    match compositor {
+        "hyprland" => cfg!(feature = "hyprland"),
+        "niri" => cfg!(feature = "niri"),
+        "sway" => cfg!(feature = "sway"),
+        "river" => cfg!(feature = "river"),
+        "kwin" => cfg!(feature = "kwin"),
+        _ => cfg!(feature = "wlroots"),
+    }
+}
+
+fn detect_battery() -> bool {
+    fs::read_dir("/sys/class/power_supply").is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            fs::read_to_string(entry.path().join("type")).is_ok_and(|kind| kind.trim() == "Battery")
+        })
+    })
+}
+
+fn runtime_socket(name: &str) -> bool {
+    env::var_os("XDG_RUNTIME_DIR").is_some_and(|dir| Path::new(&dir).join(name).exists())
+}
+
+/// The system services are bus activated, the activatable names count as available
+async fn system_services() -> anyhow::Result<Vec<String>> {
+    let conn = zbus::Connection::system().await?;
+    let dbus = DBusProxy::new(&conn).await?;
+
+    let mut names = dbus.list_names().await?;
+    names.extend(dbus.list_activatable_names().await?);
+
+    Ok(names.into_iter().map(|name| name.to_string()).collect())
+}
+
+async fn detect() -> Detected {
+    let services = system_services().await.unwrap_or_default();
+    let has_service = |name: &str| services.iter().any(|service| service == name);
+
+    Detected {
+        compositor: detect_compositor(),
+        monitors: detect_monitors(),
+        upower: has_service("org.freedesktop.UPower"),
+        battery: detect_battery(),
+        power_profiles: has_service("org.freedesktop.UPower.PowerProfiles")
+            || has_service("net.hadess.PowerProfiles"),
+        network_manager: has_service("org.freedesktop.NetworkManager"),
+        bluez: has_service("org.bluez"),
+        // PulseAudio or the pulse server of PipeWire
+        audio: runtime_socket("pulse/native"),
+        pipewire: runtime_socket("pipewire-0"),
+    }
+}
+
+fn quoted(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("\"{name}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+/// Starter configuration enabling the modules working on this machine
+fn starter_config(detected: &Detected, position: &str, outputs: &[String]) -> String {
+    let mut right = Vec::new();
+    if cfg!(feature = "system-info") {
+        right.push("\"SystemInfo\"".to_string());
+    }
+    // the wlroots and river builds can't read the active layout
+    if detected
+        .compositor
+        .is_some_and(|c| matches!(c, "hyprland" | "niri" | "sway" | "kwin") && built_for(c))
+    {
+        right.push("\"KeyboardLayout\"".to_string());
+    }
+
+    let mut group = Vec::new();
+    if cfg!(feature = "tray") {
+        group.push("Tray");
+    }
+    if cfg!(feature = "clock") {
+        group.push("Clock");
+    }
+    // the privacy module watches the PipeWire streams
+    if cfg!(feature = "privacy") && detected.pipewire {
+        group.push("Privacy");
+    }
+    group.push("Settings");
+    right.push(format!("[{}]", quoted(&group)));
+
+    let mut indicators = vec!["IdleInhibitor"];
+    if detected.power_profiles {
+        indicators.push("PowerProfile");
+    }
+    if detected.audio {
+        indicators.push("Audio");
+    }
+    if detected.bluez {
+        indicators.extend(["Bluetooth", "BluetoothDevices"]);
+    }
+    if detected.network_manager {
+        indicators.extend(["Network", "Vpn"]);
+    }
+    if detected.upower && detected.battery {
+        indicators.push("Battery");
+    }
+    if detected.upower {
+        indicators.push("PeripheralBattery");
+    }
+
+    let outputs = if outputs.is_empty() {
+        "\"All\"".to_string()
+    } else {
+        let outputs = outputs.iter().map(String::as_str).collect::<Vec<_>>();
+        format!("{{ Targets = [{}] }}", quoted(&outputs))
+    };
+    let monitors = if detected.monitors.is_empty() {
+        "none".to_string()
+    } else {
+        detected.monitors.join(", ")
+    };
+
+    format!(
+        r#"# ashell configuration, generated by `ashell init`
+#
+# `ashell doc-config` lists all the options, they are described in
+# https://malpenzibo.github.io/ashell/docs/configuration
+#
+# Detected compositor: {compositor}
+# Detected monitors: {monitors}
+# UPower: {upower}, battery: {battery}, power profiles: {power_profiles}
+# NetworkManager: {network_manager}, BlueZ: {bluez}
+# PulseAudio: {audio}, PipeWire: {pipewire}
+
+log_level = "warn"
+
+# "All", "Active" or {{ Targets = ["DP-1", "eDP-1"] }}
+outputs = {outputs}
+
+# "Top" or "Bottom"
+position = "{position}"
+
+# The modules of the bar, a nested list groups the modules
+[modules]
+left = ["Workspaces"]
+center = ["WindowTitle"]
+right = [{right}]
+
+[workspaces]
+# show the workspaces from 1 to the highest one, even the empty ones
+enable_workspace_filling = false
+
+[settings]
+# indicators of the services found on this machine
+indicators = [{indicators}]
+# lock_cmd = "hyprlock &"
+# wifi_more_cmd = "nm-connection-editor"
+# bluetooth_more_cmd = "blueman-manager"
+"#,
+        compositor = detected.compositor.unwrap_or("unknown"),
+        upower = yes_no(detected.upower),
+        battery = yes_no(detected.battery),
+        power_profiles = yes_no(detected.power_profiles),
+        network_manager = yes_no(detected.network_manager),
+        bluez = yes_no(detected.bluez),
+        audio = yes_no(detected.audio),
+        pipewire = yes_no(detected.pipewire),
+        right = right.join(", "),
+        indicators = quoted(&indicators),
+    )
+}
+
+/// Asks the question, the answer is trimmed and empty when stdin is closed
+fn ask(question: &str) -> io::Result<String> {
+    print!("{question} ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(answer.trim().to_string())
+}
+
+/// Detects the machine and writes a starter configuration, asking the bar position,
+/// the monitors and before overwriting an existing file when run from a terminal
+pub async fn run(path: Option<PathBuf>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = PathBuf::from(
+        shellexpand::full(
+            &path
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE_PATH))
+                .to_string_lossy(),
+        )?
+        .to_string(),
+    );
+    let interactive = io::stdin().is_terminal();
+
+    let detected = detect().await;
+
+    if let Some(compositor) = detected.compositor
+        && !built_for(compositor)
+    {
+        eprintln!("The session runs {compositor}, but ashell is built for another compositor");
+    }
+
+    let mut position = "Top";
+    let mut outputs = Vec::new();
+
+    if interactive {
+        println!(
+            "Compositor: {}",
+            detected.compositor.unwrap_or("not detected")
+        );
+        println!("Monitors: {}", detected.monitors.join(", "));
+
+        if ask("Bar position, top or bottom? [top]")?.eq_ignore_ascii_case("bottom") {
+            position = "Bottom";
+        }
+
+        if detected.monitors.len() > 1 {
+            let answer = ask("Monitors of the bar, separated by spaces, empty for all:")?;
+            outputs = answer
+                .split_whitespace()
+                .filter(|monitor| detected.monitors.iter().any(|m| m == monitor))
+                .map(str::to_string)
+                .collect();
+        }
+    }
+
+    if path.exists() {
+        let overwrite = interactive
+            && ask(&format!("{} exists, overwrite it? [y/N]", path.display()))?
+                .eq_ignore_ascii_case("y");
+
+        if !overwrite {
+            return Err(format!("{} already exists", path.display()).into());
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, starter_config(&detected, position, &outputs))?;
+
+    Ok(path)
+}
@@ -0,0 +1,275 @@
+use crate::outputs::BarGeometry;
+use clap::{Subcommand, ValueEnum};
+use iced::{
+    Subscription,
+    futures::{SinkExt, StreamExt, stream::pending},
+    stream::channel,
+};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::TypeId,
+    env, fs, io,
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    time::timeout,
+};
+
+/// A client has this long to send its command
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(ValueEnum, Serialize, Deserialize, Copy, Clone, Default, Debug)]
+pub enum Toggle {
+    #[default]
+    Toggle,
+    On,
+    Off,
+}
+
+impl Toggle {
+    pub fn apply(self, value: bool) -> bool {
+        match self {
+            Toggle::Toggle => !value,
+            Toggle::On => true,
+            Toggle::Off => false,
+        }
+    }
+}
+
+#[derive(ValueEnum, Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum ModuleAction {
+    Show,
+    Hide,
+    Toggle,
+}
+
+impl ModuleAction {
+    /// Applies the action to the hidden state of a module
+    pub fn hidden(self, hidden: bool) -> bool {
+        match self {
+            ModuleAction::Show => false,
+            ModuleAction::Hide => true,
+            ModuleAction::Toggle => !hidden,
+        }
+    }
+}
+
+#[derive(ValueEnum, Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum PinAction {
+    Pin,
+    Unpin,
+    Toggle,
+}
+
+impl PinAction {
+    /// Applies the action to the pinned state of a popup
+    pub fn pinned(self, pinned: bool) -> bool {
+        match self {
+            PinAction::Pin => true,
+            PinAction::Unpin => false,
+            PinAction::Toggle => !pinned,
+        }
+    }
+}
+
+/// Commands sent to the running instance with `ashell msg`
+#[derive(Subcommand, Serialize, Deserialize, Clone, Debug)]
+pub enum IpcCommand {
+    /// Hide all the modules except the ones listed in the zen_mode configuration
+    Zen {
+        #[arg(value_enum, default_value_t = Toggle::Toggle)]
+        action: Toggle,
+    },
+    /// Blank the modules listed in the privacy_mode configuration
+    Privacy {
+        #[arg(value_enum, default_value_t = Toggle::Toggle)]
+        action: Toggle,
+    },
+    /// Show or hide a module, the change is kept across restarts
+    Module {
+        #[arg(value_enum)]
+        action: ModuleAction,
+        /// name of the module as written in the configuration
+        name: String,
+    },
+    /// Pin the menu of a module on the desktop, the change is kept across restarts
+    Popup {
+        #[arg(value_enum)]
+        action: PinAction,
+        /// name of the module owning the menu
+        name: String,
+    },
+    /// Give the keyboard to the bar to switch the workspaces with the arrow keys and Enter
+    FocusWorkspaces {
+        /// name of the output, the first bar when missing
+        output: Option<String>,
+    },
+    /// Print the output, position, height and exclusive zone of the bars as JSON
+    Geometry,
+    /// Print the history of the notification center as JSON, newest first
+    Notifications,
+}
+
+/// The bars as last laid out, the geometry queries are answered without the app
+static BARS: LazyLock<Mutex<Vec<BarGeometry>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+pub fn set_bars(bars: Vec<BarGeometry>) {
+    if let Ok(mut current) = BARS.lock() {
+        *current = bars;
+    }
+}
+
+fn bars_json() -> String {
+    BARS.lock()
+        .ok()
+        .and_then(|bars| serde_json::to_string(&*bars).ok())
+        .unwrap_or_else(|| "[]".to_string())
+}
+
+/// The history of the notification center as JSON, empty without the notification daemon
+static NOTIFICATIONS: LazyLock<Mutex<String>> = LazyLock::new(|| Mutex::new("[]".to_string()));
+
+#[cfg(feature = "notifications")]
+pub fn set_notifications(notifications: &[impl Serialize]) {
+    if let Ok(json) = serde_json::to_string(notifications)
+        && let Ok(mut current) = NOTIFICATIONS.lock()
+    {
+        *current = json;
+    }
+}
+
+fn notifications_json() -> String {
+    NOTIFICATIONS
+        .lock()
+        .map(|notifications| notifications.clone())
+        .unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Without a runtime directory the socket is named after the user,
+/// the users of the machine share the temporary one
+fn socket_path() -> io::Result<PathBuf> {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => Ok(PathBuf::from(dir).join("ashell.sock")),
+        None => {
+            let uid = fs::metadata("/proc/self")?.uid();
+
+            Ok(env::temp_dir().join(format!("ashell-{uid}.sock")))
+        }
+    }
+}
+
+/// Sends the command to the running instance and returns its reply
+pub async fn send(command: &IpcCommand) -> anyhow::Result<String> {
+    let mut stream = UnixStream::connect(socket_path()?).await?;
+
+    let mut request = serde_json::to_string(command)?;
+    request.push('\n');
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).await?;
+
+    Ok(reply.trim_end().to_string())
+}
+
+/// The queries are answered here, the other commands are passed to the app
+async fn read_command(stream: UnixStream) -> io::Result<Option<IpcCommand>> {
+    let (reader, mut writer) = stream.into_split();
+
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).await?;
+
+    match serde_json::from_str::<IpcCommand>(&line) {
+        Ok(IpcCommand::Geometry) => {
+            writer
+                .write_all(format!("{}\n", bars_json()).as_bytes())
+                .await?;
+
+            Ok(None)
+        }
+        Ok(IpcCommand::Notifications) => {
+            writer
+                .write_all(format!("{}\n", notifications_json()).as_bytes())
+                .await?;
+
+            Ok(None)
+        }
+        Ok(command) => {
+            writer.write_all(b"ok\n").await?;
+
+            Ok(Some(command))
+        }
+        Err(err) => {
+            warn!("Invalid IPC command received");
+            writer
+                .write_all(format!("invalid command: {err}\n").as_bytes())
+                .await?;
+
+            Ok(None)
+        }
+    }
+}
+
+async fn bind() -> io::Result<UnixListener> {
+    let path = socket_path()?;
+
+    if UnixStream::connect(&path).await.is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "another instance is listening",
+        ));
+    }
+
+    // the socket is left behind when ashell is killed
+    if path.exists() {
+        fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    info!("Listening for commands on {path:?}");
+
+    Ok(listener)
+}
+
+pub fn subscription() -> Subscription<IpcCommand> {
+    let id = TypeId::of::<IpcCommand>();
+
+    Subscription::run_with_id(
+        id,
+        channel(10, async |mut output| match bind().await {
+            Ok(listener) => loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let mut output = output.clone();
+
+                        // a slow client doesn't hold back the others
+                        tokio::spawn(async move {
+                            match timeout(READ_TIMEOUT, read_command(stream)).await {
+                                Ok(Ok(Some(command))) => {
+                                    debug!("IPC command received: {command:?}");
+                                    let _ = output.send(command).await;
+                                }
+                                // the queries and the invalid commands are answered already
+                                Ok(Ok(None)) => {}
+                                Ok(Err(err)) => warn!("Failed to read the IPC command: {err}"),
+                                Err(_) => warn!("The IPC client didn't send its command in time"),
+                            }
+                        });
+                    }
+                    Err(err) => error!("Failed to accept an IPC connection: {err}"),
+                }
+            },
+            Err(err) => {
+                error!("Failed to listen for IPC commands: {err}");
+
+                let _ = pending::<u8>().next().await;
+            }
+        }),
+    )
+}
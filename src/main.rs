@@ -1,6 +1,6 @@
 use crate::config::get_config;
 use app::App;
-use clap::{Parser, command};
+use clap::{Parser, Subcommand, command};
 use flexi_logger::{
     Age, Cleanup, Criterion, FileSpec, LogSpecBuilder, LogSpecification, Logger, Naming,
 };
@@ -10,18 +10,36 @@ use std::panic;
 use std::path::PathBuf;
 use std::{backtrace::Backtrace, borrow::Cow};
 
+mod animations;
 mod app;
+mod battery_saver;
 mod centerbox;
 mod components;
 mod config;
+mod context_menu;
+mod doc_config;
+mod hooks;
+mod init;
+mod ipc;
 mod menu;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod modules;
 mod outputs;
 mod password_dialog;
+mod pinned_popups;
 mod position_button;
+mod privacy_mode;
+mod redraw;
+mod scheduler;
 mod services;
+mod sounds;
+mod state;
 mod theme;
 mod utils;
+#[cfg(feature = "wallpaper-colors")]
+mod wallpaper_colors;
+mod zen_mode;
 
 const NERD_FONT: &[u8] = include_bytes!("../target/generated/SymbolsNerdFont-Regular-Subset.ttf");
 const NERD_FONT_MONO: &[u8] =
@@ -34,6 +52,25 @@ const HEIGHT: f64 = 34.;
 struct Args {
     #[arg(short, long, value_parser = clap::value_parser!(PathBuf))]
     config_path: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Send a command to the running instance
+    Msg {
+        #[command(subcommand)]
+        command: ipc::IpcCommand,
+    },
+    /// Write a starter configuration enabling the modules working on this machine
+    Init,
+    /// Print the configuration options with their types and defaults
+    DocConfig {
+        /// Print the JSON Schema of the configuration instead
+        #[arg(long)]
+        schema: bool,
+    },
 }
 
 fn get_log_spec(log_level: &str) -> LogSpecification {
@@ -54,6 +91,43 @@ async fn main() -> iced::Result {
     let args = Args::parse();
     debug!("args: {args:?}");
 
+    match args.command {
+        Some(Command::Msg { command }) => match ipc::send(&command).await {
+            Ok(reply) => {
+                println!("{reply}");
+
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("Failed to send the command to ashell: {err}");
+
+                std::process::exit(1);
+            }
+        },
+        Some(Command::DocConfig { schema }) => {
+            if schema {
+                println!("{}", doc_config::schema());
+            } else {
+                println!("{}", doc_config::options());
+            }
+
+            std::process::exit(0);
+        }
+        Some(Command::Init) => match init::run(args.config_path.clone()).await {
+            Ok(path) => {
+                println!("Configuration written to {}", path.display());
+
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("Failed to write the configuration: {err}");
+
+                std::process::exit(1);
+            }
+        },
+        None => {}
+    }
+
     let logger = Logger::with(
         LogSpecBuilder::new()
             .default(log::LevelFilter::Info)
@@ -6,7 +6,7 @@ use iced::alignment::{Horizontal, Vertical};
 use iced::platform_specific::shell::commands::layer_surface::{
     KeyboardInteractivity, Layer, set_keyboard_interactivity, set_layer,
 };
-use iced::widget::container::Style;
+use iced::widget::container::{Container, Style};
 use iced::widget::mouse_area;
 use iced::window::Id;
 use iced::{self, Element, Task, Theme, widget::container};
@@ -14,11 +14,147 @@ use iced::{Border, Length, Padding};
 
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum MenuType {
+    #[cfg(feature = "updates")]
     Updates,
     Settings,
+    #[cfg(feature = "tray")]
     Tray(String),
+    #[cfg(feature = "media-player")]
     MediaPlayer,
+    #[cfg(feature = "system-info")]
     SystemInfo,
+    #[cfg(feature = "autostart")]
+    Autostart,
+    #[cfg(feature = "notes")]
+    Notes,
+    #[cfg(feature = "qr-code")]
+    QrCode,
+    #[cfg(feature = "totp")]
+    Totp,
+    #[cfg(feature = "passwords")]
+    Passwords,
+    #[cfg(feature = "encrypted-volumes")]
+    EncryptedVolumes,
+    #[cfg(feature = "usbguard")]
+    UsbGuard,
+    #[cfg(feature = "backup")]
+    Backup,
+    #[cfg(feature = "modem")]
+    Modem,
+    #[cfg(feature = "gpu")]
+    Gpu,
+    #[cfg(feature = "dashboard")]
+    Dashboard,
+    #[cfg(feature = "audio")]
+    Audio,
+    #[cfg(feature = "network")]
+    Network,
+    #[cfg(feature = "bluetooth")]
+    Bluetooth,
+    #[cfg(feature = "notifications")]
+    Notifications,
+    #[cfg(feature = "clock")]
+    Clock,
+    #[cfg(feature = "window-rules")]
+    WindowRules,
+    Context,
+}
+
+impl MenuType {
+    /// Menus of the modules, named after their module, can be pinned
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            #[cfg(feature = "updates")]
+            "Updates" => MenuType::Updates,
+            "Settings" => MenuType::Settings,
+            #[cfg(feature = "media-player")]
+            "MediaPlayer" => MenuType::MediaPlayer,
+            #[cfg(feature = "system-info")]
+            "SystemInfo" => MenuType::SystemInfo,
+            #[cfg(feature = "autostart")]
+            "Autostart" => MenuType::Autostart,
+            #[cfg(feature = "notes")]
+            "Notes" => MenuType::Notes,
+            #[cfg(feature = "qr-code")]
+            "QrCode" => MenuType::QrCode,
+            #[cfg(feature = "totp")]
+            "Totp" => MenuType::Totp,
+            #[cfg(feature = "passwords")]
+            "Passwords" => MenuType::Passwords,
+            #[cfg(feature = "encrypted-volumes")]
+            "EncryptedVolumes" => MenuType::EncryptedVolumes,
+            #[cfg(feature = "usbguard")]
+            "UsbGuard" => MenuType::UsbGuard,
+            #[cfg(feature = "backup")]
+            "Backup" => MenuType::Backup,
+            #[cfg(feature = "modem")]
+            "Modem" => MenuType::Modem,
+            #[cfg(feature = "gpu")]
+            "Gpu" => MenuType::Gpu,
+            #[cfg(feature = "dashboard")]
+            "Dashboard" => MenuType::Dashboard,
+            #[cfg(feature = "audio")]
+            "Audio" => MenuType::Audio,
+            #[cfg(feature = "network")]
+            "Network" => MenuType::Network,
+            #[cfg(feature = "bluetooth")]
+            "Bluetooth" => MenuType::Bluetooth,
+            #[cfg(feature = "notifications")]
+            "Notifications" => MenuType::Notifications,
+            #[cfg(feature = "clock")]
+            "Clock" => MenuType::Clock,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            #[cfg(feature = "updates")]
+            MenuType::Updates => "Updates",
+            MenuType::Settings => "Settings",
+            #[cfg(feature = "media-player")]
+            MenuType::MediaPlayer => "MediaPlayer",
+            #[cfg(feature = "system-info")]
+            MenuType::SystemInfo => "SystemInfo",
+            #[cfg(feature = "autostart")]
+            MenuType::Autostart => "Autostart",
+            #[cfg(feature = "notes")]
+            MenuType::Notes => "Notes",
+            #[cfg(feature = "qr-code")]
+            MenuType::QrCode => "QrCode",
+            #[cfg(feature = "totp")]
+            MenuType::Totp => "Totp",
+            #[cfg(feature = "passwords")]
+            MenuType::Passwords => "Passwords",
+            #[cfg(feature = "encrypted-volumes")]
+            MenuType::EncryptedVolumes => "EncryptedVolumes",
+            #[cfg(feature = "usbguard")]
+            MenuType::UsbGuard => "UsbGuard",
+            #[cfg(feature = "backup")]
+            MenuType::Backup => "Backup",
+            #[cfg(feature = "modem")]
+            MenuType::Modem => "Modem",
+            #[cfg(feature = "gpu")]
+            MenuType::Gpu => "Gpu",
+            #[cfg(feature = "dashboard")]
+            MenuType::Dashboard => "Dashboard",
+            #[cfg(feature = "audio")]
+            MenuType::Audio => "Audio",
+            #[cfg(feature = "network")]
+            MenuType::Network => "Network",
+            #[cfg(feature = "bluetooth")]
+            MenuType::Bluetooth => "Bluetooth",
+            #[cfg(feature = "notifications")]
+            MenuType::Notifications => "Notifications",
+            #[cfg(feature = "clock")]
+            MenuType::Clock => "Clock",
+            #[cfg(feature = "tray")]
+            MenuType::Tray(_) => return None,
+            #[cfg(feature = "window-rules")]
+            MenuType::WindowRules => return None,
+            MenuType::Context => return None,
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -111,6 +247,7 @@ impl Menu {
 }
 
 #[allow(unused)]
+#[derive(Clone, Copy)]
 pub enum MenuSize {
     Small,
     Medium,
@@ -118,7 +255,7 @@ pub enum MenuSize {
 }
 
 impl MenuSize {
-    fn size(&self) -> f32 {
+    pub fn size(&self) -> f32 {
         match self {
             MenuSize::Small => 250.,
             MenuSize::Medium => 350.,
@@ -128,6 +265,39 @@ impl MenuSize {
 }
 
 impl App {
+    /// Background and border of a menu, shared with the pinned popups
+    pub fn menu_panel<'a>(
+        &'a self,
+        content: Element<'a, app::Message>,
+        menu_size: MenuSize,
+    ) -> Container<'a, app::Message> {
+        container(content)
+            .height(Length::Shrink)
+            .width(Length::Shrink)
+            .max_width(menu_size.size())
+            .padding(self.theme.space.md)
+            .style(move |theme: &Theme| Style {
+                background: Some(
+                    theme
+                        .palette()
+                        .background
+                        .scale_alpha(self.theme.menu.opacity)
+                        .into(),
+                ),
+                border: Border {
+                    color: theme
+                        .extended_palette()
+                        .secondary
+                        .base
+                        .color
+                        .scale_alpha(self.theme.menu.opacity),
+                    width: 1.,
+                    radius: self.theme.radius.lg.into(),
+                },
+                ..Default::default()
+            })
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn menu_wrapper<'a>(
         &'a self,
@@ -138,34 +308,7 @@ impl App {
     ) -> Element<'a, app::Message> {
         mouse_area(
             container(
-                mouse_area(
-                    container(content)
-                        .height(Length::Shrink)
-                        .width(Length::Shrink)
-                        .max_width(menu_size.size())
-                        .padding(self.theme.space.md)
-                        .style(move |theme: &Theme| Style {
-                            background: Some(
-                                theme
-                                    .palette()
-                                    .background
-                                    .scale_alpha(self.theme.menu.opacity)
-                                    .into(),
-                            ),
-                            border: Border {
-                                color: theme
-                                    .extended_palette()
-                                    .secondary
-                                    .base
-                                    .color
-                                    .scale_alpha(self.theme.menu.opacity),
-                                width: 1.,
-                                radius: self.theme.radius.lg.into(),
-                            },
-                            ..Default::default()
-                        }),
-                )
-                .on_release(app::Message::None),
+                mouse_area(self.menu_panel(content, menu_size)).on_release(app::Message::None),
             )
             .align_y(match self.theme.bar_position {
                 Position::Top => Vertical::Top,
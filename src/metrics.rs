@@ -0,0 +1,140 @@
+use crate::{
+    config::MetricsConfig,
+    utils::stats::{Sample, Sampler},
+};
+use iced::{Subscription, time::every};
+use log::{debug, error};
+use std::{fmt::Write, fs, path::PathBuf, time::Duration};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Export,
+}
+
+/// Label values are quoted, the quotes and backslashes in them are escaped
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The sample in the Prometheus text format
+fn format(sample: &Sample) -> String {
+    let mut out = String::new();
+    let mut gauge = |name: &str, help: &str, values: &[(Option<String>, f32)]| {
+        if values.is_empty() {
+            return;
+        }
+
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        for (sensor, value) in values {
+            match sensor {
+                Some(sensor) => {
+                    let _ = writeln!(out, "{name}{{sensor=\"{}\"}} {value}", escape_label(sensor));
+                }
+                None => {
+                    let _ = writeln!(out, "{name} {value}");
+                }
+            }
+        }
+    };
+
+    gauge(
+        "ashell_battery_percent",
+        "Average charge of the batteries.",
+        &sample
+            .battery
+            .map(|battery| vec![(None, battery)])
+            .unwrap_or_default(),
+    );
+    gauge(
+        "ashell_cpu_usage_percent",
+        "Global CPU usage.",
+        &[(None, sample.cpu)],
+    );
+    gauge(
+        "ashell_network_receive_kilobytes_per_second",
+        "Download throughput of the network interfaces, without the loopback.",
+        &[(None, sample.download)],
+    );
+    gauge(
+        "ashell_network_transmit_kilobytes_per_second",
+        "Upload throughput of the network interfaces, without the loopback.",
+        &[(None, sample.upload)],
+    );
+    gauge(
+        "ashell_temperature_celsius",
+        "Temperature of the sensors.",
+        &sample
+            .temperatures
+            .iter()
+            .map(|(sensor, temperature)| (Some(sensor.clone()), *temperature))
+            .collect::<Vec<_>>(),
+    );
+
+    out
+}
+
+/// Replaces the file at once, the collector never reads a partial file
+fn write_atomically(path: &PathBuf, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // the collector only reads the `.prom` files
+    let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Writes the collected stats for the textfile collector of node_exporter
+pub struct Metrics {
+    config: MetricsConfig,
+    sampler: Sampler,
+}
+
+impl Metrics {
+    pub fn new(config: MetricsConfig) -> Self {
+        Self {
+            config,
+            sampler: Sampler::new(),
+        }
+    }
+
+    pub fn reload(&mut self, config: MetricsConfig) {
+        self.config = config;
+    }
+
+    fn textfile(&self) -> Option<PathBuf> {
+        self.config
+            .textfile
+            .as_ref()
+            .and_then(|path| shellexpand::full(path).ok())
+            .map(|path| PathBuf::from(path.as_ref()))
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Export => {
+                let Some(path) = self.textfile() else {
+                    return;
+                };
+
+                let sample = self.sampler.sample();
+                match write_atomically(&path, &format(&sample)) {
+                    Ok(()) => debug!("metrics written to {}", path.display()),
+                    Err(e) => error!("failed to write the metrics to {}: {e}", path.display()),
+                }
+            }
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.config.textfile.is_some() {
+            every(Duration::from_secs(self.config.interval.max(1))).map(|_| Message::Export)
+        } else {
+            Subscription::none()
+        }
+    }
+}
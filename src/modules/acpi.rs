@@ -0,0 +1,97 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    services::{
+        ReadOnlyService, ServiceEvent,
+        acpi::{AcpiEvent, AcpiService},
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Subscription, Task,
+    widget::{Row, row, text},
+};
+use std::time::Duration;
+use tokio::time::sleep;
+
+const POWER_BUTTON_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<AcpiService>),
+    HidePowerButton(usize),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+#[derive(Default)]
+pub struct Acpi {
+    service: Option<AcpiService>,
+    power_button: bool,
+    presses: usize,
+}
+
+impl Acpi {
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                    Action::None
+                }
+                ServiceEvent::Update(event) => match self.service.as_mut() {
+                    Some(service) => {
+                        service.update(event);
+
+                        // a press that logind ignores would otherwise go unnoticed
+                        if event == AcpiEvent::PowerButton && service.power_key_ignored {
+                            self.power_button = true;
+                            self.presses += 1;
+
+                            let presses = self.presses;
+                            Action::Command(Task::perform(sleep(POWER_BUTTON_TIMEOUT), move |_| {
+                                Message::HidePowerButton(presses)
+                            }))
+                        } else {
+                            Action::None
+                        }
+                    }
+                    None => Action::None,
+                },
+                ServiceEvent::Error(_) => Action::None,
+            },
+            Message::HidePowerButton(presses) => {
+                // a newer press restarted the timeout
+                if presses == self.presses {
+                    self.power_button = false;
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let service = self.service.as_ref()?;
+
+        if !service.tablet_mode && !self.power_button {
+            return None;
+        }
+
+        Some(
+            Row::new()
+                .push_maybe(service.tablet_mode.then(|| icon(StaticIcon::Tablet)))
+                .push_maybe(self.power_button.then(|| {
+                    row!(icon(StaticIcon::Power), text("Power button")).spacing(theme.space.xxs)
+                }))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xs)
+                .into(),
+        )
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        AcpiService::subscribe().map(Message::Event)
+    }
+}
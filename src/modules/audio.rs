@@ -0,0 +1,209 @@
+use crate::{
+    components::icons::{StaticIcon, icon, icon_button},
+    config::AudioModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        audio::{AudioCommand, AudioService, Device, Sinks, Volume},
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Theme,
+    mouse::ScrollDelta,
+    widget::{Column, MouseArea, button, column, container, horizontal_rule, row, slider, text},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<AudioService>),
+    ToggleMute,
+    VolumeChanged(i32),
+    SinkVolumeChanged(String, i32),
+    /// the streams move to the new default sink
+    DefaultSinkChanged(String),
+    StreamVolumeChanged(u32, i32),
+    ToggleStreamMute(u32),
+    ConfigReloaded(AudioModuleConfig),
+}
+
+/// Volume in percent, capped to the slider range
+fn percent(volume: &impl Volume) -> i32 {
+    ((volume.get_volume() * 100.).round() as i32).clamp(0, 100)
+}
+
+/// The volume of the default sink on the bar, with its devices and the playing streams in the menu
+pub struct Audio {
+    config: AudioModuleConfig,
+    service: Option<AudioService>,
+}
+
+impl Audio {
+    pub fn new(config: AudioModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+        }
+    }
+
+    fn command(&mut self, command: AudioCommand) {
+        if let Some(service) = self.service.as_mut() {
+            let _ = service.command(command);
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+            Message::ToggleMute => self.command(AudioCommand::ToggleSinkMute),
+            Message::VolumeChanged(volume) => self.command(AudioCommand::SinkVolume(volume)),
+            Message::SinkVolumeChanged(name, volume) => {
+                self.command(AudioCommand::SinkVolumeByName(name, volume));
+            }
+            Message::DefaultSinkChanged(name) => self.command(AudioCommand::MoveOutput(name)),
+            Message::StreamVolumeChanged(index, volume) => {
+                self.command(AudioCommand::StreamVolume(index, volume));
+            }
+            Message::ToggleStreamMute(index) => {
+                self.command(AudioCommand::ToggleStreamMute(index));
+            }
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let service = self.service.as_ref().filter(|s| !s.sinks.is_empty())?;
+        let volume = service.cur_sink_volume;
+        let step = i32::from(self.config.scroll_step);
+
+        Some(
+            MouseArea::new(
+                row!(icon(
+                    service.sinks.get_icon(&service.server_info.default_sink)
+                ))
+                .push_maybe(self.config.show_volume.then(|| text(format!("{volume}%"))))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xxs),
+            )
+            .on_scroll(move |delta| {
+                let delta = match delta {
+                    ScrollDelta::Lines { y, .. } | ScrollDelta::Pixels { y, .. } => y,
+                };
+                Message::VolumeChanged(if delta > 0. {
+                    (volume + step).min(100)
+                } else {
+                    (volume - step).max(0)
+                })
+            })
+            .into(),
+        )
+    }
+
+    fn sink_view<'a>(
+        theme: &'a AshellTheme,
+        sink: &'a Device,
+        default: bool,
+    ) -> Element<'a, Message> {
+        let device_icon = sink
+            .ports
+            .iter()
+            .find(|port| port.active)
+            .map_or(StaticIcon::Speaker3, |port| port.device_type.get_icon());
+        let name = sink.name.clone();
+        let label = row!(icon(device_icon), text(&sink.description))
+            .align_y(Alignment::Center)
+            .spacing(theme.space.sm);
+
+        column!(
+            if default {
+                Into::<Element<'a, Message>>::into(
+                    container(label)
+                        .padding([theme.space.xxs, theme.space.sm])
+                        .style(|theme: &Theme| container::Style {
+                            text_color: Some(theme.palette().success),
+                            ..Default::default()
+                        }),
+                )
+            } else {
+                button(label)
+                    .on_press(Message::DefaultSinkChanged(name.clone()))
+                    .padding([theme.space.xxs, theme.space.sm])
+                    .width(Length::Fill)
+                    .style(theme.ghost_button_style())
+                    .into()
+            },
+            slider(0..=100, percent(&sink.volume), move |volume| {
+                Message::SinkVolumeChanged(name.clone(), volume)
+            })
+            .step(1)
+            .width(Length::Fill),
+        )
+        .spacing(theme.space.xxs)
+        .into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let Some(service) = self.service.as_ref() else {
+            return text("Audio not available").into();
+        };
+
+        let sinks = Column::with_children(service.sinks.iter().map(|sink| {
+            Self::sink_view(theme, sink, sink.name == service.server_info.default_sink)
+        }))
+        .spacing(theme.space.sm);
+
+        let streams = Column::with_children(service.streams.iter().map(|stream| {
+            row!(
+                icon_button(
+                    theme,
+                    if stream.is_mute {
+                        StaticIcon::Speaker0
+                    } else {
+                        StaticIcon::Speaker3
+                    },
+                )
+                .on_press(Message::ToggleStreamMute(stream.index)),
+                text(&stream.name).width(Length::FillPortion(2)),
+                slider(0..=100, percent(&stream.volume), move |volume| {
+                    Message::StreamVolumeChanged(stream.index, volume)
+                })
+                .step(1)
+                .width(Length::FillPortion(3)),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs)
+            .into()
+        }))
+        .spacing(theme.space.xs);
+
+        column!(
+            text("Output devices").size(theme.font_size.lg),
+            horizontal_rule(1),
+            sinks,
+            text("Applications").size(theme.font_size.lg),
+            horizontal_rule(1),
+            if service.streams.is_empty() {
+                Into::<Element<'a, Message>>::into(text("Nothing is playing"))
+            } else {
+                streams.into()
+            },
+        )
+        .spacing(theme.space.sm)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        AudioService::subscribe().map(Message::Event)
+    }
+}
@@ -0,0 +1,472 @@
+use crate::{
+    components::icons::{IconButtonSize, StaticIcon, icon, icon_button},
+    config::{AutostartDef, AutostartRestart},
+    theme::AshellTheme,
+    utils::launcher::execute_command,
+};
+use iced::{
+    Alignment, Element, Length, Task, Theme,
+    futures::SinkExt,
+    stream::channel,
+    widget::{Column, column, container, horizontal_rule, row, text},
+};
+use log::{debug, info, warn};
+use std::{convert, fs, io, path::PathBuf, time::Duration};
+use tokio::{process::Command, time::sleep};
+
+const RESTART_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Started(String, u32),
+    Exited(String, Option<i32>),
+    Failed(String, String),
+    Relaunch(String),
+    Restart(String),
+    ConfigReloaded(Vec<AutostartDef>),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+    Waiting,
+    Starting,
+    Running(u32),
+    /// Started by a previous ashell instance during this session, still running
+    Detached(u32),
+    Exited(Option<i32>),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    def: AutostartDef,
+    status: Status,
+    restarts: u32,
+    restart_requested: bool,
+}
+
+impl Entry {
+    fn new(def: AutostartDef) -> Self {
+        let status = match read_marker(&def.name) {
+            Some(Marker::Running(pid)) => Status::Detached(pid),
+            Some(Marker::Finished) => Status::Exited(Some(0)),
+            // never started, or it died since
+            None => Status::Waiting,
+        };
+
+        Self {
+            def,
+            status,
+            restarts: 0,
+            restart_requested: false,
+        }
+    }
+
+    fn is_up(&self) -> bool {
+        matches!(
+            self.status,
+            Status::Running(_) | Status::Detached(_) | Status::Exited(Some(0))
+        )
+    }
+
+    fn has_failed(&self) -> bool {
+        match self.status {
+            Status::Failed(_) => true,
+            Status::Exited(code) => code != Some(0),
+            _ => false,
+        }
+    }
+
+    fn should_restart(&self, code: Option<i32>) -> bool {
+        let policy = match self.def.restart {
+            AutostartRestart::Never => false,
+            AutostartRestart::OnFailure => code != Some(0),
+            AutostartRestart::Always => true,
+        };
+
+        policy
+            && self
+                .def
+                .max_restarts
+                .is_none_or(|max_restarts| self.restarts < max_restarts)
+    }
+}
+
+fn marker_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR").map(|dir| {
+        PathBuf::from(dir)
+            .join("ashell")
+            .join("autostart")
+            .join(name.replace('/', "_"))
+    })
+}
+
+/// What a previous ashell instance left of an entry
+enum Marker {
+    Running(u32),
+    Finished,
+}
+
+/// Start time of the process, it tells the launched process from a reused pid
+fn start_time(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+
+    // the fields after the command name, which can contain spaces
+    stat.rsplit_once(')')?
+        .1
+        .split_whitespace()
+        .nth(19)?
+        .parse()
+        .ok()
+}
+
+/// The marker lives in the runtime dir, so it's cleared when the session ends
+fn read_marker(name: &str) -> Option<Marker> {
+    let path = marker_path(name)?;
+    let content = fs::read_to_string(path).ok()?;
+
+    if content.trim() == "finished" {
+        return Some(Marker::Finished);
+    }
+
+    let (pid, started) = content.trim().split_once(' ')?;
+    let pid = pid.parse::<u32>().ok()?;
+
+    (start_time(pid)? == started.parse::<u64>().ok()?).then_some(Marker::Running(pid))
+}
+
+fn write_marker(name: &str, content: &str) {
+    if let Some(path) = marker_path(name) {
+        let res = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, content));
+
+        if let Err(err) = res {
+            warn!("Failed to write autostart marker {path:?}: {err}");
+        }
+    }
+}
+
+/// A failed entry is launched again by the next ashell instance
+fn remove_marker(name: &str) {
+    if let Some(path) = marker_path(name)
+        && let Err(err) = fs::remove_file(&path)
+        && err.kind() != io::ErrorKind::NotFound
+    {
+        warn!("Failed to remove autostart marker {path:?}: {err}");
+    }
+}
+
+fn launch(def: &AutostartDef) -> Task<Message> {
+    let name = def.name.clone();
+    let command = def.command.clone();
+
+    info!("Launching autostart entry {name}: {command}");
+
+    Task::run(
+        channel(2, async move |mut output| {
+            match Command::new("bash").arg("-c").arg(&command).spawn() {
+                Ok(mut child) => {
+                    let pid = child.id().unwrap_or_default();
+                    let _ = output.send(Message::Started(name.clone(), pid)).await;
+
+                    let code = child.wait().await.ok().and_then(|status| status.code());
+                    let _ = output.send(Message::Exited(name, code)).await;
+                }
+                Err(err) => {
+                    let _ = output.send(Message::Failed(name, err.to_string())).await;
+                }
+            }
+        }),
+        convert::identity,
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct Autostart {
+    entries: Vec<Entry>,
+}
+
+impl Autostart {
+    pub fn new(defs: Vec<AutostartDef>) -> Self {
+        for def in &defs {
+            for dep in &def.after {
+                if !defs.iter().any(|d| &d.name == dep) {
+                    warn!(
+                        "Autostart entry {} depends on unknown entry {dep}",
+                        def.name
+                    );
+                }
+            }
+        }
+
+        Self {
+            entries: defs.into_iter().map(Entry::new).collect(),
+        }
+    }
+
+    /// Launches every entry that is still waiting and whose dependencies are up
+    pub fn start(&mut self) -> Task<Message> {
+        let ready = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry.status == Status::Waiting
+                    && entry.def.after.iter().all(|dep| {
+                        self.entries
+                            .iter()
+                            .find(|e| &e.def.name == dep)
+                            .is_none_or(Entry::is_up)
+                    })
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        Task::batch(ready.into_iter().map(|index| {
+            let entry = &mut self.entries[index];
+            entry.status = Status::Starting;
+
+            launch(&entry.def)
+        }))
+    }
+
+    fn entry_mut(&mut self, name: &str) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|entry| entry.def.name == name)
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Started(name, pid) => {
+                if let Some(entry) = self.entry_mut(&name) {
+                    entry.status = Status::Running(pid);
+                    if let Some(started) = start_time(pid) {
+                        write_marker(&name, &format!("{pid} {started}"));
+                    }
+                }
+
+                Action::Command(self.start())
+            }
+            Message::Exited(name, code) => {
+                let Some(entry) = self.entry_mut(&name) else {
+                    return Action::None;
+                };
+
+                debug!("Autostart entry {name} exited with {code:?}");
+                entry.status = Status::Exited(code);
+                if code == Some(0) {
+                    write_marker(&name, "finished");
+                } else {
+                    remove_marker(&name);
+                }
+
+                if entry.restart_requested {
+                    entry.restart_requested = false;
+                    entry.status = Status::Starting;
+
+                    Action::Command(launch(&entry.def))
+                } else if entry.should_restart(code) {
+                    entry.restarts += 1;
+                    entry.status = Status::Starting;
+
+                    Action::Command(Task::perform(sleep(RESTART_DELAY), move |_| {
+                        Message::Relaunch(name)
+                    }))
+                } else {
+                    Action::Command(self.start())
+                }
+            }
+            Message::Failed(name, err) => {
+                warn!("Failed to launch autostart entry {name}: {err}");
+
+                if let Some(entry) = self.entry_mut(&name) {
+                    entry.status = Status::Failed(err);
+                    remove_marker(&name);
+                }
+
+                Action::None
+            }
+            Message::Relaunch(name) => match self.entry_mut(&name) {
+                Some(entry) => Action::Command(launch(&entry.def)),
+                None => Action::None,
+            },
+            Message::Restart(name) => {
+                let Some(entry) = self.entry_mut(&name) else {
+                    return Action::None;
+                };
+
+                entry.restarts = 0;
+
+                match entry.status {
+                    Status::Running(pid) => {
+                        entry.restart_requested = true;
+                        execute_command(format!("kill {pid}"));
+
+                        Action::None
+                    }
+                    Status::Starting => Action::None,
+                    Status::Detached(pid) => {
+                        execute_command(format!("kill {pid}"));
+                        entry.status = Status::Starting;
+
+                        Action::Command(launch(&entry.def))
+                    }
+                    _ => {
+                        entry.status = Status::Starting;
+
+                        Action::Command(launch(&entry.def))
+                    }
+                }
+            }
+            Message::ConfigReloaded(defs) => {
+                let mut entries = Vec::with_capacity(defs.len());
+                for def in defs {
+                    match self.entries.iter().position(|e| e.def.name == def.name) {
+                        Some(index) => {
+                            let mut entry = self.entries.swap_remove(index);
+                            entry.def = def;
+                            entries.push(entry);
+                        }
+                        None => entries.push(Entry::new(def)),
+                    }
+                }
+                self.entries = entries;
+
+                Action::Command(self.start())
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let failed = self.entries.iter().filter(|e| e.has_failed()).count();
+
+        Some(
+            row!(icon(StaticIcon::Rocket))
+                .push_maybe(if failed > 0 {
+                    Some(
+                        container(text(failed)).style(|theme: &Theme| container::Style {
+                            text_color: Some(theme.palette().danger),
+                            ..Default::default()
+                        }),
+                    )
+                } else {
+                    None
+                })
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xxs)
+                .into(),
+        )
+    }
+
+    /// The failed entries the entry waits for, directly or through another waiting entry
+    fn blocked_by(&self, entry: &Entry, visited: &mut Vec<String>) -> Vec<String> {
+        let mut failed = Vec::new();
+
+        for dep in &entry.def.after {
+            if visited.contains(dep) {
+                continue;
+            }
+            visited.push(dep.clone());
+
+            match self.entries.iter().find(|e| &e.def.name == dep) {
+                Some(e) if e.has_failed() => failed.push(dep.clone()),
+                Some(e) if e.status == Status::Waiting => {
+                    failed.extend(self.blocked_by(e, visited))
+                }
+                _ => {}
+            }
+        }
+
+        failed
+    }
+
+    fn is_blocked(&self, entry: &Entry) -> bool {
+        entry.status == Status::Waiting && !self.blocked_by(entry, &mut Vec::new()).is_empty()
+    }
+
+    fn status_label(&self, entry: &Entry) -> String {
+        match &entry.status {
+            Status::Waiting => {
+                let blocked = self.blocked_by(entry, &mut Vec::new());
+                if !blocked.is_empty() {
+                    return format!("Blocked by {}", blocked.join(", "));
+                }
+
+                let pending = entry
+                    .def
+                    .after
+                    .iter()
+                    .filter(|dep| {
+                        self.entries
+                            .iter()
+                            .any(|e| &e.def.name == *dep && !e.is_up())
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                format!("Waiting for {}", pending.join(", "))
+            }
+            Status::Starting => "Starting".to_string(),
+            Status::Running(pid) => format!("Running ({pid})"),
+            Status::Detached(pid) => format!("Started earlier ({pid})"),
+            Status::Exited(Some(0)) => "Finished".to_string(),
+            Status::Exited(Some(code)) => format!("Exited ({code})"),
+            Status::Exited(None) => "Killed".to_string(),
+            Status::Failed(err) => format!("Failed: {err}"),
+        }
+    }
+
+    pub fn menu_view(&'_ self, theme: &AshellTheme) -> Element<'_, Message> {
+        column!(
+            text("Autostart").size(theme.font_size.lg),
+            horizontal_rule(1),
+            Column::with_children(
+                self.entries
+                    .iter()
+                    .map(|entry| {
+                        let failed = entry.has_failed() || self.is_blocked(entry);
+
+                        row!(
+                            column!(
+                                text(entry.def.name.clone()),
+                                container(text(self.status_label(entry)).size(theme.font_size.xs))
+                                    .style(move |theme: &Theme| container::Style {
+                                        text_color: if failed {
+                                            Some(theme.palette().danger)
+                                        } else {
+                                            None
+                                        },
+                                        ..Default::default()
+                                    }),
+                            )
+                            .width(Length::Fill),
+                            icon_button(theme, StaticIcon::Refresh)
+                                .on_press_maybe(if entry.status == Status::Starting {
+                                    None
+                                } else {
+                                    Some(Message::Restart(entry.def.name.clone()))
+                                })
+                                .size(IconButtonSize::Small),
+                        )
+                        .align_y(Alignment::Center)
+                        .spacing(theme.space.xs)
+                        .into()
+                    })
+                    .collect::<Vec<Element<_>>>(),
+            )
+            .spacing(theme.space.xs),
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::{BackupBackend, BackupModuleConfig},
+    theme::AshellTheme,
+    utils::launcher::{command_output, shell_quote},
+};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, Theme,
+    futures::SinkExt,
+    stream::channel,
+    widget::{button, column, container, horizontal_rule, row, text},
+};
+use log::{debug, info, warn};
+use std::{any::TypeId, convert, fs, process::Stdio, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+    time::sleep,
+};
+
+fn parse_borg(output: &str) -> Option<DateTime<Local>> {
+    let json = serde_json::from_str::<serde_json::Value>(output).ok()?;
+    let time = json["archives"].as_array()?.last()?["time"].as_str()?;
+
+    // borg reports the local time without offset
+    let time = NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+    Local.from_local_datetime(&time).earliest()
+}
+
+fn parse_restic(output: &str) -> Option<DateTime<Local>> {
+    let json = serde_json::from_str::<serde_json::Value>(output).ok()?;
+
+    // restic returns the latest snapshot of every host and path
+    json.as_array()?
+        .iter()
+        .filter_map(|snapshot| snapshot["time"].as_str())
+        .filter_map(|time| DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.with_timezone(&Local))
+        .max()
+}
+
+fn parse_timeshift(snapshots_dir: &str) -> Option<DateTime<Local>> {
+    fs::read_dir(snapshots_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            NaiveDateTime::parse_from_str(&entry.file_name().to_string_lossy(), "%Y-%m-%d_%H-%M-%S")
+                .ok()
+        })
+        .max()
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+}
+
+/// Uses the timestamp written in the file, falling back to its modification time
+fn parse_status_file(status_file: &str) -> Option<DateTime<Local>> {
+    let content = fs::read_to_string(status_file).ok()?;
+
+    content
+        .lines()
+        .next()
+        .and_then(|line| DateTime::parse_from_rfc3339(line.trim()).ok())
+        .map(|time| time.with_timezone(&Local))
+        .or_else(|| {
+            fs::metadata(status_file)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(DateTime::from)
+        })
+}
+
+async fn last_backup(config: BackupModuleConfig) -> Result<DateTime<Local>, String> {
+    let last_backup = match config.backend {
+        BackupBackend::Borg => {
+            let repository = config
+                .repository
+                .as_deref()
+                .map(shell_quote)
+                .unwrap_or_default();
+
+            command_output(&format!("borg list --last 1 --json {repository}"))
+                .await
+                .as_deref()
+                .and_then(parse_borg)
+        }
+        BackupBackend::Restic => {
+            let repository = config
+                .repository
+                .as_deref()
+                .map(|repository| format!("-r {}", shell_quote(repository)))
+                .unwrap_or_default();
+
+            command_output(&format!("restic snapshots --latest 1 --json {repository}"))
+                .await
+                .as_deref()
+                .and_then(parse_restic)
+        }
+        BackupBackend::Timeshift => parse_timeshift(&config.snapshots_dir),
+        BackupBackend::StatusFile => config.status_file.as_deref().and_then(parse_status_file),
+    };
+
+    last_backup.ok_or_else(|| format!("Unable to read the last backup from {:?}", config.backend))
+}
+
+/// Formats the time since the last backup, e.g. `45m`, `5h` or `3d`
+fn format_age(last_backup: DateTime<Local>) -> String {
+    let age = Local::now().signed_duration_since(last_backup);
+
+    if age.num_hours() < 1 {
+        format!("{}m", age.num_minutes().max(0))
+    } else if age.num_hours() < 48 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}d", age.num_days())
+    }
+}
+
+fn run_backup(command: String) -> Task<Message> {
+    info!("Running backup: {command}");
+
+    Task::run(
+        channel(10, async move |mut output| {
+            let child = Command::new("bash")
+                .arg("-c")
+                .arg(format!("{command} 2>&1"))
+                .stdout(Stdio::piped())
+                .spawn();
+
+            match child {
+                Ok(mut child) => {
+                    if let Some(stdout) = child.stdout.take() {
+                        // progress bars redraw the same line using carriage returns
+                        let mut chunks = BufReader::new(stdout).split(b'\r');
+
+                        while let Some(chunk) = chunks.next_segment().await.ok().flatten() {
+                            let chunk = String::from_utf8_lossy(&chunk);
+
+                            if let Some(line) = chunk.lines().rfind(|line| !line.trim().is_empty())
+                            {
+                                let _ =
+                                    output.send(Message::Progress(line.trim().to_owned())).await;
+                            }
+                        }
+                    }
+
+                    let code = child.wait().await.ok().and_then(|status| status.code());
+                    let _ = output.send(Message::Finished(code)).await;
+                }
+                Err(err) => {
+                    let _ = output.send(Message::Failed(err.to_string())).await;
+                }
+            }
+        }),
+        convert::identity,
+    )
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Checked(Result<DateTime<Local>, String>),
+    RunBackup,
+    Progress(String),
+    Finished(Option<i32>),
+    Failed(String),
+    ConfigReloaded(BackupModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+enum Run {
+    Running(Option<String>),
+    Succeeded,
+    Failed(String),
+}
+
+pub struct Backup {
+    config: BackupModuleConfig,
+    last_backup: Option<Result<DateTime<Local>, String>>,
+    run: Option<Run>,
+}
+
+impl Backup {
+    pub fn new(config: BackupModuleConfig) -> Self {
+        Self {
+            config,
+            last_backup: None,
+            run: None,
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(self.run, Some(Run::Running(_)))
+    }
+
+    fn is_overdue(&self) -> bool {
+        match &self.last_backup {
+            Some(Ok(last_backup)) => {
+                Local::now().signed_duration_since(*last_backup).num_hours()
+                    >= self.config.max_age as i64
+            }
+            Some(Err(_)) => true,
+            None => false,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Checked(last_backup) => {
+                if let Err(err) = &last_backup {
+                    warn!("{err}");
+                }
+                self.last_backup = Some(last_backup);
+
+                Action::None
+            }
+            Message::RunBackup => match self.config.backup_cmd.clone() {
+                Some(command) if !self.is_running() => {
+                    self.run = Some(Run::Running(None));
+
+                    Action::Command(run_backup(command))
+                }
+                _ => Action::None,
+            },
+            Message::Progress(line) => {
+                debug!("Backup progress: {line}");
+                self.run = Some(Run::Running(Some(line)));
+
+                Action::None
+            }
+            Message::Finished(code) => {
+                self.run = Some(if code == Some(0) {
+                    Run::Succeeded
+                } else {
+                    let err = match code {
+                        Some(code) => format!("Backup failed with exit code {code}"),
+                        None => "Backup terminated by a signal".to_string(),
+                    };
+                    warn!("{err}");
+
+                    Run::Failed(err)
+                });
+
+                Action::Command(Task::perform(
+                    last_backup(self.config.clone()),
+                    Message::Checked,
+                ))
+            }
+            Message::Failed(err) => {
+                warn!("Failed to run the backup: {err}");
+                self.run = Some(Run::Failed(err));
+
+                Action::None
+            }
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Element<'_, Message> {
+        let label = if self.is_running() {
+            Some("...".to_string())
+        } else {
+            match &self.last_backup {
+                Some(Ok(last_backup)) => Some(format_age(*last_backup)),
+                Some(Err(_)) => Some("?".to_string()),
+                None => None,
+            }
+        };
+        let overdue = self.is_overdue() && !self.is_running();
+
+        container(
+            row!(icon(StaticIcon::Backup))
+                .push_maybe(label.map(text))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xxs),
+        )
+        .style(move |theme: &Theme| container::Style {
+            text_color: overdue.then(|| theme.palette().danger),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let overdue = self.is_overdue();
+        let status = match &self.last_backup {
+            Some(Ok(last_backup)) => format!(
+                "Last backup {} ago, {}",
+                format_age(*last_backup),
+                last_backup.format("%Y-%m-%d %H:%M")
+            ),
+            Some(Err(err)) => err.clone(),
+            None => "Checking the last backup...".to_string(),
+        };
+
+        column!(
+            text("Backup").size(theme.font_size.lg),
+            horizontal_rule(1),
+            container(text(status)).style(move |theme: &Theme| container::Style {
+                text_color: overdue.then(|| theme.palette().danger),
+                ..Default::default()
+            }),
+        )
+        .push_maybe(overdue.then(|| {
+            text(format!("Older than {} hours", self.config.max_age)).size(theme.font_size.xs)
+        }))
+        .push_maybe(self.run.as_ref().map(|run| {
+            match run {
+                Run::Running(progress) => column!(text("Backup in progress..."))
+                    .push_maybe(progress.as_ref().map(|line| {
+                        text(line.clone())
+                            .size(theme.font_size.xs)
+                            .wrapping(text::Wrapping::WordOrGlyph)
+                    }))
+                    .spacing(theme.space.xxs)
+                    .into(),
+                Run::Succeeded => Element::from(text("Backup completed")),
+                Run::Failed(err) => container(text(err.clone()).size(theme.font_size.xs))
+                    .style(|theme: &Theme| container::Style {
+                        text_color: Some(theme.palette().danger),
+                        ..Default::default()
+                    })
+                    .into(),
+            }
+        }))
+        .push_maybe(self.config.backup_cmd.as_ref().map(|_| {
+            button(text("Run backup now").width(Length::Fill).center())
+                .style(theme.confirm_button_style())
+                .padding([theme.space.xxs, theme.space.sm])
+                .width(Length::Fill)
+                .on_press_maybe((!self.is_running()).then_some(Message::RunBackup))
+        }))
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let config = self.config.clone();
+
+        Subscription::run_with_id(
+            (TypeId::of::<Self>(), config.clone()),
+            channel(1, async move |mut output| {
+                loop {
+                    let last_backup = last_backup(config.clone()).await;
+                    let _ = output.send(Message::Checked(last_backup)).await;
+
+                    sleep(Duration::from_secs(config.check_interval.max(1))).await;
+                }
+            }),
+        )
+    }
+}
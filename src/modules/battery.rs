@@ -0,0 +1,164 @@
+use crate::{
+    components::icons::icon,
+    config::{BatteryFormat, BatteryModuleConfig},
+    services::{
+        ReadOnlyService, ServiceEvent,
+        upower::{BatteryData, BatteryStatus, UPowerService},
+    },
+    theme::AshellTheme,
+    utils::{IndicatorState, format_duration, launcher::execute_command},
+};
+use iced::{
+    Alignment, Element, Subscription, Theme,
+    widget::{Row, container, mouse_area, text},
+};
+use log::warn;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<UPowerService>),
+    Hovered(bool),
+    ConfigReloaded(BatteryModuleConfig),
+}
+
+pub enum Action {
+    None,
+    /// the discharging battery just went under the alert threshold
+    AlertReached,
+}
+
+/// The system battery from UPower, colored when its charge is low
+pub struct Battery {
+    config: BatteryModuleConfig,
+    service: Option<UPowerService>,
+    hovered: bool,
+    /// level reached while discharging, 1 past the warn threshold and 2 past the alert one
+    alert_level: u8,
+}
+
+impl Battery {
+    pub fn new(config: BatteryModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+            hovered: false,
+            alert_level: 0,
+        }
+    }
+
+    fn state(&self, battery: &BatteryData) -> IndicatorState {
+        match battery.status {
+            BatteryStatus::Charging(_) => IndicatorState::Success,
+            BatteryStatus::Discharging(_) if battery.capacity <= self.config.alert_threshold => {
+                IndicatorState::Danger
+            }
+            BatteryStatus::Discharging(_) if battery.capacity <= self.config.warn_threshold => {
+                IndicatorState::Warning
+            }
+            _ => IndicatorState::Normal,
+        }
+    }
+
+    /// Runs the command of a threshold once, when the battery goes past it
+    fn check_alerts(&mut self) -> Action {
+        let Some(battery) = self.service.as_ref().and_then(|s| s.system_battery) else {
+            return Action::None;
+        };
+
+        let (level, cmd) = match self.state(&battery) {
+            IndicatorState::Danger => (2, &self.config.alert_cmd),
+            IndicatorState::Warning => (1, &self.config.warn_cmd),
+            _ => (0, &None),
+        };
+
+        let reached = level > self.alert_level;
+        if reached {
+            warn!("The battery is low: {}%", battery.capacity);
+
+            if let Some(cmd) = cmd {
+                execute_command(cmd.clone());
+            }
+        }
+        self.alert_level = level;
+
+        if reached && level == 2 {
+            Action::AlertReached
+        } else {
+            Action::None
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                    self.check_alerts()
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                    self.check_alerts()
+                }
+                ServiceEvent::Error(_) => Action::None,
+            },
+            Message::Hovered(hovered) => {
+                self.hovered = hovered;
+                Action::None
+            }
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let battery = self.service.as_ref()?.system_battery?;
+        let state = self.state(&battery);
+
+        // the remaining time replaces the percentage on hover
+        let remaining = match battery.status {
+            BatteryStatus::Charging(remaining) if !remaining.is_zero() => {
+                Some(format!("Full in {}", format_duration(&remaining)))
+            }
+            BatteryStatus::Discharging(remaining) if !remaining.is_zero() => {
+                Some(format!("Empty in {}", format_duration(&remaining)))
+            }
+            _ => None,
+        };
+        let label = remaining.filter(|_| self.hovered).or_else(|| {
+            (self.config.format != BatteryFormat::Icon).then(|| format!("{}%", battery.capacity))
+        });
+        let show_icon = self.config.format != BatteryFormat::Percentage;
+
+        Some(
+            mouse_area(
+                container(
+                    Row::new()
+                        .push_maybe(show_icon.then(|| icon(battery.get_icon())))
+                        .push_maybe(label.map(text))
+                        .align_y(Alignment::Center)
+                        .spacing(theme.space.xxs),
+                )
+                .style(move |theme: &Theme| container::Style {
+                    text_color: Some(match state {
+                        IndicatorState::Success => theme.palette().success,
+                        IndicatorState::Warning => theme.extended_palette().danger.weak.color,
+                        IndicatorState::Danger => theme.palette().danger,
+                        IndicatorState::Normal => theme.palette().text,
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .on_enter(Message::Hovered(true))
+            .on_exit(Message::Hovered(false))
+            .into(),
+        )
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        UPowerService::subscribe().map(Message::Event)
+    }
+}
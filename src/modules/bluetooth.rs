@@ -0,0 +1,278 @@
+use crate::{
+    components::icons::{IconButtonSize, StaticIcon, icon, icon_button},
+    config::BluetoothModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        bluetooth::{BluetoothCommand, BluetoothDevice, BluetoothService, BluetoothState},
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, Theme,
+    widget::{
+        Column, Row, button, column, container, horizontal_rule, row, scrollable, text, toggler,
+    },
+};
+use itertools::Itertools;
+use zbus::zvariant::OwnedObjectPath;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<BluetoothService>),
+    TogglePower,
+    ToggleDiscovery,
+    PairDevice(OwnedObjectPath),
+    ConnectDevice(OwnedObjectPath),
+    DisconnectDevice(OwnedObjectPath),
+    RemoveDevice(OwnedObjectPath),
+    ConfigReloaded(BluetoothModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+/// The adapter and the connected devices on the bar, with the device management in the menu
+pub struct Bluetooth {
+    config: BluetoothModuleConfig,
+    service: Option<BluetoothService>,
+}
+
+impl Bluetooth {
+    pub fn new(config: BluetoothModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+        }
+    }
+
+    fn command(&mut self, command: BluetoothCommand) -> Action {
+        match self.service.as_mut() {
+            Some(service) => Action::Command(service.command(command).map(Message::Event)),
+            None => Action::None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                    Action::None
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                    Action::None
+                }
+                ServiceEvent::Error(_) => Action::None,
+            },
+            Message::TogglePower => self.command(BluetoothCommand::Toggle),
+            Message::ToggleDiscovery => match self.service.as_ref() {
+                Some(service) if service.discovering => {
+                    self.command(BluetoothCommand::StopDiscovery)
+                }
+                _ => self.command(BluetoothCommand::StartDiscovery),
+            },
+            Message::PairDevice(path) => self.command(BluetoothCommand::PairDevice(path)),
+            Message::ConnectDevice(path) => self.command(BluetoothCommand::ConnectDevice(path)),
+            Message::DisconnectDevice(path) => {
+                self.command(BluetoothCommand::DisconnectDevice(path))
+            }
+            Message::RemoveDevice(path) => self.command(BluetoothCommand::RemoveDevice(path)),
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+                Action::None
+            }
+        }
+    }
+
+    fn battery_label<'a>(battery: u8) -> Element<'a, Message> {
+        container(text(format!("{battery}%")))
+            .style(move |theme: &Theme| container::Style {
+                text_color: (battery <= 20).then(|| theme.palette().danger),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let service = self.service.as_ref()?;
+
+        match service.state {
+            BluetoothState::Unavailable => None,
+            BluetoothState::Inactive => Some(icon(StaticIcon::BluetoothOff).into()),
+            BluetoothState::Active => {
+                let connected = service
+                    .devices
+                    .iter()
+                    .filter(|d| d.connected)
+                    .collect::<Vec<_>>();
+
+                if !self.config.show_devices || connected.is_empty() {
+                    return Some(
+                        icon(if connected.is_empty() {
+                            StaticIcon::Bluetooth
+                        } else {
+                            StaticIcon::BluetoothConnected
+                        })
+                        .into(),
+                    );
+                }
+
+                Some(
+                    Row::with_children(connected.into_iter().map(|device| {
+                        Row::new()
+                            .push(icon(device.get_icon()))
+                            .push_maybe(
+                                device
+                                    .battery
+                                    .filter(|_| self.config.show_battery)
+                                    .map(Self::battery_label),
+                            )
+                            .align_y(Alignment::Center)
+                            .spacing(theme.space.xxs)
+                            .into()
+                    }))
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xs)
+                    .into(),
+                )
+            }
+        }
+    }
+
+    fn device_view<'a>(theme: &AshellTheme, device: &'a BluetoothDevice) -> Element<'a, Message> {
+        let connected = device.connected;
+        let label = container(
+            row!(
+                icon(device.get_icon()),
+                text(&device.name).width(Length::Fill)
+            )
+            .push_maybe(device.battery.map(Self::battery_label))
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs),
+        )
+        .style(move |theme: &Theme| container::Style {
+            text_color: connected.then(|| theme.palette().success),
+            ..Default::default()
+        });
+
+        let (action, message) = if !device.paired {
+            ("Pair", Message::PairDevice(device.path.clone()))
+        } else if connected {
+            ("Disconnect", Message::DisconnectDevice(device.path.clone()))
+        } else {
+            ("Connect", Message::ConnectDevice(device.path.clone()))
+        };
+
+        row!(
+            label,
+            button(text(action).size(theme.font_size.sm))
+                .style(theme.outline_button_style())
+                .padding([theme.space.xxs, theme.space.sm])
+                .on_press(message),
+        )
+        .push_maybe(device.paired.then(|| {
+            icon_button(theme, StaticIcon::Remove)
+                .on_press(Message::RemoveDevice(device.path.clone()))
+                .color(theme.get_theme().palette().danger)
+                .size(IconButtonSize::Small)
+        }))
+        .align_y(Alignment::Center)
+        .spacing(theme.space.xs)
+        .padding([theme.space.xxs, theme.space.xs])
+        .into()
+    }
+
+    fn devices_section<'a>(
+        theme: &'a AshellTheme,
+        title: &'a str,
+        devices: Vec<&'a BluetoothDevice>,
+    ) -> Option<Element<'a, Message>> {
+        (!devices.is_empty()).then(|| {
+            column!(
+                text(title).size(theme.font_size.sm),
+                horizontal_rule(1),
+                container(scrollable(Column::with_children(
+                    devices
+                        .into_iter()
+                        .map(|device| Self::device_view(theme, device)),
+                )))
+                .max_height(200),
+            )
+            .spacing(theme.space.xs)
+            .into()
+        })
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let Some(service) = self
+            .service
+            .as_ref()
+            .filter(|service| service.state != BluetoothState::Unavailable)
+        else {
+            return text("Bluetooth not available").into();
+        };
+        let powered = service.state == BluetoothState::Active;
+
+        let power = row!(
+            text("Bluetooth").width(Length::Fill),
+            toggler(powered).on_toggle(|_| Message::TogglePower),
+        )
+        .align_y(Alignment::Center);
+
+        if !powered {
+            return power.into();
+        }
+
+        // the connected devices come first
+        let known = service
+            .devices
+            .iter()
+            .filter(|d| d.paired)
+            .sorted_by_key(|d| (!d.connected, &d.name))
+            .collect::<Vec<_>>();
+        let available = service
+            .devices
+            .iter()
+            .filter(|d| !d.paired)
+            .sorted_by_key(|d| &d.name)
+            .collect::<Vec<_>>();
+        let empty = known.is_empty() && available.is_empty();
+
+        column!(
+            power,
+            row!(
+                text(if service.discovering {
+                    "Scanning..."
+                } else {
+                    ""
+                })
+                .size(theme.font_size.sm)
+                .width(Length::Fill),
+                icon_button(
+                    theme,
+                    if service.discovering {
+                        StaticIcon::Close
+                    } else {
+                        StaticIcon::Refresh
+                    },
+                )
+                .on_press(Message::ToggleDiscovery),
+            )
+            .align_y(Alignment::Center),
+        )
+        .push_maybe(Self::devices_section(theme, "Known devices", known))
+        .push_maybe(Self::devices_section(theme, "Available", available))
+        .push_maybe(empty.then(|| text("No devices found")))
+        .spacing(theme.space.sm)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        BluetoothService::subscribe().map(Message::Event)
+    }
+}
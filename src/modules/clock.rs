@@ -1,30 +1,191 @@
-use crate::{config::ClockModuleConfig, theme::AshellTheme};
-use chrono::{DateTime, Local};
-use iced::{Element, Subscription, time::every, widget::text};
+use crate::{
+    components::icons::{IconButtonSize, StaticIcon, icon, icon_button},
+    config::ClockModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        timedate::{NtpStatus, TimedateCommand, TimedateEvent, TimedateService},
+    },
+    theme::AshellTheme,
+};
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, Weekday};
+use chrono_tz::Tz;
+use iced::{
+    Alignment, Border, Element, Length, Subscription, Task, Theme,
+    widget::{Column, Row, button, column, container, horizontal_rule, row, text},
+};
+use itertools::Itertools;
+use log::{info, warn};
 use std::time::Duration;
+use tokio::time::sleep;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Update,
+    Timedate(ServiceEvent<TimedateService>),
+    MenuOpened,
+    Resync,
+    PreviousMonth,
+    NextMonth,
+    ConfigReloaded(ClockModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
 }
 
 pub struct Clock {
     config: ClockModuleConfig,
+    /// the valid time zones of the configuration
+    timezones: Vec<Tz>,
     date: DateTime<Local>,
+    /// first day of the month shown in the calendar
+    month: NaiveDate,
+    service: Option<TimedateService>,
+    pending: bool,
+    error: Option<String>,
+}
+
+fn format_offset(offset: i64) -> String {
+    if offset.abs() < 1_000 {
+        format!("{offset:+}µs")
+    } else if offset.abs() < 1_000_000 {
+        format!("{:+.3}ms", offset as f64 / 1_000.)
+    } else {
+        format!("{:+.3}s", offset as f64 / 1_000_000.)
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn parse_timezones(names: &[String]) -> Vec<Tz> {
+    names
+        .iter()
+        .filter_map(|name| {
+            name.parse::<Tz>()
+                .inspect_err(|_| warn!("Unknown time zone {name}"))
+                .ok()
+        })
+        .collect()
+}
+
+/// The city of the time zone, e.g. `New York` for `America/New_York`
+fn timezone_label(timezone: &Tz) -> String {
+    let name = timezone.name();
+
+    name.rsplit('/').next().unwrap_or(name).replace('_', " ")
+}
+
+fn ntp_label(ntp: &NtpStatus) -> &'static str {
+    match (ntp.enabled, ntp.synchronized) {
+        (_, true) => "Clock synchronized",
+        (true, false) => "Clock not synchronized",
+        (false, false) => "Network time disabled",
+    }
 }
 
 impl Clock {
     pub fn new(config: ClockModuleConfig) -> Self {
+        let date = Local::now();
+
         Self {
+            timezones: parse_timezones(&config.timezones),
             config,
-            date: Local::now(),
+            date,
+            month: first_of_month(date.date_naive()),
+            service: None,
+            pending: false,
+            error: None,
         }
     }
 
-    pub fn update(&mut self, message: Message) {
+    fn command(&mut self, command: TimedateCommand) -> Option<Task<Message>> {
+        let task = self
+            .service
+            .as_mut()?
+            .command(command)
+            .map(Message::Timedate);
+        self.pending = true;
+        self.error = None;
+
+        Some(task)
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
         match message {
             Message::Update => {
                 self.date = Local::now();
+
+                Action::None
+            }
+            Message::Timedate(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+
+                    Action::None
+                }
+                ServiceEvent::Update(event) => {
+                    let timezone = match &event {
+                        TimedateEvent::Timezone(timezone) => Some(timezone.clone()),
+                        TimedateEvent::Ntp(_) => {
+                            self.pending = false;
+
+                            None
+                        }
+                    };
+
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(event);
+                    }
+
+                    match timezone {
+                        Some(timezone) if self.config.follow_timezone => {
+                            info!("Timezone changed to {timezone}");
+
+                            // chrono checks /etc/localtime at most once per second
+                            Action::Command(Task::perform(sleep(Duration::from_secs(1)), |_| {
+                                Message::Update
+                            }))
+                        }
+                        _ => Action::None,
+                    }
+                }
+                ServiceEvent::Error(err) => {
+                    warn!("Time synchronization operation failed: {err}");
+                    self.error = Some(err);
+                    self.pending = false;
+
+                    Action::None
+                }
+            },
+            Message::MenuOpened => {
+                self.date = Local::now();
+                self.month = first_of_month(self.date.date_naive());
+                self.error = None;
+
+                self.command(TimedateCommand::Refresh)
+                    .map_or(Action::None, Action::Command)
+            }
+            Message::Resync => self
+                .command(TimedateCommand::Resync)
+                .map_or(Action::None, Action::Command),
+            Message::PreviousMonth => {
+                self.month = self.month - Months::new(1);
+
+                Action::None
+            }
+            Message::NextMonth => {
+                self.month = self.month + Months::new(1);
+
+                Action::None
+            }
+            Message::ConfigReloaded(config) => {
+                self.timezones = parse_timezones(&config.timezones);
+                self.config = config;
+
+                Action::None
             }
         }
     }
@@ -33,7 +194,270 @@ impl Clock {
         text(self.date.format(&self.config.format).to_string()).into()
     }
 
-    pub fn subscription(&self) -> Subscription<Message> {
+    fn calendar_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let today = self.date.date_naive();
+        let first_day = self.config.first_day_of_week.weekday();
+        let offset = (self.month.weekday().num_days_from_monday() + 7
+            - first_day.num_days_from_monday())
+            % 7;
+        let start = self.month - Days::new(offset as u64);
+        let next_month = self.month + Months::new(1);
+        let radius = theme.radius.sm;
+
+        let cell = |content: Element<'a, Message>| {
+            container(content)
+                .center_x(Length::Fill)
+                .padding([theme.space.xxs, 0])
+        };
+        let dimmed = |theme: &Theme| text::Style {
+            color: Some(theme.palette().text.scale_alpha(0.5)),
+        };
+
+        let header = row!(
+            icon_button(theme, StaticIcon::LeftChevron)
+                .on_press(Message::PreviousMonth)
+                .size(IconButtonSize::Small),
+            container(text(self.month.format("%B %Y").to_string())).center_x(Length::Fill),
+            icon_button(theme, StaticIcon::RightChevron)
+                .on_press(Message::NextMonth)
+                .size(IconButtonSize::Small),
+        )
+        .align_y(Alignment::Center);
+
+        let weekdays = Row::new()
+            .push_maybe(
+                self.config
+                    .show_week_numbers
+                    .then(|| cell(text("Wk").size(theme.font_size.xs).style(dimmed).into())),
+            )
+            .extend((0..7).map(|i| {
+                let label = (start + Days::new(i)).format("%a").to_string();
+
+                cell(
+                    text(label.chars().take(2).collect::<String>())
+                        .size(theme.font_size.xs)
+                        .style(dimmed)
+                        .into(),
+                )
+                .into()
+            }));
+
+        // the weeks containing a day of the month
+        let weeks = (0..6)
+            .map(|week| start + Days::new(week * 7))
+            .take_while(|week_start| *week_start < next_month)
+            .map(|week_start| {
+                let days = (0..7)
+                    .map(|i| week_start + Days::new(i))
+                    .collect::<Vec<_>>();
+                // ISO weeks start on monday
+                let week_number = days
+                    .iter()
+                    .find(|day| day.weekday() == Weekday::Mon)
+                    .map(|day| day.iso_week().week())
+                    .unwrap_or_default();
+
+                Row::new()
+                    .push_maybe(self.config.show_week_numbers.then(|| {
+                        cell(
+                            text(week_number)
+                                .size(theme.font_size.xs)
+                                .style(dimmed)
+                                .into(),
+                        )
+                    }))
+                    .extend(days.into_iter().map(|day| {
+                        let in_month = day >= self.month && day < next_month;
+                        let holiday = self
+                            .config
+                            .holidays
+                            .iter()
+                            .any(|holiday| holiday.date.matches(day));
+                        let is_today = day == today;
+
+                        cell(
+                            text(day.day())
+                                .size(theme.font_size.sm)
+                                .style(move |theme: &Theme| text::Style {
+                                    color: Some(match (holiday, in_month) {
+                                        (true, true) => theme.palette().danger,
+                                        (true, false) => theme.palette().danger.scale_alpha(0.5),
+                                        (false, true) => theme.palette().text,
+                                        (false, false) => theme.palette().text.scale_alpha(0.5),
+                                    }),
+                                })
+                                .into(),
+                        )
+                        .style(move |theme: &Theme| container::Style {
+                            background: is_today
+                                .then(|| theme.extended_palette().primary.weak.color.into()),
+                            border: Border::default().rounded(radius),
+                            ..Default::default()
+                        })
+                        .into()
+                    }))
+                    .into()
+            })
+            .collect::<Vec<Element<'a, Message>>>();
+
+        let holidays = self
+            .config
+            .holidays
+            .iter()
+            .filter_map(|holiday| {
+                (0..31)
+                    .map(|i| self.month + Days::new(i))
+                    .take_while(|day| *day < next_month)
+                    .find(|day| holiday.date.matches(*day))
+                    .map(|day| (day, holiday.name.clone()))
+            })
+            .sorted()
+            .map(|(day, name)| {
+                text(format!("{} · {name}", day.format("%-d %B")))
+                    .size(theme.font_size.xs)
+                    .into()
+            })
+            .collect::<Vec<Element<'a, Message>>>();
+
+        column!(header, weekdays, Column::with_children(weeks))
+            .push_maybe(
+                (!holidays.is_empty())
+                    .then(|| Column::with_children(holidays).spacing(theme.space.xxs)),
+            )
+            .spacing(theme.space.xxs)
+            .into()
+    }
+
+    fn ntp_view<'a>(&'a self, theme: &'a AshellTheme, ntp: &'a NtpStatus) -> Element<'a, Message> {
+        let details = [
+            ntp.server.clone(),
+            ntp.offset
+                .map(|offset| format!("offset {}", format_offset(offset))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" · ");
+        let synchronized = ntp.synchronized;
+
+        column!(
+            row!(
+                container(icon(if synchronized {
+                    StaticIcon::ClockCheck
+                } else {
+                    StaticIcon::ClockAlert
+                }))
+                .style(move |theme: &Theme| container::Style {
+                    text_color: Some(if synchronized {
+                        theme.palette().success
+                    } else {
+                        theme.palette().danger
+                    }),
+                    ..Default::default()
+                }),
+                column!(text(ntp_label(ntp)))
+                    .push_maybe(
+                        (!details.is_empty()).then(|| text(details).size(theme.font_size.xs))
+                    )
+                    .width(Length::Fill),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.sm),
+        )
+        .push_maybe(self.error.as_ref().map(|err| {
+            container(text(err.clone()).size(theme.font_size.xs)).style(|theme: &Theme| {
+                container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                }
+            })
+        }))
+        .push(
+            button(
+                text(if ntp.enabled {
+                    "Resync now"
+                } else {
+                    "Enable network time"
+                })
+                .width(Length::Fill)
+                .center(),
+            )
+            .style(theme.confirm_button_style())
+            .padding([theme.space.xxs, theme.space.sm])
+            .width(Length::Fill)
+            .on_press_maybe((!self.pending).then_some(Message::Resync)),
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    /// The time in the other time zones, with the day when it isn't the local one
+    fn timezones_view<'a>(&'a self, theme: &'a AshellTheme) -> Option<Element<'a, Message>> {
+        if self.timezones.is_empty() {
+            return None;
+        }
+
+        let today = self.date.date_naive();
+
+        Some(
+            Column::with_children(self.timezones.iter().map(|timezone| {
+                let date = self.date.with_timezone(timezone);
+                let time = if date.date_naive() == today {
+                    date.format("%R").to_string()
+                } else {
+                    date.format("%a %R").to_string()
+                };
+
+                row!(
+                    text(timezone_label(timezone)).width(Length::Fill),
+                    text(time),
+                    text(date.format("UTC%:z").to_string()).size(theme.font_size.xs),
+                )
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xs)
+                .into()
+            }))
+            .spacing(theme.space.xxs)
+            .into(),
+        )
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let header =
+            column!(text(self.date.format("%A %-d %B %Y").to_string()).size(theme.font_size.lg))
+                .push_maybe(
+                    self.service
+                        .as_ref()
+                        .filter(|service| !service.timezone.is_empty())
+                        .map(|service| {
+                            text(format!(
+                                "{} ({})",
+                                service.timezone,
+                                self.date.format("UTC%:z")
+                            ))
+                            .size(theme.font_size.xs)
+                        }),
+                )
+                .spacing(theme.space.xxs);
+
+        column!(header)
+            .push_maybe(
+                self.timezones_view(theme).map(|timezones| {
+                    column!(horizontal_rule(1), timezones).spacing(theme.space.xs)
+                }),
+            )
+            .push(horizontal_rule(1))
+            .push(self.calendar_view(theme))
+            .push_maybe(self.service.as_ref().map(|service| {
+                column!(horizontal_rule(1), self.ntp_view(theme, &service.ntp))
+                    .spacing(theme.space.xs)
+            }))
+            .spacing(theme.space.xs)
+            .into()
+    }
+
+    /// Interval of the `Update` ticks, sent by the redraw scheduler
+    pub fn redraw_interval(&self) -> Duration {
         let second_specifiers = [
             "%S",  // Seconds (00-60)
             "%T",  // Hour:Minute:Second
@@ -42,15 +466,17 @@ impl Clock {
             "%:z", // UTC offset with seconds
             "%s",  // Unix timestamp (seconds since epoch)
         ];
-        let interval = if second_specifiers
+        if second_specifiers
             .iter()
             .any(|&spec| self.config.format.contains(spec))
         {
             Duration::from_secs(1)
         } else {
             Duration::from_secs(5)
-        };
+        }
+    }
 
-        every(interval).map(|_| Message::Update)
+    pub fn subscription(&self) -> Subscription<Message> {
+        TimedateService::subscribe().map(Message::Timedate)
     }
 }
@@ -2,11 +2,16 @@ use crate::{
     components::icons::{DynamicIcon, StaticIcon, icon},
     config::CustomModuleDef,
     theme::AshellTheme,
-    utils::launcher::execute_command,
+    utils::{
+        app_icon::{AppIcon, named_icon},
+        backoff::Backoff,
+        launcher::execute_command,
+    },
 };
 use iced::widget::canvas;
 use iced::{
     Element, Length, Subscription, Theme,
+    futures::{SinkExt, channel::mpsc::Sender},
     stream::channel,
     widget::{Stack, row, text},
 };
@@ -17,24 +22,58 @@ use iced::{
         container,
     },
 };
-use log::{error, info};
+use log::{error, info, warn};
 use serde::Deserialize;
-use std::{any::TypeId, process::Stdio};
+use std::{any::TypeId, collections::HashMap, process::Stdio};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::Command,
+    time::sleep,
 };
 
 #[derive(Debug, Clone)]
 pub struct Custom {
     config: CustomModuleDef,
     data: CustomListenData,
+    /// icons and images sent by the listen_cmd, resolved once
+    images: HashMap<String, Option<AppIcon>>,
 }
 
+/// A line of the listen_cmd, the missing fields keep their previous value
 #[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
 pub struct CustomListenData {
-    pub alt: String,
+    pub alt: Option<String>,
     pub text: Option<String>,
+    /// icon name of the theme
+    pub icon: Option<String>,
+    /// absolute path of an image, shown instead of the icon
+    pub image: Option<String>,
+}
+
+impl CustomListenData {
+    fn image_name(&self) -> Option<&String> {
+        self.image.as_ref().or(self.icon.as_ref())
+    }
+
+    fn alt(&self) -> &str {
+        self.alt.as_deref().unwrap_or_default()
+    }
+
+    fn merge(&mut self, update: CustomListenData) {
+        if update.alt.is_some() {
+            self.alt = update.alt;
+        }
+        if update.text.is_some() {
+            self.text = update.text;
+        }
+        if update.icon.is_some() {
+            self.icon = update.icon;
+        }
+        if update.image.is_some() {
+            self.image = update.image;
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +114,7 @@ impl Custom {
         Self {
             config,
             data: CustomListenData::default(),
+            images: HashMap::new(),
         }
     }
 
@@ -84,7 +124,12 @@ impl Custom {
                 execute_command(self.config.command.clone());
             }
             Message::Update(data) => {
-                self.data = data;
+                self.data.merge(data);
+                if let Some(name) = self.data.image_name()
+                    && !self.images.contains_key(name)
+                {
+                    self.images.insert(name.clone(), named_icon(name));
+                }
             }
         }
     }
@@ -97,19 +142,30 @@ impl Custom {
 
         if let Some(icons_map) = &self.config.icons {
             for (re, icon_str) in icons_map {
-                if re.is_match(&self.data.alt) {
+                if re.is_match(self.data.alt()) {
                     icon_element = icon(DynamicIcon(icon_str.clone()));
                     break; // Use the first match
                 }
             }
         }
 
+        // the icon or image sent by the listen_cmd replaces the configured one
+        let icon_element: Element<'_, Message> = match self
+            .data
+            .image_name()
+            .and_then(|name| self.images.get(name))
+            .and_then(Option::as_ref)
+        {
+            Some(image) => image.view(self.config.image_size),
+            None => icon_element.into(),
+        };
+
         // Wrap the icon in a container to apply padding
         let padded_icon_container = container(icon_element).padding([0, 1]);
 
         let mut show_alert = false;
         if let Some(re) = &self.config.alert
-            && re.is_match(&self.data.alt)
+            && re.is_match(self.data.alt())
         {
             show_alert = true;
         }
@@ -151,6 +207,54 @@ impl Custom {
         }
     }
 
+    /// Runs the listen_cmd until it exits, sending its lines as updates
+    async fn listen(name: &str, listen_cmd: &str, output: &mut Sender<(String, Message)>) {
+        let mut child = match Command::new("bash")
+            .arg("-c")
+            .arg(listen_cmd)
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) => {
+                error!("Failed to execute command: {error}");
+                return;
+            }
+        };
+
+        if let Some(stdout) = child.stdout.take() {
+            let mut reader = BufReader::new(stdout).lines();
+
+            // every line is parsed as soon as it's written
+            while let Some(line) = reader.next_line().await.ok().flatten() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(&line) {
+                    Ok(event) => {
+                        if let Err(e) = output
+                            .send((name.to_string(), Message::Update(event)))
+                            .await
+                        {
+                            error!("Failed to send the custom module update: {e}");
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to parse JSON: {e} for line {line}");
+                    }
+                }
+            }
+        } else {
+            error!("Failed to capture stdout for command: {listen_cmd}");
+        }
+
+        match child.wait().await {
+            Ok(status) => info!("{name} listen_cmd exited with {status}"),
+            Err(e) => error!("Failed to wait for the {name} listen_cmd: {e}"),
+        }
+    }
+
     pub fn subscription(&self) -> Subscription<(String, Message)> {
         let id = TypeId::of::<Self>();
         let name = self.config.name.clone();
@@ -158,45 +262,18 @@ impl Custom {
             Subscription::run_with_id(
                 (id, name.clone(), listen_cmd.clone()),
                 channel(10, async move |mut output| {
-                    let command = Command::new("bash")
-                        .arg("-c")
-                        .arg(&listen_cmd)
-                        .stdout(Stdio::piped())
-                        .spawn();
-
-                    match command {
-                        Ok(mut child) => {
-                            if let Some(stdout) = child.stdout.take() {
-                                let mut reader = BufReader::new(stdout).lines();
-
-                                // Ensure the child process is spawned in the runtime so it can
-                                // make progress on its own while we await for any output.
-                                tokio::spawn(async move {
-                                    let status = child
-                                        .wait()
-                                        .await
-                                        .expect("child process encountered an error");
-
-                                    info!("child status was: {status}");
-                                });
-
-                                while let Some(line) = reader.next_line().await.ok().flatten() {
-                                    match serde_json::from_str(&line) {
-                                        Ok(event) => output
-                                            .try_send((name.clone(), Message::Update(event)))
-                                            .unwrap(),
-                                        Err(e) => {
-                                            error!("Failed to parse JSON: {e} for line {line}");
-                                        }
-                                    }
-                                }
-                            } else {
-                                error!("Failed to capture stdout for command: {listen_cmd}");
-                            }
-                        }
-                        Err(error) => {
-                            error!("Failed to execute command: {error}");
+                    let mut backoff = Backoff::new();
+                    loop {
+                        backoff.start();
+                        Self::listen(&name, &listen_cmd, &mut output).await;
+
+                        let delay = backoff.fail();
+                        if backoff.just_down() {
+                            error!("the {name} listen_cmd keeps exiting, restarting in {delay:?}");
+                        } else {
+                            warn!("restarting the {name} listen_cmd in {delay:?}");
                         }
+                        sleep(delay).await;
                     }
                 }),
             )
@@ -0,0 +1,202 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::DashboardModuleConfig,
+    theme::AshellTheme,
+    utils::{history::History, stats::Sampler},
+};
+use iced::{
+    Element, Length, Point, Rectangle, Renderer, Subscription, Theme,
+    mouse::Cursor,
+    time::every,
+    widget::{
+        Column, canvas,
+        canvas::{Cache, Geometry, Path, Program, Stroke},
+        column, horizontal_rule, row, text,
+    },
+};
+use std::time::Duration;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+/// The charts show the last hour
+const WINDOW: Duration = Duration::from_secs(60 * 60);
+const CHART_HEIGHT: f32 = 40.;
+
+/// A line chart of the samples, they fill the chart from the right as the history grows
+struct Chart<'a> {
+    history: &'a History,
+    max: f32,
+}
+
+impl<Message> Program<Message> for Chart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let cache = Cache::new();
+
+        vec![cache.draw(renderer, bounds.size(), |frame| {
+            let capacity = (WINDOW.as_secs() / SAMPLE_INTERVAL.as_secs()) as f32;
+            let step = bounds.width / capacity;
+            let values = self.history.values().collect::<Vec<_>>();
+            let start = bounds.width - step * values.len().saturating_sub(1) as f32;
+
+            let points = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    Point::new(
+                        start + step * i as f32,
+                        bounds.height - value.clamp(0., self.max) / self.max * bounds.height,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            frame.stroke(
+                &Path::line(
+                    Point::new(0., bounds.height),
+                    Point::new(bounds.width, bounds.height),
+                ),
+                Stroke::default()
+                    .with_width(1.)
+                    .with_color(theme.extended_palette().background.strong.color),
+            );
+
+            if let Some((first, rest)) = points.split_first() {
+                let line = Path::new(|builder| {
+                    builder.move_to(*first);
+                    for point in rest {
+                        builder.line_to(*point);
+                    }
+                });
+                frame.stroke(
+                    &line,
+                    Stroke::default()
+                        .with_width(1.5)
+                        .with_color(theme.palette().primary),
+                );
+            }
+        })]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Sample,
+    ConfigReloaded(DashboardModuleConfig),
+}
+
+/// History of the system stats, shown as charts in the menu
+pub struct Dashboard {
+    config: DashboardModuleConfig,
+    sampler: Sampler,
+    battery: History,
+    cpu: History,
+    /// throughput in KB/s
+    download: History,
+    upload: History,
+    temperature: History,
+}
+
+impl Dashboard {
+    pub fn new(config: DashboardModuleConfig) -> Self {
+        Self {
+            config,
+            sampler: Sampler::new(),
+            battery: History::new(WINDOW),
+            cpu: History::new(WINDOW),
+            download: History::new(WINDOW),
+            upload: History::new(WINDOW),
+            temperature: History::new(WINDOW),
+        }
+    }
+
+    fn sample(&mut self) {
+        let sample = self.sampler.sample();
+
+        if let Some(percentage) = sample.battery {
+            self.battery.push(percentage);
+        }
+        self.cpu.push(sample.cpu);
+        self.download.push(sample.download);
+        self.upload.push(sample.upload);
+        if let Some(temperature) = sample.temperature(&self.config.temperature_sensor) {
+            self.temperature.push(temperature);
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Sample => self.sample(),
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+            }
+        }
+    }
+
+    pub fn view(&'_ self, _theme: &AshellTheme) -> Element<'_, Message> {
+        icon(StaticIcon::ChartLine).into()
+    }
+
+    fn chart<'a>(
+        theme: &AshellTheme,
+        label: &'a str,
+        unit: &'a str,
+        history: &'a History,
+        max: Option<f32>,
+    ) -> Option<Element<'a, Message>> {
+        let last = history.last()?;
+        // the throughput scale follows the peak of the hour
+        let max = max
+            .or_else(|| history.max())
+            .filter(|max| *max > 0.)
+            .unwrap_or(1.);
+
+        Some(
+            column!(
+                row!(
+                    text(label).width(Length::Fill),
+                    text(format!("{last:.0} {unit}")).size(theme.font_size.sm),
+                ),
+                canvas(Chart { history, max })
+                    .width(Length::Fill)
+                    .height(Length::Fixed(CHART_HEIGHT)),
+            )
+            .spacing(theme.space.xxs)
+            .into(),
+        )
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let charts = [
+            Self::chart(theme, "Battery", "%", &self.battery, Some(100.)),
+            Self::chart(theme, "CPU", "%", &self.cpu, Some(100.)),
+            Self::chart(theme, "Download", "KB/s", &self.download, None),
+            Self::chart(theme, "Upload", "KB/s", &self.upload, None),
+            Self::chart(theme, "Temperature", "°C", &self.temperature, None),
+        ];
+
+        column!(
+            text("Last hour").size(theme.font_size.lg),
+            horizontal_rule(1),
+            if self.cpu.is_empty() {
+                Into::<Element<'a, Message>>::into(text("Collecting the first samples..."))
+            } else {
+                Column::with_children(charts.into_iter().flatten())
+                    .spacing(theme.space.sm)
+                    .into()
+            },
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        every(SAMPLE_INTERVAL).map(|_| Message::Sample)
+    }
+}
@@ -0,0 +1,316 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::EncryptedVolumesModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        logind::{LogindEvent, LogindService},
+        udisks::{EncryptedVolume, UDisksCommand, UDisksService, VolumeKind},
+    },
+    theme::AshellTheme,
+    utils::secret::Secret,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, Theme,
+    widget::{Column, button, column, container, horizontal_rule, row, text, text_input},
+    window::Id,
+};
+use log::{info, warn};
+use zbus::zvariant::OwnedObjectPath;
+
+const PASSPHRASE_INPUT_ID: &str = "encrypted-volumes-passphrase";
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<UDisksService>),
+    Logind(ServiceEvent<LogindService>),
+    MenuOpened,
+    ShowPrompt(OwnedObjectPath, Id),
+    PassphraseChanged(Secret),
+    Unlock(Id),
+    CancelPrompt(Id),
+    Lock(OwnedObjectPath),
+    LockAll,
+    ConfigReloaded(EncryptedVolumesModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+    RequestKeyboard(Id, Task<Message>),
+    ReleaseKeyboard(Id, Task<Message>),
+}
+
+pub struct EncryptedVolumes {
+    config: EncryptedVolumesModuleConfig,
+    service: Option<UDisksService>,
+    prompt: Option<OwnedObjectPath>,
+    passphrase: Secret,
+    pending: bool,
+    error: Option<String>,
+}
+
+impl EncryptedVolumes {
+    pub fn new(config: EncryptedVolumesModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+            prompt: None,
+            passphrase: Secret::default(),
+            pending: false,
+            error: None,
+        }
+    }
+
+    fn command(&mut self, command: UDisksCommand) -> Option<Task<Message>> {
+        let task = self.service.as_mut()?.command(command).map(Message::Event);
+        self.pending = true;
+        self.error = None;
+
+        Some(task)
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.service = Some(service);
+                    }
+                    ServiceEvent::Update(data) => {
+                        if let Some(service) = self.service.as_mut() {
+                            service.update(data);
+                        }
+                        self.pending = false;
+                    }
+                    ServiceEvent::Error(err) => {
+                        warn!("Encrypted volume operation failed: {err}");
+                        self.error = Some(err);
+                        self.pending = false;
+                    }
+                }
+
+                Action::None
+            }
+            Message::Logind(ServiceEvent::Update(event)) => {
+                let lock = match event {
+                    LogindEvent::Idle(true) => self.config.lock_on_idle,
+                    LogindEvent::Lock => self.config.lock_on_session_lock,
+                    _ => false,
+                };
+                let unlocked = self
+                    .service
+                    .as_ref()
+                    .is_some_and(|service| service.volumes.iter().any(|v| v.is_unlocked()));
+
+                if lock && unlocked {
+                    info!("Locking encrypted volumes on {event:?}");
+
+                    self.command(UDisksCommand::LockAll)
+                        .map_or(Action::None, Action::Command)
+                } else {
+                    Action::None
+                }
+            }
+            Message::Logind(_) => Action::None,
+            Message::MenuOpened => {
+                self.prompt = None;
+                self.passphrase = Secret::default();
+                self.error = None;
+
+                Action::None
+            }
+            Message::ShowPrompt(path, id) => {
+                self.prompt = Some(path);
+                self.passphrase = Secret::default();
+                self.error = None;
+
+                Action::RequestKeyboard(id, text_input::focus(PASSPHRASE_INPUT_ID))
+            }
+            Message::PassphraseChanged(passphrase) => {
+                self.passphrase = passphrase;
+
+                Action::None
+            }
+            Message::Unlock(id) => {
+                let Some(path) = self.prompt.take() else {
+                    return Action::None;
+                };
+                let passphrase = std::mem::take(&mut self.passphrase);
+
+                Action::ReleaseKeyboard(
+                    id,
+                    self.command(UDisksCommand::Unlock(path, passphrase))
+                        .unwrap_or_else(Task::none),
+                )
+            }
+            Message::CancelPrompt(id) => {
+                self.prompt = None;
+                self.passphrase = Secret::default();
+
+                Action::ReleaseKeyboard(id, Task::none())
+            }
+            Message::Lock(path) => self
+                .command(UDisksCommand::Lock(path))
+                .map_or(Action::None, Action::Command),
+            Message::LockAll => self
+                .command(UDisksCommand::LockAll)
+                .map_or(Action::None, Action::Command),
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let service = self.service.as_ref()?;
+
+        if service.volumes.is_empty() {
+            return None;
+        }
+
+        let unlocked = service.volumes.iter().filter(|v| v.is_unlocked()).count();
+
+        Some(
+            row!(icon(if unlocked > 0 {
+                StaticIcon::LockOpen
+            } else {
+                StaticIcon::Lock
+            }))
+            .push_maybe((unlocked > 0).then(|| text(unlocked)))
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xxs)
+            .into(),
+        )
+    }
+
+    fn volume_view<'a>(
+        &'a self,
+        id: Id,
+        theme: &'a AshellTheme,
+        volume: &'a EncryptedVolume,
+    ) -> Element<'a, Message> {
+        let kind = match volume.kind {
+            VolumeKind::Luks => "LUKS",
+            VolumeKind::VeraCrypt => "VeraCrypt",
+        };
+        let details = match (&volume.mount_point, volume.is_unlocked()) {
+            (Some(mount_point), _) => format!("{kind} · {} · {mount_point}", volume.device),
+            (None, true) => format!("{kind} · {} · Unlocked", volume.device),
+            (None, false) => format!("{kind} · {}", volume.device),
+        };
+
+        let header = row!(
+            icon(if volume.is_unlocked() {
+                StaticIcon::LockOpen
+            } else {
+                StaticIcon::Lock
+            }),
+            column!(
+                text(volume.label.clone()),
+                text(details).size(theme.font_size.xs),
+            )
+            .width(Length::Fill),
+            button(text(if volume.is_unlocked() {
+                "Lock"
+            } else {
+                "Unlock"
+            }))
+            .style(theme.outline_button_style())
+            .padding([theme.space.xxs, theme.space.sm])
+            .on_press_maybe((!self.pending).then(|| {
+                if volume.is_unlocked() {
+                    Message::Lock(volume.path.clone())
+                } else {
+                    Message::ShowPrompt(volume.path.clone(), id)
+                }
+            })),
+        )
+        .align_y(Alignment::Center)
+        .spacing(theme.space.xs);
+
+        if self.prompt.as_ref() == Some(&volume.path) {
+            column!(
+                header,
+                text_input("Passphrase", self.passphrase.expose())
+                    .id(PASSPHRASE_INPUT_ID)
+                    .secure(true)
+                    .size(theme.font_size.md)
+                    .padding([theme.space.xs, theme.space.md])
+                    .style(theme.text_input_style())
+                    .on_input(|value| Message::PassphraseChanged(value.into()))
+                    .on_submit(Message::Unlock(id)),
+                row!(
+                    button(text("Cancel"))
+                        .style(theme.outline_button_style())
+                        .padding([theme.space.xxs, theme.space.md])
+                        .on_press(Message::CancelPrompt(id)),
+                    button(text("Unlock"))
+                        .style(theme.confirm_button_style())
+                        .padding([theme.space.xxs, theme.space.md])
+                        .on_press(Message::Unlock(id)),
+                )
+                .spacing(theme.space.xs),
+            )
+            .spacing(theme.space.xs)
+            .into()
+        } else {
+            header.into()
+        }
+    }
+
+    pub fn menu_view<'a>(&'a self, id: Id, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let volumes = self
+            .service
+            .as_ref()
+            .map(|service| service.volumes.as_slice())
+            .unwrap_or_default();
+
+        column!(
+            row!(
+                text("Encrypted volumes")
+                    .size(theme.font_size.lg)
+                    .width(Length::Fill),
+            )
+            .push_maybe(volumes.iter().any(|v| v.is_unlocked()).then(|| {
+                button(text("Lock all"))
+                    .style(theme.outline_button_style())
+                    .padding([theme.space.xxs, theme.space.sm])
+                    .on_press_maybe((!self.pending).then_some(Message::LockAll))
+            }))
+            .align_y(Alignment::Center),
+            horizontal_rule(1),
+            Column::with_children(
+                volumes
+                    .iter()
+                    .map(|volume| self.volume_view(id, theme, volume))
+                    .collect::<Vec<_>>(),
+            )
+            .spacing(theme.space.sm),
+        )
+        .push_maybe(self.error.as_ref().map(|err| {
+            container(text(err.clone()).size(theme.font_size.xs)).style(|theme: &Theme| {
+                container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                }
+            })
+        }))
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let udisks = UDisksService::subscribe().map(Message::Event);
+
+        if self.config.lock_on_idle || self.config.lock_on_session_lock {
+            Subscription::batch(vec![
+                udisks,
+                LogindService::subscribe().map(Message::Logind),
+            ])
+        } else {
+            udisks
+        }
+    }
+}
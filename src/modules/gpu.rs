@@ -0,0 +1,220 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::GpuModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        gpu::{GpuCommand, GpuEvent, GpuMode, GpuService, UserAction},
+    },
+    theme::AshellTheme,
+    utils::launcher::execute_command,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, Theme,
+    widget::{Column, button, column, container, horizontal_rule, row, text},
+};
+use log::warn;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<GpuService>),
+    SetMode(GpuMode),
+    ApplyMode(UserAction),
+    ConfigReloaded(GpuModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+pub struct Gpu {
+    config: GpuModuleConfig,
+    service: Option<GpuService>,
+    pending: bool,
+    error: Option<String>,
+}
+
+impl Gpu {
+    pub fn new(config: GpuModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+            pending: false,
+            error: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.service = Some(service);
+                    }
+                    ServiceEvent::Update(data) => {
+                        // the status is polled while a switch could be running
+                        if !matches!(data, GpuEvent::Status { .. }) {
+                            self.pending = false;
+                        }
+                        if let Some(service) = self.service.as_mut() {
+                            service.update(data);
+                        }
+                    }
+                    ServiceEvent::Error(err) => {
+                        warn!("Failed to switch the GPU mode: {err}");
+                        self.error = Some(err);
+                        self.pending = false;
+                    }
+                }
+
+                Action::None
+            }
+            Message::SetMode(mode) => match self.service.as_mut() {
+                Some(service) => {
+                    self.pending = true;
+                    self.error = None;
+
+                    Action::Command(
+                        service
+                            .command(GpuCommand::SetMode(mode))
+                            .map(Message::Event),
+                    )
+                }
+                None => Action::None,
+            },
+            Message::ApplyMode(action) => {
+                execute_command(match action {
+                    UserAction::Logout => self.config.logout_cmd.clone(),
+                    UserAction::Reboot => self.config.reboot_cmd.clone(),
+                });
+
+                Action::None
+            }
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let service = self.service.as_ref()?;
+
+        Some(
+            row!(icon(StaticIcon::Gpu))
+                .push_maybe(service.mode.map(|mode| text(mode.name())))
+                .push_maybe(service.pending.map(|_| icon(StaticIcon::Reboot)))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xxs)
+                .into(),
+        )
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let Some(service) = self.service.as_ref() else {
+            return text("No GPU switching available").into();
+        };
+
+        let status = match service.dgpu_active {
+            Some(true) => "Dedicated GPU powered on",
+            Some(false) => "Dedicated GPU suspended",
+            None => service.backend_name(),
+        };
+
+        let modes = Column::with_children(
+            service
+                .supported
+                .iter()
+                .map(|mode| {
+                    if service.mode == Some(*mode) {
+                        container(text(mode.name()).width(Length::Fill))
+                            .padding([theme.space.xxs, theme.space.sm])
+                            .style(|theme: &Theme| container::Style {
+                                text_color: Some(theme.palette().success),
+                                ..Default::default()
+                            })
+                            .into()
+                    } else {
+                        button(text(mode.name()))
+                            .on_press_maybe((!self.pending).then_some(Message::SetMode(*mode)))
+                            .padding([theme.space.xxs, theme.space.sm])
+                            .width(Length::Fill)
+                            .style(theme.ghost_button_style())
+                            .into()
+                    }
+                })
+                .collect::<Vec<Element<'a, Message>>>(),
+        )
+        .spacing(theme.space.xxs);
+
+        column!(
+            row!(
+                icon(StaticIcon::Gpu),
+                column!(
+                    text("Graphics").size(theme.font_size.lg),
+                    text(status).size(theme.font_size.xs),
+                )
+                .width(Length::Fill),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.sm),
+            horizontal_rule(1),
+            modes,
+        )
+        .push_maybe(service.pending.map(|(mode, action)| {
+            let (label, apply) = match action {
+                UserAction::Logout => ("Log out", "logout"),
+                UserAction::Reboot => ("Reboot", "reboot"),
+            };
+
+            row!(
+                text(format!("{} mode after the {apply}", mode.name()))
+                    .size(theme.font_size.sm)
+                    .width(Length::Fill),
+                button(text(label))
+                    .style(theme.outline_button_style())
+                    .padding([theme.space.xxs, theme.space.sm])
+                    .on_press(Message::ApplyMode(action)),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs)
+        }))
+        .push_maybe(self.error.as_ref().map(|err| {
+            container(text(err.clone()).size(theme.font_size.xs)).style(|theme: &Theme| {
+                container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                }
+            })
+        }))
+        .push(horizontal_rule(1))
+        .push(text("Using the dedicated GPU"))
+        .push(if service.clients.is_empty() {
+            Element::from(text("No applications").size(theme.font_size.sm))
+        } else {
+            Column::with_children(
+                service
+                    .clients
+                    .iter()
+                    .map(|client| {
+                        row!(
+                            text(client.name.clone()).width(Length::Fill),
+                            text(client.pid).size(theme.font_size.xs),
+                        )
+                        .align_y(Alignment::Center)
+                        .into()
+                    })
+                    .collect::<Vec<Element<'a, Message>>>(),
+            )
+            .spacing(theme.space.xxs)
+            .into()
+        })
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        GpuService::subscribe().map(Message::Event)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::IdleInhibitorModuleConfig,
+    services::idle_inhibitor::IdleInhibitorManager,
+    theme::AshellTheme,
+    utils::format_duration,
+};
+use iced::{
+    Alignment, Element, Subscription, Theme,
+    time::every,
+    widget::{Row, container, text},
+};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Toggle,
+    Tick,
+    ConfigReloaded(IdleInhibitorModuleConfig),
+}
+
+/// Keeps the screen awake while it's on, released after the configured duration when set
+pub struct IdleInhibitor {
+    config: IdleInhibitorModuleConfig,
+    manager: Option<IdleInhibitorManager>,
+    /// when the timed inhibitor is released
+    until: Option<Instant>,
+}
+
+impl IdleInhibitor {
+    pub fn new(config: IdleInhibitorModuleConfig) -> Self {
+        Self {
+            config,
+            manager: IdleInhibitorManager::new(),
+            until: None,
+        }
+    }
+
+    fn is_inhibited(&self) -> bool {
+        self.manager
+            .as_ref()
+            .is_some_and(IdleInhibitorManager::is_inhibited)
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Toggle => {
+                if let Some(manager) = self.manager.as_mut() {
+                    manager.toggle();
+                }
+                self.until = self
+                    .config
+                    .duration
+                    .filter(|_| self.is_inhibited())
+                    .map(|minutes| Instant::now() + Duration::from_secs(minutes * 60));
+            }
+            Message::Tick => {
+                if self.until.is_some_and(|until| until <= Instant::now()) {
+                    self.until = None;
+                    if let Some(manager) = self.manager.as_mut().filter(|m| m.is_inhibited()) {
+                        manager.toggle();
+                    }
+                }
+            }
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        self.manager.as_ref()?;
+        let inhibited = self.is_inhibited();
+        // rounded up, the last minute shows as 1m
+        let remaining = self.until.map(|until| {
+            let left = until.saturating_duration_since(Instant::now());
+            format_duration(&Duration::from_secs(left.as_secs().div_ceil(60) * 60))
+        });
+
+        Some(
+            container(
+                Row::new()
+                    .push(icon(if inhibited {
+                        StaticIcon::Coffee
+                    } else {
+                        StaticIcon::CoffeeOutline
+                    }))
+                    .push_maybe(remaining.map(|remaining| text(remaining.trim().to_string())))
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xxs),
+            )
+            .style(move |theme: &Theme| container::Style {
+                text_color: inhibited.then(|| theme.palette().primary),
+                ..Default::default()
+            })
+            .into(),
+        )
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.until.is_some() {
+            every(Duration::from_secs(1)).map(|_| Message::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::{KeyboardLayoutManager, Message};
+use crate::utils::backoff::Backoff;
+use hyprland::{
+    ctl::switch_xkb_layout::SwitchXKBLayoutCmdTypes, event_listener::AsyncEventListener,
+    shared::HyprData,
+};
+use iced::{Subscription, futures::SinkExt, stream::channel};
+use log::{debug, error, warn};
+use std::any::TypeId;
+use tokio::time::sleep;
+
+fn get_multiple_layout_flag() -> bool {
+    match hyprland::keyword::Keyword::get("input:kb_layout") {
+        Ok(layouts) => layouts.value.to_string().split(",").count() > 1,
+        Err(_) => false,
+    }
+}
+
+fn get_active_layout() -> String {
+    hyprland::data::Devices::get()
+        .ok()
+        .and_then(|devices| {
+            devices
+                .keyboards
+                .iter()
+                .find(|k| k.main)
+                .map(|keyboard| keyboard.active_keymap.to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub struct HyprlandKeyboardLayoutManager;
+
+impl KeyboardLayoutManager for HyprlandKeyboardLayoutManager {
+    fn has_multiple_layouts() -> bool {
+        get_multiple_layout_flag()
+    }
+
+    fn get_active_layout() -> String {
+        get_active_layout()
+    }
+
+    fn next_layout() -> Result<(), Box<dyn std::error::Error>> {
+        hyprland::ctl::switch_xkb_layout::call("all", SwitchXKBLayoutCmdTypes::Next)?;
+
+        Ok(())
+    }
+
+    fn create_subscription() -> Subscription<Message> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |output| {
+                let mut backoff = Backoff::new();
+                loop {
+                    let mut event_listener = AsyncEventListener::new();
+
+                    event_listener.add_layout_changed_handler({
+                        let output = output.clone();
+                        move |e| {
+                            debug!("keymap changed: {e:?}");
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                if let Err(e) = output
+                                    .send(Message::ActiveLayoutChanged(get_active_layout()))
+                                    .await
+                                {
+                                    error!("failed to send the keymap change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    event_listener.add_config_reloaded_handler({
+                        let output = output.clone();
+                        move || {
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                if let Err(e) = output
+                                    .send(Message::LayoutConfigChanged(get_multiple_layout_flag()))
+                                    .await
+                                {
+                                    error!("failed to send the layout config change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    backoff.start();
+                    let res = event_listener.start_listener_async().await;
+
+                    let delay = backoff.fail();
+                    match res {
+                        Err(e) => {
+                            error!("restarting keymap listener in {delay:?} due to error: {e:?}")
+                        }
+                        Ok(()) => warn!("keymap listener stopped, restarting in {delay:?}"),
+                    }
+                    sleep(delay).await;
+                }
+            }),
+        )
+    }
+}
@@ -0,0 +1,119 @@
+use super::{KeyboardLayoutManager, Message};
+use iced::{
+    Subscription,
+    futures::{SinkExt, StreamExt, channel::mpsc, future::pending, stream::select},
+    stream::channel,
+};
+use log::{debug, error};
+use std::{any::TypeId, sync::LazyLock};
+
+/// A layout, as `(short name, variant, long name)`
+type Layout = (String, String, String);
+
+#[zbus::proxy(
+    interface = "org.kde.KeyboardLayouts",
+    default_service = "org.kde.keyboard",
+    default_path = "/Layouts"
+)]
+trait KeyboardLayouts {
+    #[zbus(name = "getLayout")]
+    fn get_layout(&self) -> zbus::Result<u32>;
+
+    #[zbus(name = "getLayoutsList")]
+    fn get_layouts_list(&self) -> zbus::Result<Vec<Layout>>;
+
+    #[zbus(name = "switchToNextLayout")]
+    fn switch_to_next_layout(&self) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "layoutChanged")]
+    fn layout_changed(&self, index: u32) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "layoutListChanged")]
+    fn layout_list_changed(&self) -> zbus::Result<()>;
+}
+
+static PROXY: LazyLock<Option<KeyboardLayoutsProxyBlocking<'static>>> = LazyLock::new(|| {
+    zbus::blocking::Connection::session()
+        .and_then(|conn| KeyboardLayoutsProxyBlocking::new(&conn))
+        .inspect_err(|e| error!("failed to connect to the KWin keyboard layouts: {e}"))
+        .ok()
+});
+
+fn layouts() -> zbus::Result<(Vec<Layout>, u32)> {
+    let proxy = PROXY
+        .as_ref()
+        .ok_or_else(|| zbus::Error::Failure("KWin is not available".to_string()))?;
+
+    Ok((proxy.get_layouts_list()?, proxy.get_layout()?))
+}
+
+async fn listen(output: &mut mpsc::Sender<Message>) -> zbus::Result<()> {
+    let conn = zbus::Connection::session().await?;
+    let proxy = KeyboardLayoutsProxy::new(&conn).await?;
+
+    let mut events = select(
+        proxy.receive_layout_changed().await?.map(|_| ()),
+        proxy.receive_layout_list_changed().await?.map(|_| ()),
+    );
+
+    // the names are read again, the signals only carry the index
+    while events.next().await.is_some() {
+        debug!("KWin keyboard layout changed");
+        let (list, active) = (proxy.get_layouts_list().await?, proxy.get_layout().await?);
+        let _ = output
+            .send(Message::LayoutConfigChanged(list.len() > 1))
+            .await;
+        let _ = output
+            .send(Message::ActiveLayoutChanged(active_name(&list, active)))
+            .await;
+    }
+
+    Ok(())
+}
+
+/// The long name, like the other compositors report
+fn active_name(list: &[Layout], active: u32) -> String {
+    list.get(active as usize)
+        .map(|(_, _, name)| name.clone())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub struct KwinKeyboardLayoutManager;
+
+impl KeyboardLayoutManager for KwinKeyboardLayoutManager {
+    fn has_multiple_layouts() -> bool {
+        layouts().is_ok_and(|(list, _)| list.len() > 1)
+    }
+
+    fn get_active_layout() -> String {
+        layouts()
+            .map(|(list, active)| active_name(&list, active))
+            .unwrap_or_else(|e| {
+                error!("failed to get the KWin keyboard layouts: {e}");
+
+                "unknown".to_string()
+            })
+    }
+
+    fn next_layout() -> Result<(), Box<dyn std::error::Error>> {
+        let proxy = PROXY.as_ref().ok_or("KWin is not available")?;
+        proxy.switch_to_next_layout()?;
+
+        Ok(())
+    }
+
+    fn create_subscription() -> Subscription<Message> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                if let Err(e) = listen(&mut output).await {
+                    error!("failed to listen to the KWin keyboard layouts: {e}");
+                }
+
+                pending::<()>().await;
+            }),
+        )
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{config::KeyboardLayoutModuleConfig, theme::AshellTheme};
+use iced::{Element, Subscription, widget::text};
+use log::error;
+
+#[cfg(feature = "hyprland")]
+pub mod hyprland;
+#[cfg(feature = "hyprland")]
+pub use hyprland::HyprlandKeyboardLayoutManager;
+
+#[cfg(feature = "niri")]
+pub mod niri;
+#[cfg(feature = "niri")]
+pub use niri::NiriKeyboardLayoutManager;
+
+#[cfg(feature = "sway")]
+pub mod sway;
+#[cfg(feature = "sway")]
+pub use sway::SwayKeyboardLayoutManager;
+
+#[cfg(feature = "kwin")]
+pub mod kwin;
+#[cfg(feature = "kwin")]
+pub use kwin::KwinKeyboardLayoutManager;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LayoutConfigChanged(bool),
+    ActiveLayoutChanged(String),
+    ChangeLayout,
+}
+
+pub trait KeyboardLayoutManager {
+    /// The module is hidden with a single layout
+    fn has_multiple_layouts() -> bool;
+    fn get_active_layout() -> String;
+    fn next_layout() -> Result<(), Box<dyn std::error::Error>>;
+    fn create_subscription() -> Subscription<Message>;
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyboardLayout<KM: KeyboardLayoutManager> {
+    config: KeyboardLayoutModuleConfig,
+    multiple_layout: bool,
+    active: String,
+    _phantom: std::marker::PhantomData<KM>,
+}
+
+impl<KM: KeyboardLayoutManager> KeyboardLayout<KM> {
+    pub fn new(config: KeyboardLayoutModuleConfig) -> Self {
+        Self {
+            config,
+            multiple_layout: KM::has_multiple_layouts(),
+            active: KM::get_active_layout(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::ActiveLayoutChanged(layout) => {
+                self.active = layout;
+            }
+            Message::LayoutConfigChanged(layout_flag) => self.multiple_layout = layout_flag,
+            Message::ChangeLayout => {
+                if let Err(e) = KM::next_layout() {
+                    error!("failed to keymap change: {e:?}");
+                }
+            }
+        }
+    }
+
+    pub fn view(&'_ self, _: &AshellTheme) -> Option<Element<'_, Message>> {
+        if self.multiple_layout {
+            let active = match self.config.labels.get(&self.active) {
+                Some(value) => value.to_string(),
+                None => self.active.clone(),
+            };
+
+            Some(text(active).into())
+        } else {
+            None
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        KM::create_subscription()
+    }
+}
@@ -0,0 +1,118 @@
+use super::{KeyboardLayoutManager, Message};
+use iced::{Subscription, futures::SinkExt, stream::channel};
+use log::{debug, error};
+use niri_ipc::{
+    Action, Event, KeyboardLayouts, LayoutSwitchTarget, Request, Response, socket::Socket,
+};
+use std::{any::TypeId, io, time::Duration};
+use tokio::{sync::mpsc, task, time::sleep};
+
+fn request(request: Request) -> io::Result<Response> {
+    Socket::connect()?.send(request)?.map_err(io::Error::other)
+}
+
+fn fetch_layouts() -> io::Result<KeyboardLayouts> {
+    match request(Request::KeyboardLayouts)? {
+        Response::KeyboardLayouts(layouts) => Ok(layouts),
+        _ => Err(io::Error::other(
+            "unexpected response to the keyboard layouts request",
+        )),
+    }
+}
+
+fn active_name(layouts: &KeyboardLayouts) -> String {
+    layouts
+        .names
+        .get(layouts.current_idx as usize)
+        .cloned()
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Reads the niri event stream until the socket is closed, the switches only carry the index
+fn listen_events(tx: mpsc::UnboundedSender<Message>) -> io::Result<()> {
+    let mut socket = Socket::connect()?;
+    socket
+        .send(Request::EventStream)?
+        .map_err(io::Error::other)?;
+
+    let mut layouts = fetch_layouts()?;
+    let mut read_event = socket.read_events();
+    loop {
+        let messages = match read_event()? {
+            Event::KeyboardLayoutsChanged { keyboard_layouts } => {
+                layouts = keyboard_layouts;
+                vec![
+                    Message::LayoutConfigChanged(layouts.names.len() > 1),
+                    Message::ActiveLayoutChanged(active_name(&layouts)),
+                ]
+            }
+            Event::KeyboardLayoutSwitched { idx } => {
+                layouts.current_idx = idx;
+                vec![Message::ActiveLayoutChanged(active_name(&layouts))]
+            }
+            _ => continue,
+        };
+
+        for message in messages {
+            if tx.send(message).is_err() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+pub struct NiriKeyboardLayoutManager;
+
+impl KeyboardLayoutManager for NiriKeyboardLayoutManager {
+    fn has_multiple_layouts() -> bool {
+        fetch_layouts().is_ok_and(|layouts| layouts.names.len() > 1)
+    }
+
+    fn get_active_layout() -> String {
+        fetch_layouts()
+            .map(|layouts| active_name(&layouts))
+            .unwrap_or_else(|e| {
+                error!("failed to get the niri keyboard layouts: {e}");
+
+                "unknown".to_string()
+            })
+    }
+
+    fn next_layout() -> Result<(), Box<dyn std::error::Error>> {
+        request(Request::Action(Action::SwitchLayout {
+            layout: LayoutSwitchTarget::Next,
+        }))?;
+
+        Ok(())
+    }
+
+    fn create_subscription() -> Subscription<Message> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                loop {
+                    let (tx, mut rx) = mpsc::unbounded_channel();
+
+                    // the niri socket is blocking
+                    let listener = task::spawn_blocking(move || listen_events(tx));
+
+                    while let Some(message) = rx.recv().await {
+                        debug!("niri keyboard layout changed: {message:?}");
+                        let _ = output.send(message).await;
+                    }
+
+                    match listener.await {
+                        Ok(Err(e)) => error!("niri keyboard layout event stream error: {e}"),
+                        Err(e) => error!("niri keyboard layout listener failed: {e}"),
+                        Ok(Ok(())) => {}
+                    }
+
+                    // avoid a busy loop when the socket is not available
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }),
+        )
+    }
+}
@@ -0,0 +1,122 @@
+use super::{KeyboardLayoutManager, Message};
+use iced::{
+    Subscription,
+    futures::{SinkExt, StreamExt, executor::block_on},
+    stream::channel,
+};
+use log::{debug, error};
+use std::{any::TypeId, time::Duration};
+use swayipc_async::{Connection, Event, EventType, Input, InputChange};
+use tokio::time::sleep;
+
+/// The first keyboard with layouts, the virtual keyboards have none
+async fn fetch_keyboard() -> Result<Option<Input>, swayipc_async::Error> {
+    let mut conn = Connection::new().await?;
+
+    Ok(conn
+        .get_inputs()
+        .await?
+        .into_iter()
+        .find(|input| input.input_type == "keyboard" && !input.xkb_layout_names.is_empty()))
+}
+
+fn active_name(keyboard: &Input) -> String {
+    keyboard
+        .xkb_active_layout_name
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub struct SwayKeyboardLayoutManager;
+
+impl KeyboardLayoutManager for SwayKeyboardLayoutManager {
+    fn has_multiple_layouts() -> bool {
+        block_on(fetch_keyboard())
+            .ok()
+            .flatten()
+            .is_some_and(|keyboard| keyboard.xkb_layout_names.len() > 1)
+    }
+
+    fn get_active_layout() -> String {
+        match block_on(fetch_keyboard()) {
+            Ok(keyboard) => keyboard.as_ref().map(active_name),
+            Err(e) => {
+                error!("failed to get the sway keyboards: {e}");
+
+                None
+            }
+        }
+        .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    fn next_layout() -> Result<(), Box<dyn std::error::Error>> {
+        block_on(async {
+            let mut conn = Connection::new().await?;
+
+            for res in conn
+                .run_command("input type:keyboard xkb_switch_layout next")
+                .await?
+            {
+                res?;
+            }
+
+            Ok::<_, swayipc_async::Error>(())
+        })
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
+
+    fn create_subscription() -> Subscription<Message> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                loop {
+                    let events = match Connection::new().await {
+                        Ok(conn) => conn.subscribe([EventType::Input]).await,
+                        Err(e) => Err(e),
+                    };
+
+                    match events {
+                        Ok(mut events) => {
+                            while let Some(event) = events.next().await {
+                                match event {
+                                    Ok(Event::Input(e))
+                                        if e.input.input_type == "keyboard"
+                                            && matches!(
+                                                e.change,
+                                                InputChange::XkbLayout | InputChange::XkbKeymap
+                                            ) =>
+                                    {
+                                        debug!("sway keyboard layout changed: {:?}", e.change);
+                                        let _ = output
+                                            .send(Message::LayoutConfigChanged(
+                                                e.input.xkb_layout_names.len() > 1,
+                                            ))
+                                            .await;
+                                        let _ = output
+                                            .send(Message::ActiveLayoutChanged(active_name(
+                                                &e.input,
+                                            )))
+                                            .await;
+                                    }
+                                    Ok(_) => {}
+                                    Err(e) => {
+                                        error!("sway event stream error: {e}");
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            error!("failed to subscribe to sway events: {e}");
+                        }
+                    }
+
+                    // avoid a busy loop when the socket is not available
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }),
+        )
+    }
+}
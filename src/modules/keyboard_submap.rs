@@ -1,12 +1,10 @@
 use hyprland::event_listener::AsyncEventListener;
-use iced::{Element, Subscription, stream::channel, widget::text};
-use log::{debug, error};
-use std::{
-    any::TypeId,
-    sync::{Arc, RwLock},
-};
+use iced::{Element, Subscription, futures::SinkExt, stream::channel, widget::text};
+use log::{debug, error, warn};
+use std::any::TypeId;
+use tokio::time::sleep;
 
-use crate::theme::AshellTheme;
+use crate::{theme::AshellTheme, utils::backoff::Backoff};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -48,7 +46,7 @@ impl KeyboardSubmap {
         Subscription::run_with_id(
             id,
             channel(10, async |output| {
-                let output = Arc::new(RwLock::new(output));
+                let mut backoff = Backoff::new();
                 loop {
                     let mut event_listener = AsyncEventListener::new();
 
@@ -56,22 +54,28 @@ impl KeyboardSubmap {
                         let output = output.clone();
                         move |new_submap| {
                             debug!("submap changed: {new_submap:?}");
-                            let output = output.clone();
+                            let mut output = output.clone();
                             Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output
-                                        .try_send(Message::SubmapChanged(new_submap))
-                                        .expect("error getting submap: submap changed event");
+                                if let Err(e) =
+                                    output.send(Message::SubmapChanged(new_submap)).await
+                                {
+                                    error!("failed to send the submap change: {e}");
                                 }
                             })
                         }
                     });
 
+                    backoff.start();
                     let res = event_listener.start_listener_async().await;
 
-                    if let Err(e) = res {
-                        error!("restarting submap listener due to error: {e:?}");
+                    let delay = backoff.fail();
+                    match res {
+                        Err(e) => {
+                            error!("restarting submap listener in {delay:?} due to error: {e:?}")
+                        }
+                        Ok(()) => warn!("submap listener stopped, restarting in {delay:?}"),
                     }
+                    sleep(delay).await;
                 }
             }),
         )
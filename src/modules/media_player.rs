@@ -3,18 +3,37 @@ use crate::{
     config::MediaPlayerModuleConfig,
     services::{
         ReadOnlyService, Service, ServiceEvent,
+        audio::{AudioCommand, AudioService},
         mpris::{
-            MprisPlayerCommand, MprisPlayerData, MprisPlayerService, PlaybackStatus, PlayerCommand,
+            MprisPlayerCommand, MprisPlayerData, MprisPlayerMetadata, MprisPlayerService,
+            PlaybackStatus, PlayerCommand,
         },
     },
     theme::AshellTheme,
-    utils::truncate_text,
+    utils::{
+        album_art::{self, AlbumArt},
+        lyrics::{self, Lyrics, TrackInfo},
+        truncate_text,
+    },
 };
 use iced::{
-    Background, Border, Element, Length, Subscription, Task, Theme,
+    Background, Border, ContentFit, Element, Length, Subscription, Task, Theme,
     alignment::Vertical,
-    widget::{Column, column, container, horizontal_rule, row, slider, text},
+    widget::{
+        Column, Row, Stack, button, column, container, horizontal_rule, image, row, scrollable,
+        slider, text,
+    },
 };
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+/// Side of the album art in the menu
+const ALBUM_ART_SIZE: f32 = 64.;
+
+/// Lines of the synced lyrics shown before and after the current one
+const LYRICS_CONTEXT: usize = 2;
+
+/// The position of the playing track moves by this interval on each tick
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -23,6 +42,11 @@ pub enum Message {
     Next(String),
     SetVolume(String, f64),
     Event(ServiceEvent<MprisPlayerService>),
+    Tick,
+    AlbumArtLoaded(String, Option<AlbumArt>),
+    LyricsLoaded(String, Option<Lyrics>),
+    AudioEvent(ServiceEvent<AudioService>),
+    CastTo(String),
     ConfigReloaded(MediaPlayerModuleConfig),
 }
 
@@ -34,6 +58,12 @@ pub enum Action {
 pub struct MediaPlayer {
     config: MediaPlayerModuleConfig,
     service: Option<MprisPlayerService>,
+    /// album art of the current tracks by url, None while loading or when it failed
+    album_art: HashMap<String, Option<AlbumArt>>,
+    /// lyrics of the current tracks by track, None while loading or when they're not found
+    lyrics: HashMap<String, Option<Lyrics>>,
+    /// audio outputs listed as cast targets
+    audio: Option<AudioService>,
 }
 
 impl MediaPlayer {
@@ -41,9 +71,119 @@ impl MediaPlayer {
         Self {
             config,
             service: None,
+            album_art: HashMap::new(),
+            lyrics: HashMap::new(),
+            audio: None,
+        }
+    }
+
+    /// Loads the album art and the lyrics of the new tracks
+    fn load_track_data(&mut self) -> Action {
+        let mut tasks = self.load_album_art();
+        tasks.extend(self.load_lyrics());
+
+        if tasks.is_empty() {
+            Action::None
+        } else {
+            Action::Command(Task::batch(tasks))
         }
     }
 
+    /// Loads the album art of the new tracks, forgetting the old ones
+    fn load_album_art(&mut self) -> Vec<Task<Message>> {
+        if !self.config.show_album_art {
+            self.album_art.clear();
+
+            return Vec::new();
+        }
+
+        let urls = self
+            .service
+            .iter()
+            .flat_map(|s| s.iter())
+            .filter_map(|d| d.metadata.as_ref().and_then(|m| m.art_url.clone()))
+            .collect::<Vec<_>>();
+
+        self.album_art.retain(|url, _| urls.contains(url));
+
+        let backdrop = self.config.album_art_backdrop;
+        let cache_size = self.config.album_art_cache_size * 1024 * 1024;
+        urls.into_iter()
+            .filter(|url| !self.album_art.contains_key(url))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|url| {
+                self.album_art.insert(url.clone(), None);
+
+                Task::perform(
+                    album_art::load(url.clone(), backdrop, cache_size),
+                    move |art| Message::AlbumArtLoaded(url.clone(), art),
+                )
+            })
+            .collect()
+    }
+
+    /// Loads the lyrics of the new tracks, forgetting the old ones
+    fn load_lyrics(&mut self) -> Vec<Task<Message>> {
+        if !self.config.show_lyrics {
+            self.lyrics.clear();
+
+            return Vec::new();
+        }
+
+        let tracks = self
+            .service
+            .iter()
+            .flat_map(|s| self.players(s))
+            .filter_map(|d| d.metadata.as_ref().and_then(Self::track_info))
+            .collect::<Vec<_>>();
+
+        self.lyrics
+            .retain(|key, _| tracks.iter().any(|t| &Self::track_key(t) == key));
+
+        let dir = self.config.lyrics_dir.as_deref().map(|dir| {
+            shellexpand::full(dir)
+                .map(|expanded| PathBuf::from(expanded.to_string()))
+                .unwrap_or_else(|_| PathBuf::from(dir))
+        });
+        let fetch = self.config.fetch_lyrics;
+
+        tracks
+            .into_iter()
+            .filter_map(|track| {
+                let key = Self::track_key(&track);
+                if self.lyrics.contains_key(&key) {
+                    return None;
+                }
+                self.lyrics.insert(key.clone(), None);
+
+                Some(Task::perform(
+                    lyrics::load(track, dir.clone(), fetch),
+                    move |lyrics| Message::LyricsLoaded(key.clone(), lyrics),
+                ))
+            })
+            .collect()
+    }
+
+    /// The lyrics are searched only for the tracks with an artist and a title
+    fn track_info(metadata: &MprisPlayerMetadata) -> Option<TrackInfo> {
+        Some(TrackInfo {
+            artist: metadata
+                .artists
+                .as_ref()
+                .filter(|a| !a.is_empty())?
+                .join(", "),
+            title: metadata.title.clone().filter(|t| !t.is_empty())?,
+            album: metadata.album.clone(),
+            length: metadata.length,
+            url: metadata.url.clone(),
+        })
+    }
+
+    fn track_key(track: &TrackInfo) -> String {
+        format!("{} - {}", track.artist, track.title)
+    }
+
     pub fn update(&mut self, message: Message) -> Action {
         match message {
             Message::Prev(s) => Action::Command(self.handle_command(s, PlayerCommand::Prev)),
@@ -57,19 +197,76 @@ impl MediaPlayer {
             Message::Event(event) => match event {
                 ServiceEvent::Init(s) => {
                     self.service = Some(s);
-                    Action::None
+                    self.load_track_data()
                 }
                 ServiceEvent::Update(d) => {
                     if let Some(service) = self.service.as_mut() {
                         service.update(d);
                     }
-                    Action::None
+                    self.load_track_data()
                 }
                 ServiceEvent::Error(_) => Action::None,
             },
+            Message::Tick => {
+                if let Some(service) = self.service.as_mut() {
+                    service.tick(TICK_INTERVAL);
+                }
+                Action::None
+            }
+            Message::AlbumArtLoaded(url, art) => {
+                // the track could have changed while loading
+                if let Some(entry) = self.album_art.get_mut(&url) {
+                    *entry = art;
+                }
+                Action::None
+            }
+            Message::LyricsLoaded(key, lyrics) => {
+                if let Some(entry) = self.lyrics.get_mut(&key) {
+                    *entry = lyrics;
+                }
+                Action::None
+            }
+            Message::AudioEvent(event) => {
+                match event {
+                    ServiceEvent::Init(s) => self.audio = Some(s),
+                    ServiceEvent::Update(d) => {
+                        if let Some(audio) = self.audio.as_mut() {
+                            audio.update(d);
+                        }
+                    }
+                    ServiceEvent::Error(_) => {}
+                }
+                Action::None
+            }
+            Message::CastTo(sink) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    let _ = audio.command(AudioCommand::MoveOutput(sink));
+                }
+                Action::None
+            }
             Message::ConfigReloaded(c) => {
+                let album_art_changed = c.show_album_art != self.config.show_album_art
+                    || c.album_art_backdrop != self.config.album_art_backdrop;
+                if !c.show_cast_targets {
+                    self.audio = None;
+                }
+                let lyrics_changed = c.show_lyrics != self.config.show_lyrics
+                    || c.lyrics_dir != self.config.lyrics_dir
+                    || c.fetch_lyrics != self.config.fetch_lyrics;
                 self.config = c;
-                Action::None
+
+                if album_art_changed {
+                    self.album_art.clear();
+                }
+                if lyrics_changed {
+                    self.lyrics.clear();
+                }
+
+                if album_art_changed || lyrics_changed {
+                    self.load_track_data()
+                } else {
+                    Action::None
+                }
             }
         }
     }
@@ -80,9 +277,18 @@ impl MediaPlayer {
             Some(s) => column!(
                 text("Players").size(theme.font_size.lg),
                 horizontal_rule(1),
-                column(s.iter().map(|d| {
-                    let title = text(self.get_title(d))
-                        .wrapping(text::Wrapping::WordOrGlyph)
+                column(self.players(s).into_iter().map(|d| {
+                    let title = Column::new()
+                        .push(text(self.get_title(d)).wrapping(text::Wrapping::WordOrGlyph))
+                        .push_maybe(Self::get_chapter(d).map(|chapter| {
+                            text(chapter)
+                                .size(theme.font_size.sm)
+                                .wrapping(text::Wrapping::WordOrGlyph)
+                        }))
+                        .push_maybe(
+                            self.get_position(d)
+                                .map(|position| text(position).size(theme.font_size.sm)),
+                        )
                         .width(Length::Fill);
 
                     let play_pause_icon = match d.state {
@@ -110,13 +316,68 @@ impl MediaPlayer {
                         })
                     });
 
-                    container(
-                        Column::new()
+                    let art = d
+                        .metadata
+                        .as_ref()
+                        .and_then(|m| m.art_url.as_ref())
+                        .and_then(|url| self.album_art.get(url))
+                        .and_then(Option::as_ref);
+
+                    // the players of the phones connected with KDE Connect
+                    let remote = d
+                        .is_player("kdeconnect")
+                        .then(|| icon(StaticIcon::Cellphone));
+                    let header = Row::new()
+                        .push_maybe(remote)
+                        .push(title)
+                        .push(buttons)
+                        .spacing(theme.space.xs)
+                        .align_y(Vertical::Center);
+                    let header = match art {
+                        Some(art) => row!(
+                            image(art.image.clone())
+                                .content_fit(ContentFit::Cover)
+                                .width(Length::Fixed(ALBUM_ART_SIZE))
+                                .height(Length::Fixed(ALBUM_ART_SIZE)),
+                            header
+                        )
+                        .spacing(theme.space.sm)
+                        .align_y(Vertical::Center),
+                        None => header,
+                    };
+                    let header: Element<'_, Message> = match art
+                        .and_then(|art| art.backdrop.as_ref())
+                    {
+                        // the backdrop is the base layer, its height is the height of the header
+                        Some(backdrop) => Stack::new()
+                            .push(
+                                image(backdrop.clone())
+                                    .content_fit(ContentFit::Cover)
+                                    .width(Length::Fill)
+                                    .height(Length::Fixed(
+                                        ALBUM_ART_SIZE + 2. * theme.space.sm as f32,
+                                    )),
+                            )
                             .push(
-                                row!(title, buttons)
-                                    .spacing(theme.space.xs)
-                                    .align_y(Vertical::Center),
+                                container(header)
+                                    .padding(theme.space.sm)
+                                    .center_y(Length::Fill)
+                                    .width(Length::Fill)
+                                    .style(move |app_theme: &Theme| container::Style {
+                                        background: Some(
+                                            app_theme.palette().background.scale_alpha(0.5).into(),
+                                        ),
+                                        ..container::Style::default()
+                                    }),
                             )
+                            .into(),
+                        None => header.into(),
+                    };
+
+                    container(
+                        Column::new()
+                            .push(header)
+                            .push_maybe(self.lyrics_view(d, theme))
                             .push_maybe(volume_slider)
                             .spacing(theme.space.xs),
                     )
@@ -139,11 +400,60 @@ impl MediaPlayer {
                 }))
                 .spacing(theme.space.md)
             )
+            .push_maybe(self.cast_targets_view(theme))
             .spacing(theme.space.xs)
             .into(),
         }
     }
 
+    /// The audio outputs, shown when there's a network one to cast to
+    fn cast_targets_view<'a>(&'a self, theme: &'a AshellTheme) -> Option<Element<'a, Message>> {
+        let audio = self.audio.as_ref()?;
+        if !audio.sinks.iter().any(|sink| sink.remote) {
+            return None;
+        }
+
+        let targets = audio.sinks.iter().map(|sink| {
+            let content = row!(
+                icon(if sink.remote {
+                    StaticIcon::Cast
+                } else {
+                    StaticIcon::Speaker3
+                }),
+                text(sink.description.as_str())
+            )
+            .spacing(theme.space.md)
+            .align_y(Vertical::Center);
+
+            if sink.name == audio.server_info.default_sink {
+                container(content.padding([theme.space.xxs, theme.space.sm]))
+                    .style(|app_theme: &Theme| container::Style {
+                        text_color: Some(app_theme.palette().success),
+                        ..Default::default()
+                    })
+                    .into()
+            } else {
+                button(content)
+                    .on_press(Message::CastTo(sink.name.clone()))
+                    .padding([theme.space.xxs, theme.space.sm])
+                    .width(Length::Fill)
+                    .style(theme.ghost_button_style())
+                    .into()
+            }
+        });
+
+        Some(
+            column!(
+                text("Cast to").size(theme.font_size.lg),
+                horizontal_rule(1),
+                Column::with_children(targets.collect::<Vec<Element<'_, _, _>>>())
+                    .spacing(theme.space.xxs),
+            )
+            .spacing(theme.space.xs)
+            .into(),
+        )
+    }
+
     fn handle_command(&mut self, service_name: String, command: PlayerCommand) -> Task<Message> {
         match self.service.as_mut() {
             Some(s) => s
@@ -156,34 +466,182 @@ impl MediaPlayer {
         }
     }
 
+    /// Players without the ignored ones, sorted by priority
+    fn players<'a>(&self, service: &'a MprisPlayerService) -> Vec<&'a MprisPlayerData> {
+        let mut players = service
+            .iter()
+            .filter(|d| {
+                !self
+                    .config
+                    .ignored_players
+                    .iter()
+                    .any(|name| d.is_player(name))
+            })
+            .collect::<Vec<_>>();
+
+        players.sort_by_key(|d| {
+            self.config
+                .player_priority
+                .iter()
+                .position(|name| d.is_player(name))
+                .unwrap_or(self.config.player_priority.len())
+        });
+
+        players
+    }
+
+    /// Album and track number, the book and the chapter for the audiobooks
+    fn get_chapter(d: &MprisPlayerData) -> Option<String> {
+        let metadata = d.metadata.as_ref()?;
+
+        match (&metadata.album, metadata.track_number) {
+            (Some(album), Some(track)) => Some(format!("{album} · {track}")),
+            (Some(album), None) => Some(album.clone()),
+            (None, _) => None,
+        }
+    }
+
+    fn get_position(&self, d: &MprisPlayerData) -> Option<String> {
+        if !self.config.show_position {
+            return None;
+        }
+
+        let position = d.position?;
+
+        Some(match d.metadata.as_ref().and_then(|m| m.length) {
+            Some(length) => format!(
+                "{} / {}",
+                format_duration(position),
+                format_duration(length)
+            ),
+            None => format_duration(position),
+        })
+    }
+
+    fn lyrics_view<'a>(
+        &'a self,
+        d: &'a MprisPlayerData,
+        theme: &'a AshellTheme,
+    ) -> Option<Element<'a, Message>> {
+        let track = d.metadata.as_ref().and_then(Self::track_info)?;
+        let lyrics = self.lyrics.get(&Self::track_key(&track))?.as_ref()?;
+
+        let line = |value: &'a str, current: bool| -> Element<'a, Message> {
+            text(value)
+                .size(theme.font_size.sm)
+                .wrapping(text::Wrapping::WordOrGlyph)
+                .style(move |app_theme: &Theme| text::Style {
+                    color: Some(if current {
+                        app_theme.palette().primary
+                    } else {
+                        app_theme.palette().text.scale_alpha(0.5)
+                    }),
+                })
+                .into()
+        };
+
+        if !lyrics.is_synced() {
+            return Some(
+                container(scrollable(
+                    Column::with_children(
+                        lyrics
+                            .lines
+                            .iter()
+                            .map(|(_, value)| line(value, false))
+                            .collect::<Vec<Element<'_, _, _>>>(),
+                    )
+                    .width(Length::Fill),
+                ))
+                .max_height(200)
+                .into(),
+            );
+        }
+
+        // the lines around the current one, the first ones before the track starts
+        let current = lyrics.current_line(d.position.unwrap_or_default());
+        let start = current.map_or(0, |i| i.saturating_sub(LYRICS_CONTEXT));
+
+        Some(
+            Column::with_children(
+                lyrics
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(2 * LYRICS_CONTEXT + 1)
+                    .map(|(i, (_, value))| line(value, Some(i) == current))
+                    .collect::<Vec<Element<'_, _, _>>>(),
+            )
+            .width(Length::Fill)
+            .into(),
+        )
+    }
+
     fn get_title(&self, d: &MprisPlayerData) -> String {
         match &d.metadata {
-            Some(m) => truncate_text(&m.to_string(), self.config.max_title_length),
+            Some(m) => truncate_text(&m.to_string(), self.config.max_title_length).into_owned(),
             None => "No Title".to_string(),
         }
     }
 
     pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
-        self.service.as_ref().and_then(|s| match s.len() {
-            0 => None,
-            _ => Some(
-                row![
-                    icon(StaticIcon::MusicNote),
-                    container(
-                        text(self.get_title(&s[0]))
-                            .wrapping(text::Wrapping::None)
-                            .size(theme.font_size.sm)
-                    )
-                    .clip(true)
-                ]
-                .align_y(Vertical::Center)
-                .spacing(theme.space.xs)
-                .into(),
-            ),
-        })
+        let service = self.service.as_ref()?;
+        let player = self.players(service).into_iter().next()?;
+
+        let title = match self.get_position(player) {
+            Some(position) => format!("{} {position}", self.get_title(player)),
+            None => self.get_title(player),
+        };
+
+        Some(
+            row![
+                icon(StaticIcon::MusicNote),
+                container(
+                    text(title)
+                        .wrapping(text::Wrapping::None)
+                        .size(theme.font_size.sm)
+                )
+                .clip(true)
+            ]
+            .align_y(Vertical::Center)
+            .spacing(theme.space.xs)
+            .into(),
+        )
+    }
+
+    /// Interval of the `Tick`s moving the position, sent by the redraw scheduler
+    pub fn redraw_interval(&self) -> Option<Duration> {
+        let playing = self.service.as_ref().is_some_and(|s| {
+            s.iter()
+                .any(|d| d.state == PlaybackStatus::Playing && d.position.is_some())
+        });
+
+        ((self.config.show_position || self.config.show_lyrics) && playing).then_some(TICK_INTERVAL)
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        MprisPlayerService::subscribe().map(Message::Event)
+        Subscription::batch(vec![
+            MprisPlayerService::subscribe().map(Message::Event),
+            if self.config.show_cast_targets {
+                AudioService::subscribe().map(Message::AudioEvent)
+            } else {
+                Subscription::none()
+            },
+        ])
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
     }
 }
@@ -0,0 +1,120 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::MicrophoneModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        audio::{AudioCommand, AudioService},
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Subscription, Theme,
+    mouse::ScrollDelta,
+    widget::{MouseArea, container, row, text},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<AudioService>),
+    ToggleMute,
+    VolumeChanged(i32),
+    ConfigReloaded(MicrophoneModuleConfig),
+}
+
+/// The default source, highlighted while an application records from it
+pub struct Microphone {
+    config: MicrophoneModuleConfig,
+    service: Option<AudioService>,
+}
+
+impl Microphone {
+    pub fn new(config: MicrophoneModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+        }
+    }
+
+    fn command(&mut self, command: AudioCommand) {
+        if let Some(service) = self.service.as_mut() {
+            let _ = service.command(command);
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+            Message::ToggleMute => self.command(AudioCommand::ToggleSourceMute),
+            Message::VolumeChanged(volume) => self.command(AudioCommand::SourceVolume(volume)),
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let service = self.service.as_ref()?;
+        let source = service
+            .sources
+            .iter()
+            .find(|source| source.name == service.server_info.default_source)?;
+        let recording = service.is_recording();
+        if self.config.hide_when_idle && !recording {
+            return None;
+        }
+
+        let volume = service.cur_source_volume;
+        let step = i32::from(self.config.scroll_step);
+        let muted = source.is_mute;
+
+        Some(
+            MouseArea::new(
+                container(
+                    row!(icon(if muted {
+                        StaticIcon::Mic0
+                    } else {
+                        StaticIcon::Mic1
+                    }))
+                    .push_maybe(
+                        (self.config.show_volume && !muted).then(|| text(format!("{volume}%"))),
+                    )
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xxs),
+                )
+                .style(move |theme: &Theme| container::Style {
+                    text_color: Some(if recording && !muted {
+                        theme.palette().danger
+                    } else {
+                        theme.palette().text
+                    }),
+                    ..Default::default()
+                }),
+            )
+            .on_scroll(move |delta| {
+                let delta = match delta {
+                    ScrollDelta::Lines { y, .. } | ScrollDelta::Pixels { y, .. } => y,
+                };
+                Message::VolumeChanged(if delta > 0. {
+                    (volume + step).min(100)
+                } else {
+                    (volume - step).max(0)
+                })
+            })
+            .into(),
+        )
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        AudioService::subscribe().map(Message::Event)
+    }
+}
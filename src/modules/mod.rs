@@ -6,27 +6,80 @@ use crate::{
     theme::AshellTheme,
 };
 use iced::{
-    Alignment, Border, Color, Element, Length, Subscription,
-    widget::{Row, container, row},
+    Alignment, Border, Color, Element, Length, Subscription, Theme,
+    widget::{Row, container, row, text},
     window::Id,
 };
+use std::time::Duration;
 
+#[cfg(feature = "acpi")]
+pub mod acpi;
+#[cfg(feature = "app-launcher")]
 pub mod app_launcher;
+#[cfg(feature = "audio")]
+pub mod audio;
+#[cfg(feature = "autostart")]
+pub mod autostart;
+#[cfg(feature = "backup")]
+pub mod backup;
+#[cfg(feature = "battery")]
+pub mod battery;
+#[cfg(feature = "bluetooth")]
+pub mod bluetooth;
+#[cfg(feature = "clipboard")]
 pub mod clipboard;
+#[cfg(feature = "clock")]
 pub mod clock;
 pub mod custom_module;
+#[cfg(feature = "dashboard")]
+pub mod dashboard;
+#[cfg(feature = "encrypted-volumes")]
+pub mod encrypted_volumes;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "idle-inhibitor")]
+pub mod idle_inhibitor;
 
-#[cfg(feature = "hyprland")]
+#[cfg(feature = "keyboard-layout")]
 pub mod keyboard_layout;
-#[cfg(feature = "hyprland")]
+#[cfg(feature = "keyboard-submap")]
 pub mod keyboard_submap;
+#[cfg(feature = "window-group")]
+pub mod window_group;
+#[cfg(feature = "window-rules")]
+pub mod window_rules;
 
+#[cfg(feature = "media-player")]
 pub mod media_player;
+#[cfg(feature = "audio")]
+pub mod microphone;
+#[cfg(feature = "modem")]
+pub mod modem;
+#[cfg(feature = "network")]
+pub mod network;
+#[cfg(feature = "notes")]
+pub mod notes;
+#[cfg(feature = "notifications")]
+pub mod notifications;
+#[cfg(feature = "passwords")]
+pub mod passwords;
+#[cfg(feature = "power-profiles")]
+pub mod power_profiles;
+#[cfg(feature = "privacy")]
 pub mod privacy;
+#[cfg(feature = "qr-code")]
+pub mod qr_code;
 pub mod settings;
+#[cfg(feature = "system-info")]
 pub mod system_info;
+#[cfg(feature = "totp")]
+pub mod totp;
+#[cfg(feature = "tray")]
 pub mod tray;
+#[cfg(feature = "updates")]
 pub mod updates;
+#[cfg(feature = "usbguard")]
+pub mod usbguard;
 pub mod window_title;
 pub mod workspaces;
 
@@ -65,6 +118,21 @@ impl App {
         })
     }
 
+    /// Modules of the configuration in the order they appear in the bar
+    pub fn configured_modules(&self) -> impl Iterator<Item = &ModuleName> {
+        [
+            &self.general_config.modules.left,
+            &self.general_config.modules.center,
+            &self.general_config.modules.right,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|module_def| match module_def {
+            ModuleDef::Single(module) => std::slice::from_ref(module),
+            ModuleDef::Group(group) => group.as_slice(),
+        })
+    }
+
     pub fn modules_subscriptions(&self, modules_def: &[ModuleDef]) -> Vec<Subscription<Message>> {
         modules_def
             .iter()
@@ -223,8 +291,48 @@ impl App {
         &'a self,
         id: Id,
         module_name: &'a ModuleName,
+    ) -> Option<(Element<'a, Message>, Option<OnModulePress>)> {
+        if self.state.is_hidden(&module_name.to_string()) {
+            return None;
+        }
+
+        let opacity = self.zen_mode.module_opacity(module_name);
+
+        if opacity <= 0. {
+            return None;
+        }
+
+        let view = match self.privacy_mode.placeholder(module_name) {
+            // the menu of a blanked module can't be opened
+            Some(placeholder) => Some((text(placeholder).into(), None)),
+            None => self.module_view(id, module_name),
+        };
+
+        if opacity < 1. {
+            // the elements with their own text color don't fade
+            view.map(|(content, action)| {
+                (
+                    container(content)
+                        .style(move |theme: &Theme| container::Style {
+                            text_color: Some(theme.palette().text.scale_alpha(opacity)),
+                            ..container::Style::default()
+                        })
+                        .into(),
+                    action,
+                )
+            })
+        } else {
+            view
+        }
+    }
+
+    fn module_view<'a>(
+        &'a self,
+        id: Id,
+        module_name: &'a ModuleName,
     ) -> Option<(Element<'a, Message>, Option<OnModulePress>)> {
         match module_name {
+            #[cfg(feature = "app-launcher")]
             ModuleName::AppLauncher => self.app_launcher.as_ref().map(|app_launcher| {
                 (
                     app_launcher.view().map(Message::AppLauncher),
@@ -244,12 +352,14 @@ impl App {
                     )))),
                 )
             }),
+            #[cfg(feature = "updates")]
             ModuleName::Updates => self.updates.as_ref().map(|updates| {
                 (
                     updates.view(&self.theme).map(Message::Updates),
                     Some(OnModulePress::ToggleMenu(MenuType::Updates)),
                 )
             }),
+            #[cfg(feature = "clipboard")]
             ModuleName::Clipboard => self.clipboard.as_ref().map(|clipboard| {
                 (
                     clipboard.view().map(Message::Clipboard),
@@ -258,25 +368,50 @@ impl App {
                     )))),
                 )
             }),
-            ModuleName::Workspaces => Some((
+            ModuleName::Workspaces(None) => Some((
                 self.workspaces
                     .view(id, &self.theme, &self.outputs)
                     .map(Message::Workspaces),
                 None,
             )),
-            ModuleName::WindowTitle => self.window_title.get_value().map(|title| {
-                (
-                    self.window_title
-                        .view(&self.theme, title)
-                        .map(Message::WindowTitle),
-                    None,
-                )
+            ModuleName::Workspaces(Some(name)) => {
+                self.workspaces_instances.get(name).map(|workspaces| {
+                    (
+                        workspaces
+                            .view(id, &self.theme, &self.outputs)
+                            .map(|msg| Message::WorkspacesInstance(name.clone(), msg)),
+                        None,
+                    )
+                })
+            }
+            ModuleName::WindowTitle => self.window_title.view(&self.theme).map(|view| {
+                let view = view.map(Message::WindowTitle);
+
+                // the right click opens the rules of the focused window instead of the context menu
+                #[cfg(feature = "window-rules")]
+                let view = if self.window_title.window_rules() {
+                    position_button(view)
+                        .padding(0)
+                        .style(|theme: &Theme, _| iced::widget::button::Style {
+                            text_color: theme.palette().text,
+                            ..Default::default()
+                        })
+                        .on_right_press_with_position(move |button_ui_ref| {
+                            Message::ToggleMenu(MenuType::WindowRules, id, button_ui_ref)
+                        })
+                        .into()
+                } else {
+                    view
+                };
+
+                (view, None)
             }),
+            #[cfg(feature = "system-info")]
             ModuleName::SystemInfo => Some((
                 self.system_info.view(&self.theme).map(Message::SystemInfo),
                 Some(OnModulePress::ToggleMenu(MenuType::SystemInfo)),
             )),
-            #[cfg(feature = "hyprland")]
+            #[cfg(feature = "keyboard-layout")]
             ModuleName::KeyboardLayout => self.keyboard_layout.view(&self.theme).map(|view| {
                 (
                     view.map(Message::KeyboardLayout),
@@ -285,20 +420,36 @@ impl App {
                     )))),
                 )
             }),
-            #[cfg(feature = "hyprland")]
+            #[cfg(feature = "keyboard-submap")]
             ModuleName::KeyboardSubmap => self
                 .keyboard_submap
                 .view(&self.theme)
                 .map(|view| (view.map(Message::KeyboardSubmap), None)),
+            #[cfg(feature = "window-group")]
+            ModuleName::WindowGroup => self.window_group.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::WindowGroup),
+                    Some(OnModulePress::Action(Box::new(Message::WindowGroup(
+                        window_group::Message::NextTab,
+                    )))),
+                )
+            }),
+            #[cfg(feature = "tray")]
             ModuleName::Tray => self
                 .tray
                 .view(id, &self.theme)
                 .map(|view| (view.map(Message::Tray), None)),
-            ModuleName::Clock => Some((self.clock.view(&self.theme).map(Message::Clock), None)),
+            #[cfg(feature = "clock")]
+            ModuleName::Clock => Some((
+                self.clock.view(&self.theme).map(Message::Clock),
+                Some(OnModulePress::ToggleMenu(MenuType::Clock)),
+            )),
+            #[cfg(feature = "privacy")]
             ModuleName::Privacy => self
                 .privacy
                 .view(&self.theme)
                 .map(|view| (view.map(Message::Privacy), None)),
+            #[cfg(feature = "media-player")]
             ModuleName::MediaPlayer => self.media_player.view(&self.theme).map(|view| {
                 (
                     view.map(Message::MediaPlayer),
@@ -309,48 +460,318 @@ impl App {
                 self.settings.view(&self.theme).map(Message::Settings),
                 Some(OnModulePress::ToggleMenu(MenuType::Settings)),
             )),
+            #[cfg(feature = "qr-code")]
+            ModuleName::QrCode => Some((
+                self.qr_code.view(&self.theme).map(Message::QrCode),
+                Some(OnModulePress::ToggleMenu(MenuType::QrCode)),
+            )),
+            #[cfg(feature = "usbguard")]
+            ModuleName::UsbGuard => self.usbguard.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::UsbGuard),
+                    Some(OnModulePress::ToggleMenu(MenuType::UsbGuard)),
+                )
+            }),
+            #[cfg(feature = "backup")]
+            ModuleName::Backup => Some((
+                self.backup.view(&self.theme).map(Message::Backup),
+                Some(OnModulePress::ToggleMenu(MenuType::Backup)),
+            )),
+            #[cfg(feature = "modem")]
+            ModuleName::Modem => self.modem.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::Modem),
+                    Some(OnModulePress::ToggleMenu(MenuType::Modem)),
+                )
+            }),
+            #[cfg(feature = "encrypted-volumes")]
+            ModuleName::EncryptedVolumes => self.encrypted_volumes.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::EncryptedVolumes),
+                    Some(OnModulePress::ToggleMenu(MenuType::EncryptedVolumes)),
+                )
+            }),
+            #[cfg(feature = "passwords")]
+            ModuleName::Passwords => Some((
+                self.passwords.view(&self.theme).map(Message::Passwords),
+                Some(OnModulePress::ToggleMenu(MenuType::Passwords)),
+            )),
+            #[cfg(feature = "totp")]
+            ModuleName::Totp => Some((
+                self.totp.view(&self.theme).map(Message::Totp),
+                Some(OnModulePress::ToggleMenu(MenuType::Totp)),
+            )),
+            #[cfg(feature = "notes")]
+            ModuleName::Notes => Some((
+                self.notes.view(&self.theme).map(Message::Notes),
+                Some(OnModulePress::ToggleMenu(MenuType::Notes)),
+            )),
+            #[cfg(feature = "autostart")]
+            ModuleName::Autostart => self.autostart.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::Autostart),
+                    Some(OnModulePress::ToggleMenu(MenuType::Autostart)),
+                )
+            }),
+            #[cfg(feature = "acpi")]
+            ModuleName::Acpi => self
+                .acpi
+                .view(&self.theme)
+                .map(|view| (view.map(Message::Acpi), None)),
+            #[cfg(feature = "gpu")]
+            ModuleName::Gpu => self.gpu.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::Gpu),
+                    Some(OnModulePress::ToggleMenu(MenuType::Gpu)),
+                )
+            }),
+            #[cfg(feature = "dashboard")]
+            ModuleName::Dashboard => Some((
+                self.dashboard.view(&self.theme).map(Message::Dashboard),
+                Some(OnModulePress::ToggleMenu(MenuType::Dashboard)),
+            )),
+            #[cfg(feature = "battery")]
+            ModuleName::Battery => self
+                .battery
+                .view(&self.theme)
+                .map(|view| (view.map(Message::Battery), None)),
+            #[cfg(feature = "audio")]
+            ModuleName::Audio => self.audio.view(&self.theme).map(|view| {
+                (
+                    // the left click mutes, the right click opens the devices and the streams
+                    position_button(view.map(Message::Audio))
+                        .padding(0)
+                        .style(|theme: &Theme, _| iced::widget::button::Style {
+                            text_color: theme.palette().text,
+                            ..Default::default()
+                        })
+                        .on_right_press_with_position(move |button_ui_ref| {
+                            Message::ToggleMenu(MenuType::Audio, id, button_ui_ref)
+                        })
+                        .into(),
+                    Some(OnModulePress::Action(Box::new(Message::Audio(
+                        audio::Message::ToggleMute,
+                    )))),
+                )
+            }),
+            #[cfg(feature = "audio")]
+            ModuleName::Microphone => self.microphone.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::Microphone),
+                    Some(OnModulePress::Action(Box::new(Message::Microphone(
+                        microphone::Message::ToggleMute,
+                    )))),
+                )
+            }),
+            #[cfg(feature = "network")]
+            ModuleName::Network => self.network.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::Network),
+                    Some(OnModulePress::ToggleMenu(MenuType::Network)),
+                )
+            }),
+            #[cfg(feature = "bluetooth")]
+            ModuleName::Bluetooth => self.bluetooth.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::Bluetooth),
+                    Some(OnModulePress::ToggleMenu(MenuType::Bluetooth)),
+                )
+            }),
+            #[cfg(feature = "notifications")]
+            ModuleName::Notifications => self.notifications.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::Notifications),
+                    Some(OnModulePress::ToggleMenu(MenuType::Notifications)),
+                )
+            }),
+            #[cfg(feature = "idle-inhibitor")]
+            ModuleName::IdleInhibitor => self.idle_inhibitor.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::IdleInhibitor),
+                    Some(OnModulePress::Action(Box::new(Message::IdleInhibitor(
+                        idle_inhibitor::Message::Toggle,
+                    )))),
+                )
+            }),
+            #[cfg(feature = "power-profiles")]
+            ModuleName::PowerProfiles => self.power_profiles.view(&self.theme).map(|view| {
+                (
+                    view.map(Message::PowerProfiles),
+                    Some(OnModulePress::Action(Box::new(Message::PowerProfiles(
+                        power_profiles::Message::Cycle,
+                    )))),
+                )
+            }),
         }
     }
 
+    /// The module is shown, neither hidden, faded out by the zen mode nor blanked
+    fn module_visible(&self, module_name: &ModuleName) -> bool {
+        !self.state.is_hidden(&module_name.to_string())
+            && self.zen_mode.module_opacity(module_name) > 0.
+            && self.privacy_mode.placeholder(module_name).is_none()
+    }
+
+    /// Intervals of the time-driven modules shown on a bar or in their open or pinned menu,
+    /// once per module whatever the number of surfaces showing it
+    pub fn redraw_intervals(&self) -> Vec<(ModuleName, Duration)> {
+        let bars_shown = self.outputs.has_shown_bar();
+        let mut intervals = Vec::new();
+
+        for module in self.configured_modules() {
+            let redraw: Option<(Duration, MenuType)> = match module {
+                #[cfg(feature = "clock")]
+                ModuleName::Clock => Some((self.clock.redraw_interval(), MenuType::Clock)),
+                #[cfg(feature = "media-player")]
+                ModuleName::MediaPlayer => self
+                    .media_player
+                    .redraw_interval()
+                    .map(|interval| (interval, MenuType::MediaPlayer)),
+                _ => None,
+            };
+            let Some((interval, menu_type)) = redraw else {
+                continue;
+            };
+
+            // a popup keeps its content live even when the module is hidden on the bar
+            let shown = self.outputs.is_menu_open(&menu_type)
+                || self.pinned_popups.is_pinned(&menu_type)
+                || (bars_shown && self.module_visible(module));
+
+            if shown {
+                intervals.push((module.clone(), interval));
+            }
+        }
+
+        intervals
+    }
+
     fn get_module_subscription(&self, module_name: &ModuleName) -> Option<Subscription<Message>> {
         match module_name {
+            #[cfg(feature = "app-launcher")]
             ModuleName::AppLauncher => None,
             ModuleName::Custom(name) => self.custom.get(name).map(|custom| {
                 custom
                     .subscription()
                     .map(|(name, msg)| Message::Custom(name, msg))
             }),
+            #[cfg(feature = "updates")]
             ModuleName::Updates => self
                 .updates
                 .as_ref()
                 .map(|updates| updates.subscription().map(Message::Updates)),
+            #[cfg(feature = "clipboard")]
             ModuleName::Clipboard => None,
-            ModuleName::Workspaces => Some(self.workspaces.subscription().map(Message::Workspaces)),
+            // only the workspaces have named instances, with their own state and event stream
+            ModuleName::Workspaces(None) => Some(
+                self.workspaces
+                    .subscription(&module_name.to_string())
+                    .map(Message::Workspaces),
+            ),
+            ModuleName::Workspaces(Some(name)) => {
+                self.workspaces_instances.get(name).map(|workspaces| {
+                    workspaces
+                        .subscription(&module_name.to_string())
+                        .with(name.clone())
+                        .map(|(name, msg)| Message::WorkspacesInstance(name, msg))
+                })
+            }
             ModuleName::WindowTitle => {
                 Some(self.window_title.subscription().map(Message::WindowTitle))
             }
-            ModuleName::SystemInfo => {
-                Some(self.system_info.subscription().map(Message::SystemInfo))
-            }
-            #[cfg(feature = "hyprland")]
+            #[cfg(feature = "system-info")]
+            ModuleName::SystemInfo => Some(
+                self.system_info
+                    .subscription(
+                        self.battery_saver.polling_factor(),
+                        self.outputs.is_menu_open(&MenuType::SystemInfo)
+                            || self.pinned_popups.is_pinned(&MenuType::SystemInfo),
+                    )
+                    .map(Message::SystemInfo),
+            ),
+            #[cfg(feature = "keyboard-layout")]
             ModuleName::KeyboardLayout => Some(
                 self.keyboard_layout
                     .subscription()
                     .map(Message::KeyboardLayout),
             ),
-            #[cfg(feature = "hyprland")]
+            #[cfg(feature = "keyboard-submap")]
             ModuleName::KeyboardSubmap => Some(
                 self.keyboard_submap
                     .subscription()
                     .map(Message::KeyboardSubmap),
             ),
+            #[cfg(feature = "window-group")]
+            ModuleName::WindowGroup => {
+                Some(self.window_group.subscription().map(Message::WindowGroup))
+            }
+            #[cfg(feature = "tray")]
             ModuleName::Tray => Some(self.tray.subscription().map(Message::Tray)),
+            #[cfg(feature = "clock")]
             ModuleName::Clock => Some(self.clock.subscription().map(Message::Clock)),
+            #[cfg(feature = "privacy")]
             ModuleName::Privacy => Some(self.privacy.subscription().map(Message::Privacy)),
+            #[cfg(feature = "media-player")]
             ModuleName::MediaPlayer => {
                 Some(self.media_player.subscription().map(Message::MediaPlayer))
             }
             ModuleName::Settings => Some(self.settings.subscription().map(Message::Settings)),
+            #[cfg(feature = "autostart")]
+            ModuleName::Autostart => None,
+            #[cfg(feature = "notes")]
+            ModuleName::Notes => None,
+            #[cfg(feature = "qr-code")]
+            ModuleName::QrCode => None,
+            #[cfg(feature = "passwords")]
+            ModuleName::Passwords => None,
+            #[cfg(feature = "usbguard")]
+            ModuleName::UsbGuard => Some(self.usbguard.subscription().map(Message::UsbGuard)),
+            #[cfg(feature = "backup")]
+            ModuleName::Backup => Some(self.backup.subscription().map(Message::Backup)),
+            #[cfg(feature = "modem")]
+            ModuleName::Modem => Some(self.modem.subscription().map(Message::Modem)),
+            #[cfg(feature = "encrypted-volumes")]
+            ModuleName::EncryptedVolumes => Some(
+                self.encrypted_volumes
+                    .subscription()
+                    .map(Message::EncryptedVolumes),
+            ),
+            #[cfg(feature = "totp")]
+            ModuleName::Totp => Some(self.totp.subscription().map(Message::Totp)),
+            #[cfg(feature = "acpi")]
+            ModuleName::Acpi => Some(self.acpi.subscription().map(Message::Acpi)),
+            #[cfg(feature = "gpu")]
+            ModuleName::Gpu => Some(self.gpu.subscription().map(Message::Gpu)),
+            #[cfg(feature = "dashboard")]
+            ModuleName::Dashboard => Some(self.dashboard.subscription().map(Message::Dashboard)),
+            #[cfg(feature = "battery")]
+            ModuleName::Battery => Some(self.battery.subscription().map(Message::Battery)),
+            #[cfg(feature = "audio")]
+            ModuleName::Audio => Some(self.audio.subscription().map(Message::Audio)),
+            #[cfg(feature = "audio")]
+            ModuleName::Microphone => Some(self.microphone.subscription().map(Message::Microphone)),
+            #[cfg(feature = "network")]
+            ModuleName::Network => Some(self.network.subscription().map(Message::Network)),
+            #[cfg(feature = "bluetooth")]
+            ModuleName::Bluetooth => Some(self.bluetooth.subscription().map(Message::Bluetooth)),
+            #[cfg(feature = "notifications")]
+            ModuleName::Notifications => Some(
+                self.notifications
+                    .subscription()
+                    .map(Message::Notifications),
+            ),
+            #[cfg(feature = "idle-inhibitor")]
+            ModuleName::IdleInhibitor => Some(
+                self.idle_inhibitor
+                    .subscription()
+                    .map(Message::IdleInhibitor),
+            ),
+            #[cfg(feature = "power-profiles")]
+            ModuleName::PowerProfiles => Some(
+                self.power_profiles
+                    .subscription()
+                    .map(Message::PowerProfiles),
+            ),
         }
     }
 }
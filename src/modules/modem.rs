@@ -0,0 +1,302 @@
+use crate::{
+    components::icons::{IconButtonSize, StaticIcon, icon, icon_button},
+    config::ModemModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        modem::{ModemCommand, ModemInfo, ModemService, ModemState, SmsMessage},
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, Theme,
+    widget::{Column, column, container, horizontal_rule, row, scrollable, text, toggler},
+};
+use log::warn;
+use std::collections::HashSet;
+use zbus::zvariant::OwnedObjectPath;
+
+fn signal_icon(modem: &ModemInfo) -> StaticIcon {
+    match modem.state {
+        ModemState::Failed | ModemState::Locked | ModemState::Disabled => StaticIcon::CellularOff,
+        ModemState::Searching | ModemState::Unknown => StaticIcon::Cellular0,
+        _ => match modem.signal {
+            0..=24 => StaticIcon::Cellular0,
+            25..=49 => StaticIcon::Cellular1,
+            50..=74 => StaticIcon::Cellular2,
+            _ => StaticIcon::Cellular3,
+        },
+    }
+}
+
+fn state_label(state: ModemState) -> &'static str {
+    match state {
+        ModemState::Failed => "Failed",
+        ModemState::Locked => "SIM locked",
+        ModemState::Disabled => "Disabled",
+        ModemState::Searching => "Searching...",
+        ModemState::Registered => "Registered",
+        ModemState::Connecting => "Connecting...",
+        ModemState::Connected => "Connected",
+        ModemState::Unknown => "Unknown",
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<ModemService>),
+    MenuOpened,
+    ToggleData,
+    DeleteMessage(OwnedObjectPath),
+    ConfigReloaded(ModemModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+pub struct Modem {
+    config: ModemModuleConfig,
+    service: Option<ModemService>,
+    /// ModemManager doesn't track the read messages, they are read once the menu is opened
+    read: HashSet<OwnedObjectPath>,
+    pending: bool,
+    error: Option<String>,
+}
+
+impl Modem {
+    pub fn new(config: ModemModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+            read: HashSet::new(),
+            pending: false,
+            error: None,
+        }
+    }
+
+    fn messages(&self) -> &[SmsMessage] {
+        self.service
+            .as_ref()
+            .and_then(|service| service.modem.as_ref())
+            .map(|modem| modem.messages.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn unread(&self) -> usize {
+        self.messages()
+            .iter()
+            .filter(|sms| !self.read.contains(&sms.path))
+            .count()
+    }
+
+    fn mark_as_read(&mut self) {
+        let paths = self
+            .messages()
+            .iter()
+            .map(|sms| sms.path.clone())
+            .collect::<Vec<_>>();
+
+        self.read.extend(paths);
+    }
+
+    fn command(&mut self, command: ModemCommand) -> Option<Task<Message>> {
+        let task = self.service.as_mut()?.command(command).map(Message::Event);
+        self.pending = true;
+        self.error = None;
+
+        Some(task)
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.service = Some(service);
+                        // the messages received before the start are not new
+                        self.mark_as_read();
+                    }
+                    ServiceEvent::Update(data) => {
+                        if let Some(service) = self.service.as_mut() {
+                            service.update(data);
+                        }
+                        self.pending = false;
+                    }
+                    ServiceEvent::Error(err) => {
+                        warn!("Modem operation failed: {err}");
+                        self.error = Some(err);
+                        self.pending = false;
+                    }
+                }
+
+                Action::None
+            }
+            Message::MenuOpened => {
+                self.mark_as_read();
+                self.error = None;
+
+                self.command(ModemCommand::Refresh)
+                    .map_or(Action::None, Action::Command)
+            }
+            Message::ToggleData => {
+                let connected = self
+                    .service
+                    .as_ref()
+                    .and_then(|service| service.modem.as_ref())
+                    .is_some_and(|modem| modem.is_connected());
+                let command = if connected {
+                    ModemCommand::Disconnect
+                } else {
+                    ModemCommand::Connect(self.config.apn.clone())
+                };
+
+                self.command(command).map_or(Action::None, Action::Command)
+            }
+            Message::DeleteMessage(path) => {
+                self.read.remove(&path);
+
+                self.command(ModemCommand::DeleteMessage(path))
+                    .map_or(Action::None, Action::Command)
+            }
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let modem = self.service.as_ref()?.modem.as_ref()?;
+        let unread = self.unread();
+
+        Some(
+            row!(icon(signal_icon(modem)))
+                .push_maybe(
+                    modem
+                        .access_technology
+                        .clone()
+                        .filter(|_| modem.is_connected())
+                        .map(text),
+                )
+                .push_maybe((unread > 0).then(|| {
+                    row!(icon(StaticIcon::Sms), text(unread))
+                        .align_y(Alignment::Center)
+                        .spacing(theme.space.xxs)
+                }))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xxs)
+                .into(),
+        )
+    }
+
+    fn message_view<'a>(
+        &'a self,
+        theme: &'a AshellTheme,
+        sms: &'a SmsMessage,
+    ) -> Element<'a, Message> {
+        column!(
+            row!(text(sms.number.clone()).width(Length::Fill),)
+                .push_maybe(sms.timestamp.map(|timestamp| {
+                    text(timestamp.format("%Y-%m-%d %H:%M").to_string()).size(theme.font_size.xs)
+                }))
+                .push(
+                    icon_button(theme, StaticIcon::TrashCan)
+                        .on_press_maybe(
+                            (!self.pending).then(|| Message::DeleteMessage(sms.path.clone()))
+                        )
+                        .size(IconButtonSize::Small),
+                )
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xs),
+            text(sms.text.clone())
+                .size(theme.font_size.sm)
+                .wrapping(text::Wrapping::WordOrGlyph),
+        )
+        .spacing(theme.space.xxs)
+        .into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let Some(modem) = self
+            .service
+            .as_ref()
+            .and_then(|service| service.modem.as_ref())
+        else {
+            return text("No modem available").into();
+        };
+
+        let details = [
+            modem.operator.clone(),
+            Some(state_label(modem.state).to_string()),
+            modem.access_technology.clone(),
+            Some(format!("{}%", modem.signal)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" · ");
+
+        column!(
+            row!(
+                icon(signal_icon(modem)),
+                column!(
+                    text(if modem.model.is_empty() {
+                        "Mobile broadband".to_string()
+                    } else {
+                        modem.model.clone()
+                    })
+                    .size(theme.font_size.lg),
+                    text(details).size(theme.font_size.xs),
+                )
+                .width(Length::Fill),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.sm),
+            horizontal_rule(1),
+            row!(
+                text("Mobile data").width(Length::Fill),
+                toggler(modem.is_connected() || modem.state == ModemState::Connecting)
+                    .on_toggle_maybe(
+                        (!self.pending && modem.state != ModemState::Locked)
+                            .then_some(|_: bool| Message::ToggleData)
+                    )
+                    .width(Length::Shrink),
+            )
+            .align_y(Alignment::Center),
+        )
+        .push_maybe(self.error.as_ref().map(|err| {
+            container(text(err.clone()).size(theme.font_size.xs)).style(|theme: &Theme| {
+                container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                }
+            })
+        }))
+        .push(horizontal_rule(1))
+        .push(text("Messages"))
+        .push(if modem.messages.is_empty() {
+            Element::from(text("No messages").size(theme.font_size.sm))
+        } else {
+            container(scrollable(
+                Column::with_children(
+                    modem
+                        .messages
+                        .iter()
+                        .map(|sms| self.message_view(theme, sms))
+                        .collect::<Vec<_>>(),
+                )
+                .spacing(theme.space.sm),
+            ))
+            .max_height(300)
+            .into()
+        })
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        ModemService::subscribe().map(Message::Event)
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    components::icons::{StaticIcon, icon, icon_button},
+    config::NetworkModuleConfig,
+    password_dialog,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        network::{
+            AccessPoint, ActiveConnectionInfo, KnownConnection, NetworkCommand, NetworkEvent,
+            NetworkService, dbus::ConnectivityState,
+        },
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, Theme,
+    widget::{
+        Column, Row, button, column, container, horizontal_rule, row, scrollable, text, toggler,
+    },
+    window::Id,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<NetworkService>),
+    ToggleWiFi,
+    ToggleAirplaneMode,
+    ScanNearByWiFi,
+    SelectAccessPoint(Id, AccessPoint),
+    DisconnectWiFi,
+    PasswordDialog(password_dialog::Message),
+    MenuOpened,
+    ConfigReloaded(NetworkModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+    RequestKeyboard(Id),
+    ReleaseKeyboard(Id),
+    ReleaseKeyboardWithCommand(Id, Task<Message>),
+}
+
+/// The Wi-Fi or wired connection on the bar, with the nearby access points in the menu
+pub struct Network {
+    config: NetworkModuleConfig,
+    service: Option<NetworkService>,
+    /// ssid and password being typed
+    password_dialog: Option<(String, String)>,
+}
+
+impl Network {
+    pub fn new(config: NetworkModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+            password_dialog: None,
+        }
+    }
+
+    fn command(&mut self, command: NetworkCommand) -> Action {
+        match self.service.as_mut() {
+            Some(service) => Action::Command(service.command(command).map(Message::Event)),
+            None => Action::None,
+        }
+    }
+
+    fn is_known(service: &NetworkService, ssid: &str) -> bool {
+        service.known_connections.iter().any(|c| {
+            matches!(
+                c,
+                KnownConnection::AccessPoint(AccessPoint { ssid: known, .. }) if known == ssid
+            )
+        })
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                    Action::None
+                }
+                ServiceEvent::Update(NetworkEvent::RequestPasswordForSSID(ssid)) => {
+                    self.password_dialog = Some((ssid, String::new()));
+                    Action::None
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                    Action::None
+                }
+                ServiceEvent::Error(_) => Action::None,
+            },
+            Message::ToggleWiFi => self.command(NetworkCommand::ToggleWiFi),
+            Message::ToggleAirplaneMode => self.command(NetworkCommand::ToggleAirplaneMode),
+            Message::ScanNearByWiFi | Message::MenuOpened => {
+                self.command(NetworkCommand::ScanNearByWiFi)
+            }
+            Message::DisconnectWiFi => self.command(NetworkCommand::DisconnectWiFi),
+            Message::SelectAccessPoint(id, ap) => match self.service.as_ref() {
+                // the secured networks without a saved connection need a password
+                Some(service) if !ap.public && !Self::is_known(service, &ap.ssid) => {
+                    self.password_dialog = Some((ap.ssid, String::new()));
+                    Action::RequestKeyboard(id)
+                }
+                _ => self.command(NetworkCommand::SelectAccessPoint((ap, None))),
+            },
+            Message::PasswordDialog(msg) => match msg {
+                password_dialog::Message::PasswordChanged(password) => {
+                    if let Some((_, current_password)) = &mut self.password_dialog {
+                        *current_password = password;
+                    }
+                    Action::None
+                }
+                password_dialog::Message::DialogConfirmed(id) => {
+                    let access_point = self.password_dialog.take().and_then(|(ssid, password)| {
+                        self.service.as_ref().and_then(|service| {
+                            service
+                                .wireless_access_points
+                                .iter()
+                                .find(|ap| ap.ssid == ssid)
+                                .map(|ap| (ap.clone(), password))
+                        })
+                    });
+
+                    match access_point {
+                        Some((ap, password)) => match self
+                            .command(NetworkCommand::SelectAccessPoint((ap, Some(password))))
+                        {
+                            Action::Command(task) => Action::ReleaseKeyboardWithCommand(id, task),
+                            _ => Action::ReleaseKeyboard(id),
+                        },
+                        None => Action::ReleaseKeyboard(id),
+                    }
+                }
+                password_dialog::Message::DialogCancelled(id) => {
+                    self.password_dialog = None;
+                    Action::ReleaseKeyboard(id)
+                }
+            },
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let service = self.service.as_ref()?;
+
+        let (icon_type, label, weak) = if service.airplane_mode {
+            (StaticIcon::Airplane, None, false)
+        } else {
+            match service.active_connections.iter().find(|c| {
+                matches!(
+                    c,
+                    ActiveConnectionInfo::WiFi { .. } | ActiveConnectionInfo::Wired { .. }
+                )
+            }) {
+                Some(ActiveConnectionInfo::WiFi { name, strength }) => (
+                    ActiveConnectionInfo::get_wifi_icon(*strength),
+                    match (self.config.show_ssid, self.config.show_strength) {
+                        (true, true) => Some(format!("{name} {strength}%")),
+                        (true, false) => Some(name.clone()),
+                        (false, true) => Some(format!("{strength}%")),
+                        (false, false) => None,
+                    },
+                    *strength <= 1,
+                ),
+                Some(wired) => (
+                    wired.get_icon(),
+                    self.config.show_ssid.then(|| "Wired".to_string()),
+                    false,
+                ),
+                None => (StaticIcon::Wifi0, None, false),
+            }
+        };
+        let offline = !service.airplane_mode && service.connectivity != ConnectivityState::Full;
+
+        Some(
+            container(
+                Row::new()
+                    .push(icon(icon_type))
+                    .push_maybe(label.map(text))
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xxs),
+            )
+            .style(move |theme: &Theme| container::Style {
+                text_color: if offline {
+                    Some(theme.palette().danger)
+                } else if weak {
+                    Some(theme.extended_palette().danger.weak.color)
+                } else {
+                    None
+                },
+                ..Default::default()
+            })
+            .into(),
+        )
+    }
+
+    fn access_point_view<'a>(
+        id: Id,
+        theme: &AshellTheme,
+        ap: &'a AccessPoint,
+        active: bool,
+    ) -> Element<'a, Message> {
+        let label = container(
+            row!(
+                icon(if ap.public {
+                    ActiveConnectionInfo::get_wifi_icon(ap.strength)
+                } else {
+                    ActiveConnectionInfo::get_wifi_lock_icon(ap.strength)
+                }),
+                text(&ap.ssid).width(Length::Fill),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs),
+        )
+        .style(move |theme: &Theme| container::Style {
+            text_color: active.then(|| theme.palette().success),
+            ..Default::default()
+        });
+
+        row!(
+            button(label)
+                .style(theme.ghost_button_style())
+                .padding([theme.space.xs, theme.space.xs])
+                .on_press_maybe((!active).then(|| Message::SelectAccessPoint(id, ap.clone())))
+                .width(Length::Fill)
+        )
+        .push_maybe(active.then(|| {
+            button(text("Disconnect").size(theme.font_size.sm))
+                .style(theme.outline_button_style())
+                .padding([theme.space.xxs, theme.space.sm])
+                .on_press(Message::DisconnectWiFi)
+        }))
+        .align_y(Alignment::Center)
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn menu_view<'a>(&'a self, id: Id, theme: &'a AshellTheme) -> Element<'a, Message> {
+        if let Some((ssid, password)) = &self.password_dialog {
+            return password_dialog::view(id, theme, ssid, password).map(Message::PasswordDialog);
+        }
+
+        let Some(service) = self.service.as_ref() else {
+            return text("Network not available").into();
+        };
+
+        let active_ssid = service.active_connections.iter().find_map(|c| match c {
+            ActiveConnectionInfo::WiFi { name, .. } => Some(name.as_str()),
+            _ => None,
+        });
+        // the connected network comes first
+        let mut access_points = service.wireless_access_points.iter().collect::<Vec<_>>();
+        access_points.sort_by_key(|ap| Some(ap.ssid.as_str()) != active_ssid);
+
+        let toggles = column!(
+            row!(
+                text("Airplane mode").width(Length::Fill),
+                toggler(service.airplane_mode).on_toggle(|_| Message::ToggleAirplaneMode),
+            )
+            .align_y(Alignment::Center),
+        )
+        .push_maybe(service.wifi_present.then(|| {
+            row!(
+                text("Wi-Fi").width(Length::Fill),
+                toggler(service.wifi_enabled).on_toggle(|_| Message::ToggleWiFi),
+            )
+            .align_y(Alignment::Center)
+        }))
+        .spacing(theme.space.xs);
+
+        column!(toggles)
+            .push_maybe(
+                (service.wifi_present && service.wifi_enabled && !service.airplane_mode).then(
+                    || {
+                        column!(
+                            horizontal_rule(1),
+                            row!(
+                                text("Nearby Wi-Fi").width(Length::Fill),
+                                text(if service.scanning_nearby_wifi {
+                                    "Scanning..."
+                                } else {
+                                    ""
+                                })
+                                .size(theme.font_size.sm),
+                                icon_button(theme, StaticIcon::Refresh)
+                                    .on_press(Message::ScanNearByWiFi),
+                            )
+                            .spacing(theme.space.xs)
+                            .align_y(Alignment::Center),
+                            container(scrollable(
+                                Column::with_children(access_points.into_iter().map(|ap| {
+                                    Self::access_point_view(
+                                        id,
+                                        theme,
+                                        ap,
+                                        Some(ap.ssid.as_str()) == active_ssid,
+                                    )
+                                }))
+                                .spacing(theme.space.xxs),
+                            ))
+                            .max_height(300),
+                        )
+                        .spacing(theme.space.xs)
+                    },
+                ),
+            )
+            .spacing(theme.space.sm)
+            .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        NetworkService::subscribe().map(Message::Event)
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{
+    components::icons::{StaticIcon, icon, icon_button},
+    config::NotesModuleConfig,
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{
+        Column, button, column, container, horizontal_rule, row, scrollable, text, text_editor,
+    },
+    window::Id,
+};
+use log::{error, warn};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{task, time::sleep};
+
+/// The file is written once the typing pauses for this long
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MenuOpened,
+    Edit(text_editor::Action),
+    ToggleEdit(Id),
+    ToggleTask(usize),
+    /// the revision of the content when the save was scheduled
+    Save(u64),
+    ConfigReloaded(NotesModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+    RequestKeyboard(Id),
+    ReleaseKeyboard(Id),
+}
+
+enum Line<'a> {
+    Heading(usize, &'a str),
+    Bullet(&'a str),
+    Task(bool, &'a str),
+    Rule,
+    Paragraph(&'a str),
+}
+
+impl<'a> Line<'a> {
+    fn parse(line: &'a str) -> Self {
+        let trimmed = line.trim_start();
+
+        if let Some(task) = trimmed
+            .strip_prefix("- [ ] ")
+            .or_else(|| trimmed.strip_prefix("* [ ] "))
+        {
+            Line::Task(false, task)
+        } else if let Some(task) = ["- [x] ", "- [X] ", "* [x] ", "* [X] "]
+            .iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix))
+        {
+            Line::Task(true, task)
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            Line::Bullet(item)
+        } else if trimmed.starts_with("---") && trimmed.chars().all(|c| c == '-') {
+            Line::Rule
+        } else if trimmed.starts_with('#') {
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+
+            match trimmed[level..].strip_prefix(' ') {
+                Some(title) if level <= 3 => Line::Heading(level, title),
+                _ => Line::Paragraph(line),
+            }
+        } else {
+            Line::Paragraph(line)
+        }
+    }
+}
+
+fn expand_path(path: &str) -> PathBuf {
+    shellexpand::full(path)
+        .map(|expanded| PathBuf::from(expanded.to_string()))
+        .unwrap_or_else(|_| PathBuf::from(path))
+}
+
+#[derive(Debug)]
+pub struct Notes {
+    path: PathBuf,
+    content: text_editor::Content,
+    editing: bool,
+    /// bumped on every change of the content
+    revision: u64,
+    /// the last revision written, the writes run in the background
+    saved: Arc<Mutex<u64>>,
+}
+
+impl Notes {
+    pub fn new(config: NotesModuleConfig) -> Self {
+        let path = expand_path(&config.path);
+        let content = text_editor::Content::with_text(&Self::load(&path));
+
+        Self {
+            path,
+            content,
+            editing: false,
+            revision: 0,
+            saved: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    fn load(path: &Path) -> String {
+        fs::read_to_string(path).unwrap_or_else(|err| {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to read notes file {path:?}: {err}");
+            }
+
+            String::new()
+        })
+    }
+
+    /// Writes the content off the UI thread, a late write doesn't overwrite a newer one
+    fn save(&self) {
+        let path = self.path.clone();
+        let text = self.content.text();
+        let revision = self.revision;
+        let saved = self.saved.clone();
+
+        task::spawn_blocking(move || {
+            let mut saved = saved.lock().unwrap_or_else(|e| e.into_inner());
+            if *saved >= revision {
+                return;
+            }
+
+            let res = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&path, text));
+
+            match res {
+                Ok(()) => *saved = revision,
+                Err(err) => error!("Failed to save notes file {path:?}: {err}"),
+            }
+        });
+    }
+
+    fn changed(&mut self) {
+        self.revision += 1;
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::MenuOpened => {
+                // the file could have been changed outside of ashell,
+                // unless the last changes are still being written
+                if *self.saved.lock().unwrap_or_else(|e| e.into_inner()) >= self.revision {
+                    self.content = text_editor::Content::with_text(&Self::load(&self.path));
+                }
+                self.editing = false;
+
+                Action::None
+            }
+            Message::Edit(action) => {
+                let is_edit = action.is_edit();
+                self.content.perform(action);
+
+                if is_edit {
+                    self.changed();
+                    let revision = self.revision;
+
+                    Action::Command(Task::perform(sleep(SAVE_DELAY), move |_| {
+                        Message::Save(revision)
+                    }))
+                } else {
+                    Action::None
+                }
+            }
+            Message::Save(revision) => {
+                // still typing, a later save is scheduled
+                if revision == self.revision {
+                    self.save();
+                }
+
+                Action::None
+            }
+            Message::ToggleEdit(id) => {
+                self.editing = !self.editing;
+
+                if self.editing {
+                    Action::RequestKeyboard(id)
+                } else {
+                    self.save();
+
+                    Action::ReleaseKeyboard(id)
+                }
+            }
+            Message::ToggleTask(index) => {
+                // split keeps the trailing newline
+                let text = self
+                    .content
+                    .text()
+                    .split('\n')
+                    .enumerate()
+                    .map(|(i, line)| {
+                        if i != index {
+                            line.to_owned()
+                        } else if line.contains("[ ] ") {
+                            line.replacen("[ ] ", "[x] ", 1)
+                        } else {
+                            line.replacen("[x] ", "[ ] ", 1).replacen("[X] ", "[ ] ", 1)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                self.content = text_editor::Content::with_text(&text);
+                self.changed();
+                self.save();
+
+                Action::None
+            }
+            Message::ConfigReloaded(config) => {
+                let path = expand_path(&config.path);
+
+                if path != self.path {
+                    *self = Self::new(config);
+                }
+
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, _theme: &AshellTheme) -> Element<'_, Message> {
+        icon(StaticIcon::Note).into()
+    }
+
+    fn line_view<'a>(theme: &'a AshellTheme, index: usize, line: &str) -> Element<'a, Message> {
+        match Line::parse(line) {
+            Line::Heading(level, title) => text(title.to_owned())
+                .size(match level {
+                    1 => theme.font_size.xl,
+                    2 => theme.font_size.lg,
+                    _ => theme.font_size.md,
+                })
+                .into(),
+            Line::Bullet(item) => row!(text("•"), text(item.to_owned()).width(Length::Fill))
+                .spacing(theme.space.xs)
+                .into(),
+            Line::Task(done, task) => button(
+                row!(
+                    icon(if done {
+                        StaticIcon::CheckboxChecked
+                    } else {
+                        StaticIcon::CheckboxBlank
+                    }),
+                    text(task.to_owned()).width(Length::Fill)
+                )
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xs),
+            )
+            .style(theme.ghost_button_style())
+            .padding(0)
+            .on_press(Message::ToggleTask(index))
+            .width(Length::Fill)
+            .into(),
+            Line::Rule => horizontal_rule(1).into(),
+            Line::Paragraph(line) => text(line.to_owned()).into(),
+        }
+    }
+
+    pub fn menu_view<'a>(&'a self, id: Id, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let content: Element<'a, Message> = if self.editing {
+            text_editor(&self.content)
+                .on_action(Message::Edit)
+                .placeholder("Write something...")
+                .padding(theme.space.xs)
+                .height(Length::Fixed(300.))
+                .into()
+        } else {
+            let notes = self.content.text();
+
+            if notes.trim().is_empty() {
+                container(text("No notes yet"))
+                    .padding(theme.space.xs)
+                    .into()
+            } else {
+                container(scrollable(
+                    Column::with_children(
+                        notes
+                            .lines()
+                            .enumerate()
+                            .map(|(index, line)| Self::line_view(theme, index, line))
+                            .collect::<Vec<_>>(),
+                    )
+                    .spacing(theme.space.xxs)
+                    .padding([0, theme.space.md, 0, 0]),
+                ))
+                .max_height(300)
+                .into()
+            }
+        };
+
+        column!(
+            row!(
+                text("Notes").size(theme.font_size.lg).width(Length::Fill),
+                icon_button(
+                    theme,
+                    if self.editing {
+                        StaticIcon::EyeOpened
+                    } else {
+                        StaticIcon::Edit
+                    }
+                )
+                .on_press(Message::ToggleEdit(id)),
+            )
+            .align_y(Alignment::Center),
+            horizontal_rule(1),
+            content,
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+}
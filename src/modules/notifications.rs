@@ -0,0 +1,525 @@
+use crate::{
+    components::icons::{IconButtonSize, StaticIcon, icon, icon_button},
+    config::{NotificationsModuleConfig, PopupAnchor},
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        notifications::{
+            Notification, NotificationEvent, NotificationsCommand, NotificationsService, Urgency,
+        },
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Border, Element, Length, Subscription, Task, Theme,
+    platform_specific::shell::commands::layer_surface::{
+        Anchor, KeyboardInteractivity, Layer, destroy_layer_surface, get_layer_surface,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+    },
+    time::every,
+    widget::{
+        Column, Row, button, column, container, horizontal_rule, progress_bar, row, scrollable,
+        text, text_input,
+    },
+    window::Id,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+const POPUP_WIDTH: f32 = 360.;
+const POPUP_MARGIN: i32 = 8;
+const ICON_SIZE: u16 = 32;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<NotificationsService>),
+    Dismiss(u32),
+    DismissAll,
+    InvokeAction(u32, String),
+    /// the default action is invoked, the popup is hidden when there is none
+    PopupClicked(u32),
+    ToggleDoNotDisturb,
+    Tick,
+    MenuOpened,
+    ConfigReloaded(NotificationsModuleConfig),
+    SearchChanged(String),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+    /// a popup is shown for a new notification
+    Notified(Task<Message>),
+    DoNotDisturbChanged(bool, Task<Message>),
+}
+
+/// A notification daemon, with the unread count on the bar, the popups in their own
+/// layer surface and the notification center in the menu
+pub struct Notifications {
+    config: NotificationsModuleConfig,
+    service: Option<NotificationsService>,
+    do_not_disturb: bool,
+    /// notifications shown in the popups, with the time they hide at
+    popups: Vec<(u32, Option<Instant>)>,
+    popup_surface: Option<Id>,
+    /// received since the notification center was opened
+    unread: HashSet<u32>,
+    search: String,
+}
+
+impl Notifications {
+    pub fn new(config: NotificationsModuleConfig, do_not_disturb: bool) -> Self {
+        Self {
+            config,
+            service: None,
+            do_not_disturb,
+            popups: Vec::new(),
+            popup_surface: None,
+            unread: HashSet::new(),
+            search: String::new(),
+        }
+    }
+
+    pub fn is_popup_surface(&self, id: Id) -> bool {
+        self.popup_surface == Some(id)
+    }
+
+    fn command(&mut self, command: NotificationsCommand) -> Task<Message> {
+        match self.service.as_mut() {
+            Some(service) => service.command(command).map(Message::Event),
+            None => Task::none(),
+        }
+    }
+
+    /// The surface of the popups only exists while there is something to show
+    fn sync_popup_surface(&mut self) -> Task<Message> {
+        match (self.popups.is_empty(), self.popup_surface) {
+            (false, None) => {
+                let id = Id::unique();
+                self.popup_surface = Some(id);
+
+                get_layer_surface(SctkLayerSurfaceSettings {
+                    id,
+                    namespace: "ashell-notifications".to_string(),
+                    // sized on the content
+                    size: None,
+                    layer: Layer::Overlay,
+                    keyboard_interactivity: KeyboardInteractivity::None,
+                    exclusive_zone: 0,
+                    output: IcedOutput::Active,
+                    anchor: match self.config.position {
+                        PopupAnchor::TopLeft => Anchor::TOP | Anchor::LEFT,
+                        PopupAnchor::TopRight => Anchor::TOP | Anchor::RIGHT,
+                        PopupAnchor::BottomLeft => Anchor::BOTTOM | Anchor::LEFT,
+                        PopupAnchor::BottomRight => Anchor::BOTTOM | Anchor::RIGHT,
+                    },
+                    margin: IcedMargin {
+                        top: POPUP_MARGIN,
+                        right: POPUP_MARGIN,
+                        bottom: POPUP_MARGIN,
+                        left: POPUP_MARGIN,
+                    },
+                    ..Default::default()
+                })
+            }
+            (true, Some(id)) => {
+                self.popup_surface = None;
+
+                destroy_layer_surface(id)
+            }
+            _ => Task::none(),
+        }
+    }
+
+    /// Published for `ashell msg notifications`
+    fn export_history(&self) {
+        let records = self
+            .service
+            .as_ref()
+            .map(|s| {
+                s.notifications
+                    .iter()
+                    .map(Notification::record)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        crate::ipc::set_notifications(&records);
+    }
+
+    fn hide_popup(&mut self, id: u32) -> Task<Message> {
+        self.popups.retain(|(popup, _)| *popup != id);
+
+        self.sync_popup_surface()
+    }
+
+    fn show_popup(&mut self, notification: &Notification) {
+        // the critical notifications stay until they are dismissed
+        let timeout = match notification.timeout {
+            _ if notification.urgency == Urgency::Critical => None,
+            Some(timeout) if timeout.is_zero() => None,
+            Some(timeout) => Some(timeout),
+            None => Some(Duration::from_millis(self.config.timeout)),
+        };
+
+        self.popups.retain(|(popup, _)| *popup != notification.id);
+        self.popups.push((
+            notification.id,
+            timeout.map(|timeout| Instant::now() + timeout),
+        ));
+
+        let overflow = self
+            .popups
+            .len()
+            .saturating_sub(self.config.max_popups.max(1));
+        self.popups.drain(..overflow);
+    }
+
+    fn received(&mut self, notification: Notification) -> Action {
+        let Some(service) = self.service.as_mut() else {
+            return Action::None;
+        };
+
+        let id = notification.id;
+        let popup = !self.do_not_disturb || notification.urgency == Urgency::Critical;
+        service.update(NotificationEvent::Received(notification));
+
+        service.notifications.truncate(self.config.history_size);
+        // the oldest notifications of the application go first
+        let mut kept = HashMap::<String, usize>::new();
+        let app_history_size = &self.config.app_history_size;
+        service.notifications.retain(|n| {
+            let count = kept.entry(n.app_name.clone()).or_default();
+            *count += 1;
+
+            app_history_size
+                .get(&n.app_name)
+                .is_none_or(|size| *count <= *size)
+        });
+        let ids = service
+            .notifications
+            .iter()
+            .map(|n| n.id)
+            .collect::<HashSet<_>>();
+        self.unread.retain(|id| ids.contains(id));
+        self.unread.insert(id);
+        self.export_history();
+
+        if popup && let Some(notification) = self.service.as_ref().and_then(|s| s.get(id)).cloned()
+        {
+            self.show_popup(&notification);
+
+            Action::Notified(self.sync_popup_surface())
+        } else {
+            Action::None
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                    Action::None
+                }
+                ServiceEvent::Update(NotificationEvent::Received(notification)) => {
+                    self.received(notification)
+                }
+                ServiceEvent::Update(data) => {
+                    if let NotificationEvent::Closed(id) = data {
+                        self.unread.remove(&id);
+                        self.popups.retain(|(popup, _)| *popup != id);
+                    }
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                    self.export_history();
+
+                    Action::Command(self.sync_popup_surface())
+                }
+                ServiceEvent::Error(_) => Action::None,
+            },
+            Message::Dismiss(id) => {
+                let hide = self.hide_popup(id);
+                let command = self.command(NotificationsCommand::Dismiss(id));
+
+                Action::Command(Task::batch(vec![hide, command]))
+            }
+            Message::DismissAll => {
+                self.popups.clear();
+                let mut tasks = vec![self.sync_popup_surface()];
+                let ids = self
+                    .service
+                    .as_ref()
+                    .map(|s| s.notifications.iter().map(|n| n.id).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                for id in ids {
+                    tasks.push(self.command(NotificationsCommand::Dismiss(id)));
+                }
+
+                Action::Command(Task::batch(tasks))
+            }
+            Message::InvokeAction(id, action_key) => {
+                let hide = self.hide_popup(id);
+                let command = self.command(NotificationsCommand::InvokeAction(id, action_key));
+
+                Action::Command(Task::batch(vec![hide, command]))
+            }
+            Message::PopupClicked(id) => {
+                let has_default = self
+                    .service
+                    .as_ref()
+                    .and_then(|s| s.get(id))
+                    .is_some_and(Notification::has_default_action);
+
+                if has_default {
+                    self.update(Message::InvokeAction(id, "default".to_string()))
+                } else {
+                    Action::Command(self.hide_popup(id))
+                }
+            }
+            Message::ToggleDoNotDisturb => {
+                self.do_not_disturb = !self.do_not_disturb;
+                if self.do_not_disturb {
+                    let service = self.service.as_ref();
+                    self.popups.retain(|(id, _)| {
+                        service
+                            .and_then(|s| s.get(*id))
+                            .is_some_and(|n| n.urgency == Urgency::Critical)
+                    });
+                }
+
+                Action::DoNotDisturbChanged(self.do_not_disturb, self.sync_popup_surface())
+            }
+            Message::Tick => {
+                let now = Instant::now();
+                self.popups
+                    .retain(|(_, hide_at)| hide_at.is_none_or(|hide_at| hide_at > now));
+
+                Action::Command(self.sync_popup_surface())
+            }
+            Message::MenuOpened => {
+                self.unread.clear();
+                self.search.clear();
+                Action::None
+            }
+            Message::SearchChanged(search) => {
+                self.search = search;
+                Action::None
+            }
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        self.service.as_ref()?;
+
+        let unread = self.unread.len();
+
+        Some(
+            Row::new()
+                .push(icon(if self.do_not_disturb {
+                    StaticIcon::BellOff
+                } else if unread > 0 {
+                    StaticIcon::BellBadge
+                } else {
+                    StaticIcon::Bell
+                }))
+                .push_maybe((unread > 0).then(|| text(unread.to_string())))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xxs)
+                .into(),
+        )
+    }
+
+    fn notification_view<'a>(
+        theme: &AshellTheme,
+        notification: &'a Notification,
+    ) -> Element<'a, Message> {
+        let id = notification.id;
+        let critical = notification.urgency == Urgency::Critical;
+
+        let header = row!(
+            text(&notification.app_name)
+                .size(theme.font_size.sm)
+                .width(Length::Fill),
+            text(notification.received.format("%H:%M").to_string()).size(theme.font_size.sm),
+            icon_button(theme, StaticIcon::Close)
+                .on_press(Message::Dismiss(id))
+                .size(IconButtonSize::Small),
+        )
+        .align_y(Alignment::Center)
+        .spacing(theme.space.xs);
+
+        let content = container(
+            column!(text(&notification.summary))
+                .push_maybe(
+                    (!notification.body.is_empty())
+                        .then(|| text(&notification.body).size(theme.font_size.sm)),
+                )
+                .push_maybe(notification.progress.map(|progress| {
+                    progress_bar(0.0..=100.0, progress as f32).height(Length::Fixed(6.))
+                }))
+                .spacing(theme.space.xxs),
+        )
+        .style(move |theme: &Theme| container::Style {
+            text_color: critical.then(|| theme.palette().danger),
+            ..Default::default()
+        });
+
+        let actions = notification
+            .actions
+            .iter()
+            .filter(|(key, _)| key != "default")
+            .map(|(key, label)| {
+                button(text(label).size(theme.font_size.sm))
+                    .style(theme.outline_button_style())
+                    .padding([theme.space.xxs, theme.space.sm])
+                    .on_press(Message::InvokeAction(id, key.clone()))
+                    .into()
+            })
+            .collect::<Vec<_>>();
+
+        column!(
+            header,
+            Row::new()
+                .push_maybe(notification.icon.as_ref().map(|icon| icon.view(ICON_SIZE)))
+                .push(content.width(Length::Fill))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.sm),
+        )
+        .push_maybe(
+            (!actions.is_empty()).then(|| Row::with_children(actions).spacing(theme.space.xs)),
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    /// The popups of the notifications, shown in their own layer surface
+    pub fn popup_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let Some(service) = self.service.as_ref() else {
+            return Row::new().into();
+        };
+        let opacity = theme.menu.opacity;
+        let radius = theme.radius.lg;
+
+        // the newest popup is the closest to the corner
+        let mut popups = self
+            .popups
+            .iter()
+            .rev()
+            .filter_map(|(id, _)| service.get(*id))
+            .collect::<Vec<_>>();
+        if matches!(
+            self.config.position,
+            PopupAnchor::BottomLeft | PopupAnchor::BottomRight
+        ) {
+            popups.reverse();
+        }
+
+        Column::with_children(popups.into_iter().map(|notification| {
+            button(Self::notification_view(theme, notification))
+                .on_press(Message::PopupClicked(notification.id))
+                .padding(theme.space.md)
+                .width(Length::Fixed(POPUP_WIDTH))
+                .style(move |theme: &Theme, _| button::Style {
+                    background: Some(theme.palette().background.scale_alpha(opacity).into()),
+                    text_color: theme.palette().text,
+                    border: Border {
+                        color: theme
+                            .extended_palette()
+                            .secondary
+                            .base
+                            .color
+                            .scale_alpha(opacity),
+                        width: 1.,
+                        radius: radius.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        }))
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let Some(service) = self.service.as_ref() else {
+            return text("Notification daemon not available").into();
+        };
+
+        let header = row!(
+            text("Notifications")
+                .size(theme.font_size.lg)
+                .width(Length::Fill),
+            button(text("Do not disturb").size(theme.font_size.sm))
+                .style(if self.do_not_disturb {
+                    theme.confirm_button_style()
+                } else {
+                    theme.outline_button_style()
+                })
+                .padding([theme.space.xxs, theme.space.sm])
+                .on_press(Message::ToggleDoNotDisturb),
+        )
+        .push_maybe((!service.notifications.is_empty()).then(|| {
+            button(text("Clear all").size(theme.font_size.sm))
+                .style(theme.outline_button_style())
+                .padding([theme.space.xxs, theme.space.sm])
+                .on_press(Message::DismissAll)
+        }))
+        .align_y(Alignment::Center)
+        .spacing(theme.space.xs);
+
+        let notifications = service
+            .notifications
+            .iter()
+            .filter(|notification| notification.matches(&self.search))
+            .collect::<Vec<_>>();
+
+        column!(header, horizontal_rule(1))
+            .push_maybe((!service.notifications.is_empty()).then(|| {
+                text_input("Search...", &self.search)
+                    .size(theme.font_size.md)
+                    .padding([theme.space.xs, theme.space.md])
+                    .style(theme.text_input_style())
+                    .on_input(Message::SearchChanged)
+            }))
+            .push(if service.notifications.is_empty() {
+                Into::<Element<'a, Message>>::into(text("No notifications"))
+            } else if notifications.is_empty() {
+                text("No matching notifications").into()
+            } else {
+                container(scrollable(
+                    Column::with_children(
+                        notifications
+                            .into_iter()
+                            .map(|notification| Self::notification_view(theme, notification)),
+                    )
+                    .spacing(theme.space.md),
+                ))
+                .max_height(400)
+                .into()
+            })
+            .spacing(theme.space.sm)
+            .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let service = NotificationsService::subscribe().map(Message::Event);
+
+        if self.popups.iter().any(|(_, hide_at)| hide_at.is_some()) {
+            Subscription::batch(vec![
+                service,
+                every(Duration::from_millis(250)).map(|_| Message::Tick),
+            ])
+        } else {
+            service
+        }
+    }
+}
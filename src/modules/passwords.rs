@@ -0,0 +1,365 @@
+use crate::{
+    components::icons::{IconButtonSize, StaticIcon, icon, icon_button},
+    config::{PasswordsBackend, PasswordsModuleConfig},
+    services::secret_service,
+    theme::AshellTheme,
+    utils::{
+        launcher::{command_output, execute_command, pipe_to_command, shell_quote},
+        secret::Secret,
+    },
+};
+use iced::{
+    Alignment, Element, Length, Task, Theme,
+    widget::{Column, column, container, horizontal_rule, row, scrollable, text, text_input},
+};
+use log::{debug, warn};
+use std::time::Duration;
+use tokio::time::sleep;
+use zbus::zvariant::OwnedObjectPath;
+
+const SEARCH_INPUT_ID: &str = "passwords-search";
+const MAX_RESULTS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Username,
+    Password,
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    Pass,
+    SecretService(OwnedObjectPath, Option<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    name: String,
+    source: Source,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MenuOpened,
+    EntriesLoaded(Result<Vec<Entry>, String>),
+    SearchChanged(String),
+    Submit,
+    Copy(usize, Field),
+    Copied(usize, Field, Result<Secret, String>),
+    ClearClipboard(Secret),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+enum Entries {
+    Loading,
+    Loaded(Vec<Entry>),
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+enum Status {
+    Copied(String, Field),
+    Error(String),
+}
+
+async fn load_entries(config: &PasswordsModuleConfig) -> Result<Vec<Entry>, String> {
+    match config.backend {
+        PasswordsBackend::Pass => command_output(&config.list_cmd)
+            .await
+            .ok_or_else(|| "Unable to list the password store".to_string())
+            .map(|output| {
+                output
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|name| Entry {
+                        name: name.to_owned(),
+                        source: Source::Pass,
+                    })
+                    .collect()
+            }),
+        PasswordsBackend::SecretService => secret_service::list_items()
+            .await
+            .map(|items| {
+                items
+                    .into_iter()
+                    .map(|item| Entry {
+                        name: item.label,
+                        source: Source::SecretService(item.path, item.username),
+                    })
+                    .collect()
+            })
+            .map_err(|err| format!("Unable to read the keyring: {err}")),
+    }
+}
+
+async fn read_field(
+    config: &PasswordsModuleConfig,
+    entry: &Entry,
+    field: Field,
+) -> Result<String, String> {
+    let value = match (&entry.source, field) {
+        (Source::Pass, field) => {
+            let command = match field {
+                Field::Username => &config.username_cmd,
+                Field::Password => &config.password_cmd,
+            }
+            .replace("{entry}", &shell_quote(&entry.name));
+
+            command_output(&command)
+                .await
+                .map(|output| output.trim_end_matches('\n').to_owned())
+                .filter(|output| !output.is_empty())
+                // pass entries are often stored as `website/username`
+                .or_else(|| {
+                    (field == Field::Username)
+                        .then(|| entry.name.rsplit_once('/'))
+                        .flatten()
+                        .map(|(_, username)| username.to_owned())
+                })
+        }
+        (Source::SecretService(_, username), Field::Username) => username.clone(),
+        (Source::SecretService(path, _), Field::Password) => secret_service::secret(path.clone())
+            .await
+            .inspect_err(|err| warn!("Failed to read secret of {}: {err}", entry.name))
+            .ok(),
+    };
+
+    value.ok_or_else(|| match field {
+        Field::Username => format!("No username for {}", entry.name),
+        Field::Password => format!("Unable to read the password of {}", entry.name),
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Passwords {
+    config: PasswordsModuleConfig,
+    entries: Entries,
+    search: String,
+    status: Option<Status>,
+}
+
+impl Passwords {
+    pub fn new(config: PasswordsModuleConfig) -> Self {
+        Self {
+            config,
+            entries: Entries::Loading,
+            search: String::new(),
+            status: None,
+        }
+    }
+
+    /// Indexes of the entries containing every word of the search
+    fn filtered(&self) -> Vec<usize> {
+        let Entries::Loaded(entries) = &self.entries else {
+            return Vec::new();
+        };
+        let search = self.search.to_lowercase();
+
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                let name = entry.name.to_lowercase();
+
+                search.split_whitespace().all(|word| name.contains(word))
+            })
+            .map(|(index, _)| index)
+            .take(MAX_RESULTS)
+            .collect()
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::MenuOpened => {
+                self.entries = Entries::Loading;
+                self.search.clear();
+                self.status = None;
+                let config = self.config.clone();
+
+                Action::Command(Task::batch(vec![
+                    Task::perform(
+                        async move { load_entries(&config).await },
+                        Message::EntriesLoaded,
+                    ),
+                    text_input::focus(SEARCH_INPUT_ID),
+                ]))
+            }
+            Message::EntriesLoaded(entries) => {
+                self.entries = match entries {
+                    Ok(entries) => Entries::Loaded(entries),
+                    Err(err) => {
+                        warn!("{err}");
+
+                        Entries::Error(err)
+                    }
+                };
+
+                Action::None
+            }
+            Message::SearchChanged(search) => {
+                self.search = search;
+
+                Action::None
+            }
+            Message::Submit => match self.filtered().first() {
+                Some(index) => self.update(Message::Copy(*index, Field::Password)),
+                None => Action::None,
+            },
+            Message::Copy(index, field) => {
+                let Entries::Loaded(entries) = &self.entries else {
+                    return Action::None;
+                };
+                let Some(entry) = entries.get(index).cloned() else {
+                    return Action::None;
+                };
+                let config = self.config.clone();
+
+                Action::Command(Task::perform(
+                    async move {
+                        let value = read_field(&config, &entry, field).await?;
+                        pipe_to_command(&config.copy_cmd, &value).await;
+
+                        Ok(Secret::from(value))
+                    },
+                    move |value| Message::Copied(index, field, value),
+                ))
+            }
+            Message::Copied(index, field, value) => match value {
+                Ok(value) => {
+                    if let Entries::Loaded(entries) = &self.entries
+                        && let Some(entry) = entries.get(index)
+                    {
+                        self.status = Some(Status::Copied(entry.name.clone(), field));
+                    }
+
+                    let clear_after = Duration::from_secs(self.config.clear_after);
+
+                    Action::Command(Task::perform(
+                        async move {
+                            sleep(clear_after).await;
+
+                            value
+                        },
+                        Message::ClearClipboard,
+                    ))
+                }
+                Err(err) => {
+                    warn!("{err}");
+                    self.status = Some(Status::Error(err));
+
+                    Action::None
+                }
+            },
+            Message::ClearClipboard(value) => {
+                self.status = None;
+                let paste_cmd = self.config.paste_cmd.clone();
+                let clear_cmd = self.config.clear_cmd.clone();
+
+                Action::Command(
+                    Task::perform(
+                        async move {
+                            // don't clear something copied after the secret
+                            if command_output(&paste_cmd).await.as_deref() == Some(value.expose()) {
+                                debug!("Clearing password from the clipboard");
+                                execute_command(clear_cmd);
+                            }
+                        },
+                        |_| (),
+                    )
+                    .discard(),
+                )
+            }
+        }
+    }
+
+    pub fn view(&'_ self, _theme: &AshellTheme) -> Element<'_, Message> {
+        icon(StaticIcon::Key).into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let content: Element<'a, Message> = match &self.entries {
+            Entries::Loading => text("Loading...").into(),
+            Entries::Error(err) => text(err).into(),
+            Entries::Loaded(entries) => {
+                let filtered = self.filtered();
+
+                if filtered.is_empty() {
+                    text("No entries found").into()
+                } else {
+                    container(scrollable(
+                        Column::with_children(
+                            filtered
+                                .into_iter()
+                                .map(|index| {
+                                    row!(
+                                        text(entries[index].name.clone()).width(Length::Fill),
+                                        icon_button(theme, StaticIcon::Account)
+                                            .on_press(Message::Copy(index, Field::Username))
+                                            .size(IconButtonSize::Small),
+                                        icon_button(theme, StaticIcon::Key)
+                                            .on_press(Message::Copy(index, Field::Password))
+                                            .size(IconButtonSize::Small),
+                                    )
+                                    .align_y(Alignment::Center)
+                                    .spacing(theme.space.xxs)
+                                    .into()
+                                })
+                                .collect::<Vec<Element<_>>>(),
+                        )
+                        .spacing(theme.space.xxs)
+                        .padding([0, theme.space.md, 0, 0]),
+                    ))
+                    .max_height(300)
+                    .into()
+                }
+            }
+        };
+
+        column!(
+            text("Passwords").size(theme.font_size.lg),
+            horizontal_rule(1),
+            text_input("Search...", &self.search)
+                .id(SEARCH_INPUT_ID)
+                .size(theme.font_size.md)
+                .padding([theme.space.xs, theme.space.md])
+                .style(theme.text_input_style())
+                .on_input(Message::SearchChanged)
+                .on_submit(Message::Submit),
+            content,
+        )
+        .push_maybe(self.status.as_ref().map(|status| {
+            let (message, failed) = match status {
+                Status::Copied(name, Field::Username) => (
+                    format!(
+                        "Username of {name} copied, clearing in {}s",
+                        self.config.clear_after
+                    ),
+                    false,
+                ),
+                Status::Copied(name, Field::Password) => (
+                    format!(
+                        "Password of {name} copied, clearing in {}s",
+                        self.config.clear_after
+                    ),
+                    false,
+                ),
+                Status::Error(err) => (err.clone(), true),
+            };
+
+            container(text(message).size(theme.font_size.xs)).style(move |theme: &Theme| {
+                container::Style {
+                    text_color: failed.then(|| theme.palette().danger),
+                    ..Default::default()
+                }
+            })
+        }))
+        .spacing(theme.space.xs)
+        .into()
+    }
+}
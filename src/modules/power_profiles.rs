@@ -0,0 +1,104 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::PowerProfilesModuleConfig,
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        upower::{PowerProfile, PowerProfileCommand, UPowerService},
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Subscription, Task, Theme,
+    widget::{Row, container, text},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<UPowerService>),
+    Cycle,
+    ConfigReloaded(PowerProfilesModuleConfig),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+/// The active profile of power-profiles-daemon, cycled on click
+pub struct PowerProfiles {
+    config: PowerProfilesModuleConfig,
+    service: Option<UPowerService>,
+}
+
+impl PowerProfiles {
+    pub fn new(config: PowerProfilesModuleConfig) -> Self {
+        Self {
+            config,
+            service: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                    Action::None
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                    Action::None
+                }
+                ServiceEvent::Error(_) => Action::None,
+            },
+            Message::Cycle => match self.service.as_mut() {
+                Some(service) => Action::Command(
+                    service
+                        .command(PowerProfileCommand::Toggle)
+                        .map(Message::Event),
+                ),
+                None => Action::None,
+            },
+            Message::ConfigReloaded(config) => {
+                self.config = config;
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        let profile = self.service.as_ref()?.power_profile;
+        let label = match profile {
+            PowerProfile::Balanced => "Balanced",
+            PowerProfile::Performance => "Performance",
+            PowerProfile::PowerSaver => "Power Saver",
+            // power-profiles-daemon is not running
+            PowerProfile::Unknown => return None,
+        };
+
+        Some(
+            container(
+                Row::new()
+                    .push(icon(StaticIcon::from(profile)))
+                    .push_maybe(self.config.show_label.then(|| text(label)))
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xxs),
+            )
+            .style(move |theme: &Theme| container::Style {
+                text_color: match profile {
+                    PowerProfile::Performance => Some(theme.palette().danger),
+                    PowerProfile::PowerSaver => Some(theme.palette().success),
+                    _ => None,
+                },
+                ..Default::default()
+            })
+            .into(),
+        )
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        UPowerService::subscribe().map(Message::Event)
+    }
+}
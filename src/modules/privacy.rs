@@ -5,17 +5,21 @@ use crate::{
 };
 use iced::{
     Alignment, Element, Subscription,
-    widget::{Row, container},
+    widget::{Row, container, mouse_area, text},
 };
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Event(ServiceEvent<PrivacyService>),
+    Hovered(bool),
 }
 
+/// Indicators of the microphone, webcam and screen share in use, naming the
+/// applications on hover
 #[derive(Debug, Default, Clone)]
 pub struct Privacy {
     pub service: Option<PrivacyService>,
+    hovered: bool,
 }
 
 impl Privacy {
@@ -32,6 +36,9 @@ impl Privacy {
                 }
                 ServiceEvent::Error(_) => {}
             },
+            Message::Hovered(hovered) => {
+                self.hovered = hovered;
+            }
         }
     }
 
@@ -39,24 +46,34 @@ impl Privacy {
         if let Some(service) = self.service.as_ref()
             && !service.no_access()
         {
+            let indicators = container(
+                Row::new()
+                    .push_maybe(
+                        service
+                            .screenshare_access()
+                            .then(|| icon(StaticIcon::ScreenShare)),
+                    )
+                    .push_maybe(service.webcam_access().then(|| icon(StaticIcon::Webcam)))
+                    .push_maybe(service.microphone_access().then(|| icon(StaticIcon::Mic1)))
+                    .push_maybe(
+                        self.hovered
+                            .then(|| service.applications())
+                            .filter(|apps| !apps.is_empty())
+                            .map(|apps| text(apps.join(", "))),
+                    )
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xs),
+            )
+            .style(|theme| container::Style {
+                text_color: Some(theme.extended_palette().danger.weak.color),
+                ..Default::default()
+            });
+
             Some(
-                container(
-                    Row::new()
-                        .push_maybe(
-                            service
-                                .screenshare_access()
-                                .then(|| icon(StaticIcon::ScreenShare)),
-                        )
-                        .push_maybe(service.webcam_access().then(|| icon(StaticIcon::Webcam)))
-                        .push_maybe(service.microphone_access().then(|| icon(StaticIcon::Mic1)))
-                        .align_y(Alignment::Center)
-                        .spacing(theme.space.xs),
-                )
-                .style(|theme| container::Style {
-                    text_color: Some(theme.extended_palette().danger.weak.color),
-                    ..Default::default()
-                })
-                .into(),
+                mouse_area(indicators)
+                    .on_enter(Message::Hovered(true))
+                    .on_exit(Message::Hovered(false))
+                    .into(),
             )
         } else {
             None
@@ -0,0 +1,229 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::QrCodeModuleConfig,
+    theme::AshellTheme,
+    utils::{
+        launcher::{command_output, pipe_to_command},
+        truncate_text,
+    },
+};
+use iced::{
+    Alignment, Color, Element, Length, Point, Rectangle, Renderer, Size, Task, Theme,
+    mouse::Cursor,
+    widget::{
+        button, canvas,
+        canvas::{Cache, Geometry, Path, Program},
+        column, container, horizontal_rule, row, text,
+    },
+    window::Id,
+};
+use log::warn;
+use qrcode::QrCode as QrCodeData;
+
+const QR_SIZE: f32 = 250.;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MenuOpened,
+    ClipboardRead(Option<String>),
+    Scan(Id),
+    Scanned(Option<String>),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+    CloseMenu(Id, Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+struct QrMatrix {
+    width: usize,
+    dark: Vec<bool>,
+}
+
+/// The code drawn in the cache of the module
+struct QrCanvas<'a>(&'a QrMatrix, &'a Cache);
+
+impl<Message> Program<Message> for QrCanvas<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let QrCanvas(matrix, cache) = self;
+
+        vec![cache.draw(renderer, bounds.size(), |frame| {
+            // keep a quiet zone of 2 modules around the code
+            let modules = (matrix.width + 4) as f32;
+            let module_size = bounds.width.min(bounds.height) / modules;
+
+            // QR codes are always drawn dark on light so that scanners can read them
+            frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color::WHITE);
+
+            for (index, _) in matrix.dark.iter().enumerate().filter(|(_, dark)| **dark) {
+                let x = (index % matrix.width + 2) as f32 * module_size;
+                let y = (index / matrix.width + 2) as f32 * module_size;
+
+                frame.fill(
+                    &Path::rectangle(Point::new(x, y), Size::new(module_size, module_size)),
+                    Color::BLACK,
+                );
+            }
+        })]
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Content {
+    Loading,
+    Empty,
+    Code(String, QrMatrix),
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct QrCode {
+    config: QrCodeModuleConfig,
+    content: Content,
+    /// the drawing of the code, cleared when the clipboard is read again
+    cache: Cache,
+    last_scan: Option<Option<String>>,
+}
+
+impl QrCode {
+    pub fn new(config: QrCodeModuleConfig) -> Self {
+        Self {
+            config,
+            content: Content::Loading,
+            cache: Cache::new(),
+            last_scan: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::MenuOpened => {
+                self.content = Content::Loading;
+                let paste_cmd = self.config.paste_cmd.clone();
+
+                Action::Command(Task::perform(
+                    async move { command_output(&paste_cmd).await },
+                    Message::ClipboardRead,
+                ))
+            }
+            Message::ClipboardRead(content) => {
+                self.cache.clear();
+                self.content = match content {
+                    Some(content) if !content.trim().is_empty() => {
+                        match QrCodeData::new(content.as_bytes()) {
+                            Ok(code) => Content::Code(
+                                content,
+                                QrMatrix {
+                                    width: code.width(),
+                                    dark: code
+                                        .to_colors()
+                                        .into_iter()
+                                        .map(|color| color == qrcode::Color::Dark)
+                                        .collect(),
+                                },
+                            ),
+                            Err(err) => {
+                                warn!("Failed to create QR code: {err}");
+
+                                Content::Error(format!("Unable to create a QR code: {err}"))
+                            }
+                        }
+                    }
+                    _ => Content::Empty,
+                };
+
+                Action::None
+            }
+            Message::Scan(id) => {
+                let scan_cmd = self.config.scan_cmd.clone();
+                let copy_cmd = self.config.copy_cmd.clone();
+
+                Action::CloseMenu(
+                    id,
+                    Task::perform(
+                        async move {
+                            let result = command_output(&scan_cmd)
+                                .await
+                                .map(|result| result.trim_end().to_owned())
+                                .filter(|result| !result.is_empty());
+
+                            if let Some(result) = &result {
+                                pipe_to_command(&copy_cmd, result).await;
+                            }
+
+                            result
+                        },
+                        Message::Scanned,
+                    ),
+                )
+            }
+            Message::Scanned(result) => {
+                self.last_scan = Some(result);
+
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self, _theme: &AshellTheme) -> Element<'_, Message> {
+        icon(StaticIcon::QrCode).into()
+    }
+
+    pub fn menu_view<'a>(&'a self, id: Id, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let content: Element<'a, Message> = match &self.content {
+            Content::Loading => text("Reading clipboard...").into(),
+            Content::Empty => text("The clipboard is empty").into(),
+            Content::Error(err) => text(err).into(),
+            Content::Code(content, matrix) => column!(
+                container(
+                    canvas(QrCanvas(matrix, &self.cache))
+                        .width(Length::Fixed(QR_SIZE))
+                        .height(Length::Fixed(QR_SIZE))
+                )
+                .center_x(Length::Fill),
+                text(truncate_text(content, 40)).size(theme.font_size.xs),
+            )
+            .align_x(Alignment::Center)
+            .spacing(theme.space.xs)
+            .into(),
+        };
+
+        column!(
+            text("QR Code").size(theme.font_size.lg),
+            horizontal_rule(1),
+            content,
+            horizontal_rule(1),
+        )
+        .push_maybe(self.last_scan.as_ref().map(|result| {
+            text(match result {
+                Some(result) => format!("Copied: {}", truncate_text(result, 40)),
+                None => "No QR code found".to_string(),
+            })
+            .size(theme.font_size.xs)
+        }))
+        .push(
+            button(
+                row!(icon(StaticIcon::QrCodeScan), text("Scan from screen"))
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xs),
+            )
+            .style(theme.ghost_button_style())
+            .padding(theme.space.xs)
+            .on_press(Message::Scan(id))
+            .width(Length::Fill),
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+}
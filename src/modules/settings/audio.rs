@@ -3,17 +3,22 @@ use crate::{
     components::icons::{StaticIcon, icon, icon_button},
     services::{
         ReadOnlyService, Service, ServiceEvent,
-        audio::{AudioCommand, AudioService, DeviceType, Sinks},
+        audio::{AudioCommand, AudioService, Device, DeviceType, Port, Sinks},
     },
     theme::AshellTheme,
 };
 use iced::{
-    Alignment, Element, Length, Subscription, Theme,
+    Alignment, Element, Length, Subscription, Task, Theme,
     widget::{
         Column, MouseArea, Row, button, column, container, horizontal_rule, row, slider, text,
     },
     window::Id,
 };
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// How long the new sink name is shown next to the indicator after cycling
+const CYCLED_SINK_TIMEOUT: Duration = Duration::from_secs(3);
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -28,6 +33,8 @@ pub enum Message {
     SourcesMore(Id),
     ToggleSinksMenu,
     ToggleSourcesMenu,
+    CycleSink,
+    HideCycledSink(usize),
     ConfigReloaded(AudioSettingsConfig),
 }
 
@@ -37,19 +44,26 @@ pub enum Action {
     ToggleSourcesMenu,
     CloseMenu(Id),
     CloseSubMenu,
+    Command(Task<Message>),
 }
 
 #[derive(Debug, Clone)]
 pub struct AudioSettingsConfig {
     pub sinks_more_cmd: Option<String>,
     pub sources_more_cmd: Option<String>,
+    pub sink_cycle: Vec<String>,
 }
 
 impl AudioSettingsConfig {
-    pub fn new(sinks_more_cmd: Option<String>, sources_more_cmd: Option<String>) -> Self {
+    pub fn new(
+        sinks_more_cmd: Option<String>,
+        sources_more_cmd: Option<String>,
+        sink_cycle: Vec<String>,
+    ) -> Self {
         Self {
             sinks_more_cmd,
             sources_more_cmd,
+            sink_cycle,
         }
     }
 }
@@ -57,6 +71,8 @@ impl AudioSettingsConfig {
 pub struct AudioSettings {
     config: AudioSettingsConfig,
     service: Option<AudioService>,
+    cycled_sink: Option<String>,
+    cycle_count: usize,
 }
 
 pub struct SubmenuEntry<RMessage> {
@@ -77,6 +93,34 @@ impl AudioSettings {
         Self {
             config,
             service: None,
+            cycled_sink: None,
+            cycle_count: 0,
+        }
+    }
+
+    /// Sink ports to cycle through, the ones listed in the config
+    /// or every port when the list is empty
+    fn sink_cycle(&self) -> Vec<(&Device, &Port)> {
+        let Some(service) = self.service.as_ref() else {
+            return Vec::new();
+        };
+        let ports = service
+            .sinks
+            .iter()
+            .flat_map(|sink| sink.ports.iter().map(move |port| (sink, port)));
+
+        if self.config.sink_cycle.is_empty() {
+            ports.collect()
+        } else {
+            self.config
+                .sink_cycle
+                .iter()
+                .filter_map(|entry| {
+                    ports.clone().find(|(sink, port)| {
+                        [&sink.name, &sink.description, &port.description].contains(&entry)
+                    })
+                })
+                .collect()
         }
     }
 
@@ -158,6 +202,52 @@ impl AudioSettings {
             }
             Message::ToggleSinksMenu => Action::ToggleSinksMenu,
             Message::ToggleSourcesMenu => Action::ToggleSourcesMenu,
+            Message::CycleSink => {
+                let Some(default_sink) = self
+                    .service
+                    .as_ref()
+                    .map(|service| service.server_info.default_sink.clone())
+                else {
+                    return Action::None;
+                };
+                let cycle = self.sink_cycle();
+                let current = cycle
+                    .iter()
+                    .position(|(sink, port)| sink.name == default_sink && port.active);
+                let next = match current {
+                    Some(current) => cycle.get((current + 1) % cycle.len()),
+                    None => cycle.first(),
+                }
+                .filter(|_| cycle.len() > 1 || current.is_none())
+                .map(|(sink, port)| {
+                    (
+                        sink.name.clone(),
+                        port.name.clone(),
+                        format!("{}: {}", port.description, sink.description),
+                    )
+                });
+
+                match (next, self.service.as_mut()) {
+                    (Some((sink, port, description)), Some(service)) => {
+                        let _ = service.command(AudioCommand::DefaultSink(sink, port));
+                        self.cycled_sink = Some(description);
+                        self.cycle_count += 1;
+
+                        let count = self.cycle_count;
+                        Action::Command(Task::perform(sleep(CYCLED_SINK_TIMEOUT), move |_| {
+                            Message::HideCycledSink(count)
+                        }))
+                    }
+                    _ => Action::None,
+                }
+            }
+            Message::HideCycledSink(count) => {
+                // a newer cycle restarted the timeout
+                if count == self.cycle_count {
+                    self.cycled_sink = None;
+                }
+                Action::None
+            }
             Message::ConfigReloaded(config) => {
                 self.config = config;
                 Action::None
@@ -165,28 +255,34 @@ impl AudioSettings {
         }
     }
 
-    pub fn sink_indicator(&'_ self) -> Option<Element<'_, Message>> {
+    pub fn sink_indicator<'a>(&'a self, theme: &'a AshellTheme) -> Option<Element<'a, Message>> {
         self.service
             .as_ref()
             .filter(|service| !service.sinks.is_empty())
             .map(|service| {
                 let icon_type = service.sinks.get_icon(&service.server_info.default_sink);
                 let icon = icon(icon_type);
-                MouseArea::new(icon)
-                    .on_scroll(|delta| {
-                        let cur_vol = service.cur_sink_volume;
-                        let delta = match delta {
-                            iced::mouse::ScrollDelta::Lines { y, .. } => y,
-                            iced::mouse::ScrollDelta::Pixels { y, .. } => y,
-                        };
-                        let new_volume = if delta > 0.0 {
-                            (cur_vol + 5).min(100)
-                        } else {
-                            (cur_vol - 5).max(0)
-                        };
-                        Message::SinkVolumeChanged(new_volume)
-                    })
-                    .into()
+                MouseArea::new(
+                    row!(icon)
+                        .push_maybe(self.cycled_sink.as_ref().map(text))
+                        .align_y(Alignment::Center)
+                        .spacing(theme.space.xxs),
+                )
+                .on_middle_press(Message::CycleSink)
+                .on_scroll(|delta| {
+                    let cur_vol = service.cur_sink_volume;
+                    let delta = match delta {
+                        iced::mouse::ScrollDelta::Lines { y, .. } => y,
+                        iced::mouse::ScrollDelta::Pixels { y, .. } => y,
+                    };
+                    let new_volume = if delta > 0.0 {
+                        (cur_vol + 5).min(100)
+                    } else {
+                        (cur_vol - 5).max(0)
+                    };
+                    Message::SinkVolumeChanged(new_volume)
+                })
+                .into()
             })
     }
 
@@ -10,10 +10,13 @@ use crate::{
 use iced::{
     Element, Length, Subscription, Task, Theme,
     alignment::{Horizontal, Vertical},
-    widget::{Column, Row, button, column, container, horizontal_rule, row, scrollable, text},
+    widget::{
+        Column, MouseArea, Row, button, column, container, horizontal_rule, row, scrollable, text,
+    },
     window::Id,
 };
 use itertools::Itertools;
+use log::info;
 use zbus::zvariant::OwnedObjectPath;
 
 #[derive(Debug, Clone)]
@@ -42,11 +45,21 @@ pub enum Action {
 #[derive(Debug, Clone)]
 pub struct BluetoothSettingsConfig {
     pub more_cmd: Option<String>,
+    pub pinned_devices: Vec<String>,
+    pub auto_reconnect: bool,
 }
 
 impl BluetoothSettingsConfig {
-    pub fn new(more_cmd: Option<String>) -> Self {
-        Self { more_cmd }
+    pub fn new(
+        more_cmd: Option<String>,
+        pinned_devices: Vec<String>,
+        auto_reconnect: bool,
+    ) -> Self {
+        Self {
+            more_cmd,
+            pinned_devices,
+            auto_reconnect,
+        }
     }
 }
 
@@ -71,10 +84,18 @@ impl BluetoothSettings {
                     Action::None
                 }
                 ServiceEvent::Update(data) => {
-                    if let Some(service) = self.service.as_mut() {
-                        service.update(data);
+                    let Some(service) = self.service.as_mut() else {
+                        return Action::None;
+                    };
+                    let powered_on = service.state != BluetoothState::Active
+                        && data.state == BluetoothState::Active;
+                    service.update(data);
+
+                    if powered_on && self.config.auto_reconnect {
+                        self.reconnect_pinned_devices()
+                    } else {
+                        Action::None
                     }
-                    Action::None
                 }
                 _ => Action::None,
             },
@@ -151,6 +172,42 @@ impl BluetoothSettings {
         }
     }
 
+    fn pinned_devices(&self) -> impl Iterator<Item = &BluetoothDevice> {
+        self.service
+            .iter()
+            .filter(|service| service.state == BluetoothState::Active)
+            .flat_map(|service| {
+                // keep the order of the config
+                self.config.pinned_devices.iter().filter_map(|pinned| {
+                    service
+                        .devices
+                        .iter()
+                        .find(|d| d.paired && (&d.name == pinned || &d.address == pinned))
+                })
+            })
+    }
+
+    fn reconnect_pinned_devices(&mut self) -> Action {
+        let paths = self
+            .pinned_devices()
+            .filter(|d| !d.connected)
+            .map(|d| d.path.clone())
+            .collect::<Vec<_>>();
+
+        match self.service.as_mut() {
+            Some(service) if !paths.is_empty() => {
+                info!("Reconnecting {} pinned bluetooth devices", paths.len());
+
+                Action::Command(Task::batch(paths.into_iter().map(|path| {
+                    service
+                        .command(BluetoothCommand::ConnectDevice(path))
+                        .map(Message::Event)
+                })))
+            }
+            _ => Action::None,
+        }
+    }
+
     pub fn quick_setting_button<'a>(
         &'a self,
         id: Id,
@@ -412,6 +469,37 @@ impl BluetoothSettings {
         None
     }
 
+    pub fn pinned_devices_indicator<'a>(
+        &'a self,
+        theme: &'a AshellTheme,
+    ) -> Option<Element<'a, Message>> {
+        let devices = self
+            .pinned_devices()
+            .map(|d| {
+                let connected = d.connected;
+
+                MouseArea::new(container(icon(d.get_icon())).style(move |theme: &Theme| {
+                    container::Style {
+                        text_color: connected.then(|| theme.palette().success),
+                        ..Default::default()
+                    }
+                }))
+                .on_press(if connected {
+                    Message::DisconnectDevice(d.path.clone())
+                } else {
+                    Message::ConnectDevice(d.path.clone())
+                })
+                .into()
+            })
+            .collect::<Vec<_>>();
+
+        if devices.is_empty() {
+            None
+        } else {
+            Some(Row::with_children(devices).spacing(theme.space.xs).into())
+        }
+    }
+
     pub fn subscription(&self) -> Subscription<Message> {
         BluetoothService::subscribe().map(Message::Event)
     }
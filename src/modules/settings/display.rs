@@ -0,0 +1,120 @@
+use crate::{
+    components::icons::StaticIcon, modules::settings::quick_setting_button, theme::AshellTheme,
+};
+use hyprland::{
+    data::{Monitor, Monitors},
+    keyword::Keyword,
+    shared::HyprData,
+};
+use iced::Element;
+use log::{debug, error};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ToggleMirror,
+    MenuOpened,
+}
+
+/// Mirrors the laptop screen on the other monitors, e.g. a projector, with Hyprland
+pub struct DisplaySettings {
+    monitors: Vec<Monitor>,
+    /// layouts of the mirroring monitors before the mirroring, restored when it stops
+    saved_layouts: Option<Vec<String>>,
+}
+
+/// The internal panel of a laptop, or the first monitor
+fn primary(monitors: &[Monitor]) -> Option<&Monitor> {
+    monitors
+        .iter()
+        .find(|m| ["eDP", "LVDS", "DSI"].iter().any(|p| m.name.starts_with(p)))
+        .or_else(|| monitors.iter().min_by_key(|m| m.id))
+}
+
+/// The monitor rule giving back the current layout of the monitor
+fn layout(monitor: &Monitor) -> String {
+    format!(
+        "{},{}x{}@{},{}x{},{},transform,{}",
+        monitor.name,
+        monitor.width,
+        monitor.height,
+        monitor.refresh_rate,
+        monitor.x,
+        monitor.y,
+        monitor.scale,
+        monitor.transform as u8,
+    )
+}
+
+fn set_monitor(rule: &str) {
+    debug!("setting monitor rule: {rule}");
+
+    if let Err(e) = Keyword::set("monitor", rule.to_string()) {
+        error!("failed to set the monitor rule {rule}: {e}");
+    }
+}
+
+impl DisplaySettings {
+    pub fn new() -> Self {
+        Self {
+            monitors: Vec::new(),
+            saved_layouts: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::MenuOpened => {
+                self.monitors = Monitors::get()
+                    .map(|monitors| monitors.into_iter().collect())
+                    .unwrap_or_default();
+            }
+            Message::ToggleMirror => match self.saved_layouts.take() {
+                Some(layouts) => {
+                    for rule in layouts {
+                        set_monitor(&rule);
+                    }
+                }
+                None => {
+                    let Some(primary) = primary(&self.monitors) else {
+                        return;
+                    };
+
+                    let others = self
+                        .monitors
+                        .iter()
+                        .filter(|m| m.id != primary.id)
+                        .collect::<Vec<_>>();
+                    for monitor in &others {
+                        set_monitor(&format!(
+                            "{},preferred,auto,1,mirror,{}",
+                            monitor.name, primary.name
+                        ));
+                    }
+
+                    self.saved_layouts = Some(others.into_iter().map(layout).collect());
+                }
+            },
+        }
+    }
+
+    pub fn quick_setting_button<'a>(
+        &'a self,
+        theme: &'a AshellTheme,
+    ) -> Option<(Element<'a, Message>, Option<Element<'a, Message>>)> {
+        // the button is there while mirroring, even when the projector is gone
+        (self.monitors.len() > 1 || self.saved_layouts.is_some()).then(|| {
+            (
+                quick_setting_button(
+                    theme,
+                    StaticIcon::MonitorMultiple,
+                    "Mirror Displays".to_string(),
+                    None,
+                    self.saved_layouts.is_some(),
+                    Message::ToggleMirror,
+                    None,
+                ),
+                None,
+            )
+        })
+    }
+}
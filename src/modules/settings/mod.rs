@@ -29,6 +29,8 @@ use iced::{
 mod audio;
 mod bluetooth;
 mod brightness;
+#[cfg(feature = "hyprland")]
+mod display;
 mod network;
 mod power;
 
@@ -39,6 +41,8 @@ pub struct Settings {
     brightness: BrightnessSettings,
     network: NetworkSettings,
     bluetooth: BluetoothSettings,
+    #[cfg(feature = "hyprland")]
+    display: display::DisplaySettings,
     idle_inhibitor: Option<IdleInhibitorManager>,
     sub_menu: Option<SubMenu>,
     password_dialog: Option<(String, String)>,
@@ -53,6 +57,8 @@ pub enum Message {
     Bluetooth(bluetooth::Message),
     Audio(audio::Message),
     Brightness(brightness::Message),
+    #[cfg(feature = "hyprland")]
+    Display(display::Message),
     ToggleInhibitIdle,
     Lock,
     Power(power::Message),
@@ -101,6 +107,7 @@ impl Settings {
             audio: AudioSettings::new(AudioSettingsConfig::new(
                 config.audio_sinks_more_cmd,
                 config.audio_sources_more_cmd,
+                config.audio_sink_cycle,
             )),
             brightness: BrightnessSettings::new(),
             network: NetworkSettings::new(NetworkSettingsConfig::new(
@@ -110,7 +117,11 @@ impl Settings {
             )),
             bluetooth: BluetoothSettings::new(BluetoothSettingsConfig::new(
                 config.bluetooth_more_cmd,
+                config.bluetooth_pinned_devices,
+                config.bluetooth_auto_reconnect,
             )),
+            #[cfg(feature = "hyprland")]
+            display: display::DisplaySettings::new(),
             idle_inhibitor: if config.remove_idle_btn {
                 None
             } else {
@@ -165,6 +176,7 @@ impl Settings {
                     Action::None
                 }
                 audio::Action::CloseMenu(id) => Action::CloseMenu(id),
+                audio::Action::Command(task) => Action::Command(task.map(Message::Audio)),
             },
             Message::Network(msg) => match self.network.update(msg) {
                 network::Action::None => Action::None,
@@ -226,6 +238,12 @@ impl Settings {
                 brightness::Action::None => Action::None,
                 brightness::Action::Command(task) => Action::Command(task.map(Message::Brightness)),
             },
+            #[cfg(feature = "hyprland")]
+            Message::Display(msg) => {
+                self.display.update(msg);
+
+                Action::None
+            }
             Message::ToggleSubMenu(menu_type) => {
                 if self.sub_menu == Some(menu_type) {
                     self.sub_menu.take();
@@ -308,6 +326,8 @@ impl Settings {
             }
             Message::MenuOpened => {
                 self.sub_menu = None;
+                #[cfg(feature = "hyprland")]
+                self.display.update(display::Message::MenuOpened);
 
                 let buttons = self.custom_buttons.clone();
 
@@ -386,6 +406,7 @@ impl Settings {
                     .update(audio::Message::ConfigReloaded(AudioSettingsConfig::new(
                         config.audio_sinks_more_cmd,
                         config.audio_sources_more_cmd,
+                        config.audio_sink_cycle,
                     )));
                 self.network.update(network::Message::ConfigReloaded(
                     NetworkSettingsConfig::new(
@@ -395,7 +416,11 @@ impl Settings {
                     ),
                 ));
                 self.bluetooth.update(bluetooth::Message::ConfigReloaded(
-                    BluetoothSettingsConfig::new(config.bluetooth_more_cmd),
+                    BluetoothSettingsConfig::new(
+                        config.bluetooth_more_cmd,
+                        config.bluetooth_pinned_devices,
+                        config.bluetooth_auto_reconnect,
+                    ),
                 ));
                 if config.remove_idle_btn {
                     self.idle_inhibitor = None;
@@ -507,6 +532,10 @@ impl Settings {
                                 submenu.map(|e| e.map(Message::Power)),
                             )
                         }),
+                    #[cfg(feature = "hyprland")]
+                    self.display
+                        .quick_setting_button(theme)
+                        .map(|(button, _)| (button.map(Message::Display), None)),
                 ]
                 .into_iter()
                 .flatten()
@@ -624,8 +653,10 @@ impl Settings {
                     }
                 }
                 SettingsIndicator::Audio => {
-                    if let Some(element) =
-                        self.audio.sink_indicator().map(|e| e.map(Message::Audio))
+                    if let Some(element) = self
+                        .audio
+                        .sink_indicator(theme)
+                        .map(|e| e.map(Message::Audio))
                     {
                         row = row.push(element);
                     }
@@ -657,6 +688,15 @@ impl Settings {
                         row = row.push(element);
                     }
                 }
+                SettingsIndicator::BluetoothDevices => {
+                    if let Some(element) = self
+                        .bluetooth
+                        .pinned_devices_indicator(theme)
+                        .map(|e| e.map(Message::Bluetooth))
+                    {
+                        row = row.push(element);
+                    }
+                }
                 SettingsIndicator::Battery => {
                     if let Some(element) = self
                         .power
@@ -1,6 +1,6 @@
 use super::{SubMenu, quick_setting_button};
 use crate::{
-    components::icons::{StaticIcon, icon, icon_button},
+    components::icons::{IconButtonSize, StaticIcon, icon, icon_button},
     services::{
         ReadOnlyService, Service, ServiceEvent,
         network::{
@@ -17,6 +17,7 @@ use iced::{
     window::Id,
 };
 use log::info;
+use zbus::zvariant::OwnedObjectPath;
 
 static WIFI_SIGNAL_ICONS: [StaticIcon; 6] = [
     StaticIcon::Wifi0,
@@ -77,6 +78,8 @@ pub enum Message {
     ToggleVPNMenu,
     WifiMenuOpened,
     PasswordDialogConfirmed(String, String),
+    MoveSavedNetwork(usize, usize),
+    ForgetNetwork(OwnedObjectPath),
     ConfigReloaded(NetworkSettingsConfig),
 }
 
@@ -235,6 +238,32 @@ impl NetworkSettings {
                 }
                 _ => Action::None,
             },
+            Message::MoveSavedNetwork(from, to) => match self.service.as_mut() {
+                Some(service) if from < service.saved_networks.len() => {
+                    let mut order = service
+                        .saved_networks
+                        .iter()
+                        .map(|n| n.path.clone())
+                        .collect::<Vec<_>>();
+                    let path = order.remove(from);
+                    order.insert(to.min(order.len()), path);
+
+                    Action::Command(
+                        service
+                            .command(NetworkCommand::SetSavedNetworksOrder(order))
+                            .map(Message::Event),
+                    )
+                }
+                _ => Action::None,
+            },
+            Message::ForgetNetwork(path) => match self.service.as_mut() {
+                Some(service) => Action::Command(
+                    service
+                        .command(NetworkCommand::ForgetNetwork(path))
+                        .map(Message::Event),
+                ),
+                _ => Action::None,
+            },
             Message::ConfigReloaded(config) => {
                 self.config = config;
                 Action::None
@@ -520,6 +549,9 @@ impl NetworkSettings {
             ))
             .max_height(200),
         )
+        .push_maybe(
+            (!service.saved_networks.is_empty()).then(|| Self::saved_networks_menu(service, theme)),
+        )
         .spacing(theme.space.xs);
 
         if show_more_button {
@@ -539,6 +571,60 @@ impl NetworkSettings {
         }
     }
 
+    fn saved_networks_menu<'a>(
+        service: &'a NetworkService,
+        theme: &'a AshellTheme,
+    ) -> Element<'a, Message> {
+        let last = service.saved_networks.len() - 1;
+
+        column!(
+            horizontal_rule(1),
+            text("Saved networks"),
+            container(scrollable(
+                Column::with_children(
+                    service
+                        .saved_networks
+                        .iter()
+                        .enumerate()
+                        .map(|(i, network)| {
+                            // the priority buttons are only available when the backend supports it
+                            let priorities = network.priority.is_some();
+
+                            row!(text(network.name.clone()).width(Length::Fill))
+                                .push_maybe(priorities.then(|| {
+                                    icon_button(theme, StaticIcon::UpChevron)
+                                        .on_press_maybe(
+                                            (i > 0).then_some(Message::MoveSavedNetwork(i, i - 1)),
+                                        )
+                                        .size(IconButtonSize::Small)
+                                }))
+                                .push_maybe(priorities.then(|| {
+                                    icon_button(theme, StaticIcon::DownChevron)
+                                        .on_press_maybe(
+                                            (i < last)
+                                                .then_some(Message::MoveSavedNetwork(i, i + 1)),
+                                        )
+                                        .size(IconButtonSize::Small)
+                                }))
+                                .push(
+                                    icon_button(theme, StaticIcon::TrashCan)
+                                        .on_press(Message::ForgetNetwork(network.path.clone()))
+                                        .size(IconButtonSize::Small),
+                                )
+                                .align_y(Alignment::Center)
+                                .spacing(theme.space.xxs)
+                                .into()
+                        })
+                        .collect::<Vec<Element<'a, Message>>>(),
+                )
+                .spacing(theme.space.xxs),
+            ))
+            .max_height(150),
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+
     fn vpn_menu<'a>(
         service: &'a NetworkService,
         id: Id,
@@ -2,15 +2,35 @@ use crate::{
     components::icons::{StaticIcon, icon},
     config::{SystemInfoIndicator, SystemInfoModuleConfig},
     theme::AshellTheme,
+    utils::launcher::execute_command,
 };
 use iced::{
-    Alignment, Element, Length, Subscription, Theme,
+    Alignment, Color, Element, Length, Subscription, Task, Theme,
+    alignment::Horizontal,
     time::every,
-    widget::{Column, Row, column, container, horizontal_rule, row, text},
+    widget::{Column, Row, button, column, container, horizontal_rule, row, text},
 };
 use itertools::Itertools;
-use std::time::{Duration, Instant};
-use sysinfo::{Components, Disks, Networks, System};
+use log::warn;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use sysinfo::{Components, Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System};
+
+const GB: u64 = 1_000_000_000;
+const MB: u64 = 1_000_000;
+const TRASH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// the processes are only read while the menu is shown
+const PROCESSES_INTERVAL: Duration = Duration::from_secs(2);
+const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+struct DiskData {
+    mount_point: String,
+    usage: u32,
+    available: u64,
+}
 
 struct NetworkData {
     ip: String,
@@ -19,12 +39,21 @@ struct NetworkData {
     last_check: Instant,
 }
 
+struct ProcessData {
+    name: String,
+    /// share of the whole CPU
+    cpu_usage: f32,
+    memory: u64,
+}
+
 struct SystemInfoData {
     pub cpu_usage: u32,
+    pub cores_usage: Vec<u32>,
     pub memory_usage: u32,
     pub memory_swap_usage: u32,
     pub temperature: Option<i32>,
-    pub disks: Vec<(String, u32)>,
+    pub gpu_temperature: Option<i32>,
+    pub disks: Vec<DiskData>,
     pub network: Option<NetworkData>,
 }
 
@@ -33,7 +62,7 @@ fn get_system_info(
     components: &mut Components,
     disks: &mut Disks,
     (networks, last_check): (&mut Networks, Option<Instant>),
-    temperature_sensor: &str,
+    (temperature_sensor, gpu_sensor): (&str, Option<&str>),
 ) -> SystemInfoData {
     system.refresh_memory();
     system.refresh_cpu_specifics(sysinfo::CpuRefreshKind::everything());
@@ -43,6 +72,11 @@ fn get_system_info(
     networks.refresh(true);
 
     let cpu_usage = system.global_cpu_usage().floor() as u32;
+    let cores_usage = system
+        .cpus()
+        .iter()
+        .map(|cpu| cpu.cpu_usage().floor() as u32)
+        .collect();
     let memory_usage = ((system.total_memory() - system.available_memory()) as f32
         / system.total_memory() as f32
         * 100.) as u32;
@@ -51,22 +85,26 @@ fn get_system_info(
         / system.total_swap() as f32
         * 100.) as u32;
 
-    let temperature = components
-        .iter()
-        .find(|c| c.label() == temperature_sensor)
-        .and_then(|c| c.temperature().map(|t| t as i32));
+    // the labels of the hwmon sensors, like `sensors` shows them
+    let sensor_temperature = |sensor: &str| {
+        components
+            .iter()
+            .find(|c| c.label() == sensor)
+            .and_then(|c| c.temperature().map(|t| t as i32))
+    };
+    let temperature = sensor_temperature(temperature_sensor);
+    let gpu_temperature = gpu_sensor.and_then(sensor_temperature);
 
     let disks = disks
         .into_iter()
         .filter(|d| !d.is_removable() && d.total_space() != 0)
-        .map(|d| {
-            (
-                d.mount_point().to_string_lossy().to_string(),
-                (((d.total_space() - d.available_space()) as f32) / d.total_space() as f32 * 100.)
-                    as u32,
-            )
+        .map(|d| DiskData {
+            mount_point: d.mount_point().to_string_lossy().to_string(),
+            usage: (((d.total_space() - d.available_space()) as f32) / d.total_space() as f32
+                * 100.) as u32,
+            available: d.available_space(),
         })
-        .sorted_by(|a, b| a.0.cmp(&b.0))
+        .sorted_by(|a, b| a.mount_point.cmp(&b.mount_point))
         .collect::<Vec<_>>();
 
     let elapsed = last_check.map(|v| v.elapsed().as_secs());
@@ -129,9 +167,11 @@ fn get_system_info(
 
     SystemInfoData {
         cpu_usage,
+        cores_usage,
         memory_usage,
         memory_swap_usage,
         temperature,
+        gpu_temperature,
         disks,
         network: network.0.map(|ip| NetworkData {
             ip: ip.to_string(),
@@ -142,9 +182,114 @@ fn get_system_info(
     }
 }
 
+/// The processes using the most CPU, the threads are counted in their process
+fn top_processes(system: &mut System, count: usize) -> Vec<ProcessData> {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cpu().with_memory(),
+    );
+    let cpus = system.cpus().len().max(1) as f32;
+
+    system
+        .processes()
+        .values()
+        .filter(|process| process.thread_kind().is_none())
+        .map(|process| ProcessData {
+            name: process.name().to_string_lossy().to_string(),
+            cpu_usage: process.cpu_usage() / cpus,
+            memory: process.memory(),
+        })
+        .sorted_by(|a, b| {
+            b.cpu_usage
+                .total_cmp(&a.cpu_usage)
+                .then(b.memory.cmp(&a.memory))
+        })
+        .take(count)
+        .collect()
+}
+
+/// One block per core, higher with the usage
+fn cores_bar(cores_usage: &[u32]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    cores_usage
+        .iter()
+        .map(|usage| BLOCKS[(*usage as usize * BLOCKS.len() / 101).min(BLOCKS.len() - 1)])
+        .collect()
+}
+
+fn trash_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|data| data.join("Trash").join("files"))
+}
+
+async fn trash_size() -> u64 {
+    let Some(trash_dir) = trash_dir() else {
+        return 0;
+    };
+
+    tokio::task::spawn_blocking(move || dir_size(&trash_dir))
+        .await
+        .unwrap_or_default()
+}
+
+/// Size of the directory content, symlinks are not followed
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| match entry.metadata() {
+                    Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+                    Ok(metadata) => metadata.len(),
+                    Err(_) => 0,
+                })
+                .sum()
+        })
+        .unwrap_or_default()
+}
+
+/// Times the CPUs were throttled by the temperature since the boot,
+/// the counters are reported only by the Intel CPUs
+fn throttle_count() -> Option<u64> {
+    let counts = fs::read_dir(CPU_DIR)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("cpu"))
+                .is_some_and(|id| id.parse::<u32>().is_ok())
+        })
+        .flat_map(|entry| {
+            let dir = entry.path().join("thermal_throttle");
+
+            ["core_throttle_count", "package_throttle_count"]
+                .map(|counter| fs::read_to_string(dir.join(counter)))
+        })
+        .filter_map(|count| count.ok()?.trim().parse::<u64>().ok())
+        .collect::<Vec<_>>();
+
+    (!counts.is_empty()).then(|| counts.iter().sum())
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     Update,
+    UpdateProcesses,
+    MenuOpened,
+    TrashChecked(u64),
+    EmptyTrash,
+    OpenDiskUsage,
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
 }
 
 pub struct SystemInfo {
@@ -154,6 +299,12 @@ pub struct SystemInfo {
     disks: Disks,
     networks: Networks,
     data: SystemInfoData,
+    processes: Vec<ProcessData>,
+    trash_size: Option<u64>,
+    last_trash_check: Option<Instant>,
+    throttle_count: Option<u64>,
+    /// the throttle counters increased since the last update
+    throttling: bool,
 }
 
 impl SystemInfo {
@@ -167,7 +318,10 @@ impl SystemInfo {
             &mut components,
             &mut disks,
             (&mut networks, None),
-            &config.temperature.sensor,
+            (
+                &config.temperature.sensor,
+                config.temperature.gpu_sensor.as_deref(),
+            ),
         );
 
         Self {
@@ -177,10 +331,73 @@ impl SystemInfo {
             disks,
             data,
             networks,
+            processes: Vec::new(),
+            trash_size: None,
+            last_trash_check: None,
+            throttle_count: throttle_count(),
+            throttling: false,
         }
     }
 
-    pub fn update(&mut self, message: Message) {
+    fn check_trash(&mut self) -> Action {
+        self.last_trash_check = Some(Instant::now());
+
+        Action::Command(Task::perform(trash_size(), Message::TrashChecked))
+    }
+
+    fn check_throttling(&mut self) {
+        if !self.config.temperature.throttle_alert {
+            self.throttling = false;
+            return;
+        }
+
+        let count = throttle_count();
+        let throttling = self
+            .throttle_count
+            .zip(count)
+            .is_some_and(|(old, new)| new > old);
+
+        if throttling && !self.throttling {
+            warn!("The CPU is throttled by the temperature");
+
+            if let Some(cmd) = &self.config.temperature.throttle_cmd {
+                execute_command(cmd.clone());
+            }
+        }
+
+        self.throttle_count = count;
+        self.throttling = throttling;
+    }
+
+    /// Low space on the watched mounts and a trash bigger than allowed
+    fn disk_warnings(&self) -> Vec<String> {
+        let disk = &self.config.disk;
+
+        disk.watched_mounts
+            .iter()
+            .filter_map(|mount| {
+                self.data
+                    .disks
+                    .iter()
+                    .find(|d| &d.mount_point == mount)
+                    .filter(|d| d.available < disk.min_free_space * GB)
+                    .map(|d| {
+                        format!(
+                            "Low space on {mount}: {:.1} GB free",
+                            d.available as f64 / GB as f64
+                        )
+                    })
+            })
+            .chain(
+                self.trash_size
+                    .zip(disk.trash_max_size)
+                    .filter(|(size, max_size)| *size > max_size * GB)
+                    .map(|(size, _)| format!("Trash size: {:.1} GB", size as f64 / GB as f64)),
+            )
+            .collect()
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
         match message {
             Message::Update => {
                 self.data = get_system_info(
@@ -191,8 +408,64 @@ impl SystemInfo {
                         &mut self.networks,
                         self.data.network.as_ref().map(|n| n.last_check),
                     ),
-                    &self.config.temperature.sensor,
+                    (
+                        &self.config.temperature.sensor,
+                        self.config.temperature.gpu_sensor.as_deref(),
+                    ),
                 );
+                self.check_throttling();
+
+                if self.config.disk.trash_max_size.is_some()
+                    && self
+                        .last_trash_check
+                        .is_none_or(|last_check| last_check.elapsed() >= TRASH_CHECK_INTERVAL)
+                {
+                    self.check_trash()
+                } else {
+                    Action::None
+                }
+            }
+            // the usage of the processes is measured between two refreshes,
+            // the first one after the menu is opened only reads their memory
+            Message::UpdateProcesses | Message::MenuOpened if self.config.top_processes > 0 => {
+                self.processes = top_processes(&mut self.system, self.config.top_processes);
+
+                Action::None
+            }
+            Message::UpdateProcesses | Message::MenuOpened => {
+                self.processes.clear();
+
+                Action::None
+            }
+            Message::TrashChecked(size) => {
+                self.trash_size = Some(size);
+
+                Action::None
+            }
+            Message::EmptyTrash => {
+                let command = self.config.disk.empty_trash_cmd.clone();
+
+                Action::Command(Task::perform(
+                    async move {
+                        let status = tokio::process::Command::new("bash")
+                            .arg("-c")
+                            .arg(&command)
+                            .status()
+                            .await;
+
+                        if let Err(err) = status {
+                            warn!("Failed to empty the trash with {command}: {err}");
+                        }
+
+                        trash_size().await
+                    },
+                    Message::TrashChecked,
+                ))
+            }
+            Message::OpenDiskUsage => {
+                execute_command(self.config.disk.disk_usage_cmd.clone());
+
+                Action::None
             }
         }
     }
@@ -234,16 +507,10 @@ impl SystemInfo {
             .spacing(theme.space.xxs),
         );
 
-        if let Some((warn_threshold, alert_threshold)) = threshold {
+        if let Some(threshold) = threshold {
             element
                 .style(move |theme: &Theme| container::Style {
-                    text_color: if value > warn_threshold && value < alert_threshold {
-                        Some(theme.extended_palette().danger.weak.color)
-                    } else if value >= alert_threshold {
-                        Some(theme.palette().danger)
-                    } else {
-                        None
-                    },
+                    text_color: Self::threshold_color(theme, &value, &threshold),
                     ..Default::default()
                 })
                 .into()
@@ -252,7 +519,49 @@ impl SystemInfo {
         }
     }
 
-    pub fn menu_view(&'_ self, theme: &AshellTheme) -> Element<'_, Message> {
+    fn threshold_color<V: PartialOrd>(
+        theme: &Theme,
+        value: &V,
+        (warn_threshold, alert_threshold): &(V, V),
+    ) -> Option<Color> {
+        if value > warn_threshold && value < alert_threshold {
+            Some(theme.extended_palette().danger.weak.color)
+        } else if value >= alert_threshold {
+            Some(theme.palette().danger)
+        } else {
+            None
+        }
+    }
+
+    /// The cores as blocks, colored after the busiest one
+    fn cores_indicator_element<'a>(&self, theme: &AshellTheme) -> Element<'a, Message> {
+        let busiest = self
+            .data
+            .cores_usage
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_default();
+        let threshold = (
+            self.config.cpu.warn_threshold,
+            self.config.cpu.alert_threshold,
+        );
+
+        container(
+            row!(
+                icon(StaticIcon::Cpu),
+                text(cores_bar(&self.data.cores_usage))
+            )
+            .spacing(theme.space.xxs),
+        )
+        .style(move |theme: &Theme| container::Style {
+            text_color: Self::threshold_color(theme, &busiest, &threshold),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
         column!(
             text("System Info").size(theme.font_size.lg),
             horizontal_rule(1),
@@ -263,6 +572,14 @@ impl SystemInfo {
                     "CPU Usage".to_string(),
                     format!("{}%", self.data.cpu_usage),
                 ))
+                .push_maybe(self.config.cpu.per_core.then(|| {
+                    Self::info_element(
+                        theme,
+                        StaticIcon::Cpu,
+                        "Cores".to_string(),
+                        cores_bar(&self.data.cores_usage),
+                    )
+                }))
                 .push(Self::info_element(
                     theme,
                     StaticIcon::Mem,
@@ -283,17 +600,39 @@ impl SystemInfo {
                         format!("{temp}°C"),
                     )
                 }))
+                .push_maybe(self.data.gpu_temperature.map(|temp| {
+                    Self::info_element(
+                        theme,
+                        StaticIcon::Gpu,
+                        "GPU Temperature".to_string(),
+                        format!("{temp}°C"),
+                    )
+                }))
+                .push_maybe(self.throttling.then(|| {
+                    container(
+                        row!(
+                            icon(StaticIcon::Alert),
+                            text("CPU throttled by the temperature")
+                        )
+                        .align_y(Alignment::Center)
+                        .spacing(theme.space.xs),
+                    )
+                    .style(|theme: &Theme| container::Style {
+                        text_color: Some(theme.palette().danger),
+                        ..Default::default()
+                    })
+                }))
                 .push(
                     Column::with_children(
                         self.data
                             .disks
                             .iter()
-                            .map(|(mount_point, usage)| {
+                            .map(|disk| {
                                 Self::info_element(
                                     theme,
                                     StaticIcon::Drive,
-                                    format!("Disk Usage {mount_point}"),
-                                    format!("{usage}%"),
+                                    format!("Disk Usage {}", disk.mount_point),
+                                    format!("{}%", disk.usage),
                                 )
                             })
                             .collect::<Vec<Element<_>>>(),
@@ -333,12 +672,106 @@ impl SystemInfo {
                 .spacing(theme.space.xxs)
                 .padding([0, theme.space.xs])
         )
+        .push_maybe(self.processes_menu_view(theme))
+        .push_maybe(self.disk_menu_view(theme))
         .spacing(theme.space.xs)
         .into()
     }
 
+    fn processes_menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Option<Element<'a, Message>> {
+        if self.config.top_processes == 0 || self.processes.is_empty() {
+            return None;
+        }
+
+        Some(
+            column!(horizontal_rule(1), text("Top processes"))
+                .push(
+                    Column::with_children(
+                        self.processes
+                            .iter()
+                            .map(|process| {
+                                row!(
+                                    text(&process.name).width(Length::Fill),
+                                    text(format!("{:.1}%", process.cpu_usage)),
+                                    text(format!("{} MB", process.memory / MB))
+                                        .width(Length::Fixed(theme.space.xl as f32 * 2.))
+                                        .align_x(Horizontal::Right),
+                                )
+                                .spacing(theme.space.xs)
+                                .into()
+                            })
+                            .collect::<Vec<Element<_>>>(),
+                    )
+                    .spacing(theme.space.xxs)
+                    .padding([0, theme.space.xs]),
+                )
+                .spacing(theme.space.xs)
+                .into(),
+        )
+    }
+
+    fn disk_menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Option<Element<'a, Message>> {
+        let disk = &self.config.disk;
+
+        if disk.watched_mounts.is_empty() && disk.trash_max_size.is_none() {
+            return None;
+        }
+
+        let action = |action_icon, label| {
+            button(
+                row!(icon(action_icon), text(label))
+                    .align_y(Alignment::Center)
+                    .spacing(theme.space.xs),
+            )
+            .style(theme.ghost_button_style())
+            .padding([theme.space.xxs, theme.space.xs])
+            .width(Length::Fill)
+        };
+
+        Some(
+            column!(horizontal_rule(1))
+                .extend(self.disk_warnings().into_iter().map(|warning| {
+                    container(
+                        row!(icon(StaticIcon::Alert), text(warning))
+                            .align_y(Alignment::Center)
+                            .spacing(theme.space.xs),
+                    )
+                    .padding([0, theme.space.xs])
+                    .style(|theme: &Theme| container::Style {
+                        text_color: Some(theme.palette().danger),
+                        ..Default::default()
+                    })
+                    .into()
+                }))
+                .push_maybe(disk.trash_max_size.map(|_| {
+                    action(
+                        StaticIcon::TrashCan,
+                        match self.trash_size {
+                            Some(size) => {
+                                format!("Empty trash ({:.1} GB)", size as f64 / GB as f64)
+                            }
+                            None => "Empty trash".to_string(),
+                        },
+                    )
+                    .on_press(Message::EmptyTrash)
+                }))
+                .push(
+                    action(
+                        StaticIcon::ChartDonut,
+                        "Open disk usage analyzer".to_string(),
+                    )
+                    .on_press(Message::OpenDiskUsage),
+                )
+                .spacing(theme.space.xxs)
+                .into(),
+        )
+    }
+
     pub fn view(&'_ self, theme: &AshellTheme) -> Element<'_, Message> {
         let indicators = self.config.indicators.iter().filter_map(|i| match i {
+            SystemInfoIndicator::Cpu if self.config.cpu.per_core => {
+                Some(self.cores_indicator_element(theme))
+            }
             SystemInfoIndicator::Cpu => Some(Self::indicator_info_element(
                 theme,
                 StaticIcon::Cpu,
@@ -385,25 +818,36 @@ impl SystemInfo {
                     None,
                 )
             }),
-            SystemInfoIndicator::Disk(mount) => {
-                self.data.disks.iter().find_map(|(disk_mount, disk)| {
-                    if disk_mount == mount {
-                        Some(Self::indicator_info_element(
-                            theme,
-                            StaticIcon::Drive,
-                            *disk,
-                            "%",
-                            Some((
-                                self.config.disk.warn_threshold,
-                                self.config.disk.alert_threshold,
-                            )),
-                            Some(disk_mount),
-                        ))
-                    } else {
-                        None
-                    }
-                })
-            }
+            SystemInfoIndicator::GpuTemperature => self.data.gpu_temperature.map(|temperature| {
+                Self::indicator_info_element(
+                    theme,
+                    StaticIcon::Gpu,
+                    temperature,
+                    "°C",
+                    Some((
+                        self.config.temperature.warn_threshold,
+                        self.config.temperature.alert_threshold,
+                    )),
+                    None,
+                )
+            }),
+            SystemInfoIndicator::Disk(mount) => self.data.disks.iter().find_map(|disk| {
+                if &disk.mount_point == mount {
+                    Some(Self::indicator_info_element(
+                        theme,
+                        StaticIcon::Drive,
+                        disk.usage,
+                        "%",
+                        Some((
+                            self.config.disk.warn_threshold,
+                            self.config.disk.alert_threshold,
+                        )),
+                        Some(&disk.mount_point),
+                    ))
+                } else {
+                    None
+                }
+            }),
             SystemInfoIndicator::IpAddress => self.data.network.as_ref().map(|network| {
                 Self::indicator_info_element(
                     theme,
@@ -452,13 +896,40 @@ impl SystemInfo {
             }),
         });
 
+        // warning badge for low disk space or a big trash
+        let warning = (!self.disk_warnings().is_empty()).then(|| {
+            container(icon(StaticIcon::Alert)).style(|theme: &Theme| container::Style {
+                text_color: Some(theme.palette().danger),
+                ..Default::default()
+            })
+        });
+
+        let throttling = self.throttling.then(|| {
+            container(icon(StaticIcon::Temp)).style(|theme: &Theme| container::Style {
+                text_color: Some(theme.palette().danger),
+                ..Default::default()
+            })
+        });
+
         Row::with_children(indicators)
+            .push_maybe(throttling)
+            .push_maybe(warning)
             .align_y(Alignment::Center)
             .spacing(theme.space.xxs)
             .into()
     }
 
-    pub fn subscription(&self) -> Subscription<Message> {
-        every(Duration::from_secs(5)).map(|_| Message::Update)
+    /// `polling_factor` slows the updates down to save battery
+    pub fn subscription(&self, polling_factor: u32, menu_open: bool) -> Subscription<Message> {
+        let interval = Duration::from_secs(self.config.interval.max(1) * polling_factor as u64);
+
+        Subscription::batch([
+            every(interval).map(|_| Message::Update),
+            if menu_open && self.config.top_processes > 0 {
+                every(PROCESSES_INTERVAL).map(|_| Message::UpdateProcesses)
+            } else {
+                Subscription::none()
+            },
+        ])
     }
 }
@@ -0,0 +1,413 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::{TotpAccount, TotpModuleConfig},
+    theme::AshellTheme,
+    utils::{
+        launcher::{command_output, execute_command, pipe_to_command},
+        secret,
+    },
+};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac, digest::KeyInit};
+use iced::{
+    Alignment, Element, Length, Radians, Rectangle, Renderer, Subscription, Task, Theme,
+    mouse::Cursor,
+    time::every,
+    widget::{
+        Column, button, canvas,
+        canvas::{Cache, Geometry, Path, Program, Stroke, path::Arc},
+        column, container, horizontal_rule, row, text,
+    },
+};
+use log::{debug, warn};
+use percent_encoding::percent_decode_str;
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::{
+    borrow::Cow,
+    f32::consts::PI,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::sleep;
+
+#[derive(Debug, Clone, Copy)]
+enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_uppercase().as_str() {
+            "SHA1" => Ok(Algorithm::Sha1),
+            "SHA256" => Ok(Algorithm::Sha256),
+            "SHA512" => Ok(Algorithm::Sha512),
+            _ => Err(format!("Unsupported algorithm {name}")),
+        }
+    }
+}
+
+fn sign<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as KeyInit>::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(message);
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[derive(Clone)]
+pub struct TotpSecret {
+    key: Vec<u8>,
+    algorithm: Algorithm,
+    digits: u32,
+    period: u64,
+}
+
+// never log the key
+impl fmt::Debug for TotpSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TotpSecret")
+            .field("algorithm", &self.algorithm)
+            .field("digits", &self.digits)
+            .field("period", &self.period)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TotpSecret {
+    /// Accepts a base32 secret or an `otpauth://` uri, like the ones stored by pass-otp
+    fn parse(output: &str, account: &TotpAccount) -> Result<Self, String> {
+        let line = output
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("otpauth://"))
+            .or_else(|| output.lines().map(str::trim).find(|line| !line.is_empty()))
+            .ok_or("Empty secret")?;
+
+        let mut secret = Cow::Borrowed(line);
+        let mut algorithm = Algorithm::Sha1;
+        let mut digits = account.digits;
+        let mut period = account.period;
+
+        if let Some((_, query)) = line.split_once('?') {
+            for (key, value) in query.split('&').filter_map(|param| param.split_once('=')) {
+                let value = percent_decode_str(value).decode_utf8_lossy();
+
+                match key {
+                    "secret" => secret = value,
+                    "algorithm" => algorithm = Algorithm::parse(&value)?,
+                    "digits" => digits = value.parse().unwrap_or(digits),
+                    "period" => period = value.parse().unwrap_or(period),
+                    _ => {}
+                }
+            }
+        }
+
+        let key = BASE32_NOPAD
+            .decode(
+                secret
+                    .replace([' ', '='], "")
+                    .to_ascii_uppercase()
+                    .as_bytes(),
+            )
+            .map_err(|err| format!("Invalid secret: {err}"))?;
+
+        if period == 0 || !(6..=9).contains(&digits) {
+            return Err("Invalid digits or period".to_string());
+        }
+
+        Ok(Self {
+            key,
+            algorithm,
+            digits,
+            period,
+        })
+    }
+
+    fn code(&self, time: u64) -> String {
+        let counter = (time / self.period).to_be_bytes();
+        let hash = match self.algorithm {
+            Algorithm::Sha1 => sign::<Hmac<Sha1>>(&self.key, &counter),
+            Algorithm::Sha256 => sign::<Hmac<Sha256>>(&self.key, &counter),
+            Algorithm::Sha512 => sign::<Hmac<Sha512>>(&self.key, &counter),
+        };
+
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let code = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+
+        format!(
+            "{:0width$}",
+            code % 10u32.pow(self.digits),
+            width = self.digits as usize
+        )
+    }
+
+    fn remaining(&self, time: u64) -> u64 {
+        self.period - time % self.period
+    }
+}
+
+/// The share of the period left, drawn in the cache of its account
+struct CountdownRing<'a>(f32, &'a Cache);
+
+impl<Message> Program<Message> for CountdownRing<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        vec![self.1.draw(renderer, bounds.size(), |frame| {
+            let center = frame.center();
+            let radius = bounds.width.min(bounds.height) / 2. - 2.;
+            let ring = Path::new(|builder| {
+                builder.arc(Arc {
+                    center,
+                    radius,
+                    start_angle: Radians(-PI / 2.),
+                    end_angle: Radians(-PI / 2. + 2. * PI * self.0),
+                })
+            });
+
+            frame.stroke(
+                &Path::circle(center, radius),
+                Stroke::default()
+                    .with_width(2.)
+                    .with_color(theme.extended_palette().background.strong.color),
+            );
+            frame.stroke(
+                &ring,
+                Stroke::default()
+                    .with_width(2.)
+                    .with_color(theme.palette().primary),
+            );
+        })]
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Secret {
+    Loading,
+    Loaded(TotpSecret),
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MenuOpened,
+    SecretLoaded(usize, Result<TotpSecret, String>),
+    Tick,
+    Copy(usize),
+    ClearClipboard(secret::Secret),
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+pub struct Totp {
+    config: TotpModuleConfig,
+    secrets: Vec<Secret>,
+    /// the countdown rings of the accounts, redrawn every second
+    rings: Vec<Cache>,
+    now: u64,
+    copied: Option<usize>,
+}
+
+impl Totp {
+    pub fn new(config: TotpModuleConfig) -> Self {
+        Self {
+            config,
+            secrets: Vec::new(),
+            rings: Vec::new(),
+            now: now(),
+            copied: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::MenuOpened => {
+                // secrets are fetched again every time the popup is opened
+                self.secrets = vec![Secret::Loading; self.config.accounts.len()];
+                self.rings = self.config.accounts.iter().map(|_| Cache::new()).collect();
+                self.now = now();
+                self.copied = None;
+
+                Action::Command(Task::batch(self.config.accounts.iter().enumerate().map(
+                    |(index, account)| {
+                        let account = account.clone();
+
+                        Task::perform(
+                            async move {
+                                command_output(&account.secret_cmd)
+                                    .await
+                                    .ok_or_else(|| "Unable to read the secret".to_string())
+                                    .and_then(|output| TotpSecret::parse(&output, &account))
+                            },
+                            move |secret| Message::SecretLoaded(index, secret),
+                        )
+                    },
+                )))
+            }
+            Message::SecretLoaded(index, secret) => {
+                if let Some(entry) = self.secrets.get_mut(index) {
+                    *entry = match secret {
+                        Ok(secret) => Secret::Loaded(secret),
+                        Err(err) => {
+                            warn!("Failed to load TOTP secret {index}: {err}");
+
+                            Secret::Error(err)
+                        }
+                    };
+                }
+
+                Action::None
+            }
+            Message::Tick => {
+                self.now = now();
+                self.rings.iter().for_each(Cache::clear);
+
+                Action::None
+            }
+            Message::Copy(index) => {
+                let Some(Secret::Loaded(secret)) = self.secrets.get(index) else {
+                    return Action::None;
+                };
+
+                let code = secret.code(now());
+                let copy_cmd = self.config.copy_cmd.clone();
+                let clear_after = Duration::from_secs(self.config.clear_after);
+                self.copied = Some(index);
+
+                Action::Command(Task::perform(
+                    async move {
+                        pipe_to_command(&copy_cmd, &code).await;
+                        sleep(clear_after).await;
+
+                        secret::Secret::from(code)
+                    },
+                    Message::ClearClipboard,
+                ))
+            }
+            Message::ClearClipboard(code) => {
+                let paste_cmd = self.config.paste_cmd.clone();
+                let clear_cmd = self.config.clear_cmd.clone();
+
+                Action::Command(
+                    Task::perform(
+                        async move {
+                            // don't clear something copied after the code
+                            if command_output(&paste_cmd).await.as_deref().map(str::trim)
+                                == Some(code.expose())
+                            {
+                                debug!("Clearing TOTP code from the clipboard");
+                                execute_command(clear_cmd);
+                            }
+                        },
+                        |_| (),
+                    )
+                    .discard(),
+                )
+            }
+        }
+    }
+
+    pub fn view(&'_ self, _theme: &AshellTheme) -> Element<'_, Message> {
+        icon(StaticIcon::ShieldKey).into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        column!(
+            text("Two-factor codes").size(theme.font_size.lg),
+            horizontal_rule(1),
+            if self.config.accounts.is_empty() {
+                Into::<Element<'a, Message>>::into(text("No accounts configured"))
+            } else {
+                Column::with_children(self.config.accounts.iter().enumerate().map(
+                    |(index, account)| {
+                        let status: Element<'a, Message> =
+                            match (self.secrets.get(index), self.rings.get(index)) {
+                                (Some(Secret::Loaded(secret)), Some(ring)) => row!(
+                                    text(secret.code(self.now)).size(theme.font_size.lg),
+                                    canvas(CountdownRing(
+                                        secret.remaining(self.now) as f32 / secret.period as f32,
+                                        ring,
+                                    ))
+                                    .width(Length::Fixed(theme.space.md as f32))
+                                    .height(Length::Fixed(theme.space.md as f32)),
+                                )
+                                .align_y(Alignment::Center)
+                                .spacing(theme.space.xs)
+                                .into(),
+                                (Some(Secret::Error(err)), _) => container(text(err.clone()))
+                                    .style(|theme: &Theme| container::Style {
+                                        text_color: Some(theme.palette().danger),
+                                        ..Default::default()
+                                    })
+                                    .into(),
+                                _ => text("...").into(),
+                            };
+
+                        button(
+                            row!(
+                                column!(text(account.name.clone()))
+                                    .push_maybe(
+                                        (self.copied == Some(index))
+                                            .then(|| text("Copied").size(theme.font_size.xs))
+                                    )
+                                    .width(Length::Fill),
+                                status,
+                            )
+                            .align_y(Alignment::Center)
+                            .spacing(theme.space.xs),
+                        )
+                        .style(theme.ghost_button_style())
+                        .padding(theme.space.xs)
+                        .on_press_maybe(
+                            matches!(self.secrets.get(index), Some(Secret::Loaded(_)))
+                                .then_some(Message::Copy(index)),
+                        )
+                        .width(Length::Fill)
+                        .into()
+                    },
+                ))
+                .spacing(theme.space.xxs)
+                .into()
+            },
+        )
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self
+            .secrets
+            .iter()
+            .any(|secret| matches!(secret, Secret::Loaded(_)))
+        {
+            every(Duration::from_secs(1)).map(|_| Message::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+}
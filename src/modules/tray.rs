@@ -24,6 +24,7 @@ pub enum Message {
     ToggleSubmenu(i32),
     MenuSelected(String, i32),
     MenuOpened(String),
+    Activate(String),
 }
 
 pub enum Action {
@@ -85,6 +86,14 @@ impl TrayModule {
                 }
                 _ => Action::None,
             },
+            Message::Activate(name) => match self.service.as_mut() {
+                Some(service) => Action::TrayMenuCommand(
+                    service
+                        .command(TrayCommand::Activate(name))
+                        .map(|event| Message::Event(Box::new(event))),
+                ),
+                _ => Action::None,
+            },
             Message::MenuOpened(name) => {
                 if let Some(_tray) = self
                     .service
@@ -196,6 +205,13 @@ impl TrayModule {
                                     _ => icon(StaticIcon::Point).into(),
                                 })
                                 .on_press_with_position(move |button_ui_ref| {
+                                    if item.is_menu {
+                                        Message::ToggleMenu(item.name.to_owned(), id, button_ui_ref)
+                                    } else {
+                                        Message::Activate(item.name.to_owned())
+                                    }
+                                })
+                                .on_right_press_with_position(move |button_ui_ref| {
                                     Message::ToggleMenu(item.name.to_owned(), id, button_ui_ref)
                                 })
                                 .padding(theme.space.xxs)
@@ -0,0 +1,215 @@
+use crate::{
+    components::icons::{StaticIcon, icon},
+    services::{
+        ReadOnlyService, Service, ServiceEvent,
+        usbguard::{UsbDevice, UsbGuardCommand, UsbGuardService, UsbTarget},
+    },
+    theme::AshellTheme,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, Theme,
+    widget::{Column, button, column, container, horizontal_rule, row, text, toggler},
+};
+use log::{info, warn};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<UsbGuardService>),
+    Apply(u32, UsbTarget),
+    TogglePermanent(bool),
+    DismissPrompt,
+}
+
+pub enum Action {
+    None,
+    Command(Task<Message>),
+    /// a new device was blocked, its prompt is shown
+    Prompt,
+    ClosePrompt,
+}
+
+#[derive(Default)]
+pub struct UsbGuard {
+    service: Option<UsbGuardService>,
+    permanent: bool,
+    error: Option<String>,
+    /// the prompt is shown until the blocked devices are handled or it's dismissed
+    prompting: bool,
+}
+
+impl UsbGuard {
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Event(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.service = Some(service);
+                    }
+                    ServiceEvent::Update(data) => {
+                        let blocked = self.blocked().map(|device| device.id).collect::<Vec<_>>();
+
+                        if let Some(service) = self.service.as_mut() {
+                            service.update(data);
+                        }
+                        self.error = None;
+
+                        if self.blocked().any(|device| !blocked.contains(&device.id)) {
+                            info!("New USB device blocked");
+                            self.prompting = true;
+
+                            return Action::Prompt;
+                        }
+                        if self.prompting && self.blocked().next().is_none() {
+                            self.prompting = false;
+
+                            return Action::ClosePrompt;
+                        }
+                    }
+                    ServiceEvent::Error(err) => {
+                        warn!("Failed to apply usb device policy: {err}");
+                        self.error = Some(err);
+                    }
+                }
+
+                Action::None
+            }
+            Message::DismissPrompt => {
+                self.prompting = false;
+
+                Action::ClosePrompt
+            }
+            Message::Apply(id, target) => match self.service.as_mut() {
+                Some(service) => Action::Command(
+                    service
+                        .command(UsbGuardCommand::ApplyPolicy(id, target, self.permanent))
+                        .map(Message::Event),
+                ),
+                None => Action::None,
+            },
+            Message::TogglePermanent(permanent) => {
+                self.permanent = permanent;
+
+                Action::None
+            }
+        }
+    }
+
+    fn blocked(&self) -> impl Iterator<Item = &UsbDevice> {
+        self.service
+            .iter()
+            .flat_map(|service| service.devices.iter())
+            .filter(|device| device.target == UsbTarget::Block)
+    }
+
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        self.service.as_ref()?;
+
+        let blocked = self.blocked().count();
+
+        Some(
+            row!(icon(StaticIcon::Usb))
+                .push_maybe((blocked > 0).then(|| {
+                    container(text(blocked)).style(|theme: &Theme| container::Style {
+                        text_color: Some(theme.palette().danger),
+                        ..Default::default()
+                    })
+                }))
+                .align_y(Alignment::Center)
+                .spacing(theme.space.xxs)
+                .into(),
+        )
+    }
+
+    fn device_view<'a>(theme: &'a AshellTheme, device: &'a UsbDevice) -> Element<'a, Message> {
+        let action = |label, target| {
+            button(text(label))
+                .style(theme.outline_button_style())
+                .padding([theme.space.xxs, theme.space.sm])
+                .on_press(Message::Apply(device.id, target))
+        };
+
+        row!(
+            column!(
+                text(device.name.clone()),
+                text(device.vendor_product.clone()).size(theme.font_size.xs),
+            )
+            .width(Length::Fill),
+        )
+        .push_maybe((device.target != UsbTarget::Allow).then(|| action("Allow", UsbTarget::Allow)))
+        .push_maybe(match device.target {
+            UsbTarget::Block => Some(action("Reject", UsbTarget::Reject)),
+            UsbTarget::Allow => Some(action("Block", UsbTarget::Block)),
+            UsbTarget::Reject => None,
+        })
+        .align_y(Alignment::Center)
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn menu_view<'a>(&'a self, theme: &'a AshellTheme) -> Element<'a, Message> {
+        let blocked = self
+            .blocked()
+            .map(|device| Self::device_view(theme, device))
+            .collect::<Vec<_>>();
+        let allowed = self
+            .service
+            .iter()
+            .flat_map(|service| service.devices.iter())
+            .filter(|device| device.target == UsbTarget::Allow)
+            .map(|device| Self::device_view(theme, device))
+            .collect::<Vec<_>>();
+
+        column!(
+            text("USB devices").size(theme.font_size.lg),
+            horizontal_rule(1),
+            if blocked.is_empty() {
+                Into::<Element<'a, Message>>::into(text("No blocked devices"))
+            } else {
+                column!(
+                    text("Blocked").size(theme.font_size.sm),
+                    Column::with_children(blocked).spacing(theme.space.xs),
+                )
+                .spacing(theme.space.xs)
+                .into()
+            },
+        )
+        .push_maybe((!allowed.is_empty()).then(|| {
+            column!(
+                text("Allowed").size(theme.font_size.sm),
+                Column::with_children(allowed).spacing(theme.space.xs),
+            )
+            .spacing(theme.space.xs)
+        }))
+        .push(horizontal_rule(1))
+        .push(
+            row!(
+                toggler(self.permanent)
+                    .label("Remember decision")
+                    .on_toggle(Message::TogglePermanent)
+                    .width(Length::Fill),
+            )
+            .push_maybe(self.prompting.then(|| {
+                button(text("Later"))
+                    .style(theme.outline_button_style())
+                    .padding([theme.space.xxs, theme.space.sm])
+                    .on_press(Message::DismissPrompt)
+            }))
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs),
+        )
+        .push_maybe(self.error.as_ref().map(|err| {
+            container(text(err.clone()).size(theme.font_size.xs)).style(|theme: &Theme| {
+                container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                }
+            })
+        }))
+        .spacing(theme.space.xs)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        UsbGuardService::subscribe().map(Message::Event)
+    }
+}
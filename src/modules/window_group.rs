@@ -0,0 +1,190 @@
+use crate::{theme::AshellTheme, utils::backoff::Backoff};
+use hyprland::{
+    data::Client,
+    dispatch::{Dispatch, DispatchType},
+    event_listener::AsyncEventListener,
+    shared::HyprDataActiveOptional,
+};
+use iced::{
+    Element, Subscription,
+    futures::SinkExt,
+    mouse::ScrollDelta,
+    stream::channel,
+    widget::{mouse_area, text},
+};
+use log::{debug, error, warn};
+use std::any::TypeId;
+use tokio::time::sleep;
+
+/// Tabs of the group of the focused window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Group {
+    /// position of the focused window among the tabs
+    pub index: usize,
+    pub count: usize,
+}
+
+fn get_group() -> Option<Group> {
+    let client = Client::get_active().ok().flatten()?;
+
+    // a window alone is not grouped
+    if client.grouped.len() < 2 {
+        return None;
+    }
+
+    client
+        .grouped
+        .iter()
+        .position(|address| **address == client.address)
+        .map(|index| Group {
+            index,
+            count: client.grouped.len(),
+        })
+}
+
+fn dispatch(command: &'static str, args: &str) {
+    if let Err(e) = Dispatch::call(DispatchType::Custom(command, args)) {
+        error!("failed to dispatch {command}: {e:?}");
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    GroupChanged(Option<Group>),
+    NextTab,
+    PreviousTab,
+    Ungroup,
+}
+
+pub struct WindowGroup {
+    group: Option<Group>,
+}
+
+impl Default for WindowGroup {
+    fn default() -> Self {
+        Self { group: get_group() }
+    }
+}
+
+impl WindowGroup {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::GroupChanged(group) => {
+                self.group = group;
+            }
+            Message::NextTab => dispatch("changegroupactive", "f"),
+            Message::PreviousTab => dispatch("changegroupactive", "b"),
+            // the focused window leaves the group, the other tabs stay together
+            Message::Ungroup => dispatch("moveoutofgroup", ""),
+        }
+    }
+
+    pub fn view(&'_ self, _: &AshellTheme) -> Option<Element<'_, Message>> {
+        self.group.map(|group| {
+            mouse_area(text(format!("{}/{}", group.index + 1, group.count)))
+                .on_right_press(Message::Ungroup)
+                .on_scroll(|direction| {
+                    let delta = match direction {
+                        ScrollDelta::Lines { y, .. } => y,
+                        ScrollDelta::Pixels { y, .. } => y,
+                    };
+
+                    if delta < 0.0 {
+                        Message::NextTab
+                    } else {
+                        Message::PreviousTab
+                    }
+                })
+                .into()
+        })
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |output| {
+                let mut backoff = Backoff::new();
+                loop {
+                    let mut event_listener = AsyncEventListener::new();
+
+                    // the group is read again on the events changing the focused window or its group
+                    event_listener.add_active_window_changed_handler({
+                        let output = output.clone();
+                        move |e| {
+                            debug!("active window changed: {e:?}");
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                if let Err(e) =
+                                    output.send(Message::GroupChanged(get_group())).await
+                                {
+                                    error!("failed to send the window group change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    event_listener.add_group_toggled_handler({
+                        let output = output.clone();
+                        move |e| {
+                            debug!("group toggled: {e:?}");
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                if let Err(e) =
+                                    output.send(Message::GroupChanged(get_group())).await
+                                {
+                                    error!("failed to send the window group change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    event_listener.add_window_moved_into_group_handler({
+                        let output = output.clone();
+                        move |e| {
+                            debug!("window moved into group: {e:?}");
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                if let Err(e) =
+                                    output.send(Message::GroupChanged(get_group())).await
+                                {
+                                    error!("failed to send the window group change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    event_listener.add_window_moved_out_of_group_handler({
+                        let output = output.clone();
+                        move |e| {
+                            debug!("window moved out of group: {e:?}");
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                if let Err(e) =
+                                    output.send(Message::GroupChanged(get_group())).await
+                                {
+                                    error!("failed to send the window group change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    backoff.start();
+                    let res = event_listener.start_listener_async().await;
+
+                    let delay = backoff.fail();
+                    match res {
+                        Err(e) => {
+                            error!(
+                                "restarting window group listener in {delay:?} due to error: {e:?}"
+                            )
+                        }
+                        Ok(()) => warn!("window group listener stopped, restarting in {delay:?}"),
+                    }
+                    sleep(delay).await;
+                }
+            }),
+        )
+    }
+}
@@ -0,0 +1,250 @@
+use crate::theme::AshellTheme;
+use hyprland::{ctl, data::Client, shared::HyprDataActiveOptional};
+use iced::{
+    Alignment, Element, Length, Theme,
+    widget::{button, column, container, horizontal_rule, pick_list, row, slider, text, toggler},
+};
+use log::{debug, error};
+use std::{fs, path::PathBuf};
+
+/// The include file of the rules applied from the bar, to `source` in the Hyprland configuration
+pub const RULES_FILE_PATH: &str = "~/.config/hypr/ashell-rules.conf";
+
+const HEADER: &str =
+    "# Window rules managed by ashell, the rules of a class are rewritten from the bar";
+
+/// The workspaces offered in the popup
+const MAX_WORKSPACE: i32 = 10;
+
+fn rules_path() -> Option<PathBuf> {
+    shellexpand::full(RULES_FILE_PATH)
+        .ok()
+        .map(|path| PathBuf::from(path.as_ref()))
+}
+
+/// The rules of a window class
+#[derive(Debug, Clone, PartialEq)]
+struct Rules {
+    float: bool,
+    /// opacity in percent
+    opacity: u8,
+    workspace: Option<i32>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            float: false,
+            opacity: 100,
+            workspace: None,
+        }
+    }
+}
+
+impl Rules {
+    /// The rules of the class in the content of the include file
+    fn parse(content: &str, class: &str) -> Self {
+        let matcher = class_matcher(class);
+        let mut rules = Rules::default();
+
+        for rule in content
+            .lines()
+            .filter_map(|line| line.strip_prefix("windowrulev2 = "))
+            .filter_map(|line| line.strip_suffix(&matcher))
+        {
+            let mut words = rule.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("float"), _) => rules.float = true,
+                (Some("opacity"), Some(value)) => {
+                    if let Ok(value) = value.parse::<f32>() {
+                        rules.opacity = (value * 100.).round().clamp(0., 100.) as u8;
+                    }
+                }
+                (Some("workspace"), Some(value)) => rules.workspace = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        rules
+    }
+
+    fn lines(&self, class: &str) -> Vec<String> {
+        let matcher = class_matcher(class);
+        let mut lines = Vec::new();
+
+        if self.float {
+            lines.push(format!("windowrulev2 = float{matcher}"));
+        }
+        if self.opacity < 100 {
+            lines.push(format!(
+                "windowrulev2 = opacity {:.2}{matcher}",
+                f32::from(self.opacity) / 100.
+            ));
+        }
+        if let Some(workspace) = self.workspace {
+            lines.push(format!("windowrulev2 = workspace {workspace}{matcher}"));
+        }
+
+        lines
+    }
+}
+
+/// The end of the rules matching exactly the class
+fn class_matcher(class: &str) -> String {
+    format!(", class:^({})$", regex::escape(class))
+}
+
+/// Replaces the rules of the class in the include file and reloads Hyprland
+fn write_rules(class: &str, rules: &Rules) -> anyhow::Result<()> {
+    let path = rules_path().ok_or_else(|| anyhow::anyhow!("invalid rules file path"))?;
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let matcher = class_matcher(class);
+
+    let mut lines = content
+        .lines()
+        .filter(|line| *line != HEADER && !line.ends_with(&matcher))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    lines.insert(0, HEADER.to_string());
+    lines.extend(rules.lines(class));
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, lines.join("\n") + "\n")?;
+    debug!("window rules of {class} written to {}", path.display());
+
+    ctl::reload::call()?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MenuOpened,
+    ToggleFloat(bool),
+    OpacityChanged(u8),
+    WorkspaceSelected(String),
+    Apply,
+}
+
+pub enum Action {
+    None,
+    CloseMenu,
+}
+
+#[derive(Default)]
+pub struct WindowRules {
+    /// class of the focused window when the popup was opened
+    class: Option<String>,
+    rules: Rules,
+    error: Option<String>,
+}
+
+impl WindowRules {
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::MenuOpened => {
+                self.error = None;
+                self.class = Client::get_active()
+                    .ok()
+                    .flatten()
+                    .map(|client| client.class)
+                    .filter(|class| !class.is_empty());
+                self.rules = match (&self.class, rules_path()) {
+                    (Some(class), Some(path)) => {
+                        Rules::parse(&fs::read_to_string(path).unwrap_or_default(), class)
+                    }
+                    _ => Rules::default(),
+                };
+
+                Action::None
+            }
+            Message::ToggleFloat(float) => {
+                self.rules.float = float;
+
+                Action::None
+            }
+            Message::OpacityChanged(opacity) => {
+                self.rules.opacity = opacity;
+
+                Action::None
+            }
+            Message::WorkspaceSelected(workspace) => {
+                self.rules.workspace = workspace.parse().ok();
+
+                Action::None
+            }
+            Message::Apply => {
+                let Some(class) = &self.class else {
+                    return Action::None;
+                };
+
+                match write_rules(class, &self.rules) {
+                    Ok(()) => Action::CloseMenu,
+                    Err(e) => {
+                        error!("failed to apply the window rules of {class}: {e}");
+                        self.error = Some(e.to_string());
+
+                        Action::None
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn menu_view(&'_ self, theme: &AshellTheme) -> Element<'_, Message> {
+        let Some(class) = &self.class else {
+            return text("No focused window").into();
+        };
+
+        let workspaces = std::iter::once("Any".to_string())
+            .chain((1..=MAX_WORKSPACE).map(|id| id.to_string()))
+            .collect::<Vec<_>>();
+        let workspace = self
+            .rules
+            .workspace
+            .map_or_else(|| "Any".to_string(), |id| id.to_string());
+
+        column!(
+            text(format!("Rules of {class}")).size(theme.font_size.lg),
+            horizontal_rule(1),
+            toggler(self.rules.float)
+                .label("Always float")
+                .on_toggle(Message::ToggleFloat)
+                .width(Length::Fill),
+            row!(
+                text("Opacity").width(Length::Fill),
+                slider(20..=100, self.rules.opacity, Message::OpacityChanged).width(120),
+                text(format!("{}%", self.rules.opacity)).width(40),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs),
+            row!(
+                text("Workspace").width(Length::Fill),
+                pick_list(workspaces, Some(workspace), Message::WorkspaceSelected),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs),
+        )
+        .push_maybe(self.error.as_ref().map(|err| {
+            container(text(err.clone()).size(theme.font_size.xs)).style(|theme: &Theme| {
+                container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                }
+            })
+        }))
+        .push(
+            container(
+                button(text("Apply"))
+                    .padding([theme.space.xxs, theme.space.md])
+                    .style(theme.confirm_button_style())
+                    .on_press(Message::Apply),
+            )
+            .align_right(Length::Fill),
+        )
+        .spacing(theme.space.sm)
+        .into()
+    }
+}
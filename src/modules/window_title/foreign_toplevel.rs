@@ -0,0 +1,328 @@
+use iced::{
+    Subscription,
+    futures::{SinkExt, future::pending},
+    stream::channel,
+};
+use log::{debug, error, warn};
+use std::{any::TypeId, sync::LazyLock, thread};
+use tokio::sync::watch;
+use wayland_client::{
+    Connection, Dispatch, Proxy, QueueHandle, event_created_child,
+    protocol::{
+        wl_registry::{self, WlRegistry},
+        wl_seat::WlSeat,
+    },
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+use super::{Message, WindowInfo, WindowManager};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Toplevel {
+    handle: ZwlrForeignToplevelHandleV1,
+    title: String,
+    app_id: String,
+    activated: bool,
+}
+
+impl Toplevel {
+    fn new(handle: ZwlrForeignToplevelHandleV1) -> Self {
+        Self {
+            handle,
+            title: String::new(),
+            app_id: String::new(),
+            activated: false,
+        }
+    }
+
+    fn id(&self) -> String {
+        self.handle.id().protocol_id().to_string()
+    }
+
+    fn info(&self) -> WindowInfo {
+        WindowInfo {
+            id: self.id(),
+            title: self.title.clone(),
+            class: self.app_id.clone(),
+            focused: self.activated,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ForeignToplevelState {
+    manager: Option<ZwlrForeignToplevelManagerV1>,
+    seat: Option<WlSeat>,
+    /// in the order they were opened
+    toplevels: Vec<Toplevel>,
+    tx: Option<watch::Sender<Vec<Toplevel>>>,
+}
+
+impl ForeignToplevelState {
+    /// Publishes the toplevels once the compositor sent an atomic set of changes
+    fn publish(&self) {
+        if let Some(tx) = &self.tx {
+            tx.send_if_modified(|current| {
+                let changed = *current != self.toplevels;
+                if changed {
+                    current.clone_from(&self.toplevels);
+                }
+
+                changed
+            });
+        }
+    }
+}
+
+struct ForeignToplevelClient {
+    connection: Connection,
+    seat: Option<WlSeat>,
+    toplevels: watch::Receiver<Vec<Toplevel>>,
+}
+
+impl ForeignToplevelClient {
+    fn find(&self, id: &str) -> Result<Toplevel, Box<dyn std::error::Error>> {
+        self.toplevels
+            .borrow()
+            .iter()
+            .find(|t| t.id() == id)
+            .cloned()
+            .ok_or_else(|| format!("unknown toplevel {id}").into())
+    }
+}
+
+impl ForeignToplevelClient {
+    fn connect() -> anyhow::Result<Self> {
+        let connection = Connection::connect_to_env()?;
+        let mut event_queue = connection.new_event_queue();
+        let handle = event_queue.handle();
+        connection.display().get_registry(&handle, ());
+
+        let mut state = ForeignToplevelState::default();
+        // the first roundtrip binds the globals, the second one receives the toplevels
+        event_queue.roundtrip(&mut state)?;
+        event_queue.roundtrip(&mut state)?;
+
+        if state.manager.is_none() {
+            anyhow::bail!("the compositor doesn't support wlr-foreign-toplevel-management");
+        }
+
+        let (tx, toplevels) = watch::channel(state.toplevels.clone());
+        state.tx = Some(tx);
+        let seat = state.seat.clone();
+
+        thread::spawn(move || {
+            loop {
+                if let Err(e) = event_queue.blocking_dispatch(&mut state) {
+                    error!("foreign toplevel event queue error: {e}");
+
+                    break;
+                }
+            }
+        });
+
+        // the connection must outlive the event queue, it's also used to
+        // flush the requests
+        Ok(Self {
+            connection,
+            seat,
+            toplevels,
+        })
+    }
+}
+
+static CLIENT: LazyLock<Option<ForeignToplevelClient>> = LazyLock::new(|| {
+    ForeignToplevelClient::connect()
+        .inspect_err(|e| error!("failed to connect to the foreign toplevel manager: {e}"))
+        .ok()
+});
+
+pub struct ForeignToplevelWindowManager;
+
+impl WindowManager for ForeignToplevelWindowManager {
+    fn get_window() -> Option<WindowInfo> {
+        CLIENT
+            .as_ref()?
+            .toplevels
+            .borrow()
+            .iter()
+            .find(|t| t.activated)
+            .map(Toplevel::info)
+    }
+
+    // the protocol doesn't report the workspaces, all the toplevels are listed
+    fn get_windows() -> Vec<WindowInfo> {
+        CLIENT
+            .as_ref()
+            .map(|client| {
+                client
+                    .toplevels
+                    .borrow()
+                    .iter()
+                    .map(Toplevel::info)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn focus_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("focusing window: {id}");
+
+        let client = CLIENT
+            .as_ref()
+            .ok_or("foreign toplevel manager not available")?;
+        let seat = client
+            .seat
+            .as_ref()
+            .ok_or("no seat to activate the window")?;
+
+        client.find(id)?.handle.activate(seat);
+        client.connection.flush()?;
+
+        Ok(())
+    }
+
+    fn close_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("closing window: {id}");
+
+        let client = CLIENT
+            .as_ref()
+            .ok_or("foreign toplevel manager not available")?;
+
+        client.find(id)?.handle.close();
+        client.connection.flush()?;
+
+        Ok(())
+    }
+
+    fn create_subscription(instance: &str) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                if let Some(client) = CLIENT.as_ref() {
+                    let mut toplevels = client.toplevels.clone();
+
+                    while toplevels.changed().await.is_ok() {
+                        debug!("toplevels changed");
+                        let _ = output.send(Message::TitleChanged).await;
+                    }
+
+                    warn!("foreign toplevel event queue stopped");
+                }
+
+                pending::<()>().await;
+            }),
+        )
+    }
+}
+
+impl Dispatch<WlRegistry, ()> for ForeignToplevelState {
+    fn event(
+        state: &mut Self,
+        proxy: &WlRegistry,
+        event: <WlRegistry as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        handle: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            if interface == ZwlrForeignToplevelManagerV1::interface().name
+                && state.manager.is_none()
+            {
+                debug!("binding foreign toplevel manager with version {version}");
+                state.manager = Some(proxy.bind(name, version.min(3), handle, ()));
+            } else if interface == WlSeat::interface().name && state.seat.is_none() {
+                // the toplevels are activated on the first seat
+                state.seat = Some(proxy.bind(name, version.min(7), handle, ()));
+            }
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for ForeignToplevelState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSeat,
+        _event: <WlSeat as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for ForeignToplevelState {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwlrForeignToplevelManagerV1,
+        event: <ZwlrForeignToplevelManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } => {
+                state.toplevels.push(Toplevel::new(toplevel));
+            }
+            zwlr_foreign_toplevel_manager_v1::Event::Finished => {
+                warn!("the compositor stopped sending the toplevels");
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(ForeignToplevelState, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for ForeignToplevelState {
+    fn event(
+        state: &mut Self,
+        proxy: &ZwlrForeignToplevelHandleV1,
+        event: <ZwlrForeignToplevelHandleV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                state.toplevels.retain(|t| t.handle != *proxy);
+                proxy.destroy();
+                state.publish();
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Done => state.publish(),
+            event => {
+                let Some(toplevel) = state.toplevels.iter_mut().find(|t| t.handle == *proxy) else {
+                    return;
+                };
+
+                match event {
+                    zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
+                        toplevel.title = title;
+                    }
+                    zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                        toplevel.app_id = app_id;
+                    }
+                    zwlr_foreign_toplevel_handle_v1::Event::State { state } => {
+                        toplevel.activated = state
+                            .chunks_exact(4)
+                            .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                            .any(|s| s == zwlr_foreign_toplevel_handle_v1::State::Activated as u32);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -1,55 +1,91 @@
-use crate::{
-    config::{WindowTitleConfig, WindowTitleMode},
-    utils::truncate_text,
-};
-use hyprland::{data::Client, event_listener::AsyncEventListener, shared::HyprDataActiveOptional};
-use iced::{Subscription, stream::channel};
-use log::{debug, error};
-use std::{
-    any::TypeId,
-    sync::{Arc, RwLock},
+use hyprland::{
+    data::{Client, Clients, Workspace},
+    dispatch::{Dispatch, DispatchType, WindowIdentifier},
+    event_listener::AsyncEventListener,
+    shared::{Address, HyprData, HyprDataActive, HyprDataActiveOptional},
 };
+use iced::{Subscription, futures::SinkExt, stream::channel};
+use log::{debug, error, warn};
+use std::any::TypeId;
+use tokio::time::sleep;
 
-use super::{Message, WindowManager};
+use super::{Message, WindowInfo, WindowManager};
+use crate::utils::backoff::Backoff;
 
 pub struct HyprlandWindowManager;
 
 impl WindowManager for HyprlandWindowManager {
-    fn get_window(config: &WindowTitleConfig) -> Option<String> {
-        Client::get_active().ok().and_then(|w| {
-            w.map(|w| match config.mode {
-                WindowTitleMode::Title => w.title,
-                WindowTitleMode::Class => w.class,
+    fn get_window() -> Option<WindowInfo> {
+        Client::get_active().ok().flatten().map(|w| WindowInfo {
+            id: w.address.to_string(),
+            title: w.title,
+            class: w.class,
+            focused: true,
+        })
+    }
+
+    fn get_windows() -> Vec<WindowInfo> {
+        let Ok(workspace) = Workspace::get_active() else {
+            return Vec::new();
+        };
+        let focused = Client::get_active().ok().flatten().map(|c| c.address);
+
+        Clients::get()
+            .map(|clients| {
+                clients
+                    .into_iter()
+                    .filter(|c| c.mapped && c.workspace.id == workspace.id)
+                    .map(|c| WindowInfo {
+                        focused: focused.as_ref() == Some(&c.address),
+                        id: c.address.to_string(),
+                        title: c.title,
+                        class: c.class,
+                    })
+                    .collect()
             })
-            .map(|v| {
-                if config.truncate_title_after_length > 0 {
-                    truncate_text(&v, config.truncate_title_after_length)
-                } else {
-                    v
-                }
+            .unwrap_or_else(|e| {
+                error!("failed to get hyprland clients: {e}");
+
+                Vec::new()
             })
-        })
     }
 
-    fn create_subscription() -> Subscription<Message> {
-        let id = TypeId::of::<Self>();
+    fn focus_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("focusing window: {id}");
+
+        Dispatch::call(DispatchType::FocusWindow(WindowIdentifier::Address(
+            Address::new(id),
+        )))
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
+
+    fn close_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("closing window: {id}");
+
+        Dispatch::call(DispatchType::CloseWindow(WindowIdentifier::Address(
+            Address::new(id),
+        )))
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
+
+    fn create_subscription(instance: &str) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
 
         Subscription::run_with_id(
             id,
             channel(10, async |output| {
-                let output = Arc::new(RwLock::new(output));
+                let mut backoff = Backoff::new();
                 loop {
                     let mut event_listener = AsyncEventListener::new();
 
                     event_listener.add_workspace_changed_handler({
                         let output = output.clone();
                         move |_| {
-                            let output = output.clone();
+                            let mut output = output.clone();
                             Box::pin(async move {
                                 debug!("Window closed");
-                                if let Ok(mut output) = output.write() {
-                                    debug!("Sending title changed message");
-                                    output.try_send(Message::TitleChanged).unwrap();
+                                if let Err(e) = output.send(Message::TitleChanged).await {
+                                    error!("failed to send the title change: {e}");
                                 }
                             })
                         }
@@ -58,12 +94,38 @@ impl WindowManager for HyprlandWindowManager {
                     event_listener.add_active_window_changed_handler({
                         let output = output.clone();
                         move |e| {
-                            let output = output.clone();
+                            let mut output = output.clone();
                             Box::pin(async move {
                                 debug!("Active window changed: {e:?}");
-                                if let Ok(mut output) = output.write() {
-                                    debug!("Sending title changed message");
-                                    output.try_send(Message::TitleChanged).unwrap();
+                                if let Err(e) = output.send(Message::TitleChanged).await {
+                                    error!("failed to send the title change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    // the taskbar lists the windows of the active workspace
+                    event_listener.add_window_opened_handler({
+                        let output = output.clone();
+                        move |_| {
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                debug!("Window opened");
+                                if let Err(e) = output.send(Message::TitleChanged).await {
+                                    error!("failed to send the title change: {e}");
+                                }
+                            })
+                        }
+                    });
+
+                    event_listener.add_window_moved_handler({
+                        let output = output.clone();
+                        move |_| {
+                            let mut output = output.clone();
+                            Box::pin(async move {
+                                debug!("Window moved");
+                                if let Err(e) = output.send(Message::TitleChanged).await {
+                                    error!("failed to send the title change: {e}");
                                 }
                             })
                         }
@@ -72,12 +134,11 @@ impl WindowManager for HyprlandWindowManager {
                     event_listener.add_window_closed_handler({
                         let output = output.clone();
                         move |_| {
-                            let output = output.clone();
+                            let mut output = output.clone();
                             Box::pin(async move {
                                 debug!("Window closed");
-                                if let Ok(mut output) = output.write() {
-                                    debug!("Sending title changed message");
-                                    output.try_send(Message::TitleChanged).unwrap();
+                                if let Err(e) = output.send(Message::TitleChanged).await {
+                                    error!("failed to send the title change: {e}");
                                 }
                             })
                         }
@@ -85,11 +146,17 @@ impl WindowManager for HyprlandWindowManager {
 
                     debug!("Starting title listener");
 
+                    backoff.start();
                     let res = event_listener.start_listener_async().await;
 
-                    if let Err(e) = res {
-                        error!("restarting active window listener due to error: {e:?}");
+                    let delay = backoff.fail();
+                    match res {
+                        Err(e) => error!(
+                            "restarting active window listener in {delay:?} due to error: {e:?}"
+                        ),
+                        Ok(()) => warn!("active window listener stopped, restarting in {delay:?}"),
                     }
+                    sleep(delay).await;
                 }
             }),
         )
@@ -1,7 +1,22 @@
-use crate::{config::WindowTitleConfig, theme::AshellTheme};
+use crate::{
+    config::{WindowTitleConfig, WindowTitleMode},
+    theme::AshellTheme,
+    utils::{
+        app_icon::{AppIcon, app_icon},
+        truncate_text,
+        watchdog::{self, Watchdog},
+    },
+};
 use iced::{
-    Element, Subscription,
-    widget::{container, text},
+    Alignment, Element, Subscription, mouse,
+    time::every,
+    widget::{MouseArea, Row, button, container, mouse_area, row, text},
+};
+use log::error;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    time::{Duration, Instant},
 };
 
 #[cfg(feature = "hyprland")]
@@ -16,56 +31,289 @@ pub mod niri;
 #[cfg(feature = "niri")]
 pub use niri::NiriWindowManager;
 
+#[cfg(feature = "sway")]
+pub mod sway;
+
+#[cfg(feature = "sway")]
+pub use sway::SwayWindowManager;
+
+#[cfg(any(feature = "wlroots", feature = "river", feature = "kwin"))]
+pub mod foreign_toplevel;
+
+#[cfg(any(feature = "wlroots", feature = "river", feature = "kwin"))]
+pub use foreign_toplevel::ForeignToplevelWindowManager;
+
 #[derive(Debug, Clone)]
 pub enum Message {
     TitleChanged,
+    /// the change held back by the minimum update interval is applied
+    FlushPending,
+    FocusWindow(String),
+    CloseWindow(String),
+    /// the window is dragged, it can be dropped on a workspace
+    DragStarted(String),
+    /// the press on the window is released on the window itself
+    Released(String),
+    /// compares the window with the backend, the listener is restarted when it missed changes
+    WatchdogCheck,
+}
+
+pub enum Action {
+    None,
+    DragStarted(String),
+    Released(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    /// compositor specific identifier used to focus and close the window
+    pub id: String,
+    pub title: String,
+    pub class: String,
+    pub focused: bool,
 }
 
 pub trait WindowManager {
-    fn get_window(config: &WindowTitleConfig) -> Option<String>;
-    fn create_subscription() -> Subscription<Message>;
+    fn get_window() -> Option<WindowInfo>;
+    /// Windows of the active workspace
+    fn get_windows() -> Vec<WindowInfo>;
+    fn focus_window(id: &str) -> Result<(), Box<dyn std::error::Error>>;
+    fn close_window(id: &str) -> Result<(), Box<dyn std::error::Error>>;
+    /// The instance changes with the restarts of the watchdog
+    fn create_subscription(instance: &str) -> Subscription<Message>;
 }
 
 pub struct WindowTitle<WM: WindowManager> {
     config: WindowTitleConfig,
-    value: Option<String>,
+    value: Option<WindowInfo>,
+    windows: Vec<WindowInfo>,
+    /// icons of the window classes, resolved only when shown
+    icons: HashMap<String, Option<AppIcon>>,
+    last_refresh: Instant,
+    /// a change came in before the end of the minimum update interval
+    pending: bool,
+    watchdog: Watchdog,
     _phantom: std::marker::PhantomData<WM>,
 }
 
 impl<WM: WindowManager> WindowTitle<WM> {
     pub fn new(config: WindowTitleConfig) -> Self {
-        let init = WM::get_window(&config);
-
-        Self {
-            value: init,
+        let mut window_title = Self {
+            value: None,
+            windows: Vec::new(),
+            icons: HashMap::new(),
             config,
+            last_refresh: Instant::now(),
+            pending: false,
+            watchdog: Watchdog::new("window title"),
             _phantom: std::marker::PhantomData,
+        };
+        window_title.refresh();
+
+        window_title
+    }
+
+    fn min_update_interval(&self) -> Duration {
+        Duration::from_millis(self.config.min_update_interval)
+    }
+
+    fn blacklisted(&self, window: &WindowInfo) -> bool {
+        self.config
+            .blacklist
+            .iter()
+            .any(|regex| regex.is_match(&window.title) || regex.is_match(&window.class))
+    }
+
+    fn refresh(&mut self) {
+        self.last_refresh = Instant::now();
+        self.pending = false;
+        self.value = WM::get_window().filter(|window| !self.blacklisted(window));
+
+        if self.config.taskbar {
+            self.windows = WM::get_windows()
+                .into_iter()
+                .filter(|window| !self.blacklisted(window))
+                .collect();
         }
+
+        if self.config.show_icon {
+            for window in self.value.iter().chain(self.windows.iter()) {
+                if !self.icons.contains_key(&window.class) {
+                    self.icons
+                        .insert(window.class.clone(), app_icon(&window.class));
+                }
+            }
+        }
+    }
+
+    /// Listener restarts by the watchdog
+    pub fn restarts(&self) -> u32 {
+        self.watchdog.restarts()
+    }
+
+    /// The right click on the title opens the window rules popup
+    #[cfg(feature = "window-rules")]
+    pub fn window_rules(&self) -> bool {
+        self.config.window_rules
     }
 
-    pub fn update(&mut self, message: Message) {
+    pub fn update(&mut self, message: Message) -> Action {
         match message {
             Message::TitleChanged => {
-                self.value = WM::get_window(&self.config);
+                self.watchdog.feed();
+                // the applications updating their title on every frame would relayout the bar
+                if self.last_refresh.elapsed() < self.min_update_interval() {
+                    self.pending = true;
+                } else {
+                    self.refresh();
+                }
+
+                Action::None
+            }
+            Message::FlushPending => {
+                if self.pending {
+                    self.refresh();
+                }
+
+                Action::None
+            }
+            Message::FocusWindow(id) => {
+                if let Err(e) = WM::focus_window(&id) {
+                    error!("failed to focus window {id}: {e:?}");
+                }
+
+                Action::None
+            }
+            Message::CloseWindow(id) => {
+                if let Err(e) = WM::close_window(&id) {
+                    error!("failed to close window {id}: {e:?}");
+                }
+
+                Action::None
             }
+            Message::DragStarted(id) => Action::DragStarted(id),
+            Message::Released(id) => Action::Released(id),
+            Message::WatchdogCheck => {
+                // without a focused window the backend can't be told from a dead one
+                let window = WM::get_window().filter(|window| !self.blacklisted(window));
+                let changed = window.is_some() && window != self.value;
+
+                if self.watchdog.check(changed) {
+                    self.refresh();
+                }
+
+                Action::None
+            }
+        }
+    }
+
+    /// Makes the window draggable on the workspaces
+    fn draggable<'a>(
+        window: &WindowInfo,
+        content: impl Into<Element<'a, Message>>,
+    ) -> MouseArea<'a, Message> {
+        mouse_area(content)
+            .on_press(Message::DragStarted(window.id.clone()))
+            .on_release(Message::Released(window.id.clone()))
+            .interaction(mouse::Interaction::Grab)
+    }
+
+    fn label<'a>(&self, window: &'a WindowInfo) -> Cow<'a, str> {
+        let value = match self.config.mode {
+            WindowTitleMode::Title => &window.title,
+            WindowTitleMode::Class => &window.class,
+        };
+
+        if self.config.truncate_title_after_length > 0 {
+            truncate_text(value, self.config.truncate_title_after_length)
+        } else {
+            Cow::Borrowed(value)
         }
     }
 
-    pub fn get_value(&self) -> Option<String> {
-        self.value.clone()
+    fn icon(&'_ self, window: &WindowInfo) -> Option<Element<'_, Message>> {
+        if !self.config.show_icon {
+            return None;
+        }
+
+        self.icons
+            .get(&window.class)
+            .and_then(Option::as_ref)
+            .map(|icon| icon.view(self.config.icon_size))
+    }
+
+    /// Icon and label of the window
+    fn window_view<'a>(
+        &'a self,
+        theme: &AshellTheme,
+        window: &'a WindowInfo,
+    ) -> Element<'a, Message> {
+        row!()
+            .push_maybe(self.icon(window))
+            .push(
+                text(self.label(window))
+                    .size(theme.font_size.sm)
+                    .wrapping(text::Wrapping::None),
+            )
+            .align_y(Alignment::Center)
+            .spacing(theme.space.xs)
+            .into()
     }
 
-    pub fn view(&'_ self, theme: &AshellTheme, title: String) -> Element<'_, Message> {
-        container(
-            text(title.to_string())
-                .size(theme.font_size.sm)
-                .wrapping(text::Wrapping::None),
+    pub fn view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        if self.config.taskbar {
+            return self.taskbar_view(theme);
+        }
+
+        self.value
+            .as_ref()
+            .filter(|window| !self.label(window).is_empty())
+            .map(|window| {
+                Self::draggable(
+                    window,
+                    container(self.window_view(theme, window)).clip(true),
+                )
+                .into()
+            })
+    }
+
+    fn taskbar_view(&'_ self, theme: &AshellTheme) -> Option<Element<'_, Message>> {
+        if self.windows.is_empty() {
+            return None;
+        }
+
+        Some(
+            Row::with_children(
+                self.windows
+                    .iter()
+                    .map(|window| {
+                        // the inner mouse area captures the clicks, they're handled by the app
+                        // to tell the clicks from the drags, the button only shows the state
+                        mouse_area(
+                            button(Self::draggable(window, self.window_view(theme, window)))
+                                .padding([theme.space.xxs, theme.space.xs])
+                                .style(theme.taskbar_button_style(window.focused))
+                                .on_press(Message::FocusWindow(window.id.clone())),
+                        )
+                        .on_middle_press(Message::CloseWindow(window.id.clone()))
+                        .into()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .spacing(theme.space.xxs)
+            .into(),
         )
-        .clip(true)
-        .into()
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        WM::create_subscription()
+        Subscription::batch(vec![
+            WM::create_subscription(&self.watchdog.instance("WindowTitle")),
+            every(watchdog::CHECK_INTERVAL).map(|_| Message::WatchdogCheck),
+            if self.pending {
+                every(self.min_update_interval()).map(|_| Message::FlushPending)
+            } else {
+                Subscription::none()
+            },
+        ])
     }
 }
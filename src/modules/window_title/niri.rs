@@ -1,34 +1,154 @@
-use crate::config::WindowTitleConfig;
-use iced::{Subscription, stream::channel};
-use std::future::pending;
-use std::{
-    any::TypeId,
-    sync::{Arc, RwLock},
-};
-use tokio::task;
+use iced::{Subscription, futures::SinkExt, stream::channel};
+use itertools::Itertools;
+use log::{debug, error};
+use niri_ipc::{Action, Event, Request, Response, Window, socket::Socket};
+use std::{any::TypeId, io, time::Duration};
+use tokio::{sync::mpsc, task, time::sleep};
 
-use super::{Message, WindowManager};
+use super::{Message, WindowInfo, WindowManager};
+
+fn request(request: Request) -> io::Result<Response> {
+    Socket::connect()?.send(request)?.map_err(io::Error::other)
+}
+
+fn focused_window() -> io::Result<Option<Window>> {
+    let Response::FocusedWindow(window) = request(Request::FocusedWindow)? else {
+        return Err(io::Error::other(
+            "unexpected response to the focused window request",
+        ));
+    };
+
+    Ok(window)
+}
+
+/// Windows of the focused workspace
+fn fetch_windows() -> io::Result<Vec<WindowInfo>> {
+    let Response::Workspaces(workspaces) = request(Request::Workspaces)? else {
+        return Err(io::Error::other(
+            "unexpected response to the workspaces request",
+        ));
+    };
+    let Response::Windows(windows) = request(Request::Windows)? else {
+        return Err(io::Error::other(
+            "unexpected response to the windows request",
+        ));
+    };
+
+    let Some(workspace) = workspaces.iter().find(|w| w.is_focused) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(windows
+        .into_iter()
+        .filter(|w| w.workspace_id == Some(workspace.id))
+        .sorted_by_key(|w| w.id)
+        .map(window_info)
+        .collect())
+}
+
+fn window_info(window: Window) -> WindowInfo {
+    WindowInfo {
+        id: window.id.to_string(),
+        title: window.title.unwrap_or_default(),
+        class: window.app_id.unwrap_or_default(),
+        focused: window.is_focused,
+    }
+}
+
+fn window_id(id: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    id.parse()
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+}
+
+/// Reads the niri event stream until the socket is closed
+fn listen_events(tx: mpsc::UnboundedSender<()>) -> io::Result<()> {
+    let mut socket = Socket::connect()?;
+    socket
+        .send(Request::EventStream)?
+        .map_err(io::Error::other)?;
+
+    let mut read_event = socket.read_events();
+    loop {
+        match read_event()? {
+            // a changed window could be the focused one or in the taskbar
+            Event::WindowFocusChanged { .. }
+            | Event::WindowOpenedOrChanged { .. }
+            | Event::WindowClosed { .. }
+            | Event::WindowsChanged { .. }
+            | Event::WorkspaceActivated { .. } => {
+                if tx.send(()).is_err() {
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+    }
+}
 
 pub struct NiriWindowManager;
 
 impl WindowManager for NiriWindowManager {
-    fn get_window(_config: &WindowTitleConfig) -> Option<String> {
-        None
+    fn get_window() -> Option<WindowInfo> {
+        focused_window()
+            .unwrap_or_else(|e| {
+                error!("failed to get niri focused window: {e}");
+
+                None
+            })
+            .map(window_info)
+    }
+
+    fn get_windows() -> Vec<WindowInfo> {
+        fetch_windows().unwrap_or_else(|e| {
+            error!("failed to get niri windows: {e}");
+
+            Vec::new()
+        })
+    }
+
+    fn focus_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("focusing window: {id}");
+
+        request(Request::Action(Action::FocusWindow { id: window_id(id)? }))?;
+
+        Ok(())
     }
 
-    fn create_subscription() -> Subscription<Message> {
-        let id = TypeId::of::<Self>();
+    fn close_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("closing window: {id}");
+
+        request(Request::Action(Action::CloseWindow {
+            id: Some(window_id(id)?),
+        }))?;
+
+        Ok(())
+    }
+
+    fn create_subscription(instance: &str) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
 
         Subscription::run_with_id(
             id,
-            channel(10, async |output| {
-                let _output = Arc::new(RwLock::new(output));
+            channel(10, async |mut output| {
                 loop {
-                    task::spawn(async move {
-                        pending::<()>().await;
-                    })
-                    .await
-                    .unwrap();
+                    let (tx, mut rx) = mpsc::unbounded_channel();
+
+                    // the niri socket is blocking
+                    let listener = task::spawn_blocking(move || listen_events(tx));
+
+                    while rx.recv().await.is_some() {
+                        debug!("niri focused window changed");
+                        let _ = output.send(Message::TitleChanged).await;
+                    }
+
+                    match listener.await {
+                        Ok(Err(e)) => error!("niri event stream error: {e}"),
+                        Err(e) => error!("niri event listener failed: {e}"),
+                        Ok(Ok(())) => {}
+                    }
+
+                    // avoid a busy loop when the socket is not available
+                    sleep(Duration::from_secs(1)).await;
                 }
             }),
         )
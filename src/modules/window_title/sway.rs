@@ -0,0 +1,157 @@
+use iced::{
+    Subscription,
+    futures::{SinkExt, StreamExt, executor::block_on},
+    stream::channel,
+};
+use log::{debug, error};
+use std::{any::TypeId, time::Duration};
+use swayipc_async::{Connection, Event, EventType, Node, NodeType, WindowChange};
+use tokio::time::sleep;
+
+use super::{Message, WindowInfo, WindowManager};
+
+fn window_info(node: &Node) -> WindowInfo {
+    WindowInfo {
+        id: node.id.to_string(),
+        title: node.name.clone().unwrap_or_default(),
+        // xwayland windows don't have an app_id
+        class: node
+            .app_id
+            .clone()
+            .or_else(|| {
+                node.window_properties
+                    .as_ref()
+                    .and_then(|p| p.class.clone())
+            })
+            .unwrap_or_default(),
+        focused: node.focused,
+    }
+}
+
+async fn focused_window() -> Result<Option<WindowInfo>, swayipc_async::Error> {
+    let mut conn = Connection::new().await?;
+    let tree = conn.get_tree().await?;
+
+    // an empty workspace is focused when there are no windows
+    Ok(tree
+        .find_as_ref(|n| n.focused && matches!(n.node_type, NodeType::Con | NodeType::FloatingCon))
+        .map(window_info))
+}
+
+/// Collects the windows of the container, tiled and floating ones
+fn collect_windows(node: &Node, windows: &mut Vec<WindowInfo>) {
+    if matches!(node.node_type, NodeType::Con | NodeType::FloatingCon) && node.pid.is_some() {
+        windows.push(window_info(node));
+    }
+
+    for child in node.nodes.iter().chain(node.floating_nodes.iter()) {
+        collect_windows(child, windows);
+    }
+}
+
+async fn focused_workspace_windows() -> Result<Vec<WindowInfo>, swayipc_async::Error> {
+    let mut conn = Connection::new().await?;
+    let tree = conn.get_tree().await?;
+
+    let mut windows = Vec::new();
+    if let Some(workspace) = tree.find_focused_as_ref(|n| n.node_type == NodeType::Workspace) {
+        collect_windows(workspace, &mut windows);
+    }
+
+    Ok(windows)
+}
+
+async fn run_on_window(id: &str, command: &str) -> Result<(), swayipc_async::Error> {
+    let mut conn = Connection::new().await?;
+
+    for res in conn.run_command(format!("[con_id={id}] {command}")).await? {
+        res?;
+    }
+
+    Ok(())
+}
+
+pub struct SwayWindowManager;
+
+impl WindowManager for SwayWindowManager {
+    fn get_window() -> Option<WindowInfo> {
+        block_on(focused_window()).unwrap_or_else(|e| {
+            error!("failed to get sway focused window: {e}");
+
+            None
+        })
+    }
+
+    fn get_windows() -> Vec<WindowInfo> {
+        block_on(focused_workspace_windows()).unwrap_or_else(|e| {
+            error!("failed to get sway windows: {e}");
+
+            Vec::new()
+        })
+    }
+
+    fn focus_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("focusing window: {id}");
+
+        block_on(run_on_window(id, "focus")).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
+
+    fn close_window(id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("closing window: {id}");
+
+        block_on(run_on_window(id, "kill")).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
+
+    fn create_subscription(instance: &str) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                loop {
+                    let events = match Connection::new().await {
+                        Ok(conn) => {
+                            conn.subscribe([EventType::Window, EventType::Workspace])
+                                .await
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                    match events {
+                        Ok(mut events) => {
+                            while let Some(event) = events.next().await {
+                                match event {
+                                    // only the changes that can update the focused window title
+                                    // or the windows of the taskbar
+                                    Ok(Event::Window(e))
+                                        if !matches!(
+                                            e.change,
+                                            WindowChange::Focus
+                                                | WindowChange::Title
+                                                | WindowChange::Close
+                                                | WindowChange::New
+                                                | WindowChange::Move
+                                        ) => {}
+                                    Ok(event) => {
+                                        debug!("sway event: {event:?}");
+                                        let _ = output.send(Message::TitleChanged).await;
+                                    }
+                                    Err(e) => {
+                                        error!("sway event stream error: {e}");
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            error!("failed to subscribe to sway events: {e}");
+                        }
+                    }
+
+                    // avoid a busy loop when the socket is not available
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }),
+        )
+    }
+}
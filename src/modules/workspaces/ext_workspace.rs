@@ -0,0 +1,432 @@
+use super::{Displayed, Message, Workspace, WorkspaceManager};
+use crate::config::WorkspacesModuleConfig;
+use iced::{
+    Subscription,
+    futures::{SinkExt, future::pending},
+    stream::channel,
+};
+use itertools::Itertools;
+use log::{debug, error, warn};
+use std::{any::TypeId, collections::HashMap, sync::LazyLock, thread};
+use tokio::sync::watch;
+use wayland_client::{
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+    backend::ObjectId,
+    event_created_child,
+    protocol::{
+        wl_output::{self, WlOutput},
+        wl_registry::{self, WlRegistry},
+    },
+};
+use wayland_protocols::ext::workspace::v1::client::{
+    ext_workspace_group_handle_v1::{self, ExtWorkspaceGroupHandleV1},
+    ext_workspace_handle_v1::{self, ExtWorkspaceHandleV1},
+    ext_workspace_manager_v1::{self, ExtWorkspaceManagerV1},
+};
+
+/// A workspace as advertised by the compositor, in display order
+#[derive(Debug, Clone)]
+struct ExtWorkspace {
+    handle: ExtWorkspaceHandleV1,
+    name: String,
+    output: Option<String>,
+    active: bool,
+    urgent: bool,
+}
+
+#[derive(Debug)]
+struct WorkspaceData {
+    handle: ExtWorkspaceHandleV1,
+    name: String,
+    coordinates: Vec<u32>,
+    group: Option<ObjectId>,
+    active: bool,
+    urgent: bool,
+    hidden: bool,
+}
+
+#[derive(Debug, Default)]
+struct GroupData {
+    outputs: Vec<ObjectId>,
+}
+
+#[derive(Default)]
+struct ExtWorkspaceState {
+    manager: Option<ExtWorkspaceManagerV1>,
+    outputs: HashMap<ObjectId, String>,
+    groups: HashMap<ObjectId, GroupData>,
+    workspaces: HashMap<ObjectId, WorkspaceData>,
+    tx: Option<watch::Sender<Vec<ExtWorkspace>>>,
+}
+
+impl ExtWorkspaceState {
+    fn snapshot(&self) -> Vec<ExtWorkspace> {
+        self.workspaces
+            .values()
+            .filter(|w| !w.hidden)
+            .map(|w| {
+                let output = w
+                    .group
+                    .as_ref()
+                    .and_then(|group| self.groups.get(group))
+                    .and_then(|group| group.outputs.first())
+                    .and_then(|output| self.outputs.get(output))
+                    .filter(|output| !output.is_empty())
+                    .cloned();
+
+                (output, w)
+            })
+            .sorted_by(|(a_output, a), (b_output, b)| {
+                a_output
+                    .cmp(b_output)
+                    .then_with(|| a.coordinates.cmp(&b.coordinates))
+                    .then_with(|| a.name.cmp(&b.name))
+            })
+            .map(|(output, w)| ExtWorkspace {
+                handle: w.handle.clone(),
+                name: w.name.clone(),
+                output,
+                active: w.active,
+                urgent: w.urgent,
+            })
+            .collect()
+    }
+
+    /// Publishes the workspaces once the compositor sent an atomic set of changes
+    fn publish(&self) {
+        if let Some(tx) = &self.tx {
+            tx.send_replace(self.snapshot());
+        }
+    }
+}
+
+struct ExtWorkspaceClient {
+    connection: Connection,
+    manager: ExtWorkspaceManagerV1,
+    workspaces: watch::Receiver<Vec<ExtWorkspace>>,
+}
+
+impl ExtWorkspaceClient {
+    fn connect() -> anyhow::Result<Self> {
+        let connection = Connection::connect_to_env()?;
+        let mut event_queue = connection.new_event_queue();
+        let handle = event_queue.handle();
+        connection.display().get_registry(&handle, ());
+
+        let mut state = ExtWorkspaceState::default();
+        // the first roundtrip binds the globals, the second one receives the workspaces
+        event_queue.roundtrip(&mut state)?;
+        event_queue.roundtrip(&mut state)?;
+
+        let manager = state
+            .manager
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("the compositor doesn't support ext-workspace-v1"))?;
+
+        let (tx, workspaces) = watch::channel(state.snapshot());
+        state.tx = Some(tx);
+
+        thread::spawn(move || {
+            loop {
+                if let Err(e) = event_queue.blocking_dispatch(&mut state) {
+                    error!("ext-workspace event queue error: {e}");
+
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            connection,
+            manager,
+            workspaces,
+        })
+    }
+}
+
+static CLIENT: LazyLock<Option<ExtWorkspaceClient>> = LazyLock::new(|| {
+    ExtWorkspaceClient::connect()
+        .inspect_err(|e| error!("failed to connect to the ext-workspace manager: {e}"))
+        .ok()
+});
+
+pub struct ExtWorkspaceManager;
+
+impl WorkspaceManager for ExtWorkspaceManager {
+    fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace> {
+        let Some(client) = CLIENT.as_ref() else {
+            return Vec::new();
+        };
+        let workspaces = client.workspaces.borrow();
+
+        let outputs = workspaces
+            .iter()
+            .filter_map(|w| w.output.clone())
+            .unique()
+            .sorted()
+            .collect::<Vec<_>>();
+
+        // the ids are the positions in the display order
+        workspaces
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let monitor = w.output.clone().unwrap_or_default();
+
+                Workspace {
+                    id: i as i32 + 1,
+                    name: if w.name.is_empty() {
+                        config
+                            .workspace_names
+                            .get(i)
+                            .cloned()
+                            .unwrap_or_else(|| (i + 1).to_string())
+                    } else {
+                        w.name.clone()
+                    },
+                    monitor_id: outputs
+                        .iter()
+                        .position(|o| *o == monitor)
+                        .map(|i| i as i128),
+                    monitor,
+                    idx: None,
+                    // the protocol doesn't tell which output is focused
+                    displayed: if w.active {
+                        Displayed::Active
+                    } else {
+                        Displayed::Hidden
+                    },
+                    // the protocol doesn't expose the windows
+                    windows: 0,
+                    urgent: w.urgent,
+                    window_classes: Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    fn create_subscription(
+        instance: &str,
+        _config: &WorkspacesModuleConfig,
+    ) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                if let Some(client) = CLIENT.as_ref() {
+                    let mut workspaces = client.workspaces.clone();
+
+                    while workspaces.changed().await.is_ok() {
+                        debug!("ext-workspace workspaces changed");
+                        let _ = output.send(Message::WorkspacesChanged).await;
+                    }
+
+                    warn!("ext-workspace event queue stopped");
+                }
+
+                pending::<()>().await;
+            }),
+        )
+    }
+
+    fn change_workspace(
+        id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("changing workspace to: {id}");
+
+        let client = CLIENT
+            .as_ref()
+            .ok_or("the ext-workspace manager is not available")?;
+        let workspaces = client.workspaces.borrow();
+        let workspace = workspaces
+            .get((id as usize).saturating_sub(1))
+            .ok_or("unknown workspace")?;
+
+        workspace.handle.activate();
+        client.manager.commit();
+        client.connection.flush()?;
+
+        Ok(())
+    }
+
+    // ext-workspace has no special workspaces
+    fn toggle_special_workspace(_workspace: &Workspace) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    fn move_window_to_workspace(
+        _window_id: Option<&str>,
+        _workspace_id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("the ext-workspace protocol can't move windows".into())
+    }
+}
+
+impl Dispatch<WlRegistry, ()> for ExtWorkspaceState {
+    fn event(
+        state: &mut Self,
+        proxy: &WlRegistry,
+        event: <WlRegistry as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        handle: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            if interface == ExtWorkspaceManagerV1::interface().name && state.manager.is_none() {
+                debug!("binding ext-workspace manager with version {version}");
+                state.manager = Some(proxy.bind(name, version.min(1), handle, ()));
+            } else if interface == WlOutput::interface().name {
+                // the output name is sent since version 4
+                let output: WlOutput = proxy.bind(name, version.min(4), handle, ());
+                state.outputs.insert(output.id(), String::new());
+            }
+        }
+    }
+}
+
+impl Dispatch<WlOutput, ()> for ExtWorkspaceState {
+    fn event(
+        state: &mut Self,
+        proxy: &WlOutput,
+        event: <WlOutput as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event {
+            state.outputs.insert(proxy.id(), name);
+        }
+    }
+}
+
+impl Dispatch<ExtWorkspaceManagerV1, ()> for ExtWorkspaceState {
+    fn event(
+        state: &mut Self,
+        _proxy: &ExtWorkspaceManagerV1,
+        event: <ExtWorkspaceManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_workspace_manager_v1::Event::WorkspaceGroup { workspace_group } => {
+                state
+                    .groups
+                    .insert(workspace_group.id(), GroupData::default());
+            }
+            ext_workspace_manager_v1::Event::Workspace { workspace } => {
+                state.workspaces.insert(
+                    workspace.id(),
+                    WorkspaceData {
+                        handle: workspace,
+                        name: String::new(),
+                        coordinates: Vec::new(),
+                        group: None,
+                        active: false,
+                        urgent: false,
+                        hidden: false,
+                    },
+                );
+            }
+            ext_workspace_manager_v1::Event::Done => state.publish(),
+            ext_workspace_manager_v1::Event::Finished => {
+                warn!("the compositor stopped sending the workspaces");
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(ExtWorkspaceState, ExtWorkspaceManagerV1, [
+        ext_workspace_manager_v1::EVT_WORKSPACE_GROUP_OPCODE => (ExtWorkspaceGroupHandleV1, ()),
+        ext_workspace_manager_v1::EVT_WORKSPACE_OPCODE => (ExtWorkspaceHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ExtWorkspaceGroupHandleV1, ()> for ExtWorkspaceState {
+    fn event(
+        state: &mut Self,
+        proxy: &ExtWorkspaceGroupHandleV1,
+        event: <ExtWorkspaceGroupHandleV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_workspace_group_handle_v1::Event::OutputEnter { output } => {
+                if let Some(group) = state.groups.get_mut(&proxy.id()) {
+                    group.outputs.push(output.id());
+                }
+            }
+            ext_workspace_group_handle_v1::Event::OutputLeave { output } => {
+                if let Some(group) = state.groups.get_mut(&proxy.id()) {
+                    group.outputs.retain(|o| *o != output.id());
+                }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceEnter { workspace } => {
+                if let Some(workspace) = state.workspaces.get_mut(&workspace.id()) {
+                    workspace.group = Some(proxy.id());
+                }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceLeave { workspace } => {
+                if let Some(workspace) = state.workspaces.get_mut(&workspace.id())
+                    && workspace.group == Some(proxy.id())
+                {
+                    workspace.group = None;
+                }
+            }
+            ext_workspace_group_handle_v1::Event::Removed => {
+                state.groups.remove(&proxy.id());
+                proxy.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtWorkspaceHandleV1, ()> for ExtWorkspaceState {
+    fn event(
+        state: &mut Self,
+        proxy: &ExtWorkspaceHandleV1,
+        event: <ExtWorkspaceHandleV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let ext_workspace_handle_v1::Event::Removed = event {
+            state.workspaces.remove(&proxy.id());
+            proxy.destroy();
+
+            return;
+        }
+
+        let Some(workspace) = state.workspaces.get_mut(&proxy.id()) else {
+            return;
+        };
+
+        match event {
+            ext_workspace_handle_v1::Event::Name { name } => workspace.name = name,
+            ext_workspace_handle_v1::Event::Coordinates { coordinates } => {
+                workspace.coordinates = coordinates
+                    .chunks_exact(4)
+                    .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+            }
+            ext_workspace_handle_v1::Event::State {
+                state: WEnum::Value(flags),
+            } => {
+                workspace.active = flags.contains(ext_workspace_handle_v1::State::Active);
+                workspace.urgent = flags.contains(ext_workspace_handle_v1::State::Urgent);
+                workspace.hidden = flags.contains(ext_workspace_handle_v1::State::Hidden);
+            }
+            _ => {}
+        }
+    }
+}
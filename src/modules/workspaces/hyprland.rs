@@ -1,150 +1,490 @@
-use super::{Displayed, Message, Workspace, WorkspaceManager};
-use crate::config::WorkspacesModuleConfig;
+use super::{Displayed, Message, Workspace, WorkspaceManager, add_persistent_workspaces};
+use crate::{config::WorkspacesModuleConfig, utils::backoff::Backoff};
 use hyprland::{
-    dispatch::MonitorIdentifier,
+    dispatch::{MonitorIdentifier, WindowIdentifier},
     event_listener::AsyncEventListener,
-    shared::{HyprData, HyprDataActive, HyprDataVec},
+    shared::{Address, HyprData, HyprDataActive, HyprDataVec},
 };
-use iced::{Subscription, stream::channel};
+use iced::{Subscription, futures::SinkExt, stream::channel};
 use itertools::Itertools;
-use log::{debug, error};
+use log::{debug, error, warn};
 use std::{
     any::TypeId,
     collections::HashMap,
-    sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
+use tokio::{sync::mpsc, task, time::sleep};
+
+const PLACEHOLDER_SPECIAL_ID: i32 = -1000;
+/// Window of the events coalesced in a single refresh
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(50);
+/// Maximum age of the state updated from the events before it's fetched again
+const RESYNC_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone)]
 pub struct VirtualDesktop {
     pub active: bool,
     pub windows: u16,
+    pub window_classes: Vec<String>,
 }
 
-pub struct HyprlandWorkspaceManager;
+fn window_classes(clients: &[Client], workspace_id: i32) -> Vec<String> {
+    clients
+        .iter()
+        .filter(|c| c.workspace_id == workspace_id)
+        .map(|c| c.class.clone())
+        .collect()
+}
 
-impl WorkspaceManager for HyprlandWorkspaceManager {
-    fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace> {
-        let active = hyprland::data::Workspace::get_active().ok();
-        let monitors = hyprland::data::Monitors::get()
-            .map(|m| m.to_vec())
-            .unwrap_or_default();
+/// The special workspaces come first, in the configured order
+fn sort_workspaces(workspaces: &mut [Workspace], config: &WorkspacesModuleConfig) {
+    workspaces.sort_by_key(|w| {
+        let position = config
+            .special_workspaces
+            .iter()
+            .position(|special| w.id < 0 && special.name == w.name)
+            .unwrap_or(usize::MAX);
+
+        (w.id >= 0, position, w.id)
+    });
+}
+
+/// Events of the listener, the changes are coalesced before reaching the module
+enum Event {
+    /// the state must be fetched again, for the events not applied to the state
+    Resync,
+    Urgent(i32),
+    /// the listener keeps failing, the workspaces may be outdated
+    ListenerDown,
+    WorkspaceFocused(i32),
+    WindowOpened {
+        address: Address,
+        workspace: String,
+        class: String,
+    },
+    WindowClosed(Address),
+    WindowMoved {
+        address: Address,
+        workspace_id: i32,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Client {
+    address: Address,
+    workspace_id: i32,
+    class: String,
+}
+
+/// State of Hyprland the workspaces are built from, kept up to date by the events
+#[derive(Debug, Clone)]
+pub struct HyprlandState {
+    active: Option<i32>,
+    monitors: Vec<hyprland::data::Monitor>,
+    workspaces: Vec<hyprland::data::Workspace>,
+    clients: Vec<Client>,
+}
+
+impl HyprlandState {
+    fn fetch() -> Self {
         let workspaces = hyprland::data::Workspaces::get()
             .map(|w| w.to_vec())
             .unwrap_or_default();
 
-        // in some cases we can get duplicate workspaces, so we need to deduplicate them
-        let workspaces: Vec<_> = workspaces.into_iter().unique_by(|w| w.id).collect();
+        Self {
+            active: hyprland::data::Workspace::get_active().ok().map(|w| w.id),
+            monitors: hyprland::data::Monitors::get()
+                .map(|m| m.to_vec())
+                .unwrap_or_default(),
+            // in some cases we can get duplicate workspaces, so we need to deduplicate them
+            workspaces: workspaces.into_iter().unique_by(|w| w.id).collect(),
+            clients: hyprland::data::Clients::get()
+                .map(|c| {
+                    c.to_vec()
+                        .into_iter()
+                        .map(|c| Client {
+                            address: c.address,
+                            workspace_id: c.workspace.id,
+                            class: c.class,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
 
-        // We need capacity for at least all the existing entries.
-        let mut result: Vec<Workspace> = Vec::with_capacity(workspaces.len());
+    fn add_windows(&mut self, workspace_id: i32, count: i16) -> bool {
+        match self.workspaces.iter_mut().find(|w| w.id == workspace_id) {
+            Some(workspace) => {
+                workspace.windows = workspace.windows.saturating_add_signed(count);
+                true
+            }
+            None => false,
+        }
+    }
 
-        let (special, normal): (Vec<_>, Vec<_>) = workspaces.into_iter().partition(|w| w.id < 0);
+    /// Applies the event, false when the state must be fetched again
+    fn apply(&mut self, event: Event) -> bool {
+        match event {
+            Event::WorkspaceFocused(id) => {
+                let Some(workspace) = self.workspaces.iter().find(|w| w.id == id) else {
+                    return false;
+                };
+                let Some(monitor) = self
+                    .monitors
+                    .iter_mut()
+                    .find(|m| Some(m.id) == workspace.monitor_id)
+                else {
+                    return false;
+                };
 
-        // map special workspaces
-        for w in special.iter() {
-            // Special workspaces are active if they are assigned to any monitor.
-            // Currently a special and normal workspace can be active at the same time on the same monitor.
-            let active = monitors.iter().any(|m| m.special_workspace.id == w.id);
-            result.push(Workspace {
-                id: w.id,
-                name: w
-                    .name
-                    .split(":")
-                    .last()
-                    .map_or_else(|| "".to_string(), |s| s.to_owned()),
-                monitor_id: w.monitor_id,
-                monitor: w.monitor.clone(),
-                displayed: if active {
-                    Displayed::Active
-                } else {
-                    Displayed::Hidden
-                },
-                windows: w.windows,
-            });
-        }
+                monitor.active_workspace.id = id;
+                monitor.active_workspace.name = workspace.name.clone();
+                self.active = Some(id);
 
-        if config.enable_virtual_desktops {
-            let monitor_count = monitors.len();
-            let mut virtual_desktops: HashMap<i32, VirtualDesktop> = HashMap::new();
-
-            // map normal workspaces
-            for w in normal.iter() {
-                // Calculate the virtual desktop ID based on the workspace ID and the number of workspaces per virtual desktop
-                let vdesk_id = ((w.id - 1) / monitor_count as i32) + 1;
-
-                if let Some(vdesk) = virtual_desktops.get_mut(&vdesk_id) {
-                    vdesk.windows += w.windows;
-                    vdesk.active = vdesk.active || Some(w.id) == active.as_ref().map(|a| a.id);
-                } else {
-                    virtual_desktops.insert(
-                        vdesk_id,
-                        VirtualDesktop {
-                            active: Some(w.id) == active.as_ref().map(|a| a.id),
-                            windows: w.windows,
-                        },
-                    );
-                }
+                true
             }
-
-            // Add virtual desktops to the result as workspaces
-            virtual_desktops.into_iter().for_each(|(id, vdesk)| {
-                // Try to get a name from the config, default to ID
-                let idx = (id - 1) as usize;
-                let display_name = config
-                    .workspace_names
-                    .get(idx)
-                    .cloned()
-                    .unwrap_or_else(|| id.to_string());
-                let active = if vdesk.active {
-                    Displayed::Active
-                } else {
-                    Displayed::Hidden
+            Event::WindowOpened {
+                address,
+                workspace,
+                class,
+            } => {
+                let Some(workspace_id) = self
+                    .workspaces
+                    .iter()
+                    .find(|w| w.name == workspace)
+                    .map(|w| w.id)
+                else {
+                    return false;
                 };
-                result.push(Workspace {
-                    id,
-                    name: display_name,
-                    monitor_id: None,
-                    monitor: "".to_string(),
-                    displayed: active,
-                    windows: vdesk.windows,
+
+                self.clients.push(Client {
+                    address,
+                    workspace_id,
+                    class,
                 });
-            });
-        } else {
-            // map normal workspaces
-            for w in normal.iter() {
-                let display_name = if w.id > 0 {
-                    let idx = (w.id - 1) as usize;
-                    config
-                        .workspace_names
-                        .get(idx)
-                        .cloned()
-                        .unwrap_or_else(|| w.id.to_string())
-                } else {
-                    w.name.clone()
+                self.add_windows(workspace_id, 1)
+            }
+            Event::WindowClosed(address) => {
+                let Some(index) = self.clients.iter().position(|c| c.address == address) else {
+                    return false;
                 };
-                let active = active.as_ref().is_some_and(|a| a.id == w.id);
-                let visible = monitors.iter().any(|m| m.active_workspace.id == w.id);
-                result.push(Workspace {
-                    id: w.id,
-                    name: display_name,
-                    monitor_id: w.monitor_id,
-                    monitor: w.monitor.clone(),
-                    displayed: match (active, visible) {
-                        (true, _) => Displayed::Active,
-                        (false, true) => Displayed::Visible,
-                        (false, false) => Displayed::Hidden,
-                    },
-                    windows: w.windows,
+
+                let client = self.clients.remove(index);
+                self.add_windows(client.workspace_id, -1)
+            }
+            Event::WindowMoved {
+                address,
+                workspace_id,
+            } => {
+                let Some(client) = self.clients.iter_mut().find(|c| c.address == address) else {
+                    return false;
+                };
+
+                let previous = std::mem::replace(&mut client.workspace_id, workspace_id);
+                self.add_windows(previous, -1) && self.add_windows(workspace_id, 1)
+            }
+            Event::Urgent(_) | Event::ListenerDown => true,
+            Event::Resync => false,
+        }
+    }
+}
+
+async fn listen(tx: mpsc::UnboundedSender<Event>, enable_virtual_desktops: bool) {
+    let mut backoff = Backoff::new();
+    loop {
+        let mut event_listener = AsyncEventListener::new();
+
+        event_listener.add_workspace_added_handler({
+            let tx = tx.clone();
+            move |e| {
+                debug!("workspace added: {e:?}");
+                let _ = tx.send(Event::Resync);
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_workspace_changed_handler({
+            let tx = tx.clone();
+            move |e| {
+                debug!("workspace changed: {e:?}");
+                let _ = tx.send(Event::WorkspaceFocused(e.id));
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_workspace_deleted_handler({
+            let tx = tx.clone();
+            move |e| {
+                debug!("workspace deleted: {e:?}");
+                let _ = tx.send(Event::Resync);
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_workspace_moved_handler({
+            let tx = tx.clone();
+            move |e| {
+                debug!("workspace moved: {e:?}");
+                let _ = tx.send(Event::Resync);
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_changed_special_handler({
+            let tx = tx.clone();
+            move |e| {
+                debug!("special workspace changed: {e:?}");
+                let _ = tx.send(Event::Resync);
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_special_removed_handler({
+            let tx = tx.clone();
+            move |e| {
+                debug!("special workspace removed: {e:?}");
+                let _ = tx.send(Event::Resync);
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_window_closed_handler({
+            let tx = tx.clone();
+            move |address| {
+                let _ = tx.send(Event::WindowClosed(address));
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_window_opened_handler({
+            let tx = tx.clone();
+            move |e| {
+                let _ = tx.send(Event::WindowOpened {
+                    address: e.window_address,
+                    workspace: e.workspace_name,
+                    class: e.window_class,
                 });
+                Box::pin(async {})
+            }
+        });
+
+        event_listener.add_window_moved_handler({
+            let tx = tx.clone();
+            move |e| {
+                let _ = tx.send(Event::WindowMoved {
+                    address: e.window_address,
+                    workspace_id: e.workspace_id,
+                });
+                Box::pin(async {})
+            }
+        });
+
+        // the urgency is not in the workspace data, it's tracked by the module
+        event_listener.add_urgent_state_changed_handler({
+            let tx = tx.clone();
+            move |address| {
+                debug!("urgent window: {address:?}");
+                let tx = tx.clone();
+                Box::pin(async move {
+                    let Some(id) = hyprland::data::Clients::get_async()
+                        .await
+                        .ok()
+                        .and_then(|clients| {
+                            clients.to_vec().into_iter().find(|c| c.address == address)
+                        })
+                        .map(|c| c.workspace.id)
+                    else {
+                        return;
+                    };
+
+                    let id = if enable_virtual_desktops && id > 0 {
+                        let monitor_count = hyprland::data::Monitors::get_async()
+                            .await
+                            .map(|m| m.to_vec().len())
+                            .unwrap_or_default()
+                            .max(1);
+
+                        ((id - 1) / monitor_count as i32) + 1
+                    } else {
+                        id
+                    };
+
+                    let _ = tx.send(Event::Urgent(id));
+                })
             }
+        });
+
+        event_listener.add_active_monitor_changed_handler({
+            let tx = tx.clone();
+            move |_| {
+                let _ = tx.send(Event::Resync);
+                Box::pin(async {})
+            }
+        });
+
+        backoff.start();
+        let res = event_listener.start_listener_async().await;
+
+        let delay = backoff.fail();
+        match res {
+            Err(e) => error!("restarting workspaces listener in {delay:?} due to error: {e:?}"),
+            Ok(()) => warn!("workspaces listener stopped, restarting in {delay:?}"),
+        }
+        if backoff.just_down() {
+            let _ = tx.send(Event::ListenerDown);
         }
+        sleep(delay).await;
+    }
+}
 
-        if !config.enable_workspace_filling || normal.is_empty() {
-            // nothing more to do, early return
-            result.sort_by_key(|w| w.id);
-            return result;
-        };
+/// Workspaces of the bar from the state of Hyprland
+pub fn build_workspaces(state: &HyprlandState, config: &WorkspacesModuleConfig) -> Vec<Workspace> {
+    let active = state.active;
+    let monitors = &state.monitors;
+    let clients = if config.show_window_icons {
+        state.clients.as_slice()
+    } else {
+        &[]
+    };
+
+    // We need capacity for at least all the existing entries.
+    let mut result: Vec<Workspace> = Vec::with_capacity(state.workspaces.len());
+
+    let (special, normal): (Vec<_>, Vec<_>) = state.workspaces.iter().partition(|w| w.id < 0);
+
+    // map special workspaces
+    for w in special.iter() {
+        // Special workspaces are active if they are assigned to any monitor.
+        // Currently a special and normal workspace can be active at the same time on the same monitor.
+        let active = monitors.iter().any(|m| m.special_workspace.id == w.id);
+        result.push(Workspace {
+            id: w.id,
+            name: w
+                .name
+                .split(":")
+                .last()
+                .map_or_else(|| "".to_string(), |s| s.to_owned()),
+            monitor_id: w.monitor_id,
+            monitor: w.monitor.clone(),
+            idx: None,
+            displayed: if active {
+                Displayed::Active
+            } else {
+                Displayed::Hidden
+            },
+            windows: w.windows,
+            urgent: false,
+            window_classes: window_classes(clients, w.id),
+        });
+    }
+
+    // the persistent special workspaces that don't exist yet, toggling them creates them
+    let missing_special = config
+        .special_workspaces
+        .iter()
+        .filter(|s| s.persistent && !result.iter().any(|w| w.name == s.name))
+        .collect_vec();
+    for (i, special) in missing_special.into_iter().enumerate() {
+        result.push(Workspace {
+            // out of the range of the special workspace ids of Hyprland
+            id: PLACEHOLDER_SPECIAL_ID - i as i32,
+            name: special.name.clone(),
+            monitor_id: None,
+            monitor: "".to_string(),
+            idx: None,
+            displayed: Displayed::Hidden,
+            windows: 0,
+            urgent: false,
+            window_classes: Vec::new(),
+        });
+    }
+
+    if config.enable_virtual_desktops {
+        let monitor_count = monitors.len();
+        let mut virtual_desktops: HashMap<i32, VirtualDesktop> = HashMap::new();
+
+        // map normal workspaces
+        for w in normal.iter() {
+            // Calculate the virtual desktop ID based on the workspace ID and the number of workspaces per virtual desktop
+            let vdesk_id = ((w.id - 1) / monitor_count as i32) + 1;
 
+            if let Some(vdesk) = virtual_desktops.get_mut(&vdesk_id) {
+                vdesk.windows += w.windows;
+                vdesk.active = vdesk.active || Some(w.id) == active;
+                vdesk.window_classes.extend(window_classes(clients, w.id));
+            } else {
+                virtual_desktops.insert(
+                    vdesk_id,
+                    VirtualDesktop {
+                        active: Some(w.id) == active,
+                        windows: w.windows,
+                        window_classes: window_classes(clients, w.id),
+                    },
+                );
+            }
+        }
+
+        // Add virtual desktops to the result as workspaces
+        virtual_desktops.into_iter().for_each(|(id, vdesk)| {
+            // Try to get a name from the config, default to ID
+            let idx = (id - 1) as usize;
+            let display_name = config
+                .workspace_names
+                .get(idx)
+                .cloned()
+                .unwrap_or_else(|| id.to_string());
+            let active = if vdesk.active {
+                Displayed::Active
+            } else {
+                Displayed::Hidden
+            };
+            result.push(Workspace {
+                id,
+                name: display_name,
+                monitor_id: None,
+                monitor: "".to_string(),
+                idx: None,
+                displayed: active,
+                windows: vdesk.windows,
+                urgent: false,
+                window_classes: vdesk.window_classes,
+            });
+        });
+    } else {
+        // map normal workspaces
+        for w in normal.iter() {
+            let display_name = if w.id > 0 {
+                let idx = (w.id - 1) as usize;
+                config
+                    .workspace_names
+                    .get(idx)
+                    .cloned()
+                    .unwrap_or_else(|| w.id.to_string())
+            } else {
+                w.name.clone()
+            };
+            let active = active == Some(w.id);
+            let visible = monitors.iter().any(|m| m.active_workspace.id == w.id);
+            result.push(Workspace {
+                id: w.id,
+                name: display_name,
+                monitor_id: w.monitor_id,
+                monitor: w.monitor.clone(),
+                idx: None,
+                displayed: match (active, visible) {
+                    (true, _) => Displayed::Active,
+                    (false, true) => Displayed::Visible,
+                    (false, false) => Displayed::Hidden,
+                },
+                windows: w.windows,
+                urgent: false,
+                window_classes: window_classes(clients, w.id),
+            });
+        }
+    }
+
+    if config.enable_workspace_filling && !normal.is_empty() {
         // To show workspaces that don't exist in Hyprland we need to create fake ones
         let existing_ids = result.iter().map(|w| w.id).collect_vec();
         let mut max_id = *existing_ids
@@ -180,179 +520,102 @@ impl WorkspaceManager for HyprlandWorkspaceManager {
                 name: display_name,
                 monitor_id: None,
                 monitor: "".to_string(),
+                idx: None,
                 displayed: Displayed::Hidden,
                 windows: 0,
+                urgent: false,
+                window_classes: Vec::new(),
             });
         }
+    }
 
-        result.sort_by_key(|w| w.id);
+    if !config.enable_virtual_desktops {
+        add_persistent_workspaces(&mut result, config, |monitor| {
+            monitors.iter().find(|m| m.name == monitor).map(|m| m.id)
+        });
+    }
 
-        result
+    sort_workspaces(&mut result, config);
+
+    result
+}
+
+pub struct HyprlandWorkspaceManager;
+
+impl WorkspaceManager for HyprlandWorkspaceManager {
+    fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace> {
+        build_workspaces(&HyprlandState::fetch(), config)
     }
 
-    fn create_subscription(config: &WorkspacesModuleConfig) -> Subscription<Message> {
-        let id = TypeId::of::<Self>();
-        let enable_workspace_filling = config.enable_workspace_filling;
+    fn create_subscription(
+        instance: &str,
+        config: &WorkspacesModuleConfig,
+    ) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
+        // the workspace filling is applied on the state, the listener doesn't restart for it
+        let enable_virtual_desktops = config.enable_virtual_desktops;
 
         Subscription::run_with_id(
-            (id, enable_workspace_filling),
-            channel(10, async move |output| {
-                let output = Arc::new(RwLock::new(output));
-                loop {
-                    let mut event_listener = AsyncEventListener::new();
-
-                    event_listener.add_workspace_added_handler({
-                        let output = output.clone();
-                        move |e| {
-                            debug!("workspace added: {e:?}");
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output
-                                        .try_send(Message::WorkspacesChanged)
-                                        .expect("error getting workspaces: workspace added event");
-                                }
-                            })
-                        }
-                    });
-
-                    event_listener.add_workspace_changed_handler({
-                        let output = output.clone();
-                        move |e| {
-                            debug!("workspace changed: {e:?}");
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output
-                                        .try_send(Message::WorkspacesChanged)
-                                        .expect("error getting workspaces: workspace change event");
-                                }
-                            })
-                        }
-                    });
-
-                    event_listener.add_workspace_deleted_handler({
-                        let output = output.clone();
-                        move |e| {
-                            debug!("workspace deleted: {e:?}");
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output.try_send(Message::WorkspacesChanged).expect(
-                                        "error getting workspaces: workspace destroy event",
-                                    );
-                                }
-                            })
-                        }
-                    });
-
-                    event_listener.add_workspace_moved_handler({
-                        let output = output.clone();
-                        move |e| {
-                            debug!("workspace moved: {e:?}");
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output
-                                        .try_send(Message::WorkspacesChanged)
-                                        .expect("error getting workspaces: workspace moved event");
-                                }
-                            })
-                        }
-                    });
-
-                    event_listener.add_changed_special_handler({
-                        let output = output.clone();
-                        move |e| {
-                            debug!("special workspace changed: {e:?}");
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output.try_send(Message::WorkspacesChanged).expect(
-                                        "error getting workspaces: special workspace change event",
-                                    );
-                                }
-                            })
-                        }
-                    });
-
-                    event_listener.add_special_removed_handler({
-                        let output = output.clone();
-                        move |e| {
-                            debug!("special workspace removed: {e:?}");
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output.try_send(Message::WorkspacesChanged).expect(
-                                        "error getting workspaces: special workspace removed event",
-                                    );
-                                }
-                            })
+            (id, enable_virtual_desktops),
+            channel(10, async move |mut output| {
+                let (tx, mut rx) = mpsc::unbounded_channel();
+
+                let forward = async {
+                    let mut state: Option<HyprlandState> = None;
+                    let mut last_resync = Instant::now();
+
+                    while let Some(event) = rx.recv().await {
+                        // a window moving fires several events, they are applied together
+                        sleep(REFRESH_DEBOUNCE).await;
+
+                        let mut events = vec![event];
+                        while let Ok(event) = rx.try_recv() {
+                            events.push(event);
                         }
-                    });
-
-                    event_listener.add_window_closed_handler({
-                        let output = output.clone();
-                        move |_| {
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output
-                                        .try_send(Message::WorkspacesChanged)
-                                        .expect("error getting workspaces: window close event");
-                                }
+
+                        let urgent = events
+                            .iter()
+                            .filter_map(|event| match event {
+                                Event::Urgent(id) => Some(*id),
+                                _ => None,
                             })
+                            .collect_vec();
+                        let listener_down = events
+                            .iter()
+                            .any(|event| matches!(event, Event::ListenerDown));
+                        let changed = events
+                            .iter()
+                            .any(|event| !matches!(event, Event::Urgent(_) | Event::ListenerDown));
+
+                        // the state drifts if an event is missed, it's fetched again from time to time
+                        let resync = last_resync.elapsed() >= RESYNC_INTERVAL
+                            || !state.as_mut().is_some_and(|state| {
+                                events.into_iter().all(|event| state.apply(event))
+                            });
+
+                        if resync {
+                            match task::spawn_blocking(HyprlandState::fetch).await {
+                                Ok(fetched) => state = Some(fetched),
+                                Err(e) => error!("failed to get the hyprland state: {e}"),
+                            }
+                            last_resync = Instant::now();
                         }
-                    });
-
-                    event_listener.add_window_opened_handler({
-                        let output = output.clone();
-                        move |_| {
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output
-                                        .try_send(Message::WorkspacesChanged)
-                                        .expect("error getting workspaces: window open event");
-                                }
-                            })
+
+                        if changed && let Some(state) = &state {
+                            let _ = output
+                                .send(Message::HyprlandChanged(Box::new(state.clone())))
+                                .await;
                         }
-                    });
-
-                    event_listener.add_window_moved_handler({
-                        let output = output.clone();
-                        move |_| {
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output
-                                        .try_send(Message::WorkspacesChanged)
-                                        .expect("error getting workspaces: window moved event");
-                                }
-                            })
+                        for id in urgent {
+                            let _ = output.send(Message::WorkspaceUrgent(id)).await;
                         }
-                    });
-
-                    event_listener.add_active_monitor_changed_handler({
-                        let output = output.clone();
-                        move |_| {
-                            let output = output.clone();
-                            Box::pin(async move {
-                                if let Ok(mut output) = output.write() {
-                                    output.try_send(Message::WorkspacesChanged).expect(
-                                        "error getting workspaces: active monitor change event",
-                                    );
-                                }
-                            })
+                        if listener_down {
+                            let _ = output.send(Message::ListenerDown).await;
                         }
-                    });
-
-                    let res = event_listener.start_listener_async().await;
-
-                    if let Err(e) = res {
-                        error!("restarting workspaces listener due to error: {e:?}");
                     }
-                }
+                };
+
+                tokio::join!(listen(tx, enable_virtual_desktops), forward);
             }),
         )
     }
@@ -378,10 +641,14 @@ impl WorkspaceManager for HyprlandWorkspaceManager {
 
     fn toggle_special_workspace(workspace: &Workspace) -> Result<(), Box<dyn std::error::Error>> {
         debug!("toggle special workspace: {}", workspace.id);
-        let res =
-            hyprland::dispatch::Dispatch::call(hyprland::dispatch::DispatchType::FocusMonitor(
-                MonitorIdentifier::Id(workspace.monitor_id.unwrap_or_default()),
-            ))
+        // a workspace that doesn't exist yet opens on the focused monitor
+        let res = workspace
+            .monitor_id
+            .map_or(Ok(()), |monitor_id| {
+                hyprland::dispatch::Dispatch::call(hyprland::dispatch::DispatchType::FocusMonitor(
+                    MonitorIdentifier::Id(monitor_id),
+                ))
+            })
             .and_then(|_| {
                 hyprland::dispatch::Dispatch::call(
                     hyprland::dispatch::DispatchType::ToggleSpecialWorkspace(Some(
@@ -392,4 +659,32 @@ impl WorkspaceManager for HyprlandWorkspaceManager {
 
         res.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
     }
+
+    fn move_window_to_workspace(
+        window_id: Option<&str>,
+        workspace_id: i32,
+        config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("moving window {window_id:?} to workspace {workspace_id}");
+        let window = window_id.map(|id| WindowIdentifier::Address(Address::new(id)));
+        let res = if config.enable_virtual_desktops {
+            let args = match &window {
+                Some(window) => format!("{workspace_id},{window}"),
+                None => workspace_id.to_string(),
+            };
+            hyprland::dispatch::Dispatch::call(hyprland::dispatch::DispatchType::Custom(
+                "movetodesksilent",
+                &args,
+            ))
+        } else {
+            hyprland::dispatch::Dispatch::call(
+                hyprland::dispatch::DispatchType::MoveToWorkspaceSilent(
+                    hyprland::dispatch::WorkspaceIdentifierWithSpecial::Id(workspace_id),
+                    window,
+                ),
+            )
+        };
+
+        res.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
 }
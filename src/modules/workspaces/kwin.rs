@@ -0,0 +1,202 @@
+use super::{Displayed, Message, Workspace, WorkspaceManager};
+use crate::config::WorkspacesModuleConfig;
+use iced::{
+    Subscription,
+    futures::{SinkExt, Stream, StreamExt, stream::select_all},
+    stream::channel,
+};
+use log::{debug, error};
+use std::{any::TypeId, pin::Pin, sync::Mutex, time::Duration};
+use tokio::time::sleep;
+use zbus::proxy::CacheProperties;
+
+/// A virtual desktop, as `(position, id, name)`
+type Desktop = (u32, String, String);
+
+#[zbus::proxy(
+    interface = "org.kde.KWin.VirtualDesktopManager",
+    default_service = "org.kde.KWin",
+    default_path = "/VirtualDesktopManager"
+)]
+trait VirtualDesktopManager {
+    #[zbus(property, name = "current")]
+    fn current(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "current")]
+    fn set_current(&self, id: &str) -> zbus::Result<()>;
+
+    #[zbus(property, name = "desktops")]
+    fn desktops(&self) -> zbus::Result<Vec<Desktop>>;
+
+    #[zbus(signal, name = "currentChanged")]
+    fn current_changed(&self, id: String) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "desktopCreated")]
+    fn desktop_created(&self, id: String, desktop: Desktop) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "desktopRemoved")]
+    fn desktop_removed(&self, id: String) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "desktopDataChanged")]
+    fn desktop_data_changed(&self, id: String, desktop: Desktop) -> zbus::Result<()>;
+}
+
+/// The workspaces are read on demand, KWin doesn't always announce the property changes
+static PROXY: Mutex<Option<VirtualDesktopManagerProxyBlocking<'static>>> = Mutex::new(None);
+
+/// The proxy is kept once connected, a failed connection is retried on the next call
+fn proxy() -> zbus::Result<VirtualDesktopManagerProxyBlocking<'static>> {
+    let mut cached = PROXY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(proxy) = cached.as_ref() {
+        return Ok(proxy.clone());
+    }
+
+    let conn = zbus::blocking::Connection::session()?;
+    let proxy = VirtualDesktopManagerProxyBlocking::builder(&conn)
+        .cache_properties(CacheProperties::No)
+        .build()?;
+    *cached = Some(proxy.clone());
+
+    Ok(proxy)
+}
+
+/// The desktops in the order of their position
+fn desktops(proxy: &VirtualDesktopManagerProxyBlocking) -> zbus::Result<Vec<Desktop>> {
+    let mut desktops = proxy.desktops()?;
+    desktops.sort_by_key(|(position, _, _)| *position);
+
+    Ok(desktops)
+}
+
+async fn events() -> zbus::Result<impl Stream<Item = ()>> {
+    let conn = zbus::Connection::session().await?;
+    let proxy = VirtualDesktopManagerProxy::new(&conn).await?;
+
+    let events: Vec<Pin<Box<dyn Stream<Item = ()> + Send>>> = vec![
+        proxy.receive_current_changed().await?.map(|_| ()).boxed(),
+        proxy.receive_desktop_created().await?.map(|_| ()).boxed(),
+        proxy.receive_desktop_removed().await?.map(|_| ()).boxed(),
+        proxy
+            .receive_desktop_data_changed()
+            .await?
+            .map(|_| ())
+            .boxed(),
+    ];
+
+    Ok(select_all(events))
+}
+
+pub struct KwinWorkspaceManager;
+
+impl WorkspaceManager for KwinWorkspaceManager {
+    fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace> {
+        let proxy = match proxy() {
+            Ok(proxy) => proxy,
+            Err(e) => {
+                error!("failed to connect to the KWin virtual desktops: {e}");
+
+                return Vec::new();
+            }
+        };
+        let (desktops, current) = match (desktops(&proxy), proxy.current()) {
+            (Ok(desktops), Ok(current)) => (desktops, current),
+            (Err(e), _) | (_, Err(e)) => {
+                error!("failed to get the KWin virtual desktops: {e}");
+
+                return Vec::new();
+            }
+        };
+
+        // the virtual desktops span all the outputs
+        desktops
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, id, name))| Workspace {
+                id: i as i32 + 1,
+                name: if name.is_empty() {
+                    config
+                        .workspace_names
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| (i + 1).to_string())
+                } else {
+                    name
+                },
+                monitor_id: None,
+                monitor: String::new(),
+                idx: None,
+                displayed: if id == current {
+                    Displayed::Active
+                } else {
+                    Displayed::Hidden
+                },
+                // the interface doesn't expose the windows
+                windows: 0,
+                urgent: false,
+                window_classes: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn create_subscription(
+        instance: &str,
+        _config: &WorkspacesModuleConfig,
+    ) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                loop {
+                    match events().await {
+                        Ok(mut events) => {
+                            // KWin may have started since the last read
+                            let _ = output.send(Message::WorkspacesChanged).await;
+
+                            while events.next().await.is_some() {
+                                debug!("KWin virtual desktops changed");
+                                let _ = output.send(Message::WorkspacesChanged).await;
+                            }
+                            error!("the KWin virtual desktops stream ended");
+                        }
+                        Err(e) => error!("failed to listen to the KWin virtual desktops: {e}"),
+                    }
+
+                    // avoid a busy loop when KWin is not available
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }),
+        )
+    }
+
+    fn change_workspace(
+        id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("changing workspace to: {id}");
+
+        let proxy = proxy()?;
+        let desktops = desktops(&proxy)?;
+        let (_, desktop, _) = usize::try_from(id - 1)
+            .ok()
+            .and_then(|i| desktops.get(i))
+            .ok_or("unknown workspace")?;
+
+        proxy.set_current(desktop)?;
+
+        Ok(())
+    }
+
+    // KWin has no special workspaces
+    fn toggle_special_workspace(_workspace: &Workspace) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    fn move_window_to_workspace(
+        _window_id: Option<&str>,
+        _workspace_id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("KWin doesn't expose the windows over D-Bus".into())
+    }
+}
@@ -1,5 +1,34 @@
-use crate::{config::WorkspacesModuleConfig, outputs::Outputs, theme::AshellTheme};
-use iced::{Element, Subscription, window::Id};
+use crate::{
+    components::icons::{StaticIcon, icon},
+    config::{
+        ScrollModifier, SpecialWorkspaceBar, WorkspaceAction, WorkspaceStyle,
+        WorkspacesModuleConfig,
+    },
+    outputs::Outputs,
+    theme::AshellTheme,
+    utils::{
+        app_icon::{AppIcon, app_icon},
+        launcher::execute_command,
+        watchdog::{self, Watchdog},
+    },
+};
+use iced::{Element, Subscription, Theme, keyboard, mouse::ScrollDelta, time::every, window::Id};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "wlroots")]
+pub mod ext_workspace;
+#[cfg(feature = "wlroots")]
+pub use ext_workspace::ExtWorkspaceManager;
+
+#[cfg(feature = "river")]
+pub mod river;
+#[cfg(feature = "river")]
+pub use river::RiverWorkspaceManager;
+
+#[cfg(feature = "kwin")]
+pub mod kwin;
+#[cfg(feature = "kwin")]
+pub use kwin::KwinWorkspaceManager;
 
 #[cfg(feature = "hyprland")]
 pub mod hyprland;
@@ -11,6 +40,11 @@ pub mod niri;
 #[cfg(feature = "niri")]
 pub use niri::NiriWorkspaceManager;
 
+#[cfg(feature = "sway")]
+pub mod sway;
+#[cfg(feature = "sway")]
+pub use sway::SwayWorkspaceManager;
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Displayed {
     Active,
@@ -18,37 +52,145 @@ pub enum Displayed {
     Hidden,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Workspace {
     pub id: i32,
     pub name: String,
     pub monitor_id: Option<i128>,
     pub monitor: String,
+    /// position on the monitor, for the backends with per-output workspaces like niri
+    pub idx: Option<u8>,
     pub displayed: Displayed,
     pub windows: u16,
+    /// a window in the workspace demands attention
+    pub urgent: bool,
+    /// classes or app_ids of the windows, only filled when the icons are shown
+    pub window_classes: Vec<String>,
+}
+
+impl Workspace {
+    /// The number shown to the user, the id is global and meaningless with per-output workspaces
+    pub fn number(&self) -> i32 {
+        self.idx.map_or(self.id, i32::from)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum MouseBinding {
+    LeftClick,
+    MiddleClick,
+    RightClick,
+    ScrollLeft,
+    ScrollRight,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     WorkspacesChanged,
+    /// the state updated from the events, the workspaces are built without querying Hyprland
+    #[cfg(feature = "hyprland")]
+    HyprlandChanged(Box<hyprland::HyprlandState>),
+    /// the event listener of the backend keeps failing, until the workspaces change again
+    ListenerDown,
+    /// sent by the backends that don't keep the urgency in the workspace state
+    WorkspaceUrgent(i32),
     ChangeWorkspace(i32),
     ToggleSpecialWorkspace(i32),
-    Scroll(i32),
+    /// a mouse button or a horizontal scroll on a workspace, mapped by the bindings
+    MouseAction(i32, MouseBinding),
+    /// the monitor of the bar, the scroll stays on it with per-output workspaces
+    Scroll(i32, Option<String>),
+    /// the modifiers are only known while the bar has the keyboard,
+    /// they're cleared when it loses the keyboard or the pointer
+    ModifiersChanged(keyboard::Modifiers),
+    /// a press is released on the workspace, the app moves the dragged window
+    WindowDropped(i32),
+    MoveWindow(String, i32),
+    /// moves the keyboard selection by the direction among the workspaces of the monitor,
+    /// 0 selects the active workspace
+    MoveFocus(i32, Option<String>),
+    ActivateFocused,
+    ClearFocus,
+    /// compares the workspaces with the backend, the listener is restarted when it missed changes
+    WatchdogCheck,
+}
+
+#[cfg(any(feature = "hyprland", feature = "sway"))]
+fn display_name(config: &WorkspacesModuleConfig, id: i32) -> String {
+    config
+        .workspace_names
+        .get((id - 1) as usize)
+        .cloned()
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Shows the persistent workspaces on their monitor, the missing ones are created
+#[cfg(any(feature = "hyprland", feature = "sway"))]
+fn add_persistent_workspaces(
+    workspaces: &mut Vec<Workspace>,
+    config: &WorkspacesModuleConfig,
+    monitor_id: impl Fn(&str) -> Option<i128>,
+) {
+    for (monitor, ids) in &config.persistent_workspaces {
+        for &id in ids.iter().filter(|id| **id > 0) {
+            match workspaces.iter_mut().find(|w| w.id == id) {
+                // the workspaces added by the filling belong to no monitor
+                Some(workspace) => {
+                    if workspace.monitor.is_empty() {
+                        workspace.monitor = monitor.clone();
+                        workspace.monitor_id = monitor_id(monitor);
+                    }
+                }
+                None => workspaces.push(Workspace {
+                    id,
+                    name: display_name(config, id),
+                    monitor_id: monitor_id(monitor),
+                    monitor: monitor.clone(),
+                    idx: None,
+                    displayed: Displayed::Hidden,
+                    windows: 0,
+                    urgent: false,
+                    window_classes: Vec::new(),
+                }),
+            }
+        }
+    }
 }
 
 pub trait WorkspaceManager {
     fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace>;
-    fn create_subscription(config: &WorkspacesModuleConfig) -> Subscription<Message>;
+    /// The instance tells apart the subscriptions of the modules of the same type
+    fn create_subscription(
+        instance: &str,
+        config: &WorkspacesModuleConfig,
+    ) -> Subscription<Message>;
     fn change_workspace(
         id: i32,
         config: &WorkspacesModuleConfig,
     ) -> Result<(), Box<dyn std::error::Error>>;
     fn toggle_special_workspace(workspace: &Workspace) -> Result<(), Box<dyn std::error::Error>>;
+    /// moves the focused window when `window_id` is None
+    fn move_window_to_workspace(
+        window_id: Option<&str>,
+        workspace_id: i32,
+        config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>>;
 }
 
 pub struct Workspaces<WM: WorkspaceManager> {
     config: WorkspacesModuleConfig,
     workspaces: Vec<Workspace>,
+    icons: HashMap<String, Option<AppIcon>>,
+    /// formatted labels by workspace id, built when the workspaces change
+    labels: HashMap<i32, String>,
+    /// urgent workspaces reported by the events, until they're focused
+    urgent: HashSet<i32>,
+    /// workspace selected with the keyboard
+    focused: Option<i32>,
+    /// the workspaces may be outdated, a warning is shown
+    listener_down: bool,
+    watchdog: Watchdog,
+    modifiers: keyboard::Modifiers,
     _phantom: std::marker::PhantomData<WM>,
 }
 
@@ -56,17 +198,212 @@ impl<WM: WorkspaceManager> Workspaces<WM> {
     pub fn new(config: WorkspacesModuleConfig) -> Self {
         let workspaces = WM::get_workspaces(&config);
 
-        Self {
+        let mut module = Self {
             config,
             workspaces,
+            icons: HashMap::new(),
+            labels: HashMap::new(),
+            urgent: HashSet::new(),
+            focused: None,
+            listener_down: false,
+            watchdog: Watchdog::new("workspaces"),
+            modifiers: keyboard::Modifiers::default(),
             _phantom: std::marker::PhantomData,
+        };
+        module.resolve_icons();
+        module.refresh_labels();
+
+        module
+    }
+
+    fn refresh_urgent(&mut self) {
+        for w in self.workspaces.iter_mut() {
+            if w.displayed == Displayed::Active {
+                self.urgent.remove(&w.id);
+            }
+
+            w.urgent = w.urgent || self.urgent.contains(&w.id);
+        }
+    }
+
+    fn move_window_modifier_held(&self) -> bool {
+        match self.config.scroll_move_window_modifier {
+            Some(ScrollModifier::Shift) => self.modifiers.shift(),
+            Some(ScrollModifier::Ctrl) => self.modifiers.control(),
+            Some(ScrollModifier::Alt) => self.modifiers.alt(),
+            Some(ScrollModifier::Super) => self.modifiers.logo(),
+            None => false,
+        }
+    }
+
+    /// Runs the command of the workspace, configured by number or by name
+    fn run_command(&self, id: i32) {
+        let workspace = self.workspaces.iter().find(|w| w.id == id);
+        let number = workspace.map_or(id, Workspace::number);
+        let command = self
+            .config
+            .commands
+            .get(&number.to_string())
+            .or_else(|| workspace.and_then(|w| self.config.commands.get(&w.name)));
+
+        if let Some(command) = command {
+            execute_command(command.clone());
+        }
+    }
+
+    fn set_workspaces(&mut self, workspaces: Vec<Workspace>) {
+        self.listener_down = false;
+        let previous = std::mem::replace(&mut self.workspaces, workspaces);
+        self.refresh_urgent();
+
+        // most of the events don't change what the bar shows
+        if self.workspaces != previous {
+            self.resolve_icons();
+            self.refresh_labels();
+            self.focused = self
+                .focused
+                .filter(|id| self.workspaces.iter().any(|w| w.id == *id));
+        }
+    }
+
+    /// What the listener keeps up to date, the urgency comes from the events on some backends
+    fn watched(workspaces: &[Workspace]) -> Vec<(i32, &Displayed, u16)> {
+        workspaces
+            .iter()
+            .map(|w| (w.id, &w.displayed, w.windows))
+            .collect()
+    }
+
+    /// Listener restarts by the watchdog
+    pub fn restarts(&self) -> u32 {
+        self.watchdog.restarts()
+    }
+
+    fn resolve_icons(&mut self) {
+        for class in self.workspaces.iter().flat_map(|w| w.window_classes.iter()) {
+            if !self.icons.contains_key(class) {
+                self.icons.insert(class.clone(), app_icon(class));
+            }
         }
     }
 
+    fn is_hidden(&self, workspace: &Workspace) -> bool {
+        (self.config.hide_empty
+            && workspace.windows == 0
+            && workspace.displayed == Displayed::Hidden)
+            || (self.config.hide_special && workspace.id < 0)
+    }
+
+    fn refresh_labels(&mut self) {
+        self.labels = self
+            .workspaces
+            .iter()
+            .map(|w| (w.id, self.format_label(w)))
+            .collect();
+    }
+
+    fn label<'a>(&'a self, workspace: &'a Workspace) -> &'a str {
+        self.labels
+            .get(&workspace.id)
+            .map_or(workspace.name.as_str(), String::as_str)
+    }
+
+    fn format_label(&self, workspace: &Workspace) -> String {
+        let special = self
+            .config
+            .special_workspaces
+            .iter()
+            .find(|special| workspace.id < 0 && special.name == workspace.name);
+        let name = special
+            .and_then(|special| special.label.as_deref())
+            .unwrap_or(&workspace.name);
+        let special_icon = special.and_then(|special| special.icon.as_deref());
+
+        let Some(format) = &self.config.format else {
+            return match special_icon {
+                Some(icon) => format!("{icon} {name}").trim().to_string(),
+                None => name.to_string(),
+            };
+        };
+
+        let icon = special_icon
+            .or_else(|| {
+                self.config
+                    .icons
+                    .get(&workspace.number().to_string())
+                    .or_else(|| self.config.icons.get(&workspace.name))
+                    .map(String::as_str)
+            })
+            .unwrap_or_default();
+
+        format
+            .replace("{icon}", icon)
+            .replace("{name}", name)
+            .replace("{id}", &workspace.number().to_string())
+            .replace("{windows}", &workspace.windows.to_string())
+            .trim()
+            .to_string()
+    }
+
+    fn window_icons<'a>(
+        &'a self,
+        workspace: &'a Workspace,
+        size: u16,
+    ) -> Vec<Element<'a, Message>> {
+        workspace
+            .window_classes
+            .iter()
+            .filter_map(|class| self.icons.get(class).and_then(Option::as_ref))
+            .map(|icon| icon.view(size))
+            .collect()
+    }
+
+    /// The focused workspace
+    pub fn active(&self) -> Option<i32> {
+        self.workspaces
+            .iter()
+            .find(|w| w.displayed == Displayed::Active)
+            .map(|w| w.id)
+    }
+
+    /// How the bar of the output looks, set by the special workspace shown on it
+    pub fn special_bar(&self, monitor_name: &str) -> SpecialWorkspaceBar {
+        self.workspaces
+            .iter()
+            .filter(|w| {
+                w.id < 0
+                    && w.displayed == Displayed::Active
+                    && !w.monitor.is_empty()
+                    && monitor_name.contains(&w.monitor)
+            })
+            .find_map(|w| {
+                self.config
+                    .special_workspaces
+                    .iter()
+                    .find(|special| special.name == w.name)
+            })
+            .map_or(SpecialWorkspaceBar::Show, |special| special.bar)
+    }
+
     pub fn update(&mut self, message: Message) {
         match message {
             Message::WorkspacesChanged => {
-                self.workspaces = WM::get_workspaces(&self.config);
+                self.watchdog.feed();
+                self.set_workspaces(WM::get_workspaces(&self.config));
+            }
+            #[cfg(feature = "hyprland")]
+            Message::HyprlandChanged(state) => {
+                self.watchdog.feed();
+                self.set_workspaces(hyprland::build_workspaces(&state, &self.config));
+            }
+            Message::ListenerDown => {
+                log::error!("the workspaces listener keeps failing, showing the last known state");
+                self.listener_down = true;
+            }
+            Message::WorkspaceUrgent(id) => {
+                self.watchdog.feed();
+                self.urgent.insert(id);
+                self.refresh_urgent();
             }
             Message::ChangeWorkspace(id) => {
                 if id > 0 {
@@ -76,163 +413,409 @@ impl<WM: WorkspaceManager> Workspaces<WM> {
                         .any(|w| w.displayed == Displayed::Active && w.id == id);
 
                     if !already_active {
-                        if let Err(e) = WM::change_workspace(id, &self.config) {
-                            log::error!("failed to dispatch workspace change: {e:?}");
+                        match WM::change_workspace(id, &self.config) {
+                            Ok(()) => self.run_command(id),
+                            Err(e) => log::error!("failed to dispatch workspace change: {e:?}"),
                         }
                     }
                 }
             }
             Message::ToggleSpecialWorkspace(id) => {
                 if let Some(special) = self.workspaces.iter().find(|w| w.id == id && w.id < 0) {
-                    if let Err(e) = WM::toggle_special_workspace(special) {
-                        log::error!("failed to dispatch special workspace toggle: {e:?}");
+                    match WM::toggle_special_workspace(special) {
+                        // the command runs when the special workspace is shown
+                        Ok(()) if special.displayed == Displayed::Hidden => self.run_command(id),
+                        Ok(()) => {}
+                        Err(e) => {
+                            log::error!("failed to dispatch special workspace toggle: {e:?}")
+                        }
                     }
                 }
             }
-            Message::Scroll(direction) => {
-                let current_workspace = self
-                    .workspaces
-                    .iter()
-                    .find(|w| w.displayed.eq(&Displayed::Active));
-                let Some(current_id) = current_workspace.map(|w| w.id) else {
+            Message::Scroll(direction, monitor) => {
+                // with per-output workspaces the scroll moves among the ones of the monitor
+                let per_output = self.workspaces.iter().any(|w| w.idx.is_some());
+                let current = if per_output {
+                    self.workspaces
+                        .iter()
+                        .filter(|w| {
+                            w.displayed != Displayed::Hidden
+                                && monitor
+                                    .as_deref()
+                                    .is_none_or(|monitor| monitor.contains(&w.monitor))
+                        })
+                        .min_by_key(|w| w.displayed != Displayed::Active)
+                } else {
+                    self.workspaces
+                        .iter()
+                        .find(|w| w.displayed.eq(&Displayed::Active))
+                };
+                let Some((current_number, current_monitor)) =
+                    current.map(|w| (w.number(), w.monitor.clone()))
+                else {
                     return;
                 };
 
-                let next_workspace = if direction > 0 {
-                    self.workspaces
+                // the special workspaces can't be changed to
+                let mut candidates = self
+                    .workspaces
+                    .iter()
+                    .filter(|w| {
+                        w.id > 0
+                            && (!self.config.scroll_skip_empty || w.windows > 0)
+                            && (!per_output || w.monitor == current_monitor)
+                    })
+                    .map(|w| (w.number(), w.id))
+                    .collect::<Vec<_>>();
+                candidates.sort_unstable();
+
+                let next_id = if direction > 0 {
+                    candidates
                         .iter()
-                        .filter(|w| w.id > current_id)
-                        .min_by_key(|w| w.id)
+                        .find(|(number, _)| *number > current_number)
+                        .or(candidates.first().filter(|_| self.config.scroll_wraparound))
                 } else {
-                    self.workspaces
+                    candidates
                         .iter()
-                        .filter(|w| w.id < current_id)
-                        .max_by_key(|w| w.id)
+                        .rfind(|(number, _)| *number < current_number)
+                        .or(candidates.last().filter(|_| self.config.scroll_wraparound))
+                }
+                .map(|(_, id)| id);
+                let Some(next_id) = next_id.copied() else {
+                    return;
                 };
-                let Some(next_workspace) = next_workspace else {
+
+                if self.move_window_modifier_held()
+                    && let Err(e) = WM::move_window_to_workspace(None, next_id, &self.config)
+                {
+                    log::error!("failed to move the focused window to workspace: {e:?}");
+                }
+                Self::update(self, Message::ChangeWorkspace(next_id));
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+            }
+            Message::WindowDropped(_) => {}
+            Message::MoveWindow(window_id, workspace_id) => {
+                if let Err(e) =
+                    WM::move_window_to_workspace(Some(&window_id), workspace_id, &self.config)
+                {
+                    log::error!("failed to move window {window_id} to workspace: {e:?}");
+                }
+            }
+            Message::MoveFocus(direction, monitor) => {
+                use crate::config::WorkspaceVisibilityMode;
+
+                let ids = self
+                    .workspaces
+                    .iter()
+                    .filter(|w| {
+                        !self.is_hidden(w)
+                            && (self.config.visibility_mode == WorkspaceVisibilityMode::All
+                                || monitor
+                                    .as_deref()
+                                    .is_none_or(|monitor| monitor.contains(&w.monitor)))
+                    })
+                    .map(|w| (w.id, w.displayed == Displayed::Active))
+                    .collect::<Vec<_>>();
+                let current = ids.iter().position(|(id, active)| match self.focused {
+                    Some(focused) => *id == focused,
+                    None => *active,
+                });
+
+                self.focused = match current {
+                    Some(current) => ids
+                        .get((current as i32 + direction).rem_euclid(ids.len() as i32) as usize)
+                        .map(|(id, _)| *id),
+                    _ => ids.first().map(|(id, _)| *id),
+                };
+            }
+            Message::ActivateFocused => {
+                if let Some(id) = self.focused.take() {
+                    if id > 0 {
+                        self.update(Message::ChangeWorkspace(id));
+                    } else {
+                        self.update(Message::ToggleSpecialWorkspace(id));
+                    }
+                }
+            }
+            Message::ClearFocus => {
+                self.focused = None;
+            }
+            Message::WatchdogCheck => {
+                // the backend answers with no workspace when it's down, the listener knows it
+                if self.listener_down {
                     return;
+                }
+                let workspaces = WM::get_workspaces(&self.config);
+                let changed = !workspaces.is_empty()
+                    && Self::watched(&workspaces) != Self::watched(&self.workspaces);
+
+                if self.watchdog.check(changed) {
+                    self.set_workspaces(workspaces);
+                }
+            }
+            Message::MouseAction(id, binding) => {
+                let bindings = &self.config.bindings;
+                let action = match binding {
+                    MouseBinding::LeftClick => &bindings.left_click,
+                    MouseBinding::MiddleClick => &bindings.middle_click,
+                    MouseBinding::RightClick => &bindings.right_click,
+                    MouseBinding::ScrollLeft => &bindings.scroll_left,
+                    MouseBinding::ScrollRight => &bindings.scroll_right,
                 };
-                Self::update(self, Message::ChangeWorkspace(next_workspace.id));
+
+                match action.clone() {
+                    Some(WorkspaceAction::ChangeWorkspace) if id > 0 => {
+                        self.update(Message::ChangeWorkspace(id));
+                    }
+                    Some(WorkspaceAction::ChangeWorkspace)
+                    | Some(WorkspaceAction::ToggleSpecialWorkspace) => {
+                        self.update(Message::ToggleSpecialWorkspace(id));
+                    }
+                    // hyprland reads the negative ids as relative workspaces
+                    Some(WorkspaceAction::MoveFocusedWindow) if id > 0 => {
+                        if let Err(e) = WM::move_window_to_workspace(None, id, &self.config) {
+                            log::error!("failed to move the focused window to workspace: {e:?}");
+                        }
+                    }
+                    Some(WorkspaceAction::Command(command)) => {
+                        let workspace = self.workspaces.iter().find(|w| w.id == id);
+                        let number = workspace.map_or(id, Workspace::number);
+                        let name = workspace.map(|w| w.name.as_str()).unwrap_or_default();
+
+                        crate::utils::launcher::execute_command(
+                            command
+                                .replace("{id}", &number.to_string())
+                                .replace("{name}", name),
+                        );
+                    }
+                    Some(WorkspaceAction::MoveFocusedWindow) | None => {}
+                }
             }
         }
     }
 
-    pub fn view<'a>(
+    fn workspace_button<'a>(
         &'a self,
-        id: Id,
+        w: &'a Workspace,
         theme: &'a AshellTheme,
-        outputs: &Outputs,
     ) -> Element<'a, Message> {
-        use crate::config::WorkspaceVisibilityMode;
         use iced::{
             Length, alignment,
             widget::{MouseArea, Row, button, container, text},
         };
 
-        let monitor_name = outputs.get_monitor_name(id);
+        let empty = w.windows == 0;
+        let icons = if self.config.show_window_icons && self.config.style != WorkspaceStyle::Dots {
+            self.window_icons(w, theme.font_size.sm)
+        } else {
+            Vec::new()
+        };
+        // the fixed widths fit only the plain names
+        let with_icons = !icons.is_empty() || self.config.format.is_some();
 
-        Into::<Element<Message>>::into(
-            MouseArea::new(
-                Row::with_children(
-                    self.workspaces
-                        .iter()
-                        .filter_map(|w| {
-                            let show = match self.config.visibility_mode {
-                                WorkspaceVisibilityMode::All => true,
-                                WorkspaceVisibilityMode::MonitorSpecific => {
-                                    monitor_name
-                                        .unwrap_or_else(|| &w.monitor)
-                                        .contains(&w.monitor)
-                                        || !outputs.has_name(&w.monitor)
-                                }
-                                WorkspaceVisibilityMode::MonitorSpecificExclusive => monitor_name
-                                    .unwrap_or_else(|| &w.monitor)
-                                    .contains(&w.monitor),
-                            };
-                            if show {
-                                let empty = w.windows == 0;
-
-                                let color_index = if self.config.enable_virtual_desktops {
-                                    // For virtual desktops, we use the workspace ID as the index
-                                    Some(w.id as i128)
-                                } else {
-                                    // For normal workspaces, we use the monitor ID as the index
-                                    w.monitor_id
-                                };
-                                let color = color_index.map(|i| {
-                                    if w.id > 0 {
-                                        theme.workspace_colors.get(i as usize).copied()
-                                    } else {
-                                        theme
-                                            .special_workspace_colors
-                                            .as_ref()
-                                            .unwrap_or(&theme.workspace_colors)
-                                            .get(i as usize)
-                                            .copied()
-                                    }
-                                });
-
-                                Some(
-                                    button(
-                                        container(text(w.name.as_str()).size(theme.font_size.xs))
-                                            .align_x(alignment::Horizontal::Center)
-                                            .align_y(alignment::Vertical::Center),
-                                    )
-                                    .style(theme.workspace_button_style(empty, color))
-                                    .padding(if w.id < 0 {
-                                        match w.displayed {
-                                            Displayed::Active => [0, theme.space.md],
-                                            Displayed::Visible => [0, theme.space.sm],
-                                            Displayed::Hidden => [0, theme.space.xs],
-                                        }
-                                    } else {
-                                        [0, 0]
-                                    })
-                                    .on_press(if w.id > 0 {
-                                        Message::ChangeWorkspace(w.id)
-                                    } else {
-                                        Message::ToggleSpecialWorkspace(w.id)
-                                    })
-                                    .width(match (w.id < 0, &w.displayed) {
-                                        (true, _) => Length::Shrink,
-                                        (_, Displayed::Active) => {
-                                            Length::Fixed(theme.space.xl as f32)
-                                        }
-                                        (_, Displayed::Visible) => {
-                                            Length::Fixed(theme.space.lg as f32)
-                                        }
-                                        (_, Displayed::Hidden) => {
-                                            Length::Fixed(theme.space.md as f32)
-                                        }
-                                    })
-                                    .height(theme.space.md)
-                                    .into(),
-                                )
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<Element<'_, _, _>>>(),
+        let color_index = if self.config.enable_virtual_desktops {
+            // For virtual desktops, we use the workspace ID as the index
+            Some(w.id as i128)
+        } else {
+            // For normal workspaces, we use the monitor ID as the index
+            w.monitor_id
+        };
+        let color = if w.urgent {
+            Some(Some(theme.urgent_workspace_color))
+        } else {
+            color_index.map(|i| {
+                if w.id > 0 {
+                    theme.workspace_colors.get(i as usize).copied()
+                } else {
+                    theme
+                        .special_workspace_colors
+                        .as_ref()
+                        .unwrap_or(&theme.workspace_colors)
+                        .get(i as usize)
+                        .copied()
+                }
+            })
+        };
+
+        let workspace = match self.config.style {
+            // the active dot stretches, the hidden ones are the smallest
+            WorkspaceStyle::Dots => button(Row::new())
+                .padding(0)
+                .width(match w.displayed {
+                    Displayed::Active => theme.space.lg,
+                    Displayed::Visible => theme.space.sm,
+                    Displayed::Hidden => theme.space.xs,
+                })
+                .height(theme.space.xs),
+            WorkspaceStyle::Pills | WorkspaceStyle::Numbers => {
+                // the numbers have the same size whatever the state
+                let shrink = with_icons || self.config.style == WorkspaceStyle::Numbers;
+
+                button(
+                    container(
+                        Row::new()
+                            .push(text(self.label(w)).size(theme.font_size.xs))
+                            .extend(icons)
+                            .spacing(theme.space.xxs)
+                            .align_y(alignment::Vertical::Center),
+                    )
+                    .align_x(alignment::Horizontal::Center)
+                    .align_y(alignment::Vertical::Center),
                 )
-                .spacing(theme.space.xxs),
-            )
-            .on_scroll(move |direction| {
-                let delta = match direction {
-                    iced::mouse::ScrollDelta::Lines { y, .. } => y,
-                    iced::mouse::ScrollDelta::Pixels { y, .. } => y,
+                .padding(if shrink {
+                    [0, theme.space.xs]
+                } else if w.id < 0 {
+                    match w.displayed {
+                        Displayed::Active => [0, theme.space.md],
+                        Displayed::Visible => [0, theme.space.sm],
+                        Displayed::Hidden => [0, theme.space.xs],
+                    }
+                } else {
+                    [0, 0]
+                })
+                .width(match (w.id < 0 || shrink, &w.displayed) {
+                    (true, _) => Length::Shrink,
+                    (_, Displayed::Active) => Length::Fixed(theme.space.xl as f32),
+                    (_, Displayed::Visible) => Length::Fixed(theme.space.lg as f32),
+                    (_, Displayed::Hidden) => Length::Fixed(theme.space.md as f32),
+                })
+                .height(theme.space.md)
+            }
+        };
+
+        // a window dragged from the window title is dropped here
+        MouseArea::new(
+            workspace
+                .style(theme.workspace_button_style(empty, self.focused == Some(w.id), color))
+                .on_press(Message::MouseAction(w.id, MouseBinding::LeftClick)),
+        )
+        .on_release(Message::WindowDropped(w.id))
+        .on_middle_press(Message::MouseAction(w.id, MouseBinding::MiddleClick))
+        .on_right_press(Message::MouseAction(w.id, MouseBinding::RightClick))
+        .on_scroll({
+            let id = w.id;
+            let monitor = w.monitor.clone();
+            move |delta| {
+                let (x, y) = match delta {
+                    ScrollDelta::Lines { x, y } => (x, y),
+                    ScrollDelta::Pixels { x, y } => (x, y),
                 };
 
-                // Scrolling down should increase workspace ID
-                if delta < 0.0 {
-                    Message::Scroll(1)
+                if x.abs() > y.abs() {
+                    Message::MouseAction(
+                        id,
+                        if x < 0.0 {
+                            MouseBinding::ScrollRight
+                        } else {
+                            MouseBinding::ScrollLeft
+                        },
+                    )
                 } else {
-                    Message::Scroll(-1)
+                    scroll_message(y, Some(monitor.clone()))
                 }
-            }),
-        )
+            }
+        })
+        .into()
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        id: Id,
+        theme: &'a AshellTheme,
+        outputs: &Outputs,
+    ) -> Element<'a, Message> {
+        use crate::config::WorkspaceVisibilityMode;
+        use iced::widget::{MouseArea, Row, container, text, vertical_rule};
+
+        let monitor_name = outputs.get_monitor_name(id);
+
+        let workspaces = self.workspaces.iter().filter(|w| {
+            let show = match self.config.visibility_mode {
+                WorkspaceVisibilityMode::All => true,
+                WorkspaceVisibilityMode::MonitorSpecific => {
+                    monitor_name
+                        .unwrap_or_else(|| &w.monitor)
+                        .contains(&w.monitor)
+                        || !outputs.has_name(&w.monitor)
+                }
+                WorkspaceVisibilityMode::MonitorSpecificExclusive => monitor_name
+                    .unwrap_or_else(|| &w.monitor)
+                    .contains(&w.monitor),
+            };
+
+            show && !self.is_hidden(w)
+        });
+
+        let content = if self.config.group_by_monitor
+            && self.config.visibility_mode == WorkspaceVisibilityMode::All
+        {
+            // the monitors are in the order of their first workspace
+            let mut groups: Vec<(&str, Vec<&Workspace>)> = Vec::new();
+            for w in workspaces {
+                match groups.iter_mut().find(|(monitor, _)| *monitor == w.monitor) {
+                    Some((_, group)) => group.push(w),
+                    None => groups.push((&w.monitor, vec![w])),
+                }
+            }
+
+            Row::with_children(groups.into_iter().enumerate().map(|(i, (monitor, group))| {
+                Row::new()
+                    .push_maybe((i > 0).then(|| container(vertical_rule(1)).height(theme.space.md)))
+                    .push_maybe(
+                        (self.config.show_monitor_names && !monitor.is_empty())
+                            .then(|| text(monitor).size(theme.font_size.xs)),
+                    )
+                    .extend(group.into_iter().map(|w| self.workspace_button(w, theme)))
+                    .spacing(theme.space.xxs)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+            }))
+            .spacing(theme.space.xs)
+        } else {
+            Row::with_children(workspaces.map(|w| self.workspace_button(w, theme)))
+                .spacing(theme.space.xxs)
+        };
+
+        // the workspaces are outdated while the listener is down
+        let content = Row::new()
+            .push_maybe(self.listener_down.then(|| {
+                container(icon(StaticIcon::Alert)).style(|theme: &Theme| container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                })
+            }))
+            .push(content)
+            .spacing(theme.space.xxs)
+            .align_y(iced::Alignment::Center);
+
+        let monitor_name = monitor_name.map(str::to_string);
+        MouseArea::new(content)
+            .on_scroll(move |direction| {
+                let delta = match direction {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y,
+                };
+
+                scroll_message(delta, monitor_name.clone())
+            })
+            .into()
     }
 
-    pub fn subscription(&self) -> Subscription<Message> {
-        WM::create_subscription(&self.config)
+    pub fn subscription(&self, instance: &str) -> Subscription<Message> {
+        Subscription::batch(vec![
+            WM::create_subscription(&self.watchdog.instance(instance), &self.config),
+            every(watchdog::CHECK_INTERVAL).map(|_| Message::WatchdogCheck),
+        ])
+    }
+}
+
+fn scroll_message(delta: f32, monitor: Option<String>) -> Message {
+    // Scrolling down should increase workspace ID
+    if delta < 0.0 {
+        Message::Scroll(1, monitor)
+    } else {
+        Message::Scroll(-1, monitor)
     }
 }
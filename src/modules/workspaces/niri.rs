@@ -1,51 +1,215 @@
-use super::{Message, Workspace, WorkspaceManager};
+use super::{Displayed, Message, Workspace, WorkspaceManager};
 use crate::config::WorkspacesModuleConfig;
-use iced::{Subscription, stream::channel};
-use std::future::pending;
-use std::{
-    any::TypeId,
-    sync::{Arc, RwLock},
+use iced::{
+    Subscription,
+    futures::{SinkExt, channel::mpsc::Sender},
+    stream::channel,
 };
-use tokio::task;
+use itertools::Itertools;
+use log::{debug, error};
+use niri_ipc::{
+    Action, Event, Reply, Request, Response, Window, WorkspaceReferenceArg,
+    socket::{SOCKET_PATH_ENV, Socket},
+};
+use std::{any::TypeId, collections::HashMap, env, io, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+    time::sleep,
+};
+
+fn request(request: Request) -> io::Result<Response> {
+    Socket::connect()?.send(request)?.map_err(io::Error::other)
+}
+
+fn fetch_workspaces(config: &WorkspacesModuleConfig) -> io::Result<Vec<Workspace>> {
+    let Response::Workspaces(workspaces) = request(Request::Workspaces)? else {
+        return Err(io::Error::other(
+            "unexpected response to the workspaces request",
+        ));
+    };
+    let Response::Windows(windows) = request(Request::Windows)? else {
+        return Err(io::Error::other(
+            "unexpected response to the windows request",
+        ));
+    };
+
+    let outputs = workspaces
+        .iter()
+        .filter_map(|w| w.output.clone())
+        .unique()
+        .sorted()
+        .collect::<Vec<_>>();
+
+    Ok(workspaces
+        .into_iter()
+        .sorted_by(|a, b| a.output.cmp(&b.output).then(a.idx.cmp(&b.idx)))
+        .map(|w| {
+            let monitor = w.output.unwrap_or_default();
+
+            Workspace {
+                id: w.id as i32,
+                name: w.name.unwrap_or_else(|| {
+                    config
+                        .workspace_names
+                        .get((w.idx as usize).saturating_sub(1))
+                        .cloned()
+                        .unwrap_or_else(|| w.idx.to_string())
+                }),
+                monitor_id: outputs
+                    .iter()
+                    .position(|o| *o == monitor)
+                    .map(|i| i as i128),
+                monitor,
+                idx: Some(w.idx),
+                displayed: match (w.is_focused, w.is_active) {
+                    (true, _) => Displayed::Active,
+                    (false, true) => Displayed::Visible,
+                    (false, false) => Displayed::Hidden,
+                },
+                windows: windows
+                    .iter()
+                    .filter(|window| window.workspace_id == Some(w.id))
+                    .count() as u16,
+                urgent: windows
+                    .iter()
+                    .any(|window| window.workspace_id == Some(w.id) && window.is_urgent),
+                window_classes: if config.show_window_icons {
+                    windows
+                        .iter()
+                        .filter(|window| window.workspace_id == Some(w.id))
+                        .filter_map(|window| window.app_id.clone())
+                        .collect()
+                } else {
+                    Vec::new()
+                },
+            }
+        })
+        .collect())
+}
+
+/// What the workspaces show of a window, its workspace, its app and its urgency
+fn window_state(window: &Window) -> (Option<u64>, Option<String>, bool) {
+    (window.workspace_id, window.app_id.clone(), window.is_urgent)
+}
+
+/// Reads the niri event stream until the socket is closed, the stream is
+/// read asynchronously so that it ends with the subscription
+async fn listen_events(output: &mut Sender<Message>) -> io::Result<()> {
+    let path = env::var_os(SOCKET_PATH_ENV)
+        .ok_or_else(|| io::Error::other(format!("{SOCKET_PATH_ENV} is not set")))?;
+    let (reader, mut writer) = UnixStream::connect(path).await?.into_split();
+
+    let mut request = serde_json::to_string(&Request::EventStream)?;
+    request.push('\n');
+    writer.write_all(request.as_bytes()).await?;
+
+    let mut lines = BufReader::new(reader).lines();
+    let reply = lines
+        .next_line()
+        .await?
+        .ok_or_else(|| io::Error::other("niri closed the event stream"))?;
+    serde_json::from_str::<Reply>(&reply)?.map_err(io::Error::other)?;
+
+    // the windows are tracked to leave out the title changes
+    let mut windows = HashMap::new();
+    while let Some(line) = lines.next_line().await? {
+        // the events added by a newer niri are skipped
+        let Ok(event) = serde_json::from_str::<Event>(&line) else {
+            continue;
+        };
 
-#[derive(Debug, Clone)]
-pub struct VirtualDesktop {
-    pub active: bool,
-    pub windows: u16,
+        let changed = match event {
+            Event::WorkspacesChanged { .. } | Event::WorkspaceActivated { .. } => true,
+            Event::WindowsChanged { windows: all } => {
+                windows = all
+                    .iter()
+                    .map(|window| (window.id, window_state(window)))
+                    .collect();
+                true
+            }
+            Event::WindowOpenedOrChanged { window } => {
+                let state = window_state(&window);
+                windows.insert(window.id, state.clone()) != Some(state)
+            }
+            Event::WindowClosed { id } => windows.remove(&id).is_some(),
+            Event::WindowUrgencyChanged { id, urgent } => windows
+                .get_mut(&id)
+                .is_some_and(|(_, _, is_urgent)| std::mem::replace(is_urgent, urgent) != urgent),
+            _ => false,
+        };
+
+        if changed {
+            debug!("niri workspaces changed");
+            let _ = output.send(Message::WorkspacesChanged).await;
+        }
+    }
+
+    Ok(())
 }
 
 pub struct NiriWorkspaceManager;
 
 impl WorkspaceManager for NiriWorkspaceManager {
-    fn get_workspaces(_config: &WorkspacesModuleConfig) -> Vec<Workspace> {
-        vec![]
+    fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace> {
+        fetch_workspaces(config).unwrap_or_else(|e| {
+            error!("failed to get niri workspaces: {e}");
+
+            Vec::new()
+        })
     }
 
-    fn create_subscription(_config: &WorkspacesModuleConfig) -> Subscription<Message> {
-        let id = TypeId::of::<Self>();
+    fn create_subscription(
+        instance: &str,
+        _config: &WorkspacesModuleConfig,
+    ) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
 
         Subscription::run_with_id(
             id,
-            channel(10, async |output| {
-                let _output = Arc::new(RwLock::new(output));
+            channel(10, async |mut output| {
                 loop {
-                    task::spawn(async move {
-                        pending::<()>().await;
-                    })
-                    .await
-                    .unwrap();
+                    if let Err(e) = listen_events(&mut output).await {
+                        error!("niri event stream error: {e}");
+                    }
+
+                    // avoid a busy loop when the socket is not available
+                    sleep(Duration::from_secs(1)).await;
                 }
             }),
         )
     }
 
     fn change_workspace(
-        _id: i32,
+        id: i32,
         _config: &WorkspacesModuleConfig,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("changing workspace to: {id}");
+
+        request(Request::Action(Action::FocusWorkspace {
+            reference: WorkspaceReferenceArg::Id(id as u64),
+        }))?;
+
+        Ok(())
+    }
+
+    fn move_window_to_workspace(
+        window_id: Option<&str>,
+        workspace_id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("moving window {window_id:?} to workspace {workspace_id}");
+
+        request(Request::Action(Action::MoveWindowToWorkspace {
+            window_id: window_id.map(str::parse).transpose()?,
+            reference: WorkspaceReferenceArg::Id(workspace_id as u64),
+            focus: false,
+        }))?;
+
         Ok(())
     }
 
+    // niri has no special workspaces
     fn toggle_special_workspace(_workspace: &Workspace) -> Result<(), Box<dyn std::error::Error>> {
         Ok(())
     }
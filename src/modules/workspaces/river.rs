@@ -0,0 +1,493 @@
+use super::{Displayed, Message, Workspace, WorkspaceManager};
+use crate::config::WorkspacesModuleConfig;
+use iced::{
+    Subscription,
+    futures::{SinkExt, future::pending},
+    stream::channel,
+};
+use log::{debug, error, warn};
+use std::{any::TypeId, collections::HashMap, sync::LazyLock, thread};
+use tokio::sync::watch;
+use wayland_client::{
+    Connection, Dispatch, Proxy, QueueHandle,
+    backend::ObjectId,
+    protocol::{
+        wl_output::{self, WlOutput},
+        wl_registry::{self, WlRegistry},
+        wl_seat::WlSeat,
+    },
+};
+
+/// Bindings of the River protocols, generated from their definitions in `protocols/`
+#[allow(
+    non_upper_case_globals,
+    non_camel_case_types,
+    unused_imports,
+    clippy::all
+)]
+mod protocols {
+    pub mod status {
+        use wayland_client;
+        use wayland_client::protocol::*;
+
+        pub mod __interfaces {
+            use wayland_client::protocol::__interfaces::*;
+            wayland_scanner::generate_interfaces!("protocols/river-status-unstable-v1.xml");
+        }
+        use self::__interfaces::*;
+
+        wayland_scanner::generate_client_code!("protocols/river-status-unstable-v1.xml");
+    }
+
+    pub mod control {
+        use wayland_client;
+        use wayland_client::protocol::*;
+
+        pub mod __interfaces {
+            use wayland_client::protocol::__interfaces::*;
+            wayland_scanner::generate_interfaces!("protocols/river-control-unstable-v1.xml");
+        }
+        use self::__interfaces::*;
+
+        wayland_scanner::generate_client_code!("protocols/river-control-unstable-v1.xml");
+    }
+}
+
+use protocols::{
+    control::{
+        zriver_command_callback_v1::{self, ZriverCommandCallbackV1},
+        zriver_control_v1::ZriverControlV1,
+    },
+    status::{
+        zriver_output_status_v1::{self, ZriverOutputStatusV1},
+        zriver_seat_status_v1::{self, ZriverSeatStatusV1},
+        zriver_status_manager_v1::ZriverStatusManagerV1,
+    },
+};
+
+/// The first tags are shown as the workspaces, River has 32 of them
+const TAG_COUNT: u32 = 9;
+/// The ids of the workspaces of each output, in the order of the output names
+const OUTPUT_ID_STRIDE: i32 = 10;
+
+/// Tags of an output, as bitmasks
+#[derive(Debug, Clone, Default)]
+struct RiverOutput {
+    name: String,
+    focused: bool,
+    focused_tags: u32,
+    urgent_tags: u32,
+    /// tags of each window of the output
+    view_tags: Vec<u32>,
+}
+
+#[derive(Default)]
+struct RiverState {
+    status_manager: Option<ZriverStatusManagerV1>,
+    control: Option<ZriverControlV1>,
+    seat: Option<WlSeat>,
+    /// the bound outputs, with the global name to forget them when they're removed
+    wl_outputs: HashMap<u32, WlOutput>,
+    outputs: HashMap<ObjectId, RiverOutput>,
+    focused_output: Option<ObjectId>,
+    tx: Option<watch::Sender<Vec<RiverOutput>>>,
+}
+
+impl RiverState {
+    fn snapshot(&self) -> Vec<RiverOutput> {
+        let mut outputs = self
+            .outputs
+            .iter()
+            .map(|(id, output)| RiverOutput {
+                focused: self.focused_output.as_ref() == Some(id),
+                ..output.clone()
+            })
+            .collect::<Vec<_>>();
+        outputs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        outputs
+    }
+
+    /// River has no atomic set of changes, every event is published
+    fn publish(&self) {
+        if let Some(tx) = &self.tx {
+            tx.send_replace(self.snapshot());
+        }
+    }
+
+    fn track_output(&self, output: &WlOutput, handle: &QueueHandle<Self>) {
+        if let Some(manager) = &self.status_manager {
+            manager.get_river_output_status(output, handle, output.id());
+        }
+    }
+}
+
+struct RiverClient {
+    connection: Connection,
+    handle: QueueHandle<RiverState>,
+    control: ZriverControlV1,
+    seat: WlSeat,
+    outputs: watch::Receiver<Vec<RiverOutput>>,
+}
+
+impl RiverClient {
+    fn connect() -> anyhow::Result<Self> {
+        let connection = Connection::connect_to_env()?;
+        let mut event_queue = connection.new_event_queue();
+        let handle = event_queue.handle();
+        connection.display().get_registry(&handle, ());
+
+        let mut state = RiverState::default();
+        // the first roundtrip binds the globals
+        event_queue.roundtrip(&mut state)?;
+
+        let status_manager = state
+            .status_manager
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("the compositor doesn't support river-status"))?;
+        let control = state
+            .control
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("the compositor doesn't support river-control"))?;
+        let seat = state
+            .seat
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("the compositor has no seat"))?;
+
+        // the outputs bound before the status manager
+        for output in state.wl_outputs.values() {
+            state.track_output(output, &handle);
+        }
+        status_manager.get_river_seat_status(&seat, &handle, ());
+
+        // the second roundtrip receives the tags
+        event_queue.roundtrip(&mut state)?;
+
+        let (tx, outputs) = watch::channel(state.snapshot());
+        state.tx = Some(tx);
+
+        thread::spawn(move || {
+            loop {
+                if let Err(e) = event_queue.blocking_dispatch(&mut state) {
+                    error!("river event queue error: {e}");
+
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            connection,
+            handle,
+            control,
+            seat,
+            outputs,
+        })
+    }
+
+    /// Runs a River command, like `riverctl` does
+    fn run_command(&self, args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("running river command: {args:?}");
+
+        for arg in args {
+            self.control.add_argument(arg.to_string());
+        }
+        self.control.run_command(&self.seat, &self.handle, ());
+        self.connection.flush()?;
+
+        Ok(())
+    }
+}
+
+static CLIENT: LazyLock<Option<RiverClient>> = LazyLock::new(|| {
+    RiverClient::connect()
+        .inspect_err(|e| error!("failed to connect to river: {e}"))
+        .ok()
+});
+
+/// The output and the tag bitmask of the workspace
+fn decode_id(id: i32, outputs: &[RiverOutput]) -> Option<(&RiverOutput, u32)> {
+    let index = id - 1;
+    let tag = (index % OUTPUT_ID_STRIDE) as u32;
+    let output = outputs.get((index / OUTPUT_ID_STRIDE) as usize)?;
+
+    (index >= 0 && tag < TAG_COUNT).then_some((output, 1 << tag))
+}
+
+pub struct RiverWorkspaceManager;
+
+impl WorkspaceManager for RiverWorkspaceManager {
+    fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace> {
+        let Some(client) = CLIENT.as_ref() else {
+            return Vec::new();
+        };
+        let outputs = client.outputs.borrow();
+
+        // the tags are per output, each output has its own workspaces
+        outputs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, output)| {
+                (0..TAG_COUNT).map(move |tag| {
+                    let mask = 1 << tag;
+
+                    Workspace {
+                        id: i as i32 * OUTPUT_ID_STRIDE + tag as i32 + 1,
+                        name: config
+                            .workspace_names
+                            .get(tag as usize)
+                            .cloned()
+                            .unwrap_or_else(|| (tag + 1).to_string()),
+                        monitor_id: Some(i as i128),
+                        monitor: output.name.clone(),
+                        idx: None,
+                        // several tags can be focused at once
+                        displayed: match (output.focused_tags & mask != 0, output.focused) {
+                            (true, true) => Displayed::Active,
+                            (true, false) => Displayed::Visible,
+                            (false, _) => Displayed::Hidden,
+                        },
+                        windows: output
+                            .view_tags
+                            .iter()
+                            .filter(|tags| *tags & mask != 0)
+                            .count() as u16,
+                        urgent: output.urgent_tags & mask != 0,
+                        window_classes: Vec::new(),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn create_subscription(
+        instance: &str,
+        _config: &WorkspacesModuleConfig,
+    ) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                if let Some(client) = CLIENT.as_ref() {
+                    let mut outputs = client.outputs.clone();
+
+                    while outputs.changed().await.is_ok() {
+                        debug!("river tags changed");
+                        let _ = output.send(Message::WorkspacesChanged).await;
+                    }
+
+                    warn!("river event queue stopped");
+                }
+
+                pending::<()>().await;
+            }),
+        )
+    }
+
+    fn change_workspace(
+        id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("changing workspace to: {id}");
+
+        let client = CLIENT.as_ref().ok_or("river is not available")?;
+        let outputs = client.outputs.borrow();
+        let (output, mask) = decode_id(id, &outputs).ok_or("unknown workspace")?;
+
+        if !output.focused {
+            client.run_command(&["focus-output", &output.name])?;
+        }
+        client.run_command(&["set-focused-tags", &mask.to_string()])
+    }
+
+    // River has no special workspaces
+    fn toggle_special_workspace(_workspace: &Workspace) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    fn move_window_to_workspace(
+        window_id: Option<&str>,
+        workspace_id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // the commands only apply to the focused window
+        if window_id.is_some() {
+            return Err("river can only move the focused window".into());
+        }
+
+        let client = CLIENT.as_ref().ok_or("river is not available")?;
+        let outputs = client.outputs.borrow();
+        let (output, mask) = decode_id(workspace_id, &outputs).ok_or("unknown workspace")?;
+
+        if !output.focused {
+            client.run_command(&["send-to-output", &output.name])?;
+        }
+        client.run_command(&["set-view-tags", &mask.to_string()])
+    }
+}
+
+impl Dispatch<WlRegistry, ()> for RiverState {
+    fn event(
+        state: &mut Self,
+        proxy: &WlRegistry,
+        event: <WlRegistry as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        handle: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } => {
+                if interface == ZriverStatusManagerV1::interface().name {
+                    debug!("binding river status manager with version {version}");
+                    state.status_manager = Some(proxy.bind(name, version.min(4), handle, ()));
+                } else if interface == ZriverControlV1::interface().name {
+                    state.control = Some(proxy.bind(name, version.min(1), handle, ()));
+                } else if interface == WlSeat::interface().name && state.seat.is_none() {
+                    state.seat = Some(proxy.bind(name, version.min(1), handle, ()));
+                } else if interface == WlOutput::interface().name {
+                    // the output name is sent since version 4
+                    let output: WlOutput = proxy.bind(name, version.min(4), handle, ());
+                    state.outputs.insert(output.id(), RiverOutput::default());
+                    // the outputs plugged later are tracked right away
+                    state.track_output(&output, handle);
+                    state.wl_outputs.insert(name, output);
+                }
+            }
+            wl_registry::Event::GlobalRemove { name } => {
+                if let Some(output) = state.wl_outputs.remove(&name) {
+                    state.outputs.remove(&output.id());
+                    state.publish();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlOutput, ()> for RiverState {
+    fn event(
+        state: &mut Self,
+        proxy: &WlOutput,
+        event: <WlOutput as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event
+            && let Some(output) = state.outputs.get_mut(&proxy.id())
+        {
+            output.name = name;
+            state.publish();
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for RiverState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSeat,
+        _event: <WlSeat as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZriverStatusManagerV1, ()> for RiverState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZriverStatusManagerV1,
+        _event: <ZriverStatusManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZriverOutputStatusV1, ObjectId> for RiverState {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZriverOutputStatusV1,
+        event: <ZriverOutputStatusV1 as Proxy>::Event,
+        output: &ObjectId,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        let Some(output) = state.outputs.get_mut(output) else {
+            return;
+        };
+
+        match event {
+            zriver_output_status_v1::Event::FocusedTags { tags } => output.focused_tags = tags,
+            zriver_output_status_v1::Event::ViewTags { tags } => {
+                output.view_tags = tags
+                    .chunks_exact(4)
+                    .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+            }
+            zriver_output_status_v1::Event::UrgentTags { tags } => output.urgent_tags = tags,
+            _ => return,
+        }
+
+        state.publish();
+    }
+}
+
+impl Dispatch<ZriverSeatStatusV1, ()> for RiverState {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZriverSeatStatusV1,
+        event: <ZriverSeatStatusV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zriver_seat_status_v1::Event::FocusedOutput { output } => {
+                state.focused_output = Some(output.id());
+            }
+            zriver_seat_status_v1::Event::UnfocusedOutput { output } => {
+                if state.focused_output == Some(output.id()) {
+                    state.focused_output = None;
+                }
+            }
+            _ => return,
+        }
+
+        state.publish();
+    }
+}
+
+impl Dispatch<ZriverControlV1, ()> for RiverState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZriverControlV1,
+        _event: <ZriverControlV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZriverCommandCallbackV1, ()> for RiverState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZriverCommandCallbackV1,
+        event: <ZriverCommandCallbackV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let zriver_command_callback_v1::Event::Failure { failure_message } = event {
+            error!("river command failed: {failure_message}");
+        }
+    }
+}
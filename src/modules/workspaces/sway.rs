@@ -0,0 +1,242 @@
+use super::{
+    Displayed, Message, Workspace, WorkspaceManager, add_persistent_workspaces, display_name,
+};
+use crate::config::WorkspacesModuleConfig;
+use iced::{
+    Subscription,
+    futures::{SinkExt, StreamExt, executor::block_on},
+    stream::channel,
+};
+use itertools::Itertools;
+use log::{debug, error};
+use std::{any::TypeId, time::Duration};
+use swayipc_async::{Connection, Event, EventType, Node, NodeType, WindowChange};
+use tokio::time::sleep;
+
+/// Collects the app_id, or the class of the xwayland windows, of the windows in the node
+fn collect_window_classes(node: &Node, classes: &mut Vec<String>) {
+    if matches!(node.node_type, NodeType::Con | NodeType::FloatingCon)
+        && node.pid.is_some()
+        && let Some(class) = node.app_id.clone().or_else(|| {
+            node.window_properties
+                .as_ref()
+                .and_then(|p| p.class.clone())
+        })
+    {
+        classes.push(class);
+    }
+
+    for child in node.nodes.iter().chain(node.floating_nodes.iter()) {
+        collect_window_classes(child, classes);
+    }
+}
+
+async fn fetch_workspaces(
+    config: &WorkspacesModuleConfig,
+) -> Result<Vec<Workspace>, swayipc_async::Error> {
+    let mut conn = Connection::new().await?;
+    let outputs = conn.get_outputs().await?;
+    let workspaces = conn.get_workspaces().await?;
+    let tree = if config.show_window_icons {
+        Some(conn.get_tree().await?)
+    } else {
+        None
+    };
+
+    // named workspaces without a number can't be reached with `workspace number`
+    let mut result = workspaces
+        .into_iter()
+        .filter(|w| w.num > 0)
+        .unique_by(|w| w.num)
+        .map(|w| Workspace {
+            id: w.num,
+            name: if config.workspace_names.is_empty() {
+                w.name.clone()
+            } else {
+                display_name(config, w.num)
+            },
+            monitor_id: outputs
+                .iter()
+                .position(|o| o.name == w.output)
+                .map(|i| i as i128),
+            monitor: w.output,
+            idx: None,
+            displayed: match (w.focused, w.visible) {
+                (true, _) => Displayed::Active,
+                (false, true) => Displayed::Visible,
+                (false, false) => Displayed::Hidden,
+            },
+            windows: w.focus.len() as u16,
+            urgent: w.urgent,
+            window_classes: tree
+                .as_ref()
+                .and_then(|tree| {
+                    tree.find_as_ref(|n| n.node_type == NodeType::Workspace && n.num == Some(w.num))
+                })
+                .map(|workspace| {
+                    let mut classes = Vec::new();
+                    collect_window_classes(workspace, &mut classes);
+
+                    classes
+                })
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+
+    if config.enable_workspace_filling && !result.is_empty() {
+        let max_id = result
+            .iter()
+            .map(|w| w.id)
+            .max()
+            .unwrap_or_default()
+            .max(config.max_workspaces.unwrap_or_default() as i32);
+        let missing_ids = (1..=max_id)
+            .filter(|id| !result.iter().any(|w| w.id == *id))
+            .collect::<Vec<_>>();
+
+        result.extend(missing_ids.into_iter().map(|id| Workspace {
+            id,
+            name: display_name(config, id),
+            monitor_id: None,
+            monitor: "".to_string(),
+            idx: None,
+            displayed: Displayed::Hidden,
+            windows: 0,
+            urgent: false,
+            window_classes: Vec::new(),
+        }));
+    }
+
+    add_persistent_workspaces(&mut result, config, |monitor| {
+        outputs
+            .iter()
+            .position(|o| o.name == monitor)
+            .map(|i| i as i128)
+    });
+
+    result.sort_by_key(|w| w.id);
+
+    Ok(result)
+}
+
+pub struct SwayWorkspaceManager;
+
+impl WorkspaceManager for SwayWorkspaceManager {
+    fn get_workspaces(config: &WorkspacesModuleConfig) -> Vec<Workspace> {
+        block_on(fetch_workspaces(config)).unwrap_or_else(|e| {
+            error!("failed to get sway workspaces: {e}");
+
+            Vec::new()
+        })
+    }
+
+    fn create_subscription(
+        instance: &str,
+        _config: &WorkspacesModuleConfig,
+    ) -> Subscription<Message> {
+        let id = (TypeId::of::<Self>(), instance.to_string());
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                loop {
+                    let events = match Connection::new().await {
+                        Ok(conn) => {
+                            conn.subscribe([
+                                EventType::Workspace,
+                                EventType::Window,
+                                EventType::Output,
+                            ])
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                    match events {
+                        Ok(mut events) => {
+                            while let Some(event) = events.next().await {
+                                match event {
+                                    // only the windows that change the workspace content
+                                    Ok(Event::Window(e))
+                                        if !matches!(
+                                            e.change,
+                                            WindowChange::New
+                                                | WindowChange::Close
+                                                | WindowChange::Move
+                                                | WindowChange::Urgent
+                                        ) => {}
+                                    Ok(event) => {
+                                        debug!("sway event: {event:?}");
+                                        let _ = output.send(Message::WorkspacesChanged).await;
+                                    }
+                                    Err(e) => {
+                                        error!("sway event stream error: {e}");
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            error!("failed to subscribe to sway events: {e}");
+                        }
+                    }
+
+                    // avoid a busy loop when the socket is not available
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }),
+        )
+    }
+
+    fn change_workspace(
+        id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("changing workspace to: {id}");
+
+        block_on(async {
+            let mut conn = Connection::new().await?;
+
+            for res in conn.run_command(format!("workspace number {id}")).await? {
+                res?;
+            }
+
+            Ok::<_, swayipc_async::Error>(())
+        })
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
+
+    fn move_window_to_workspace(
+        window_id: Option<&str>,
+        workspace_id: i32,
+        _config: &WorkspacesModuleConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        debug!("moving window {window_id:?} to workspace {workspace_id}");
+
+        // without criteria the command moves the focused window
+        let criteria = window_id
+            .map(|id| format!("[con_id={id}] "))
+            .unwrap_or_default();
+
+        block_on(async {
+            let mut conn = Connection::new().await?;
+
+            for res in conn
+                .run_command(format!(
+                    "{criteria}move container to workspace number {workspace_id}"
+                ))
+                .await?
+            {
+                res?;
+            }
+
+            Ok::<_, swayipc_async::Error>(())
+        })
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
+    }
+
+    // sway has no special workspaces, the scratchpad is not reported as a workspace
+    fn toggle_special_workspace(_workspace: &Workspace) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+}
@@ -8,6 +8,7 @@ use iced::{
     window::Id,
 };
 use log::debug;
+use serde::Serialize;
 use wayland_client::protocol::wl_output::WlOutput;
 
 use crate::{
@@ -24,10 +25,28 @@ struct ShellInfo {
     style: AppearanceStyle,
     menu: Menu,
     scale_factor: f64,
+    /// the bar takes the keyboard when clicked
+    keyboard_navigation: bool,
+    /// shrunk to a line, without exclusive zone
+    hidden: bool,
 }
 
 #[derive(Debug, Clone)]
-pub struct Outputs(Vec<(String, Option<ShellInfo>, Option<WlOutput>)>);
+pub struct Outputs(
+    Vec<(String, Option<ShellInfo>, Option<WlOutput>)>,
+    /// the space of the bars is reserved with the Hyprland monitor rules, not the exclusive zone
+    bool,
+);
+
+/// What a bar takes on its output, answered to `ashell msg geometry`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BarGeometry {
+    pub output: String,
+    pub position: Position,
+    pub height: u32,
+    pub exclusive_zone: i32,
+    pub hidden: bool,
+}
 
 pub enum HasOutput<'a> {
     Main,
@@ -39,11 +58,20 @@ impl Outputs {
         style: AppearanceStyle,
         position: Position,
         scale_factor: f64,
+        keyboard_navigation: bool,
+        hyprland_reserved_area: bool,
     ) -> (Self, Task<Message>) {
-        let (id, menu_id, task) = Self::create_output_layers(style, None, position, scale_factor);
+        let (id, menu_id, task) = Self::create_output_layers(
+            style,
+            None,
+            position,
+            scale_factor,
+            keyboard_navigation,
+            hyprland_reserved_area,
+        );
 
-        (
-            Self(vec![(
+        let outputs = Self(
+            vec![(
                 "Fallback".to_string(),
                 Some(ShellInfo {
                     id,
@@ -51,11 +79,16 @@ impl Outputs {
                     position,
                     style,
                     scale_factor,
+                    keyboard_navigation,
+                    hidden: false,
                 }),
                 None,
-            )]),
-            task,
-        )
+            )],
+            hyprland_reserved_area,
+        );
+        outputs.publish_geometry();
+
+        (outputs, task)
     }
 
     fn get_height(style: AppearanceStyle, scale_factor: f64) -> f64 {
@@ -67,11 +100,21 @@ impl Outputs {
             * scale_factor
     }
 
+    fn exclusive_zone(height: f64, hyprland_reserved_area: bool) -> i32 {
+        if hyprland_reserved_area {
+            0
+        } else {
+            height as i32
+        }
+    }
+
     fn create_output_layers<Message: 'static>(
         style: AppearanceStyle,
         wl_output: Option<WlOutput>,
         position: Position,
         scale_factor: f64,
+        keyboard_navigation: bool,
+        hyprland_reserved_area: bool,
     ) -> (Id, Id, Task<Message>) {
         let id = Id::unique();
         let height = Self::get_height(style, scale_factor);
@@ -81,8 +124,8 @@ impl Outputs {
             namespace: "ashell-main-layer".to_string(),
             size: Some((None, Some(height as u32))),
             layer: Layer::Bottom,
-            keyboard_interactivity: KeyboardInteractivity::None,
-            exclusive_zone: height as i32,
+            keyboard_interactivity: Self::bar_keyboard_interactivity(keyboard_navigation),
+            exclusive_zone: Self::exclusive_zone(height, hyprland_reserved_area),
             output: wl_output.clone().map_or(IcedOutput::Active, |wl_output| {
                 IcedOutput::Output(wl_output)
             }),
@@ -111,6 +154,14 @@ impl Outputs {
         (id, menu_id, Task::batch(vec![task, menu_task]))
     }
 
+    fn bar_keyboard_interactivity(keyboard_navigation: bool) -> KeyboardInteractivity {
+        if keyboard_navigation {
+            KeyboardInteractivity::OnDemand
+        } else {
+            KeyboardInteractivity::None
+        }
+    }
+
     fn name_in_config(name: &str, outputs: &config::Outputs) -> bool {
         match outputs {
             config::Outputs::All => true,
@@ -147,6 +198,24 @@ impl Outputs {
         })
     }
 
+    pub fn get_output_name(&self, wl_output: &WlOutput) -> Option<&str> {
+        self.0.iter().find_map(|(name, _, output)| {
+            if output.as_ref() == Some(wl_output) {
+                Some(name.as_str())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// A bar is shown, not hidden by a special workspace
+    pub fn has_shown_bar(&self) -> bool {
+        self.0
+            .iter()
+            .filter_map(|(_, shell_info, _)| shell_info.as_ref())
+            .any(|shell_info| !shell_info.hidden)
+    }
+
     pub fn has_name(&self, name: &str) -> bool {
         self.0
             .iter()
@@ -162,14 +231,21 @@ impl Outputs {
         name: &str,
         wl_output: WlOutput,
         scale_factor: f64,
+        keyboard_navigation: bool,
     ) -> Task<Message> {
         let target = Self::name_in_config(name, request_outputs);
 
         if target {
             debug!("Found target output, creating a new layer surface");
 
-            let (id, menu_id, task) =
-                Self::create_output_layers(style, Some(wl_output.clone()), position, scale_factor);
+            let (id, menu_id, task) = Self::create_output_layers(
+                style,
+                Some(wl_output.clone()),
+                position,
+                scale_factor,
+                keyboard_navigation,
+                self.1,
+            );
 
             let destroy_task = match self.0.iter().position(|(key, _, _)| key.as_str() == name) {
                 Some(index) => {
@@ -196,6 +272,8 @@ impl Outputs {
                     position,
                     style,
                     scale_factor,
+                    keyboard_navigation,
+                    hidden: false,
                 }),
                 Some(wl_output),
             ));
@@ -223,6 +301,7 @@ impl Outputs {
                     }
                     _ => Task::none(),
                 };
+            self.publish_geometry();
 
             Task::batch(vec![destroy_task, destroy_fallback_task, task])
         } else {
@@ -238,6 +317,7 @@ impl Outputs {
         position: Position,
         wl_output: WlOutput,
         scale_factor: f64,
+        keyboard_navigation: bool,
     ) -> Task<Message> {
         match self.0.iter().position(|(_, _, assigned_wl_output)| {
             assigned_wl_output
@@ -261,12 +341,20 @@ impl Outputs {
                 self.0.push((name, None, wl_output));
 
                 if self.0.iter().any(|(_, shell_info, _)| shell_info.is_some()) {
+                    self.publish_geometry();
+
                     Task::batch(vec![destroy_task])
                 } else {
                     debug!("No outputs left, creating a fallback layer surface");
 
-                    let (id, menu_id, task) =
-                        Self::create_output_layers(style, None, position, scale_factor);
+                    let (id, menu_id, task) = Self::create_output_layers(
+                        style,
+                        None,
+                        position,
+                        scale_factor,
+                        keyboard_navigation,
+                        self.1,
+                    );
 
                     self.0.push((
                         "Fallback".to_string(),
@@ -276,9 +364,12 @@ impl Outputs {
                             position,
                             style,
                             scale_factor,
+                            keyboard_navigation,
+                            hidden: false,
                         }),
                         None,
                     ));
+                    self.publish_geometry();
 
                     Task::batch(vec![destroy_task, task])
                 }
@@ -287,12 +378,48 @@ impl Outputs {
         }
     }
 
+    /// Hides the bars of the outputs matching the predicate, the others are shown again
+    pub fn set_hidden<Message: 'static>(&mut self, hidden: impl Fn(&str) -> bool) -> Task<Message> {
+        let mut tasks = Vec::new();
+
+        for (name, shell_info, _) in self.0.iter_mut() {
+            let Some(shell_info) = shell_info else {
+                continue;
+            };
+            let hide = hidden(name);
+            if shell_info.hidden == hide {
+                continue;
+            }
+
+            debug!("Output {name} hidden: {hide}");
+            shell_info.hidden = hide;
+            let (height, exclusive_zone) = if hide {
+                (1, 0)
+            } else {
+                let height = Self::get_height(shell_info.style, shell_info.scale_factor);
+                (height as u32, Self::exclusive_zone(height, self.1))
+            };
+            tasks.push(Task::batch(vec![
+                set_size(shell_info.id, None, Some(height)),
+                set_exclusive_zone(shell_info.id, exclusive_zone),
+            ]));
+        }
+
+        if !tasks.is_empty() {
+            self.publish_geometry();
+        }
+
+        Task::batch(tasks)
+    }
+
     pub fn sync<Message: 'static>(
         &mut self,
         style: AppearanceStyle,
         request_outputs: &config::Outputs,
         position: Position,
         scale_factor: f64,
+        keyboard_navigation: bool,
+        hyprland_reserved_area: bool,
     ) -> Task<Message> {
         debug!("Syncing outputs: {self:?}, request_outputs: {request_outputs:?}");
 
@@ -324,6 +451,13 @@ impl Outputs {
         debug!("Adding outputs: {to_add:?}");
 
         let mut tasks = Vec::new();
+        // the new bars are created with the current setting
+        let reserved_area_changed = self.1 != hyprland_reserved_area;
+        self.1 = hyprland_reserved_area;
+        #[cfg(feature = "hyprland")]
+        if reserved_area_changed && !hyprland_reserved_area {
+            reserve_hyprland_area(&[]);
+        }
 
         for (name, wl_output) in to_add {
             if let Some(wl_output) = wl_output {
@@ -334,12 +468,19 @@ impl Outputs {
                     name.as_str(),
                     wl_output,
                     scale_factor,
+                    keyboard_navigation,
                 ));
             }
         }
 
         for wl_output in to_remove {
-            tasks.push(self.remove(style, position, wl_output, scale_factor));
+            tasks.push(self.remove(
+                style,
+                position,
+                wl_output,
+                scale_factor,
+                keyboard_navigation,
+            ));
         }
 
         for shell_info in self.0.iter_mut().filter_map(|(_, shell_info, _)| {
@@ -368,7 +509,9 @@ impl Outputs {
 
         for shell_info in self.0.iter_mut().filter_map(|(_, shell_info, _)| {
             if let Some(shell_info) = shell_info
-                && (shell_info.style != style || shell_info.scale_factor != scale_factor)
+                && (shell_info.style != style
+                    || shell_info.scale_factor != scale_factor
+                    || reserved_area_changed)
             {
                 Some(shell_info)
             } else {
@@ -381,16 +524,73 @@ impl Outputs {
             );
             shell_info.style = style;
             shell_info.scale_factor = scale_factor;
+            if shell_info.hidden {
+                continue;
+            }
             let height = Self::get_height(style, scale_factor);
             tasks.push(Task::batch(vec![
                 set_size(shell_info.id, None, Some(height as u32)),
-                set_exclusive_zone(shell_info.id, height as i32),
+                set_exclusive_zone(
+                    shell_info.id,
+                    Self::exclusive_zone(height, hyprland_reserved_area),
+                ),
             ]));
         }
 
+        for shell_info in self.0.iter_mut().filter_map(|(_, shell_info, _)| {
+            shell_info
+                .as_mut()
+                .filter(|shell_info| shell_info.keyboard_navigation != keyboard_navigation)
+        }) {
+            shell_info.keyboard_navigation = keyboard_navigation;
+            tasks.push(set_keyboard_interactivity(
+                shell_info.id,
+                Self::bar_keyboard_interactivity(keyboard_navigation),
+            ));
+        }
+        self.publish_geometry();
+
         Task::batch(tasks)
     }
 
+    pub fn geometry(&self) -> Vec<BarGeometry> {
+        self.0
+            .iter()
+            .filter_map(|(name, shell_info, _)| {
+                let shell_info = shell_info.as_ref()?;
+                let height = if shell_info.hidden {
+                    1.
+                } else {
+                    Self::get_height(shell_info.style, shell_info.scale_factor)
+                };
+
+                Some(BarGeometry {
+                    output: name.clone(),
+                    position: shell_info.position,
+                    height: height as u32,
+                    exclusive_zone: if shell_info.hidden {
+                        0
+                    } else {
+                        Self::exclusive_zone(height, self.1)
+                    },
+                    hidden: shell_info.hidden,
+                })
+            })
+            .collect()
+    }
+
+    /// Shares the geometry with the IPC listener, and the compositor when asked
+    fn publish_geometry(&self) {
+        let bars = self.geometry();
+
+        #[cfg(feature = "hyprland")]
+        if self.1 {
+            reserve_hyprland_area(&bars);
+        }
+
+        crate::ipc::set_bars(bars);
+    }
+
     pub fn menu_is_open(&self) -> bool {
         self.0.iter().any(|(_, shell_info, _)| {
             shell_info
@@ -400,6 +600,15 @@ impl Outputs {
         })
     }
 
+    pub fn is_menu_open(&self, menu_type: &MenuType) -> bool {
+        self.0.iter().any(|(_, shell_info, _)| {
+            shell_info
+                .as_ref()
+                .and_then(|shell_info| shell_info.menu.menu_info.as_ref())
+                .is_some_and(|(open, _)| open == menu_type)
+        })
+    }
+
     pub fn toggle_menu<Message: 'static>(
         &mut self,
         id: Id,
@@ -589,4 +798,66 @@ impl Outputs {
             _ => Task::none(),
         }
     }
+
+    /// Gives the keyboard to the bar of the output, the first bar when no output is given
+    pub fn grab_bar_keyboard<Message: 'static>(
+        &self,
+        output: Option<&str>,
+    ) -> Option<(Id, Task<Message>)> {
+        self.0
+            .iter()
+            .filter(|(name, _, _)| output.is_none_or(|output| name.contains(output)))
+            .find_map(|(_, shell_info, _)| shell_info.as_ref())
+            .map(|shell_info| {
+                (
+                    shell_info.id,
+                    set_keyboard_interactivity(shell_info.id, KeyboardInteractivity::Exclusive),
+                )
+            })
+    }
+
+    pub fn release_bar_keyboard<Message: 'static>(&self, id: Id) -> Task<Message> {
+        match self
+            .0
+            .iter()
+            .find_map(|(_, shell_info, _)| shell_info.as_ref().filter(|info| info.id == id))
+        {
+            Some(shell_info) => set_keyboard_interactivity(
+                id,
+                Self::bar_keyboard_interactivity(shell_info.keyboard_navigation),
+            ),
+            None => Task::none(),
+        }
+    }
+}
+
+/// Reserves the space of the bars with the `addreserved` monitor rules,
+/// the monitors without a bar get their space back
+#[cfg(feature = "hyprland")]
+fn reserve_hyprland_area(bars: &[BarGeometry]) {
+    use hyprland::{data::Monitors, keyword::Keyword, shared::HyprData};
+
+    let monitors = match Monitors::get() {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            log::error!("failed to get the hyprland monitors: {e}");
+            return;
+        }
+    };
+
+    for monitor in monitors {
+        let (top, bottom) = bars
+            .iter()
+            .filter(|bar| !bar.hidden && bar.output.contains(&monitor.name))
+            .fold((0, 0), |(top, bottom), bar| match bar.position {
+                Position::Top => (top + bar.height, bottom),
+                Position::Bottom => (top, bottom + bar.height),
+            });
+        let rule = format!("{},addreserved,{top},{bottom},0,0", monitor.name);
+
+        debug!("setting monitor rule: {rule}");
+        if let Err(e) = Keyword::set("monitor", rule) {
+            log::error!("failed to reserve the bar area on {}: {e}", monitor.name);
+        }
+    }
 }
@@ -0,0 +1,143 @@
+use crate::{
+    config::{PinnedPopupConfig, PopupAnchor},
+    menu::MenuType,
+};
+use iced::{
+    Task,
+    platform_specific::shell::commands::layer_surface::{
+        Anchor, KeyboardInteractivity, Layer, destroy_layer_surface, get_layer_surface,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+    },
+    window::Id,
+};
+use log::info;
+use std::collections::HashMap;
+
+/// Module menus kept open on the desktop, each one in its own layer surface
+pub struct PinnedPopups {
+    config: HashMap<String, PinnedPopupConfig>,
+    popups: Vec<(Id, MenuType)>,
+}
+
+impl PinnedPopups {
+    pub fn new(config: HashMap<String, PinnedPopupConfig>) -> Self {
+        Self {
+            config,
+            popups: Vec::new(),
+        }
+    }
+
+    fn create_surface<Message: 'static>(config: PinnedPopupConfig) -> (Id, Task<Message>) {
+        let id = Id::unique();
+        let margin = config.margin;
+
+        let task = get_layer_surface(SctkLayerSurfaceSettings {
+            id,
+            namespace: "ashell-pinned-popup".to_string(),
+            // sized on the content
+            size: None,
+            layer: Layer::Bottom,
+            // the popups with a text input need the keyboard
+            keyboard_interactivity: KeyboardInteractivity::OnDemand,
+            exclusive_zone: 0,
+            output: IcedOutput::Active,
+            anchor: match config.anchor {
+                PopupAnchor::TopLeft => Anchor::TOP | Anchor::LEFT,
+                PopupAnchor::TopRight => Anchor::TOP | Anchor::RIGHT,
+                PopupAnchor::BottomLeft => Anchor::BOTTOM | Anchor::LEFT,
+                PopupAnchor::BottomRight => Anchor::BOTTOM | Anchor::RIGHT,
+            },
+            margin: IcedMargin {
+                top: margin,
+                right: margin,
+                bottom: margin,
+                left: margin,
+            },
+            ..Default::default()
+        });
+
+        (id, task)
+    }
+
+    fn popup_config(&self, menu_type: &MenuType) -> PinnedPopupConfig {
+        menu_type
+            .name()
+            .and_then(|name| self.config.get(name))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Moves the pinned popups whose position changed
+    pub fn reload<Message: 'static>(
+        &mut self,
+        config: HashMap<String, PinnedPopupConfig>,
+    ) -> Task<Message> {
+        let old_config = std::mem::replace(&mut self.config, config);
+        let mut tasks = Vec::new();
+
+        for (id, menu_type) in self.popups.iter_mut() {
+            let name = menu_type.name().unwrap_or_default();
+
+            if old_config.get(name) != self.config.get(name) {
+                let config = self.config.get(name).copied().unwrap_or_default();
+                let (new_id, task) = Self::create_surface(config);
+
+                tasks.push(destroy_layer_surface(*id));
+                tasks.push(task);
+                *id = new_id;
+            }
+        }
+
+        Task::batch(tasks)
+    }
+
+    pub fn menu_type(&self, id: Id) -> Option<&MenuType> {
+        self.popups
+            .iter()
+            .find_map(|(popup_id, menu_type)| (*popup_id == id).then_some(menu_type))
+    }
+
+    pub fn is_pinned(&self, menu_type: &MenuType) -> bool {
+        self.popups.iter().any(|(_, pinned)| pinned == menu_type)
+    }
+
+    /// Names of the pinned popups, in the order they were pinned
+    pub fn names(&self) -> Vec<String> {
+        self.popups
+            .iter()
+            .filter_map(|(_, menu_type)| menu_type.name().map(str::to_string))
+            .collect()
+    }
+
+    pub fn pin<Message: 'static>(&mut self, menu_type: MenuType) -> Task<Message> {
+        if self.is_pinned(&menu_type) || menu_type.name().is_none() {
+            return Task::none();
+        }
+
+        info!("Pinning the {menu_type:?} popup");
+
+        let (id, task) = Self::create_surface(self.popup_config(&menu_type));
+        self.popups.push((id, menu_type));
+
+        task
+    }
+
+    pub fn unpin<Message: 'static>(&mut self, menu_type: &MenuType) -> Task<Message> {
+        match self
+            .popups
+            .iter()
+            .position(|(_, pinned)| pinned == menu_type)
+        {
+            Some(index) => {
+                info!("Unpinning the {menu_type:?} popup");
+
+                let (id, _) = self.popups.remove(index);
+
+                destroy_layer_surface(id)
+            }
+            None => Task::none(),
+        }
+    }
+}
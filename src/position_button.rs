@@ -28,6 +28,7 @@ where
 {
     content: Element<'a, Message, Theme, Renderer>,
     on_press: Option<OnPress<'a, Message>>,
+    on_right_press: Option<Box<dyn Fn(ButtonUIRef) -> Message + 'a>>,
     id: Id,
     width: Length,
     height: Length,
@@ -49,6 +50,7 @@ where
             content,
             id: Id::unique(),
             on_press: None,
+            on_right_press: None,
             width: size.width.fluid(),
             height: size.height.fluid(),
             padding: DEFAULT_PADDING,
@@ -91,6 +93,18 @@ where
         self
     }
 
+    /// Sets the message produced when the [`Button`] is pressed with the
+    /// right mouse button, the position is the one of the cursor.
+    ///
+    /// It doesn't enable the [`Button`].
+    pub fn on_right_press_with_position(
+        mut self,
+        on_right_press: impl Fn(ButtonUIRef) -> Message + 'a,
+    ) -> Self {
+        self.on_right_press = Some(Box::new(on_right_press));
+        self
+    }
+
     /// Sets whether the contents of the [`Button`] should be clipped on
     /// overflow.
     pub fn clip(mut self, clip: bool) -> Self {
@@ -253,6 +267,18 @@ where
                     }
                 }
             }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                if let Some(on_right_press) = self.on_right_press.as_ref()
+                    && let Some(position) = cursor.position_over(layout.bounds())
+                {
+                    shell.publish(on_right_press(ButtonUIRef {
+                        position,
+                        viewport: (viewport.width, viewport.height),
+                    }));
+
+                    return event::Status::Captured;
+                }
+            }
             Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
                 if let Some(on_press) = self.on_press.as_ref() {
                     let state = tree.state.downcast_mut::<State>();
@@ -0,0 +1,100 @@
+use crate::{
+    config::{ModuleName, PrivacyModeConfig},
+    ipc::Toggle,
+    services::{ReadOnlyService, ServiceEvent, privacy::PrivacyService},
+};
+use iced::Subscription;
+use log::info;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Set(Toggle),
+    Privacy(ServiceEvent<PrivacyService>),
+}
+
+pub enum Action {
+    None,
+    Enabled,
+}
+
+/// Blanks the sensitive modules while the screen is shared or on demand
+pub struct PrivacyMode {
+    config: PrivacyModeConfig,
+    enabled: bool,
+    service: Option<PrivacyService>,
+}
+
+impl PrivacyMode {
+    pub fn new(config: PrivacyModeConfig) -> Self {
+        Self {
+            config,
+            enabled: false,
+            service: None,
+        }
+    }
+
+    pub fn reload(&mut self, config: PrivacyModeConfig) {
+        self.config = config;
+    }
+
+    fn screen_shared(&self) -> bool {
+        self.config.on_screenshare
+            && self
+                .service
+                .as_ref()
+                .is_some_and(|service| service.screenshare_access())
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.enabled || self.screen_shared()
+    }
+
+    /// The placeholder shown instead of the module, if it's blanked
+    pub fn placeholder(&self, module: &ModuleName) -> Option<&str> {
+        (self.is_active() && self.config.modules.contains(module))
+            .then_some(self.config.placeholder.as_str())
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        let was_active = self.is_active();
+
+        match message {
+            Message::Set(toggle) => {
+                self.enabled = toggle.apply(self.enabled);
+            }
+            Message::Privacy(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+        }
+
+        match (was_active, self.is_active()) {
+            (false, true) => {
+                info!("Privacy mode enabled");
+
+                Action::Enabled
+            }
+            (true, false) => {
+                info!("Privacy mode disabled");
+
+                Action::None
+            }
+            _ => Action::None,
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.config.on_screenshare {
+            PrivacyService::subscribe().map(Message::Privacy)
+        } else {
+            Subscription::none()
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::config::ModuleName;
+use iced::{Subscription, time::every};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick(Instant),
+}
+
+/// A single timer for the time-driven modules, like the clock seconds,
+/// ticking at the rate of the fastest one shown
+#[derive(Default)]
+pub struct RedrawScheduler {
+    /// last tick of each module
+    last: Vec<(ModuleName, Instant)>,
+}
+
+/// Interval of the timer, the one of the fastest module
+fn rate(intervals: &[(ModuleName, Duration)]) -> Option<Duration> {
+    intervals.iter().map(|(_, interval)| *interval).min()
+}
+
+impl RedrawScheduler {
+    /// Modules, among the shown ones with their interval, due for a redraw
+    pub fn due(&mut self, now: Instant, intervals: &[(ModuleName, Duration)]) -> Vec<ModuleName> {
+        // the timer isn't exact, a tick slightly early is still in time
+        let tolerance = rate(intervals).unwrap_or_default() / 2;
+
+        self.last
+            .retain(|(module, _)| intervals.iter().any(|(m, _)| m == module));
+
+        let mut due = Vec::new();
+        for (module, interval) in intervals {
+            match self.last.iter_mut().find(|(m, _)| m == module) {
+                Some((_, last)) => {
+                    if now.saturating_duration_since(*last) + tolerance >= *interval {
+                        *last = now;
+                        due.push(module.clone());
+                    }
+                }
+                None => {
+                    self.last.push((module.clone(), now));
+                    due.push(module.clone());
+                }
+            }
+        }
+
+        due
+    }
+
+    /// No timer runs without a time-driven module shown
+    pub fn subscription(&self, intervals: &[(ModuleName, Duration)]) -> Subscription<Message> {
+        match rate(intervals) {
+            Some(rate) => every(rate).map(Message::Tick),
+            None => Subscription::none(),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{
+    config::{
+        Appearance, AppearanceColor, AppearanceStyle, ColorScheme, ScheduleEvent, ScheduleTime,
+        SchedulerConfig,
+    },
+    utils::launcher::execute_command,
+};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeDelta, TimeZone};
+use hex_color::HexColor;
+use iced::{
+    Subscription, Task,
+    futures::{SinkExt, StreamExt, stream::pending},
+    stream::channel,
+};
+use log::{debug, info, warn};
+use serde::Deserialize;
+use std::{
+    any::TypeId, collections::HashMap, env, f64::consts::PI, fs, path::PathBuf, time::Duration,
+};
+use tokio::{process::Command, time::sleep};
+
+/// Max time slept before checking the clock again, a long sleep
+/// is not reliable when the system is suspended in the meantime
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Triggered(usize),
+    /// the most recent event when the scheduler starts, its command isn't run again
+    Restored(usize),
+    PywalLoaded(Option<Appearance>),
+}
+
+pub enum Action {
+    None,
+    AppearanceChanged,
+    Command(Task<Message>),
+}
+
+/// The colors generated by pywal
+#[derive(Deserialize)]
+struct PywalColors {
+    special: PywalSpecialColors,
+    colors: HashMap<String, HexColor>,
+}
+
+#[derive(Deserialize)]
+struct PywalSpecialColors {
+    background: HexColor,
+    foreground: HexColor,
+}
+
+fn pywal_colors_path() -> Option<PathBuf> {
+    env::var_os("PYWAL_CACHE_DIR")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache").join("wal")))
+        .map(|dir| dir.join("colors.json"))
+}
+
+/// The appearance made of the last colors generated by pywal
+fn pywal_appearance() -> Option<Appearance> {
+    let path = pywal_colors_path()?;
+    let colors = fs::read_to_string(&path)
+        .inspect_err(|err| warn!("Failed to read the pywal colors {path:?}: {err}"))
+        .ok()?;
+    let colors = serde_json::from_str::<PywalColors>(&colors)
+        .inspect_err(|err| warn!("Failed to parse the pywal colors {path:?}: {err}"))
+        .ok()?;
+    let color = |name: &str| {
+        colors
+            .colors
+            .get(name)
+            .copied()
+            .map(AppearanceColor::Simple)
+    };
+
+    Some(Appearance {
+        background_color: AppearanceColor::Simple(colors.special.background),
+        text_color: AppearanceColor::Simple(colors.special.foreground),
+        primary_color: color("color4")?,
+        secondary_color: color("color0")?,
+        success_color: color("color2")?,
+        danger_color: color("color1")?,
+        workspace_colors: vec![color("color4")?, color("color5")?],
+        ..Appearance::default()
+    })
+}
+
+/// Waits for the command of the event, usually running `wal`, before reading the colors
+async fn run_pywal(command: Option<String>) -> Option<Appearance> {
+    if let Some(command) = command {
+        match Command::new("bash").arg("-c").arg(&command).status().await {
+            Ok(status) if !status.success() => warn!("Scheduled command exited with {status}"),
+            Err(err) => warn!("Failed to run the scheduled command: {err}"),
+            Ok(_) => {}
+        }
+    }
+
+    pywal_appearance()
+}
+
+/// Asks the apps following the desktop settings, through the portal, for the color scheme
+fn set_color_scheme(color_scheme: ColorScheme) {
+    let value = match color_scheme {
+        ColorScheme::Light => "prefer-light",
+        ColorScheme::Dark => "prefer-dark",
+    };
+
+    execute_command(format!(
+        "gsettings set org.gnome.desktop.interface color-scheme {value}"
+    ));
+}
+
+type Location = (f64, f64);
+
+/// Computes sunrise and sunset for the given date using the sunrise equation
+fn sun_times(date: NaiveDate, (latitude, longitude): Location) -> Option<(i64, i64)> {
+    let to_rad = PI / 180.;
+    let julian_day = date.num_days_from_ce() as f64 + 1_721_424.5;
+
+    let n = (julian_day - 2_451_545.0 + 0.0008).ceil();
+    let mean_solar_time = n - longitude / 360.;
+    let mean_anomaly = (357.5291 + 0.985_600_28 * mean_solar_time).rem_euclid(360.);
+    let center = 1.9148 * (mean_anomaly * to_rad).sin()
+        + 0.02 * (2. * mean_anomaly * to_rad).sin()
+        + 0.0003 * (3. * mean_anomaly * to_rad).sin();
+    let ecliptic_longitude = (mean_anomaly + center + 180. + 102.9372).rem_euclid(360.);
+    let transit = 2_451_545.0 + mean_solar_time + 0.0053 * (mean_anomaly * to_rad).sin()
+        - 0.0069 * (2. * ecliptic_longitude * to_rad).sin();
+
+    let declination_sin = (ecliptic_longitude * to_rad).sin() * (23.4397 * to_rad).sin();
+    let declination_cos = declination_sin.asin().cos();
+    let hour_angle_cos = ((-0.833 * to_rad).sin() - (latitude * to_rad).sin() * declination_sin)
+        / ((latitude * to_rad).cos() * declination_cos);
+
+    // the sun never rises or never sets on this day
+    if !(-1.0..=1.0).contains(&hour_angle_cos) {
+        return None;
+    }
+
+    let hour_angle = hour_angle_cos.acos() / to_rad;
+    let to_timestamp = |julian_date: f64| ((julian_date - 2_440_587.5) * 86_400.) as i64;
+
+    Some((
+        to_timestamp(transit - hour_angle / 360.),
+        to_timestamp(transit + hour_angle / 360.),
+    ))
+}
+
+fn occurrence(
+    (at, offset): (ScheduleTime, i64),
+    date: NaiveDate,
+    location: Option<Location>,
+) -> Option<DateTime<Local>> {
+    let time = match at {
+        ScheduleTime::At(time) => date.and_time(time).and_local_timezone(Local).earliest(),
+        ScheduleTime::Sunrise => location
+            .and_then(|location| sun_times(date, location))
+            .and_then(|(sunrise, _)| Local.timestamp_opt(sunrise, 0).single()),
+        ScheduleTime::Sunset => location
+            .and_then(|location| sun_times(date, location))
+            .and_then(|(_, sunset)| Local.timestamp_opt(sunset, 0).single()),
+    }?;
+
+    time.checked_add_signed(TimeDelta::minutes(offset))
+}
+
+fn occurrences(
+    events: &[(ScheduleTime, i64)],
+    location: Option<Location>,
+    now: DateTime<Local>,
+) -> impl Iterator<Item = (usize, DateTime<Local>)> + '_ {
+    let today = now.date_naive();
+
+    [
+        today.checked_sub_days(Days::new(1)),
+        Some(today),
+        today.checked_add_days(Days::new(1)),
+        today.checked_add_days(Days::new(2)),
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(move |date| {
+        events.iter().enumerate().filter_map(move |(index, event)| {
+            occurrence(*event, date, location).map(|time| (index, time))
+        })
+    })
+}
+
+pub struct Scheduler {
+    config: SchedulerConfig,
+    active_appearance: Option<String>,
+    /// generated by pywal, it takes the place of the named appearance
+    pywal_appearance: Option<Appearance>,
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        if config.location().is_none()
+            && config
+                .events
+                .iter()
+                .any(|event| matches!(event.at, ScheduleTime::Sunrise | ScheduleTime::Sunset))
+        {
+            warn!("Scheduler events at sunrise or sunset require latitude and longitude");
+        }
+
+        Self {
+            config,
+            active_appearance: None,
+            pywal_appearance: None,
+        }
+    }
+
+    pub fn reload(&mut self, config: SchedulerConfig) {
+        let active_appearance = self
+            .active_appearance
+            .take()
+            .filter(|name| config.appearances.contains_key(name));
+        let pywal_appearance = self
+            .pywal_appearance
+            .take()
+            .filter(|_| config.events.iter().any(|event| event.pywal));
+
+        *self = Self::new(config);
+        self.active_appearance = active_appearance;
+        self.pywal_appearance = pywal_appearance;
+    }
+
+    /// The appearance selected by the last event, keeping the
+    /// style and scale factor of the base configuration
+    pub fn appearance(&self, style: AppearanceStyle, scale_factor: f64) -> Option<Appearance> {
+        self.pywal_appearance
+            .as_ref()
+            .or_else(|| {
+                self.active_appearance
+                    .as_ref()
+                    .and_then(|name| self.config.appearances.get(name))
+            })
+            .map(|appearance| Appearance {
+                style,
+                scale_factor,
+                ..appearance.clone()
+            })
+    }
+
+    /// Applies the color scheme and the appearance of the event, the pywal
+    /// colors are read once the command is done when it's given
+    fn apply(&mut self, event: ScheduleEvent, run_command: bool) -> Action {
+        if let Some(color_scheme) = event.color_scheme {
+            set_color_scheme(color_scheme);
+        }
+
+        if event.pywal {
+            let command = event.command.filter(|_| run_command);
+
+            return Action::Command(Task::perform(run_pywal(command), Message::PywalLoaded));
+        }
+
+        if run_command && let Some(command) = event.command {
+            execute_command(command);
+        }
+
+        match event.appearance {
+            Some(name) if self.config.appearances.contains_key(&name) => {
+                self.active_appearance = Some(name);
+                self.pywal_appearance = None;
+
+                Action::AppearanceChanged
+            }
+            Some(name) => {
+                warn!("Scheduled appearance {name} is not defined");
+
+                Action::None
+            }
+            None => Action::None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Triggered(index) => {
+                let Some(event) = self.config.events.get(index).cloned() else {
+                    return Action::None;
+                };
+
+                info!("Running scheduled event {:?}", event.at);
+
+                self.apply(event, true)
+            }
+            Message::Restored(index) => {
+                let Some(event) = self.config.events.get(index).cloned() else {
+                    return Action::None;
+                };
+
+                info!("Restoring the appearance of scheduled event {:?}", event.at);
+
+                self.apply(event, false)
+            }
+            Message::PywalLoaded(appearance) => match appearance {
+                Some(appearance) => {
+                    self.pywal_appearance = Some(appearance);
+
+                    Action::AppearanceChanged
+                }
+                None => Action::None,
+            },
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.config.events.is_empty() {
+            return Subscription::none();
+        }
+
+        let id = TypeId::of::<Self>();
+        let events = self
+            .config
+            .events
+            .iter()
+            .map(|event| (event.at, event.offset))
+            .collect::<Vec<_>>();
+        let location = self.config.location();
+
+        Subscription::run_with_id(
+            (
+                id,
+                events.clone(),
+                location.map(|(lat, lon)| (lat.to_bits(), lon.to_bits())),
+            ),
+            channel(10, async move |mut output| {
+                // restore the appearance of the most recent event, its command ran already
+                if let Some((index, _)) = occurrences(&events, location, Local::now())
+                    .filter(|(_, time)| *time <= Local::now())
+                    .max_by_key(|(_, time)| *time)
+                {
+                    let _ = output.send(Message::Restored(index)).await;
+                }
+
+                loop {
+                    let now = Local::now();
+                    let next = occurrences(&events, location, now)
+                        .filter(|(_, time)| *time > now)
+                        .min_by_key(|(_, time)| *time);
+
+                    match next {
+                        Some((index, time)) => {
+                            debug!("Next scheduled event at {time}");
+
+                            while let Ok(remaining) = (time - Local::now()).to_std() {
+                                if remaining.is_zero() {
+                                    break;
+                                }
+                                sleep(remaining.min(MAX_SLEEP)).await;
+                            }
+
+                            let _ = output.send(Message::Triggered(index)).await;
+                        }
+                        None => {
+                            let _ = pending::<u8>().next().await;
+                        }
+                    }
+                }
+            }),
+        )
+    }
+}
+
+impl SchedulerConfig {
+    fn location(&self) -> Option<Location> {
+        self.latitude.zip(self.longitude)
+    }
+}
@@ -0,0 +1,168 @@
+use super::{ReadOnlyService, ServiceEvent, logind::LogindManagerProxy};
+use iced::{
+    Subscription,
+    futures::{SinkExt, StreamExt, channel::mpsc::Sender, stream::pending},
+    stream::channel,
+};
+use log::{debug, error, info, warn};
+use std::{any::TypeId, ops::Deref};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader, Lines},
+    net::UnixStream,
+};
+
+const ACPID_SOCKET: &str = "/var/run/acpid.socket";
+
+#[derive(Debug, Clone, Default)]
+pub struct AcpiData {
+    pub lid_closed: bool,
+    pub tablet_mode: bool,
+    /// logind is configured to do nothing when the power button is pressed
+    pub power_key_ignored: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AcpiService {
+    data: AcpiData,
+}
+
+impl Deref for AcpiService {
+    type Target = AcpiData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcpiEvent {
+    /// the lid is closed
+    Lid(bool),
+    TabletMode(bool),
+    PowerButton,
+}
+
+impl AcpiEvent {
+    /// Parses an event of acpid, like `button/lid LID close`
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let event = fields.next()?;
+        let last = fields.last();
+
+        match event {
+            "button/lid" => match last? {
+                "close" => Some(AcpiEvent::Lid(true)),
+                "open" => Some(AcpiEvent::Lid(false)),
+                _ => None,
+            },
+            "button/power" => Some(AcpiEvent::PowerButton),
+            // the last field is 1 in tablet mode, 0 in laptop mode
+            "video/tabletmode" => last
+                .and_then(|value| u32::from_str_radix(value, 16).ok())
+                .map(|value| AcpiEvent::TabletMode(value != 0)),
+            _ => None,
+        }
+    }
+}
+
+enum State {
+    Init,
+    Active(Lines<BufReader<UnixStream>>),
+    Error,
+}
+
+impl AcpiService {
+    /// The lid state and the power key action, acpid reports only the changes
+    async fn logind_state() -> anyhow::Result<AcpiData> {
+        let conn = zbus::Connection::system().await?;
+        let manager = LogindManagerProxy::new(&conn).await?;
+
+        Ok(AcpiData {
+            lid_closed: manager.lid_closed().await?,
+            tablet_mode: false,
+            power_key_ignored: manager.handle_power_key().await? == "ignore",
+        })
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match UnixStream::connect(ACPID_SOCKET).await {
+                Ok(stream) => {
+                    let data = Self::logind_state()
+                        .await
+                        .inspect_err(|err| warn!("Failed to get the lid state: {err}"))
+                        .unwrap_or_default();
+
+                    let _ = output.send(ServiceEvent::Init(AcpiService { data })).await;
+
+                    info!("Listening for acpid events");
+
+                    State::Active(BufReader::new(stream).lines())
+                }
+                Err(err) => {
+                    error!("Failed to connect to acpid: {err}");
+
+                    State::Error
+                }
+            },
+            State::Active(mut lines) => match lines.next_line().await {
+                Ok(Some(line)) => {
+                    debug!("Acpid event: {line}");
+
+                    if let Some(event) = AcpiEvent::parse(&line) {
+                        let _ = output.send(ServiceEvent::Update(event)).await;
+                    }
+
+                    State::Active(lines)
+                }
+                Ok(None) => {
+                    error!("Acpid closed the connection");
+
+                    State::Error
+                }
+                Err(err) => {
+                    error!("Failed to read the acpid events: {err}");
+
+                    State::Error
+                }
+            },
+            State::Error => {
+                let _ = pending::<u8>().next().await;
+
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for AcpiService {
+    type UpdateEvent = AcpiEvent;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        match event {
+            AcpiEvent::Lid(closed) => {
+                self.data.lid_closed = closed;
+            }
+            AcpiEvent::TabletMode(tablet_mode) => {
+                self.data.tablet_mode = tablet_mode;
+            }
+            AcpiEvent::PowerButton => {}
+        }
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                let mut state = State::Init;
+
+                loop {
+                    state = AcpiService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
@@ -9,7 +9,7 @@ use libpulse_binding::{
     callbacks::ListResult,
     context::{
         self, Context, FlagSet,
-        introspect::{Introspector, SinkInfo, SourceInfo},
+        introspect::{Introspector, SinkInfo, SinkInputInfo, SourceInfo, SourceOutputInfo},
         subscribe::InterestMaskSet,
     },
     def::{DevicePortType, PortAvailable, SinkState, SourceState},
@@ -28,16 +28,40 @@ use std::{
 };
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
+/// Properties of the sinks streaming to other devices: AirPlay and the network tunnels
+const REMOTE_SINK_PROPERTIES: [&str; 3] =
+    ["raop.ip", "tunnel.remote.server", "pulse.server.address"];
+
 #[derive(Debug, Clone)]
 pub struct Device {
+    pub index: u32,
     pub name: String,
     pub description: String,
     pub volume: ChannelVolumes,
     pub is_mute: bool,
     pub in_use: bool,
+    /// the sink streams to another device, it has a single network port
+    pub remote: bool,
     pub ports: Vec<Port>,
 }
 
+/// An application playing on a sink
+#[derive(Debug, Clone)]
+pub struct PlaybackStream {
+    pub index: u32,
+    pub name: String,
+    pub volume: ChannelVolumes,
+    pub is_mute: bool,
+}
+
+/// An application recording from a source
+#[derive(Debug, Clone)]
+pub struct RecordingStream {
+    pub name: String,
+    /// index of the source
+    pub source: u32,
+}
+
 #[derive(Debug, Clone)]
 pub struct Port {
     pub name: String,
@@ -52,6 +76,7 @@ pub enum DeviceType {
     Speaker,
     Headset,
     Hdmi,
+    Network,
 }
 
 impl DeviceType {
@@ -61,6 +86,7 @@ impl DeviceType {
             DeviceType::Headphones => StaticIcon::Headphones1,
             DeviceType::Headset => StaticIcon::Headset,
             DeviceType::Hdmi => StaticIcon::MonitorSpeaker,
+            DeviceType::Network => StaticIcon::Cast,
         }
     }
 }
@@ -125,10 +151,21 @@ pub struct AudioData {
     pub server_info: ServerInfo,
     pub sinks: Vec<Device>,
     pub sources: Vec<Device>,
+    pub streams: Vec<PlaybackStream>,
+    pub recording: Vec<RecordingStream>,
     pub cur_sink_volume: i32,
     pub cur_source_volume: i32,
 }
 
+impl AudioData {
+    /// An application records from a source, the monitors of the sinks don't count
+    pub fn is_recording(&self) -> bool {
+        self.recording
+            .iter()
+            .any(|stream| self.sources.iter().any(|s| s.index == stream.source))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AudioService {
     data: AudioData,
@@ -171,6 +208,8 @@ impl AudioService {
                                 server_info: ServerInfo::default(),
                                 sinks: Vec::new(),
                                 sources: Vec::new(),
+                                streams: Vec::new(),
+                                recording: Vec::new(),
                                 cur_sink_volume: 0,
                                 cur_source_volume: 0,
                             },
@@ -210,6 +249,20 @@ impl AudioService {
 
                     State::Active(handle)
                 }
+                Some(PulseAudioServerEvent::Streams(streams)) => {
+                    let _ = output
+                        .send(ServiceEvent::Update(AudioEvent::Streams(streams)))
+                        .await;
+
+                    State::Active(handle)
+                }
+                Some(PulseAudioServerEvent::Recording(recording)) => {
+                    let _ = output
+                        .send(ServiceEvent::Update(AudioEvent::Recording(recording)))
+                        .await;
+
+                    State::Active(handle)
+                }
                 None => State::Active(handle),
             },
             State::Error => {
@@ -227,6 +280,8 @@ pub enum AudioEvent {
     Sinks(Vec<Device>),
     Sources(Vec<Device>),
     ServerInfo(ServerInfo),
+    Streams(Vec<PlaybackStream>),
+    Recording(Vec<RecordingStream>),
 }
 
 enum State {
@@ -330,6 +385,12 @@ impl ReadOnlyService for AudioService {
                     .unwrap_or_default()
                     * 100.) as i32;
             }
+            AudioEvent::Streams(streams) => {
+                self.data.streams = streams;
+            }
+            AudioEvent::Recording(recording) => {
+                self.data.recording = recording;
+            }
         }
     }
 
@@ -356,6 +417,11 @@ pub enum AudioCommand {
     SourceVolume(i32),
     DefaultSink(String, String),
     DefaultSource(String, String),
+    /// sets the default sink and moves the playing streams there
+    MoveOutput(String),
+    SinkVolumeByName(String, i32),
+    StreamVolume(u32, i32),
+    ToggleStreamMute(u32),
 }
 
 impl Service for AudioService {
@@ -426,6 +492,34 @@ impl Service for AudioService {
                     .commander
                     .send(PulseAudioCommand::DefaultSource(name, port));
             }
+            AudioCommand::MoveOutput(name) => {
+                let _ = self.commander.send(PulseAudioCommand::MoveOutput(name));
+            }
+            AudioCommand::SinkVolumeByName(name, volume) => {
+                if let Some(sink) = self.data.sinks.iter_mut().find(|sink| sink.name == name)
+                    && let Some(volume) = sink.volume.scale_volume(volume as f64 / 100.)
+                {
+                    let _ = self
+                        .commander
+                        .send(PulseAudioCommand::SinkVolume(name, *volume));
+                }
+            }
+            AudioCommand::StreamVolume(index, volume) => {
+                if let Some(stream) = self.data.streams.iter_mut().find(|s| s.index == index)
+                    && let Some(volume) = stream.volume.scale_volume(volume as f64 / 100.)
+                {
+                    let _ = self
+                        .commander
+                        .send(PulseAudioCommand::StreamVolume(index, *volume));
+                }
+            }
+            AudioCommand::ToggleStreamMute(index) => {
+                if let Some(stream) = self.data.streams.iter().find(|s| s.index == index) {
+                    let _ = self
+                        .commander
+                        .send(PulseAudioCommand::StreamMute(index, !stream.is_mute));
+                }
+            }
         }
 
         iced::Task::none()
@@ -437,6 +531,8 @@ enum PulseAudioServerEvent {
     Sinks(Vec<Device>),
     Sources(Vec<Device>),
     ServerInfo(ServerInfo),
+    Streams(Vec<PlaybackStream>),
+    Recording(Vec<RecordingStream>),
 }
 
 enum PulseAudioCommand {
@@ -446,6 +542,9 @@ enum PulseAudioCommand {
     SourceVolume(String, ChannelVolumes),
     DefaultSink(String, String),
     DefaultSource(String, String),
+    MoveOutput(String),
+    StreamVolume(u32, ChannelVolumes),
+    StreamMute(u32, bool),
 }
 
 struct PulseAudioServer {
@@ -527,7 +626,9 @@ impl PulseAudioServer {
                     server.context.subscribe(
                         InterestMaskSet::SERVER
                             .union(InterestMaskSet::SINK)
-                            .union(InterestMaskSet::SOURCE),
+                            .union(InterestMaskSet::SOURCE)
+                            .union(InterestMaskSet::SINK_INPUT)
+                            .union(InterestMaskSet::SOURCE_OUTPUT),
                         |res| {
                             if !res {
                                 error!("Audio subscription failed!");
@@ -578,6 +679,42 @@ impl PulseAudioServer {
                         }
                     };
 
+                    let streams = Rc::new(RefCell::new(Vec::new()));
+                    match server.wait_for_response(server.introspector.get_sink_input_info_list({
+                        let tx = from_server_tx.clone();
+                        let streams = streams.clone();
+                        move |info| {
+                            Self::populate_and_send_streams(info, &tx, &mut streams.borrow_mut());
+                        }
+                    })) {
+                        Ok(_) => {}
+                        Err(e) => {
+                            error!("Failed to get sink input info: {e}");
+                            let _ = from_server_tx.send(PulseAudioServerEvent::Error);
+                        }
+                    };
+
+                    let recording = Rc::new(RefCell::new(Vec::new()));
+                    match server.wait_for_response(server.introspector.get_source_output_info_list(
+                        {
+                            let tx = from_server_tx.clone();
+                            let recording = recording.clone();
+                            move |info| {
+                                Self::populate_and_send_recording(
+                                    info,
+                                    &tx,
+                                    &mut recording.borrow_mut(),
+                                );
+                            }
+                        },
+                    )) {
+                        Ok(_) => {}
+                        Err(e) => {
+                            error!("Failed to get source output info: {e}");
+                            let _ = from_server_tx.send(PulseAudioServerEvent::Error);
+                        }
+                    };
+
                     let introspector = server.context.introspect();
                     server.context.set_subscribe_callback(Some(Box::new(
                         move |_facility, _operation, _idx| {
@@ -612,6 +749,30 @@ impl PulseAudioServer {
                                     );
                                 }
                             });
+                            introspector.get_sink_input_info_list({
+                                let tx = from_server_tx.clone();
+                                let streams = streams.clone();
+
+                                move |info| {
+                                    Self::populate_and_send_streams(
+                                        info,
+                                        &tx,
+                                        &mut streams.borrow_mut(),
+                                    );
+                                }
+                            });
+                            introspector.get_source_output_info_list({
+                                let tx = from_server_tx.clone();
+                                let recording = recording.clone();
+
+                                move |info| {
+                                    Self::populate_and_send_recording(
+                                        info,
+                                        &tx,
+                                        &mut recording.borrow_mut(),
+                                    );
+                                }
+                            });
                         },
                     )));
 
@@ -668,6 +829,17 @@ impl PulseAudioServer {
                                 Some(PulseAudioCommand::DefaultSource(name, port)) => {
                                     let _ = server.set_default_source(&name, &port);
                                 }
+                                Some(PulseAudioCommand::MoveOutput(name)) => {
+                                    if let Err(e) = server.move_output(&name) {
+                                        error!("Failed to move the audio output to {name}: {e}");
+                                    }
+                                }
+                                Some(PulseAudioCommand::StreamVolume(index, volume)) => {
+                                    let _ = server.set_stream_volume(index, &volume);
+                                }
+                                Some(PulseAudioCommand::StreamMute(index, mute)) => {
+                                    let _ = server.set_stream_mute(index, mute);
+                                }
                                 None => {}
                             }
                         }
@@ -724,6 +896,7 @@ impl PulseAudioServer {
                     .ports
                     .iter()
                     .any(|port| port.available != PortAvailable::No)
+                    || is_remote(&data.proplist)
                 {
                     debug!("Adding sink data: {data:?}");
                     sinks.push(data.into());
@@ -766,6 +939,50 @@ impl PulseAudioServer {
         }
     }
 
+    fn populate_and_send_streams(
+        info: ListResult<&SinkInputInfo<'_>>,
+        tx: &UnboundedSender<PulseAudioServerEvent>,
+        streams: &mut Vec<PlaybackStream>,
+    ) {
+        match info {
+            ListResult::Item(data) => {
+                // the event sounds and the like can't be changed
+                if data.has_volume && data.volume_writable {
+                    debug!("Adding sink input data: {data:?}");
+                    streams.push(data.into());
+                }
+            }
+            ListResult::End => {
+                debug!("New sink input list {streams:?}");
+                let _ = tx.send(PulseAudioServerEvent::Streams(streams.clone()));
+                streams.clear();
+            }
+            ListResult::Error => error!("Error during sink input list population"),
+        }
+    }
+
+    fn populate_and_send_recording(
+        info: ListResult<&SourceOutputInfo<'_>>,
+        tx: &UnboundedSender<PulseAudioServerEvent>,
+        recording: &mut Vec<RecordingStream>,
+    ) {
+        match info {
+            ListResult::Item(data) => {
+                // the paused streams don't record anything
+                if !data.corked {
+                    debug!("Adding source output data: {data:?}");
+                    recording.push(data.into());
+                }
+            }
+            ListResult::End => {
+                debug!("New source output list {recording:?}");
+                let _ = tx.send(PulseAudioServerEvent::Recording(recording.clone()));
+                recording.clear();
+            }
+            ListResult::Error => error!("Error during source output list population"),
+        }
+    }
+
     fn set_sink_mute(&mut self, name: &str, mute: bool) -> anyhow::Result<()> {
         let op = self.introspector.set_sink_mute_by_name(name, mute, None);
 
@@ -794,14 +1011,54 @@ impl PulseAudioServer {
         self.wait_for_response(op)
     }
 
+    fn set_stream_volume(&mut self, index: u32, volume: &ChannelVolumes) -> anyhow::Result<()> {
+        let op = self.introspector.set_sink_input_volume(index, volume, None);
+
+        self.wait_for_response(op)
+    }
+
+    fn set_stream_mute(&mut self, index: u32, mute: bool) -> anyhow::Result<()> {
+        let op = self.introspector.set_sink_input_mute(index, mute, None);
+
+        self.wait_for_response(op)
+    }
+
     fn set_default_sink(&mut self, name: &str, port: &str) -> anyhow::Result<()> {
         let op = self.context.set_default_sink(name, |_| {});
         self.wait_for_response(op)?;
 
+        // the remote sinks don't have ports
+        if port.is_empty() {
+            return Ok(());
+        }
+
         let op = self.introspector.set_sink_port_by_name(name, port, None);
         self.wait_for_response(op)
     }
 
+    fn move_output(&mut self, name: &str) -> anyhow::Result<()> {
+        let op = self.context.set_default_sink(name, |_| {});
+        self.wait_for_response(op)?;
+
+        let inputs = Rc::new(RefCell::new(Vec::new()));
+        let op = self.introspector.get_sink_input_info_list({
+            let inputs = inputs.clone();
+            move |info: ListResult<&SinkInputInfo<'_>>| {
+                if let ListResult::Item(input) = info {
+                    inputs.borrow_mut().push(input.index);
+                }
+            }
+        });
+        self.wait_for_response(op)?;
+
+        for index in inputs.take() {
+            let op = self.introspector.move_sink_input_by_name(index, name, None);
+            self.wait_for_response(op)?;
+        }
+
+        Ok(())
+    }
+
     fn set_default_source(&mut self, name: &str, port: &str) -> anyhow::Result<()> {
         let op = self.context.set_default_source(name, |_| {});
         self.wait_for_response(op)?;
@@ -826,9 +1083,18 @@ impl<'a> From<&'a libpulse_binding::context::introspect::ServerInfo<'a>> for Ser
     }
 }
 
+fn is_remote(proplist: &Proplist) -> bool {
+    REMOTE_SINK_PROPERTIES
+        .iter()
+        .any(|property| proplist.contains(property))
+}
+
 impl From<&SinkInfo<'_>> for Device {
     fn from(value: &SinkInfo<'_>) -> Self {
+        let remote = is_remote(&value.proplist);
+
         Self {
+            index: value.index,
             name: value
                 .name
                 .as_ref()
@@ -840,32 +1106,70 @@ impl From<&SinkInfo<'_>> for Device {
             volume: value.volume,
             is_mute: value.mute,
             in_use: value.state == SinkState::Running,
-            ports: value
-                .ports
-                .iter()
-                .filter_map(|port| {
-                    if port.available != PortAvailable::No {
-                        Some(Port {
-                            name: port
-                                .name
-                                .as_ref()
-                                .map_or(String::default(), |n| n.to_string()),
-                            description: port.description.as_ref().unwrap().to_string(),
-                            device_type: match port.r#type {
-                                DevicePortType::Headphones => DeviceType::Headphones,
-                                DevicePortType::Speaker => DeviceType::Speaker,
-                                DevicePortType::Headset => DeviceType::Headset,
-                                DevicePortType::HDMI => DeviceType::Hdmi,
-                                _ => DeviceType::Speaker,
-                            },
-                            active: value.active_port.as_ref().and_then(|p| p.name.as_ref())
-                                == port.name.as_ref(),
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>(),
+            remote,
+            ports: if remote && value.ports.is_empty() {
+                vec![Port {
+                    name: String::default(),
+                    description: "Network".to_string(),
+                    device_type: DeviceType::Network,
+                    active: true,
+                }]
+            } else {
+                value
+                    .ports
+                    .iter()
+                    .filter_map(|port| {
+                        if port.available != PortAvailable::No {
+                            Some(Port {
+                                name: port
+                                    .name
+                                    .as_ref()
+                                    .map_or(String::default(), |n| n.to_string()),
+                                description: port.description.as_ref().unwrap().to_string(),
+                                device_type: match port.r#type {
+                                    DevicePortType::Headphones => DeviceType::Headphones,
+                                    DevicePortType::Speaker => DeviceType::Speaker,
+                                    DevicePortType::Headset => DeviceType::Headset,
+                                    DevicePortType::HDMI => DeviceType::Hdmi,
+                                    _ => DeviceType::Speaker,
+                                },
+                                active: value.active_port.as_ref().and_then(|p| p.name.as_ref())
+                                    == port.name.as_ref(),
+                            })
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            },
+        }
+    }
+}
+
+impl From<&SinkInputInfo<'_>> for PlaybackStream {
+    fn from(value: &SinkInputInfo<'_>) -> Self {
+        Self {
+            index: value.index,
+            name: value
+                .proplist
+                .get_str(APPLICATION_NAME)
+                .or_else(|| value.name.as_ref().map(|n| n.to_string()))
+                .unwrap_or_default(),
+            volume: value.volume,
+            is_mute: value.mute,
+        }
+    }
+}
+
+impl From<&SourceOutputInfo<'_>> for RecordingStream {
+    fn from(value: &SourceOutputInfo<'_>) -> Self {
+        Self {
+            name: value
+                .proplist
+                .get_str(APPLICATION_NAME)
+                .or_else(|| value.name.as_ref().map(|n| n.to_string()))
+                .unwrap_or_default(),
+            source: value.source,
         }
     }
 }
@@ -873,6 +1177,7 @@ impl From<&SinkInfo<'_>> for Device {
 impl From<&SourceInfo<'_>> for Device {
     fn from(value: &SourceInfo<'_>) -> Self {
         Self {
+            index: value.index,
             name: value
                 .name
                 .as_ref()
@@ -884,6 +1189,7 @@ impl From<&SourceInfo<'_>> for Device {
             volume: value.volume,
             is_mute: value.mute,
             in_use: value.state == SourceState::Running,
+            remote: false,
             ports: value
                 .ports
                 .iter()
@@ -104,6 +104,8 @@ impl BluetoothDbus<'_> {
                 .await?;
 
             let name = device.alias().await?;
+            let address = device.address().await?;
+            let device_icon = device.icon().await.ok();
             let connected = device.connected().await?;
             let paired = device.paired().await?;
 
@@ -120,6 +122,8 @@ impl BluetoothDbus<'_> {
 
             devices.push(BluetoothDevice {
                 name,
+                address,
+                icon: device_icon,
                 battery,
                 path: device_path,
                 connected,
@@ -207,6 +211,12 @@ trait Device {
     #[zbus(property)]
     fn alias(&self) -> zbus::Result<String>;
 
+    #[zbus(property)]
+    fn address(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn icon(&self) -> zbus::Result<String>;
+
     #[zbus(property)]
     fn connected(&self) -> zbus::Result<bool>;
 
@@ -1,4 +1,5 @@
 use super::{ReadOnlyService, Service, ServiceEvent};
+use crate::components::icons::StaticIcon;
 use dbus::{BatteryProxy, BluetoothDbus};
 use iced::{
     Subscription, Task,
@@ -28,12 +29,28 @@ pub enum BluetoothState {
 #[derive(Debug, Clone)]
 pub struct BluetoothDevice {
     pub name: String,
+    pub address: String,
+    /// freedesktop icon name reported by bluez, e.g. `audio-headset`
+    pub icon: Option<String>,
     pub battery: Option<u8>,
     pub path: OwnedObjectPath,
     pub connected: bool,
     pub paired: bool,
 }
 
+impl BluetoothDevice {
+    pub fn get_icon(&self) -> StaticIcon {
+        match self.icon.as_deref() {
+            Some("audio-headphones" | "audio-headset") => StaticIcon::Headphones1,
+            Some("audio-card") => StaticIcon::Speaker3,
+            Some("input-keyboard") => StaticIcon::Keyboard,
+            Some("input-mouse" | "input-tablet") => StaticIcon::Mouse,
+            Some("input-gaming") => StaticIcon::Gamepad,
+            _ => StaticIcon::Bluetooth,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BluetoothData {
     pub state: BluetoothState,
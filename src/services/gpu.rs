@@ -0,0 +1,422 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use iced::{
+    Subscription, Task,
+    futures::{SinkExt, StreamExt, channel::mpsc::Sender, stream::pending},
+    stream::channel,
+};
+use log::{debug, error, info};
+use std::{any::TypeId, convert, fs, ops::Deref, time::Duration};
+use tokio::{process::Command, time::sleep};
+use zbus::proxy;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuMode {
+    Integrated,
+    Hybrid,
+    Dedicated,
+    Vfio,
+    Egpu,
+}
+
+impl GpuMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            GpuMode::Integrated => "Integrated",
+            GpuMode::Hybrid => "Hybrid",
+            GpuMode::Dedicated => "Dedicated",
+            GpuMode::Vfio => "VFIO",
+            GpuMode::Egpu => "eGPU",
+        }
+    }
+
+    fn from_supergfx(mode: u32) -> Option<Self> {
+        match mode {
+            0 => Some(GpuMode::Hybrid),
+            1 => Some(GpuMode::Integrated),
+            // NvidiaNoModeset and AsusMuxDgpu
+            2 | 5 => Some(GpuMode::Dedicated),
+            3 => Some(GpuMode::Vfio),
+            4 => Some(GpuMode::Egpu),
+            _ => None,
+        }
+    }
+
+    fn as_supergfx(self) -> u32 {
+        match self {
+            GpuMode::Hybrid => 0,
+            GpuMode::Integrated => 1,
+            GpuMode::Vfio => 3,
+            GpuMode::Egpu => 4,
+            GpuMode::Dedicated => 5,
+        }
+    }
+
+    fn from_envycontrol(mode: &str) -> Option<Self> {
+        match mode {
+            "integrated" => Some(GpuMode::Integrated),
+            "hybrid" => Some(GpuMode::Hybrid),
+            "nvidia" => Some(GpuMode::Dedicated),
+            _ => None,
+        }
+    }
+
+    fn as_envycontrol(self) -> Option<&'static str> {
+        match self {
+            GpuMode::Integrated => Some("integrated"),
+            GpuMode::Hybrid => Some("hybrid"),
+            GpuMode::Dedicated => Some("nvidia"),
+            GpuMode::Vfio | GpuMode::Egpu => None,
+        }
+    }
+}
+
+/// What the user has to do to apply a new mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserAction {
+    Logout,
+    Reboot,
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuClient {
+    pub pid: u32,
+    pub name: String,
+}
+
+/// The processes with an nvidia device open
+fn dgpu_clients() -> Vec<GpuClient> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            // the file descriptors of the processes of other users can't be read
+            let uses_dgpu = fs::read_dir(entry.path().join("fd"))
+                .ok()?
+                .flatten()
+                .filter_map(|fd| fs::read_link(fd.path()).ok())
+                .any(|target| {
+                    target
+                        .to_str()
+                        .and_then(|target| target.strip_prefix("/dev/nvidia"))
+                        .is_some_and(|device| {
+                            !device.is_empty() && device.chars().all(|c| c.is_ascii_digit())
+                        })
+                });
+
+            uses_dgpu.then(|| GpuClient {
+                pid,
+                name: fs::read_to_string(entry.path().join("comm"))
+                    .map(|name| name.trim().to_string())
+                    .unwrap_or_else(|_| pid.to_string()),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Supergfx(zbus::Connection),
+    Envycontrol,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GpuData {
+    pub mode: Option<GpuMode>,
+    pub supported: Vec<GpuMode>,
+    /// the dedicated GPU is powered on, only reported by supergfxctl
+    pub dgpu_active: Option<bool>,
+    pub clients: Vec<GpuClient>,
+    /// the mode applied after the user action
+    pub pending: Option<(GpuMode, UserAction)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum GpuEvent {
+    Status {
+        dgpu_active: Option<bool>,
+        clients: Vec<GpuClient>,
+    },
+    Mode(GpuMode),
+    Pending(GpuMode, UserAction),
+}
+
+#[derive(Debug, Clone)]
+pub enum GpuCommand {
+    SetMode(GpuMode),
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuService {
+    backend: Backend,
+    data: GpuData,
+}
+
+impl Deref for GpuService {
+    type Target = GpuData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    Active(Backend),
+    Error,
+}
+
+impl GpuService {
+    pub fn backend_name(&self) -> &'static str {
+        match self.backend {
+            Backend::Supergfx(_) => "supergfxctl",
+            Backend::Envycontrol => "envycontrol",
+        }
+    }
+
+    async fn supergfx_data(conn: &zbus::Connection) -> anyhow::Result<GpuData> {
+        let gfx = SupergfxProxy::new(conn).await?;
+        let pending_mode = GpuMode::from_supergfx(gfx.pending_mode().await?);
+        let pending_action = match gfx.pending_user_action().await? {
+            0 => Some(UserAction::Logout),
+            1 => Some(UserAction::Reboot),
+            _ => None,
+        };
+
+        Ok(GpuData {
+            mode: GpuMode::from_supergfx(gfx.mode().await?),
+            supported: gfx
+                .supported()
+                .await?
+                .into_iter()
+                .filter_map(GpuMode::from_supergfx)
+                .collect(),
+            dgpu_active: None,
+            clients: Vec::new(),
+            pending: pending_mode.zip(pending_action),
+        })
+    }
+
+    async fn envycontrol_data() -> anyhow::Result<GpuData> {
+        let output = Command::new("envycontrol").arg("--query").output().await?;
+        // the newer versions print `Current graphics mode is: hybrid`
+        let mode = String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .last()
+            .and_then(GpuMode::from_envycontrol);
+
+        Ok(GpuData {
+            mode,
+            supported: vec![GpuMode::Integrated, GpuMode::Hybrid, GpuMode::Dedicated],
+            ..GpuData::default()
+        })
+    }
+
+    async fn initialize() -> Option<(Backend, GpuData)> {
+        match zbus::Connection::system().await {
+            Ok(conn) => match GpuService::supergfx_data(&conn).await {
+                Ok(data) => return Some((Backend::Supergfx(conn), data)),
+                Err(err) => debug!("supergfxd not available: {err}"),
+            },
+            Err(err) => debug!("Failed to connect to system bus: {err}"),
+        }
+
+        match GpuService::envycontrol_data().await {
+            Ok(data) if data.mode.is_some() => Some((Backend::Envycontrol, data)),
+            Ok(_) => None,
+            Err(err) => {
+                debug!("envycontrol not available: {err}");
+                None
+            }
+        }
+    }
+
+    async fn status(backend: &Backend) -> GpuEvent {
+        let dgpu_active = match backend {
+            Backend::Supergfx(conn) => match SupergfxProxy::new(conn).await {
+                Ok(gfx) => gfx.power().await.ok().and_then(|power| match power {
+                    // Active and AsusMuxDiscreet
+                    0 | 4 => Some(true),
+                    1..=3 => Some(false),
+                    _ => None,
+                }),
+                Err(_) => None,
+            },
+            Backend::Envycontrol => None,
+        };
+
+        GpuEvent::Status {
+            dgpu_active,
+            // a suspended dGPU has no clients
+            clients: if dgpu_active == Some(false) {
+                Vec::new()
+            } else {
+                dgpu_clients()
+            },
+        }
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match GpuService::initialize().await {
+                Some((backend, data)) => {
+                    info!("GPU service initialized");
+
+                    let _ = output
+                        .send(ServiceEvent::Init(GpuService {
+                            backend: backend.clone(),
+                            data,
+                        }))
+                        .await;
+                    let _ = output
+                        .send(ServiceEvent::Update(GpuService::status(&backend).await))
+                        .await;
+
+                    State::Active(backend)
+                }
+                None => {
+                    error!("Neither supergfxctl nor envycontrol is available");
+
+                    State::Error
+                }
+            },
+            State::Active(backend) => {
+                sleep(POLL_INTERVAL).await;
+
+                let _ = output
+                    .send(ServiceEvent::Update(GpuService::status(&backend).await))
+                    .await;
+
+                State::Active(backend)
+            }
+            State::Error => {
+                let _ = pending::<u8>().next().await;
+
+                State::Error
+            }
+        }
+    }
+
+    async fn set_mode(backend: Backend, mode: GpuMode) -> anyhow::Result<Option<UserAction>> {
+        match backend {
+            Backend::Supergfx(conn) => {
+                let action = SupergfxProxy::new(&conn)
+                    .await?
+                    .set_mode(mode.as_supergfx())
+                    .await?;
+
+                match action {
+                    0 => Ok(Some(UserAction::Logout)),
+                    1 => Ok(Some(UserAction::Reboot)),
+                    2 => Err(anyhow::anyhow!("Switch to the integrated mode first")),
+                    3 => Err(anyhow::anyhow!("Disable the eGPU first")),
+                    _ => Ok(None),
+                }
+            }
+            Backend::Envycontrol => {
+                let mode = mode
+                    .as_envycontrol()
+                    .ok_or_else(|| anyhow::anyhow!("Mode not supported by envycontrol"))?;
+                let output = Command::new("pkexec")
+                    .args(["envycontrol", "--switch", mode])
+                    .output()
+                    .await?;
+
+                if output.status.success() {
+                    Ok(Some(UserAction::Reboot))
+                } else {
+                    Err(anyhow::anyhow!(
+                        "{}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for GpuService {
+    type UpdateEvent = GpuEvent;
+    type Error = String;
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        match event {
+            GpuEvent::Status {
+                dgpu_active,
+                clients,
+            } => {
+                self.data.dgpu_active = dgpu_active;
+                self.data.clients = clients;
+            }
+            GpuEvent::Mode(mode) => {
+                self.data.mode = Some(mode);
+                self.data.pending = None;
+            }
+            GpuEvent::Pending(mode, action) => {
+                self.data.pending = Some((mode, action));
+            }
+        }
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                let mut state = State::Init;
+
+                loop {
+                    state = GpuService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+impl Service for GpuService {
+    type Command = GpuCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let backend = self.backend.clone();
+
+        match command {
+            GpuCommand::SetMode(mode) => Task::perform(
+                async move {
+                    debug!("Switching the GPU mode to {mode:?}");
+
+                    match GpuService::set_mode(backend, mode).await {
+                        Ok(Some(action)) => ServiceEvent::Update(GpuEvent::Pending(mode, action)),
+                        Ok(None) => ServiceEvent::Update(GpuEvent::Mode(mode)),
+                        Err(err) => ServiceEvent::Error(err.to_string()),
+                    }
+                },
+                convert::identity,
+            ),
+        }
+    }
+}
+
+#[proxy(
+    default_service = "org.supergfxctl.Daemon",
+    default_path = "/org/supergfxctl/Gfx",
+    interface = "org.supergfxctl.Daemon"
+)]
+trait Supergfx {
+    fn mode(&self) -> zbus::Result<u32>;
+
+    fn set_mode(&self, mode: u32) -> zbus::Result<u32>;
+
+    fn supported(&self) -> zbus::Result<Vec<u32>>;
+
+    fn power(&self) -> zbus::Result<u32>;
+
+    fn pending_mode(&self) -> zbus::Result<u32>;
+
+    fn pending_user_action(&self) -> zbus::Result<u32>;
+}
@@ -0,0 +1,206 @@
+use super::{ReadOnlyService, ServiceEvent};
+use iced::{
+    Subscription,
+    futures::{SinkExt, Stream, StreamExt, channel::mpsc::Sender, stream::pending, stream_select},
+    stream::channel,
+};
+use log::{debug, error, info};
+use std::{any::TypeId, ops::Deref};
+use zbus::{proxy, zvariant::OwnedObjectPath};
+
+#[derive(Debug, Clone, Default)]
+pub struct LogindData {
+    pub locked: bool,
+    pub idle: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogindService {
+    data: LogindData,
+}
+
+impl Deref for LogindService {
+    type Target = LogindData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogindEvent {
+    Lock,
+    Unlock,
+    Idle(bool),
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection, OwnedObjectPath),
+    Error,
+}
+
+impl LogindService {
+    async fn session_path(conn: &zbus::Connection) -> anyhow::Result<OwnedObjectPath> {
+        let manager = LogindManagerProxy::new(conn).await?;
+
+        // `auto` resolves to the session of the caller or, if the caller
+        // is not part of a session, to the display session of the user
+        Ok(manager.get_session("auto").await?)
+    }
+
+    async fn hints(conn: &zbus::Connection, path: &OwnedObjectPath) -> anyhow::Result<LogindData> {
+        let session = LogindSessionProxy::builder(conn)
+            .path(path.clone())?
+            .build()
+            .await?;
+
+        Ok(LogindData {
+            locked: session.locked_hint().await?,
+            idle: session.idle_hint().await?,
+        })
+    }
+
+    async fn events(
+        conn: &zbus::Connection,
+        path: &OwnedObjectPath,
+    ) -> anyhow::Result<impl Stream<Item = LogindEvent> + use<>> {
+        let session = LogindSessionProxy::builder(conn)
+            .path(path.clone())?
+            .build()
+            .await?;
+
+        let lock = session.receive_lock().await?.map(|_| LogindEvent::Lock);
+        let unlock = session.receive_unlock().await?.map(|_| LogindEvent::Unlock);
+        // the idle hint is set by the idle daemon, e.g. swayidle with `idlehint`
+        let idle = session
+            .receive_idle_hint_changed()
+            .await
+            .filter_map(|change| async move { change.get().await.ok().map(LogindEvent::Idle) })
+            .boxed();
+
+        Ok(stream_select!(lock, unlock, idle))
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => match Self::session_path(&conn).await {
+                    Ok(path) => {
+                        debug!("Logind session path: {path:?}");
+
+                        let data = Self::hints(&conn, &path).await.unwrap_or_default();
+
+                        let _ = output
+                            .send(ServiceEvent::Init(LogindService { data }))
+                            .await;
+
+                        State::Active(conn, path)
+                    }
+                    Err(err) => {
+                        error!("Failed to get logind session: {err}");
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to system bus for logind: {err}");
+
+                    State::Error
+                }
+            },
+            State::Active(conn, path) => {
+                info!("Listening for logind session events");
+
+                match Self::events(&conn, &path).await {
+                    Ok(mut events) => {
+                        while let Some(event) = events.next().await {
+                            debug!("Logind session event: {event:?}");
+                            let _ = output.send(ServiceEvent::Update(event)).await;
+                        }
+
+                        State::Active(conn, path)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for logind events: {err}");
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                let _ = pending::<u8>().next().await;
+
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for LogindService {
+    type UpdateEvent = LogindEvent;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        match event {
+            LogindEvent::Lock => {
+                self.data.locked = true;
+            }
+            LogindEvent::Unlock => {
+                self.data.locked = false;
+            }
+            LogindEvent::Idle(idle) => {
+                self.data.idle = idle;
+            }
+        }
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                let mut state = State::Init;
+
+                loop {
+                    state = LogindService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1",
+    interface = "org.freedesktop.login1.Manager"
+)]
+pub trait LogindManager {
+    fn get_session(&self, session_id: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(property)]
+    fn lid_closed(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn handle_power_key(&self) -> zbus::Result<String>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1/session/auto",
+    interface = "org.freedesktop.login1.Session"
+)]
+trait LogindSession {
+    #[zbus(property)]
+    fn locked_hint(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+
+    #[zbus(signal)]
+    fn lock(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn unlock(&self) -> zbus::Result<()>;
+}
@@ -1,15 +1,33 @@
 use iced::{Subscription, Task};
 
+pub mod acpi;
 pub mod audio;
 pub mod bluetooth;
 pub mod brightness;
+#[cfg(feature = "gpu")]
+pub mod gpu;
 pub mod idle_inhibitor;
+pub mod logind;
+#[cfg(feature = "modem")]
+pub mod modem;
+#[cfg(feature = "media-player")]
 pub mod mpris;
 pub mod network;
+#[cfg(feature = "notifications")]
+pub mod notifications;
 pub mod privacy;
+#[cfg(feature = "passwords")]
+pub mod secret_service;
 mod throttle;
+#[cfg(feature = "clock")]
+pub mod timedate;
+#[cfg(feature = "tray")]
 pub mod tray;
+#[cfg(feature = "encrypted-volumes")]
+pub mod udisks;
 pub mod upower;
+#[cfg(feature = "usbguard")]
+pub mod usbguard;
 
 #[allow(unused)]
 #[derive(Debug, Clone)]
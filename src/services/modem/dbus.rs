@@ -0,0 +1,315 @@
+use super::{ModemInfo, ModemState, SmsMessage};
+use chrono::{DateTime, Local};
+use log::warn;
+use std::collections::HashMap;
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+const MODEM_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem";
+const MODEM_3GPP_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem.Modem3gpp";
+
+// MMSmsState
+const SMS_STATE_RECEIVING: u32 = 2;
+const SMS_STATE_RECEIVED: u32 = 3;
+
+/// Short label of the best access technology of the MMModemAccessTechnology bitmask
+fn access_technology(flags: u32) -> Option<String> {
+    let label = if flags & (1 << 15) != 0 {
+        "5G"
+    } else if flags & (1 << 14 | 1 << 16 | 1 << 17) != 0 {
+        "LTE"
+    } else if flags & (1 << 9) != 0 {
+        "H+"
+    } else if flags & (1 << 5 | 1 << 6 | 1 << 7 | 1 << 8 | 1 << 11 | 1 << 12 | 1 << 13) != 0 {
+        "3G"
+    } else if flags & (1 << 4) != 0 {
+        "E"
+    } else if flags & (1 << 1 | 1 << 2 | 1 << 3 | 1 << 10) != 0 {
+        "2G"
+    } else {
+        return None;
+    };
+
+    Some(label.to_string())
+}
+
+pub struct ModemManagerDbus<'a> {
+    pub manager: ModemManagerObjectManagerProxy<'a>,
+}
+
+impl ModemManagerDbus<'_> {
+    pub async fn new(conn: &zbus::Connection) -> anyhow::Result<Self> {
+        let manager = ModemManagerObjectManagerProxy::new(conn).await?;
+
+        Ok(Self { manager })
+    }
+
+    fn conn(&self) -> &zbus::Connection {
+        self.manager.inner().connection()
+    }
+
+    /// Reads the first modem, laptops have at most one WWAN card
+    pub async fn modem(&self) -> anyhow::Result<Option<ModemInfo>> {
+        let objects = self.manager.get_managed_objects().await?;
+
+        let Some((path, interfaces)) = objects
+            .iter()
+            .filter(|(_, interfaces)| interfaces.contains_key(MODEM_INTERFACE))
+            .min_by_key(|(path, _)| path.as_str())
+        else {
+            return Ok(None);
+        };
+
+        let modem = ModemProxy::builder(self.conn())
+            .path(path.clone())?
+            .build()
+            .await?;
+
+        let operator = if interfaces.contains_key(MODEM_3GPP_INTERFACE) {
+            Modem3gppProxy::builder(self.conn())
+                .path(path.clone())?
+                .build()
+                .await?
+                .operator_name()
+                .await
+                .ok()
+                .filter(|operator| !operator.is_empty())
+        } else {
+            None
+        };
+
+        Ok(Some(ModemInfo {
+            path: path.clone(),
+            model: [modem.manufacturer().await, modem.model().await]
+                .into_iter()
+                .flatten()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+            operator,
+            state: ModemState::from(modem.state().await.unwrap_or_default()),
+            signal: modem.signal_quality().await.unwrap_or_default().0.min(100) as u8,
+            access_technology: access_technology(
+                modem.access_technologies().await.unwrap_or_default(),
+            ),
+            messages: self.messages(path).await.unwrap_or_else(|err| {
+                warn!("Failed to read the modem messages: {err}");
+
+                Vec::new()
+            }),
+        }))
+    }
+
+    async fn messages(&self, modem: &OwnedObjectPath) -> anyhow::Result<Vec<SmsMessage>> {
+        let messaging = MessagingProxy::builder(self.conn())
+            .path(modem.clone())?
+            .build()
+            .await?;
+
+        let mut messages = Vec::new();
+        for path in messaging.messages().await? {
+            let sms = SmsProxy::builder(self.conn())
+                .path(path.clone())?
+                .build()
+                .await?;
+
+            // skip the drafts and the sent messages
+            if !matches!(
+                sms.state().await.unwrap_or_default(),
+                SMS_STATE_RECEIVING | SMS_STATE_RECEIVED
+            ) {
+                continue;
+            }
+
+            messages.push(SmsMessage {
+                path,
+                number: sms.number().await.unwrap_or_default(),
+                text: sms.text().await.unwrap_or_default(),
+                timestamp: sms
+                    .timestamp()
+                    .await
+                    .ok()
+                    .and_then(|timestamp| DateTime::parse_from_rfc3339(&timestamp).ok())
+                    .map(|timestamp| timestamp.with_timezone(&Local)),
+            });
+        }
+
+        messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+        Ok(messages)
+    }
+
+    pub async fn connect(&self, modem: &OwnedObjectPath, apn: Option<&str>) -> anyhow::Result<()> {
+        let proxy = ModemProxy::builder(self.conn())
+            .path(modem.clone())?
+            .build()
+            .await?;
+
+        if let Some(apn) = apn {
+            // the simple interface enables and registers the modem when needed
+            let simple = SimpleProxy::builder(self.conn())
+                .path(modem.clone())?
+                .build()
+                .await?;
+
+            simple
+                .connect(HashMap::from([("apn", Value::from(apn))]))
+                .await?;
+        } else {
+            // reuse the bearer created by NetworkManager or by a previous connection
+            let bearer = proxy
+                .bearers()
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("No APN configured for the modem"))?;
+
+            if ModemState::from(proxy.state().await?) == ModemState::Disabled {
+                proxy.enable(true).await?;
+            }
+
+            BearerProxy::builder(self.conn())
+                .path(bearer)?
+                .build()
+                .await?
+                .connect()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn disconnect(&self, modem: &OwnedObjectPath) -> anyhow::Result<()> {
+        let simple = SimpleProxy::builder(self.conn())
+            .path(modem.clone())?
+            .build()
+            .await?;
+
+        // the root path disconnects every bearer
+        simple.disconnect(&OwnedObjectPath::try_from("/")?).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_message(
+        &self,
+        modem: &OwnedObjectPath,
+        sms: &OwnedObjectPath,
+    ) -> anyhow::Result<()> {
+        let messaging = MessagingProxy::builder(self.conn())
+            .path(modem.clone())?
+            .build()
+            .await?;
+
+        messaging.delete(sms).await?;
+
+        Ok(())
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.ModemManager1",
+    default_path = "/org/freedesktop/ModemManager1",
+    interface = "org.freedesktop.DBus.ObjectManager"
+)]
+pub trait ModemManagerObjectManager {
+    fn get_managed_objects(&self) -> zbus::Result<ManagedObjects>;
+
+    #[zbus(signal)]
+    fn interfaces_added(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn interfaces_removed(&self) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.ModemManager1",
+    interface = "org.freedesktop.ModemManager1.Modem"
+)]
+pub trait Modem {
+    fn enable(&self, enable: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn manufacturer(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn model(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<i32>;
+
+    #[zbus(property)]
+    fn signal_quality(&self) -> zbus::Result<(u32, bool)>;
+
+    #[zbus(property)]
+    fn access_technologies(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn bearers(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.ModemManager1",
+    interface = "org.freedesktop.ModemManager1.Modem.Modem3gpp"
+)]
+pub trait Modem3gpp {
+    #[zbus(property)]
+    fn operator_name(&self) -> zbus::Result<String>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.ModemManager1",
+    interface = "org.freedesktop.ModemManager1.Modem.Simple"
+)]
+trait Simple {
+    fn connect(&self, properties: HashMap<&str, Value<'_>>) -> zbus::Result<OwnedObjectPath>;
+
+    fn disconnect(&self, bearer: &OwnedObjectPath) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.ModemManager1",
+    interface = "org.freedesktop.ModemManager1.Bearer"
+)]
+trait Bearer {
+    fn connect(&self) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.ModemManager1",
+    interface = "org.freedesktop.ModemManager1.Modem.Messaging"
+)]
+pub trait Messaging {
+    fn delete(&self, path: &OwnedObjectPath) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn messages(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    #[zbus(signal)]
+    fn added(&self, path: OwnedObjectPath, received: bool) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn deleted(&self, path: OwnedObjectPath) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.ModemManager1",
+    interface = "org.freedesktop.ModemManager1.Sms"
+)]
+trait Sms {
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn number(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn text(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn timestamp(&self) -> zbus::Result<String>;
+}
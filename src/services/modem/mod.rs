@@ -0,0 +1,334 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use chrono::{DateTime, Local};
+use dbus::{MessagingProxy, Modem3gppProxy, ModemManagerDbus, ModemProxy};
+use iced::{
+    Subscription, Task,
+    futures::{
+        SinkExt, Stream, StreamExt,
+        channel::mpsc::Sender,
+        stream::{pending, select_all},
+    },
+    stream::channel,
+};
+use log::{debug, error, info};
+use std::{any::TypeId, convert, ops::Deref};
+use zbus::zvariant::OwnedObjectPath;
+
+mod dbus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModemState {
+    Failed,
+    Locked,
+    Disabled,
+    Searching,
+    Registered,
+    Connecting,
+    Connected,
+    Unknown,
+}
+
+// MMModemState
+impl From<i32> for ModemState {
+    fn from(state: i32) -> Self {
+        match state {
+            -1 => ModemState::Failed,
+            2 => ModemState::Locked,
+            3 | 4 => ModemState::Disabled,
+            5..=7 => ModemState::Searching,
+            8 | 9 => ModemState::Registered,
+            10 => ModemState::Connecting,
+            11 => ModemState::Connected,
+            _ => ModemState::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SmsMessage {
+    pub path: OwnedObjectPath,
+    pub number: String,
+    pub text: String,
+    pub timestamp: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModemInfo {
+    pub path: OwnedObjectPath,
+    pub model: String,
+    pub operator: Option<String>,
+    pub state: ModemState,
+    /// signal quality in percent
+    pub signal: u8,
+    pub access_technology: Option<String>,
+    /// received messages, the most recent first
+    pub messages: Vec<SmsMessage>,
+}
+
+impl ModemInfo {
+    pub fn is_connected(&self) -> bool {
+        self.state == ModemState::Connected
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ModemData {
+    pub modem: Option<ModemInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModemService {
+    conn: zbus::Connection,
+    data: ModemData,
+}
+
+impl Deref for ModemService {
+    type Target = ModemData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ModemCommand {
+    Refresh,
+    /// Connects the mobile data, using the given APN if any
+    Connect(Option<String>),
+    Disconnect,
+    DeleteMessage(OwnedObjectPath),
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl ModemService {
+    async fn initialize_data(conn: &zbus::Connection) -> anyhow::Result<ModemData> {
+        let modem_manager = ModemManagerDbus::new(conn).await?;
+        let modem = modem_manager.modem().await?;
+
+        Ok(ModemData { modem })
+    }
+
+    async fn events(
+        conn: &zbus::Connection,
+        modem: Option<&OwnedObjectPath>,
+    ) -> anyhow::Result<impl Stream<Item = ()> + use<>> {
+        let modem_manager = ModemManagerDbus::new(conn).await?;
+
+        let mut events = vec![
+            modem_manager
+                .manager
+                .receive_interfaces_added()
+                .await?
+                .map(|_| {})
+                .boxed(),
+            modem_manager
+                .manager
+                .receive_interfaces_removed()
+                .await?
+                .map(|_| {})
+                .boxed(),
+        ];
+
+        if let Some(path) = modem {
+            let modem = ModemProxy::builder(conn)
+                .path(path.clone())?
+                .build()
+                .await?;
+            let modem_3gpp = Modem3gppProxy::builder(conn)
+                .path(path.clone())?
+                .build()
+                .await?;
+            let messaging = MessagingProxy::builder(conn)
+                .path(path.clone())?
+                .build()
+                .await?;
+
+            events.extend([
+                modem.receive_state_changed().await.map(|_| {}).boxed(),
+                modem
+                    .receive_signal_quality_changed()
+                    .await
+                    .map(|_| {})
+                    .boxed(),
+                modem
+                    .receive_access_technologies_changed()
+                    .await
+                    .map(|_| {})
+                    .boxed(),
+                modem_3gpp
+                    .receive_operator_name_changed()
+                    .await
+                    .map(|_| {})
+                    .boxed(),
+                messaging.receive_added().await?.map(|_| {}).boxed(),
+                messaging.receive_deleted().await?.map(|_| {}).boxed(),
+            ]);
+        }
+
+        Ok(select_all(events))
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => match ModemService::initialize_data(&conn).await {
+                    Ok(data) => {
+                        info!("Modem service initialized");
+
+                        let _ = output
+                            .send(ServiceEvent::Init(ModemService {
+                                data,
+                                conn: conn.clone(),
+                            }))
+                            .await;
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to initialize modem service: {err}");
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to system bus: {err}");
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for modem events");
+
+                let modem = match ModemService::initialize_data(&conn).await {
+                    Ok(data) => data.modem.map(|modem| modem.path),
+                    Err(err) => {
+                        error!("Failed to read the modem: {err}");
+
+                        return State::Error;
+                    }
+                };
+
+                match ModemService::events(&conn, modem.as_ref()).await {
+                    Ok(mut events) => {
+                        while events.next().await.is_some() {
+                            if let Ok(data) = ModemService::initialize_data(&conn).await {
+                                let changed =
+                                    data.modem.as_ref().map(|modem| &modem.path) != modem.as_ref();
+                                let _ = output.send(ServiceEvent::Update(data)).await;
+
+                                // listen to the new modem
+                                if changed {
+                                    debug!("Modem changed");
+                                    break;
+                                }
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for modem events: {err}");
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                error!("Modem service error");
+
+                let _ = pending::<u8>().next().await;
+
+                State::Error
+            }
+        }
+    }
+
+    fn refresh_after(
+        conn: zbus::Connection,
+        action: impl Future<Output = anyhow::Result<()>> + Send + 'static,
+    ) -> Task<ServiceEvent<Self>> {
+        Task::perform(
+            async move {
+                let res = action.await;
+
+                match (res, ModemService::initialize_data(&conn).await) {
+                    (Err(err), _) => ServiceEvent::Error(err.to_string()),
+                    (Ok(_), Ok(data)) => ServiceEvent::Update(data),
+                    (Ok(_), Err(err)) => ServiceEvent::Error(err.to_string()),
+                }
+            },
+            convert::identity,
+        )
+    }
+}
+
+impl ReadOnlyService for ModemService {
+    type UpdateEvent = ModemData;
+    type Error = String;
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                let mut state = State::Init;
+
+                loop {
+                    state = ModemService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+impl Service for ModemService {
+    type Command = ModemCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let conn = self.conn.clone();
+        let Some(modem) = self.modem.as_ref().map(|modem| modem.path.clone()) else {
+            return Task::none();
+        };
+
+        match command {
+            ModemCommand::Refresh => ModemService::refresh_after(conn, async { Ok(()) }),
+            ModemCommand::Connect(apn) => {
+                debug!("Connecting modem {modem:?}");
+
+                ModemService::refresh_after(conn.clone(), async move {
+                    ModemManagerDbus::new(&conn)
+                        .await?
+                        .connect(&modem, apn.as_deref())
+                        .await
+                })
+            }
+            ModemCommand::Disconnect => {
+                debug!("Disconnecting modem {modem:?}");
+
+                ModemService::refresh_after(conn.clone(), async move {
+                    ModemManagerDbus::new(&conn).await?.disconnect(&modem).await
+                })
+            }
+            ModemCommand::DeleteMessage(sms) => {
+                debug!("Deleting message {sms:?}");
+
+                ModemService::refresh_after(conn.clone(), async move {
+                    ModemManagerDbus::new(&conn)
+                        .await?
+                        .delete_message(&modem, &sms)
+                        .await
+                })
+            }
+        }
+    }
+}
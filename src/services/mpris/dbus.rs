@@ -10,6 +10,9 @@ pub trait MprisPlayer {
     fn play_pause(&self) -> Result<()>;
     fn previous(&self) -> Result<()>;
 
+    #[zbus(signal)]
+    fn seeked(&self, position: i64) -> Result<()>;
+
     #[zbus(property)]
     fn playback_status(&self) -> Result<String>;
     #[zbus(property)]
@@ -20,4 +23,6 @@ pub trait MprisPlayer {
     fn volume(&self) -> Result<f64>;
     #[zbus(property)]
     fn can_control(&self) -> Result<bool>;
+    #[zbus(property)]
+    fn position(&self) -> Result<i64>;
 }
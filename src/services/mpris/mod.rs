@@ -11,7 +11,7 @@ use iced::{
     stream::channel,
 };
 use log::{debug, error, info};
-use std::{any::TypeId, collections::HashMap, fmt::Display, ops::Deref, sync::Arc};
+use std::{any::TypeId, collections::HashMap, fmt::Display, ops::Deref, sync::Arc, time::Duration};
 use zbus::{fdo::DBusProxy, zvariant::OwnedValue};
 
 mod dbus;
@@ -40,13 +40,42 @@ pub struct MprisPlayerData {
     pub metadata: Option<MprisPlayerMetadata>,
     pub volume: Option<f64>,
     pub state: PlaybackStatus,
+    /// the players don't notify the position changes, it's advanced by `tick`
+    pub position: Option<Duration>,
     proxy: MprisPlayerProxy<'static>,
 }
 
+impl MprisPlayerData {
+    /// Name of the player without the MPRIS prefix, i.e. `spotify` or `firefox.instance_1_84`
+    pub fn player_name(&self) -> &str {
+        self.service
+            .strip_prefix(MPRIS_PLAYER_SERVICE_PREFIX)
+            .unwrap_or(&self.service)
+    }
+
+    /// Matches the name of the player, ignoring the instance suffix
+    pub fn is_player(&self, name: &str) -> bool {
+        let player_name = self.player_name();
+
+        player_name.eq_ignore_ascii_case(name)
+            || player_name
+                .split_once('.')
+                .is_some_and(|(player, _)| player.eq_ignore_ascii_case(name))
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct MprisPlayerMetadata {
     pub artists: Option<Vec<String>>,
     pub title: Option<String>,
+    /// the book or the podcast for the audiobook and podcast players
+    pub album: Option<String>,
+    /// the chapter or the episode for the audiobook and podcast players
+    pub track_number: Option<i32>,
+    pub length: Option<Duration>,
+    pub art_url: Option<String>,
+    /// location of the track, `file://` for the local files
+    pub url: Option<String>,
 }
 
 impl Display for MprisPlayerMetadata {
@@ -71,8 +100,41 @@ impl From<HashMap<String, OwnedValue>> for MprisPlayerMetadata {
             Some(v) => v.clone().try_into().ok(),
             None => None,
         };
-
-        Self { artists, title }
+        let album = value
+            .get("xesam:album")
+            .and_then(|v| v.clone().try_into().ok())
+            .filter(|album: &String| !album.is_empty());
+        let track_number = value
+            .get("xesam:trackNumber")
+            .and_then(|v| v.clone().try_into().ok());
+        // the length should be a signed integer but some players send an unsigned one
+        let length = value
+            .get("mpris:length")
+            .and_then(|v| {
+                i64::try_from(v.clone())
+                    .ok()
+                    .and_then(|l| u64::try_from(l).ok())
+                    .or_else(|| u64::try_from(v.clone()).ok())
+            })
+            .map(Duration::from_micros);
+        let art_url = value
+            .get("mpris:artUrl")
+            .and_then(|v| v.clone().try_into().ok())
+            .filter(|url: &String| !url.is_empty());
+        let url = value
+            .get("xesam:url")
+            .and_then(|v| v.clone().try_into().ok())
+            .filter(|url: &String| !url.is_empty());
+
+        Self {
+            artists,
+            title,
+            album,
+            track_number,
+            length,
+            art_url,
+            url,
+        }
     }
 }
 
@@ -96,6 +158,25 @@ enum State {
     Error,
 }
 
+impl MprisPlayerService {
+    /// Advances the position of the playing players
+    pub fn tick(&mut self, elapsed: Duration) {
+        for player in self
+            .data
+            .iter_mut()
+            .filter(|d| d.state == PlaybackStatus::Playing)
+        {
+            if let Some(position) = player.position.as_mut() {
+                *position += elapsed;
+
+                if let Some(length) = player.metadata.as_ref().and_then(|m| m.length) {
+                    *position = (*position).min(length);
+                }
+            }
+        }
+    }
+}
+
 impl ReadOnlyService for MprisPlayerService {
     type UpdateEvent = Vec<MprisPlayerData>;
     type Error = ();
@@ -161,12 +242,19 @@ impl MprisPlayerService {
                         .await
                         .map(PlaybackStatus::from)
                         .unwrap_or_default();
+                    let position = proxy
+                        .position()
+                        .await
+                        .ok()
+                        .and_then(|p| u64::try_from(p).ok())
+                        .map(Duration::from_micros);
 
                     Some(MprisPlayerData {
                         service: s.to_string(),
                         metadata,
                         volume,
                         state,
+                        position,
                         proxy,
                     })
                 }
@@ -272,6 +360,17 @@ impl MprisPlayerService {
             );
         }
 
+        // the position is read again after a seek
+        for s in data.iter() {
+            combined.push(
+                s.proxy
+                    .receive_seeked()
+                    .await?
+                    .map(|_| debug!("Player seeked"))
+                    .boxed(),
+            );
+        }
+
         Ok(combined)
     }
 
@@ -3,7 +3,7 @@ use crate::services::{
     network::{NetworkBackend, NetworkData, NetworkEvent},
 };
 
-use super::{AccessPoint, ActiveConnectionInfo, KnownConnection, Vpn};
+use super::{AccessPoint, ActiveConnectionInfo, KnownConnection, SavedNetwork, Vpn};
 use iced::futures::{Stream, StreamExt, stream::select_all};
 use itertools::Itertools;
 use log::{debug, warn};
@@ -44,6 +44,9 @@ impl super::NetworkBackend for NetworkDbus<'_> {
             .await?;
         debug!("Known connections: {known_connections:?}");
 
+        let saved_networks = nm.saved_networks().await?;
+        debug!("Saved networks: {saved_networks:?}");
+
         Ok(NetworkData {
             wifi_present,
             active_connections,
@@ -52,6 +55,7 @@ impl super::NetworkBackend for NetworkDbus<'_> {
             connectivity: nm.connectivity().await?,
             wireless_access_points,
             known_connections,
+            saved_networks,
             scanning_nearby_wifi: false,
         })
     }
@@ -98,6 +102,21 @@ impl super::NetworkBackend for NetworkDbus<'_> {
         Ok(())
     }
 
+    async fn disconnect_wifi(&self) -> anyhow::Result<()> {
+        for device_path in self.wireless_devices().await? {
+            let device = DeviceProxy::builder(self.0.inner().connection())
+                .path(device_path)?
+                .build()
+                .await?;
+
+            if DeviceState::from(device.state().await?) == DeviceState::Activated {
+                device.disconnect().await?;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn select_access_point(
         &mut self,
         access_point: &AccessPoint,
@@ -194,6 +213,89 @@ impl super::NetworkBackend for NetworkDbus<'_> {
         self.known_connections_internal(&wireless_access_points)
             .await
     }
+
+    async fn saved_networks(&self) -> anyhow::Result<Vec<SavedNetwork>> {
+        let settings = NetworkSettingsDbus::new(self.0.inner().connection()).await?;
+
+        let mut saved_networks = Vec::new();
+        for c in settings.know_connections().await? {
+            let cs = ConnectionSettingsProxy::builder(self.0.inner().connection())
+                .path(c.clone())?
+                .build()
+                .await?;
+            let Ok(s) = cs.get_settings().await else {
+                warn!("Failed to get settings for connection {c}");
+                continue;
+            };
+
+            if !s.contains_key("802-11-wireless") {
+                continue;
+            }
+
+            let name = s
+                .get("connection")
+                .and_then(|c| c.get("id"))
+                .map(|v| match v.deref() {
+                    Value::Str(v) => v.to_string(),
+                    _ => "".to_string(),
+                })
+                .unwrap_or_default();
+
+            saved_networks.push(SavedNetwork {
+                name,
+                path: c,
+                priority: Some(autoconnect_priority(&s)),
+            });
+        }
+
+        saved_networks.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.name.cmp(&b.name)));
+
+        Ok(saved_networks)
+    }
+
+    async fn set_saved_networks_order(&self, order: Vec<OwnedObjectPath>) -> anyhow::Result<()> {
+        let len = order.len();
+
+        for (i, c) in order.into_iter().enumerate() {
+            let priority = (len - i) as i32;
+
+            let cs = ConnectionSettingsProxy::builder(self.0.inner().connection())
+                .path(c.clone())?
+                .build()
+                .await?;
+            let mut s = cs.get_settings().await?;
+
+            if autoconnect_priority(&s) == priority {
+                continue;
+            }
+
+            // GetSettings doesn't return the secrets, without them Update would drop the password
+            if let Ok(secrets) = cs.get_secrets("802-11-wireless-security").await {
+                for (setting, values) in secrets {
+                    s.entry(setting).or_default().extend(values);
+                }
+            }
+
+            debug!("Set autoconnect priority of {c} to {priority}");
+            s.entry("connection".to_string()).or_default().insert(
+                "autoconnect-priority".to_string(),
+                Value::from(priority).try_to_owned()?,
+            );
+
+            cs.update(s).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn forget_network(&self, path: OwnedObjectPath) -> anyhow::Result<()> {
+        let cs = ConnectionSettingsProxy::builder(self.0.inner().connection())
+            .path(path)?
+            .build()
+            .await?;
+
+        Ok(cs.delete().await?)
+    }
 }
 
 impl<'a> Deref for NetworkDbus<'a> {
@@ -399,6 +501,23 @@ impl NetworkDbus<'_> {
             })
             .boxed();
 
+        let saved_networks = settings
+            .receive_connections_changed()
+            .await
+            .then({
+                let conn = conn.clone();
+                move |_| {
+                    let conn = conn.clone();
+                    async move {
+                        let nm = NetworkDbus::new(&conn).await.unwrap();
+                        let saved_networks = nm.saved_networks().await.unwrap_or_default();
+
+                        NetworkEvent::SavedNetworks(saved_networks)
+                    }
+                }
+            })
+            .boxed();
+
         let events = select_all(vec![
             wireless_enabled,
             wireless_devices_changed,
@@ -407,6 +526,7 @@ impl NetworkDbus<'_> {
             access_points,
             strength_changes,
             known_connections,
+            saved_networks,
         ]);
 
         Ok(events)
@@ -683,6 +803,17 @@ impl NetworkDbus<'_> {
     }
 }
 
+fn autoconnect_priority(settings: &HashMap<String, HashMap<String, OwnedValue>>) -> i32 {
+    settings
+        .get("connection")
+        .and_then(|c| c.get("autoconnect-priority"))
+        .and_then(|v| match v.deref() {
+            Value::I32(v) => Some(*v),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
 pub struct NetworkSettingsDbus<'a>(SettingsProxy<'a>);
 
 impl<'a> Deref for NetworkSettingsDbus<'a> {
@@ -964,6 +1095,8 @@ pub trait Device {
 
     #[zbus(property)]
     fn state(&self) -> Result<u32>;
+
+    fn disconnect(&self) -> Result<()>;
 }
 
 #[proxy(
@@ -1057,4 +1190,11 @@ trait ConnectionSettings {
     fn update(&self, settings: HashMap<String, HashMap<String, OwnedValue>>) -> Result<()>;
 
     fn get_settings(&self) -> Result<HashMap<String, HashMap<String, OwnedValue>>>;
+
+    fn get_secrets(
+        &self,
+        setting_name: &str,
+    ) -> Result<HashMap<String, HashMap<String, OwnedValue>>>;
+
+    fn delete(&self) -> Result<()>;
 }
@@ -24,7 +24,9 @@ use crate::services::bluetooth::BluetoothService;
 use zbus::interface;
 
 use super::dbus::DeviceState;
-use super::{AccessPoint, ActiveConnectionInfo, KnownConnection, NetworkBackend, NetworkEvent};
+use super::{
+    AccessPoint, ActiveConnectionInfo, KnownConnection, NetworkBackend, NetworkEvent, SavedNetwork,
+};
 use iced::futures::future::join_all;
 use iced::futures::stream::select_all;
 use iced::futures::{Stream, StreamExt};
@@ -82,6 +84,9 @@ impl super::NetworkBackend for IwdDbus<'_> {
         let known_connections = nm.known_connections().await?;
         debug!("Known connections: {known_connections:?}");
 
+        let saved_networks = nm.saved_networks().await?;
+        debug!("Saved networks: {saved_networks:?}");
+
         let is_scanning = join_all(self.stations().await?.iter().map(|s| s.scanning()))
             .await
             .into_iter()
@@ -102,6 +107,7 @@ impl super::NetworkBackend for IwdDbus<'_> {
                 .into(),
             wireless_access_points,
             known_connections,
+            saved_networks,
             scanning_nearby_wifi: is_scanning,
         })
     }
@@ -149,6 +155,15 @@ impl super::NetworkBackend for IwdDbus<'_> {
         Ok(())
     }
 
+    async fn disconnect_wifi(&self) -> anyhow::Result<()> {
+        for station in self.stations().await? {
+            if station.state().await? == "connected" {
+                station.disconnect().await?;
+            }
+        }
+        Ok(())
+    }
+
     async fn select_access_point(
         &mut self,
         ap: &AccessPoint,
@@ -213,6 +228,36 @@ impl super::NetworkBackend for IwdDbus<'_> {
         self.set_wifi_enabled(!airplane).await?;
         Ok(())
     }
+
+    async fn saved_networks(&self) -> anyhow::Result<Vec<SavedNetwork>> {
+        let mut networks = Vec::new();
+        for n in self.known_networks_proxies().await? {
+            networks.push(SavedNetwork {
+                name: n.name().await?,
+                path: n.inner().path().clone().into(),
+                priority: None,
+            });
+        }
+        networks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(networks)
+    }
+
+    async fn set_saved_networks_order(&self, order: Vec<OwnedObjectPath>) -> anyhow::Result<()> {
+        // IWD picks the network to autoconnect on its own, ranking them by signal and security
+        Err(anyhow::anyhow!(
+            "Network priorities not supported by IWD backend"
+        ))
+    }
+
+    async fn forget_network(&self, path: OwnedObjectPath) -> anyhow::Result<()> {
+        let known_network = KnownNetworkProxy::builder(self.inner().connection())
+            .destination("net.connman.iwd")?
+            .path(path)?
+            .build()
+            .await?;
+        known_network.forget().await?;
+        Ok(())
+    }
 }
 
 /// Macro to simplify listing proxies based on their interface name.
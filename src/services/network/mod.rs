@@ -37,6 +37,9 @@ pub trait NetworkBackend: Send + Sync {
     /// Enables or disables Wi-Fi.
     async fn set_wifi_enabled(&self, enable: bool) -> anyhow::Result<()>;
 
+    /// Disconnects the Wi-Fi devices from their network, until the next connection.
+    async fn disconnect_wifi(&self) -> anyhow::Result<()>;
+
     /// Connects to a specific access point, potentially with a password.
     /// Returns the updated list of known connections.
     async fn select_access_point(
@@ -54,6 +57,15 @@ pub trait NetworkBackend: Send + Sync {
         connection_path: OwnedObjectPath,
         enable: bool,
     ) -> anyhow::Result<Vec<KnownConnection>>;
+
+    /// Lists the saved Wi-Fi networks, ordered by autoconnect priority.
+    async fn saved_networks(&self) -> anyhow::Result<Vec<SavedNetwork>>;
+
+    /// Assigns descending autoconnect priorities following the given order.
+    async fn set_saved_networks_order(&self, order: Vec<OwnedObjectPath>) -> anyhow::Result<()>;
+
+    /// Removes a saved Wi-Fi network.
+    async fn forget_network(&self, path: OwnedObjectPath) -> anyhow::Result<()>;
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +79,7 @@ pub enum NetworkEvent {
     },
     ActiveConnections(Vec<ActiveConnectionInfo>),
     KnownConnections(Vec<KnownConnection>),
+    SavedNetworks(Vec<SavedNetwork>),
     WirelessAccessPoint(Vec<AccessPoint>),
     Strength((String, u8)),
     RequestPasswordForSSID(String),
@@ -77,9 +90,12 @@ pub enum NetworkEvent {
 pub enum NetworkCommand {
     ScanNearByWiFi,
     ToggleWiFi,
+    DisconnectWiFi,
     ToggleAirplaneMode,
     SelectAccessPoint((AccessPoint, Option<String>)),
     ToggleVpn(Vpn),
+    SetSavedNetworksOrder(Vec<OwnedObjectPath>),
+    ForgetNetwork(OwnedObjectPath),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -99,6 +115,15 @@ pub struct Vpn {
     pub path: OwnedObjectPath,
 }
 
+#[derive(Debug, Clone)]
+pub struct SavedNetwork {
+    pub name: String,
+    pub path: OwnedObjectPath,
+    /// Autoconnect priority, higher values are preferred.
+    /// Always `None` with iwd that has no priorities.
+    pub priority: Option<i32>,
+}
+
 #[derive(Debug, Clone)]
 pub enum KnownConnection {
     AccessPoint(AccessPoint),
@@ -136,6 +161,7 @@ pub struct NetworkData {
     pub wireless_access_points: Vec<AccessPoint>,
     pub active_connections: Vec<ActiveConnectionInfo>,
     pub known_connections: Vec<KnownConnection>,
+    pub saved_networks: Vec<SavedNetwork>,
     pub wifi_enabled: bool,
     pub airplane_mode: bool,
     pub connectivity: ConnectivityState,
@@ -193,6 +219,16 @@ impl ReadOnlyService for NetworkService {
             NetworkEvent::KnownConnections(known_connections) => {
                 self.data.known_connections = known_connections;
             }
+            NetworkEvent::SavedNetworks(saved_networks) => {
+                // drop the access points of the networks that have been forgotten
+                self.data.known_connections.retain(|c| match c {
+                    KnownConnection::AccessPoint(ap) => {
+                        saved_networks.iter().any(|n| n.name == ap.ssid)
+                    }
+                    KnownConnection::Vpn(_) => true,
+                });
+                self.data.saved_networks = saved_networks;
+            }
             NetworkEvent::Strength((ssid, new_strength)) => {
                 if let Some(ap) = self
                     .data
@@ -346,6 +382,15 @@ impl NetworkBackend for BackendChoiceWithConnection {
         }
     }
 
+    async fn disconnect_wifi(&self) -> anyhow::Result<()> {
+        match self.choice {
+            BackendChoice::NetworkManager => {
+                NetworkDbus::new(&self.conn).await?.disconnect_wifi().await
+            }
+            BackendChoice::Iwd => IwdDbus::new(&self.conn).await?.disconnect_wifi().await,
+        }
+    }
+
     async fn known_connections(&self) -> anyhow::Result<Vec<KnownConnection>> {
         match self.choice {
             BackendChoice::NetworkManager => {
@@ -357,6 +402,44 @@ impl NetworkBackend for BackendChoiceWithConnection {
             BackendChoice::Iwd => IwdDbus::new(&self.conn).await?.known_connections().await,
         }
     }
+
+    async fn saved_networks(&self) -> anyhow::Result<Vec<SavedNetwork>> {
+        match self.choice {
+            BackendChoice::NetworkManager => {
+                NetworkDbus::new(&self.conn).await?.saved_networks().await
+            }
+            BackendChoice::Iwd => IwdDbus::new(&self.conn).await?.saved_networks().await,
+        }
+    }
+
+    async fn set_saved_networks_order(&self, order: Vec<OwnedObjectPath>) -> anyhow::Result<()> {
+        match self.choice {
+            BackendChoice::NetworkManager => {
+                NetworkDbus::new(&self.conn)
+                    .await?
+                    .set_saved_networks_order(order)
+                    .await
+            }
+            BackendChoice::Iwd => {
+                IwdDbus::new(&self.conn)
+                    .await?
+                    .set_saved_networks_order(order)
+                    .await
+            }
+        }
+    }
+
+    async fn forget_network(&self, path: OwnedObjectPath) -> anyhow::Result<()> {
+        match self.choice {
+            BackendChoice::NetworkManager => {
+                NetworkDbus::new(&self.conn)
+                    .await?
+                    .forget_network(path)
+                    .await
+            }
+            BackendChoice::Iwd => IwdDbus::new(&self.conn).await?.forget_network(path).await,
+        }
+    }
 }
 
 impl NetworkService {
@@ -554,6 +637,17 @@ impl Service for NetworkService {
                     |wifi_enabled| ServiceEvent::Update(NetworkEvent::WiFiEnabled(wifi_enabled)),
                 )
             }
+            NetworkCommand::DisconnectWiFi => Task::perform(
+                async move {
+                    if let Err(err) = bc.disconnect_wifi().await {
+                        error!("Failed to disconnect the Wi-Fi: {err}");
+                    }
+                    bc.known_connections().await.unwrap_or_default()
+                },
+                |known_connections| {
+                    ServiceEvent::Update(NetworkEvent::KnownConnections(known_connections))
+                },
+            ),
             NetworkCommand::SelectAccessPoint((access_point, password)) => Task::perform(
                 async move {
                     bc.select_access_point(&access_point, password)
@@ -590,6 +684,24 @@ impl Service for NetworkService {
                     },
                 )
             }
+            NetworkCommand::SetSavedNetworksOrder(order) => Task::perform(
+                async move {
+                    if let Err(err) = bc.set_saved_networks_order(order).await {
+                        error!("Failed to reorder the saved networks: {err}");
+                    }
+                    bc.saved_networks().await.unwrap_or_default()
+                },
+                |saved_networks| ServiceEvent::Update(NetworkEvent::SavedNetworks(saved_networks)),
+            ),
+            NetworkCommand::ForgetNetwork(path) => Task::perform(
+                async move {
+                    if let Err(err) = bc.forget_network(path).await {
+                        error!("Failed to forget the network: {err}");
+                    }
+                    bc.saved_networks().await.unwrap_or_default()
+                },
+                |saved_networks| ServiceEvent::Update(NetworkEvent::SavedNetworks(saved_networks)),
+            ),
         }
     }
 }
@@ -0,0 +1,140 @@
+use super::{Notification, NotificationEvent};
+use iced::futures::channel::mpsc::UnboundedSender;
+use log::warn;
+use std::collections::HashMap;
+use zbus::{
+    Connection, Result,
+    fdo::{self, DBusProxy, RequestNameFlags, RequestNameReply},
+    interface,
+    names::WellKnownName,
+    object_server::SignalEmitter,
+    zvariant::OwnedValue,
+};
+
+const NAME: WellKnownName =
+    WellKnownName::from_static_str_unchecked("org.freedesktop.Notifications");
+const OBJECT_PATH: &str = "/org/freedesktop/Notifications";
+
+/// Reasons of the `NotificationClosed` signal
+#[derive(Debug, Clone, Copy)]
+pub enum CloseReason {
+    Dismissed = 2,
+    Closed = 3,
+}
+
+pub struct NotificationServer {
+    events: UnboundedSender<NotificationEvent>,
+    next_id: u32,
+}
+
+impl NotificationServer {
+    pub async fn start_server(
+        events: UnboundedSender<NotificationEvent>,
+    ) -> anyhow::Result<Connection> {
+        let connection = Connection::session().await?;
+        connection
+            .object_server()
+            .at(OBJECT_PATH, NotificationServer { events, next_id: 1 })
+            .await?;
+
+        let dbus_proxy = DBusProxy::new(&connection).await?;
+        match dbus_proxy
+            .request_name(NAME, RequestNameFlags::DoNotQueue.into())
+            .await?
+        {
+            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => Ok(connection),
+            _ => anyhow::bail!("bus name '{NAME}' already owned by another notification daemon"),
+        }
+    }
+
+    pub async fn closed(connection: &Connection, id: u32, reason: CloseReason) {
+        let result = match SignalEmitter::new(connection, OBJECT_PATH) {
+            Ok(emitter) => Self::notification_closed(&emitter, id, reason as u32).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to send the closing of the notification {id}: {e}");
+        }
+    }
+
+    pub async fn invoked(connection: &Connection, id: u32, action_key: &str) {
+        let result = match SignalEmitter::new(connection, OBJECT_PATH) {
+            Ok(emitter) => Self::action_invoked(&emitter, id, action_key).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to send the action {action_key} of the notification {id}: {e}");
+        }
+    }
+}
+
+#[interface(name = "org.freedesktop.Notifications")]
+impl NotificationServer {
+    fn get_capabilities(&self) -> Vec<&str> {
+        vec!["actions", "body", "icon-static", "persistence"]
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &mut self,
+        app_name: String,
+        replaces_id: u32,
+        app_icon: String,
+        summary: String,
+        body: String,
+        actions: Vec<String>,
+        hints: HashMap<String, OwnedValue>,
+        expire_timeout: i32,
+    ) -> u32 {
+        let id = if replaces_id == 0 {
+            let id = self.next_id;
+            // zero means no notification to replace
+            self.next_id = self.next_id.checked_add(1).unwrap_or(1);
+            id
+        } else {
+            replaces_id
+        };
+
+        let notification = Notification::new(
+            id,
+            app_name,
+            &app_icon,
+            summary,
+            body,
+            actions,
+            &hints,
+            expire_timeout,
+        );
+        if let Err(e) = self
+            .events
+            .unbounded_send(NotificationEvent::Received(notification))
+        {
+            warn!("Failed to forward the notification {id}: {e}");
+        }
+
+        id
+    }
+
+    async fn close_notification(
+        &self,
+        id: u32,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> fdo::Result<()> {
+        let _ = self.events.unbounded_send(NotificationEvent::Closed(id));
+        Self::notification_closed(&emitter, id, CloseReason::Closed as u32).await?;
+
+        Ok(())
+    }
+
+    fn get_server_information(&self) -> (&str, &str, &str, &str) {
+        ("ashell", "ashell", env!("CARGO_PKG_VERSION"), "1.2")
+    }
+
+    #[zbus(signal)]
+    async fn notification_closed(emitter: &SignalEmitter<'_>, id: u32, reason: u32) -> Result<()>;
+
+    #[zbus(signal)]
+    async fn action_invoked(emitter: &SignalEmitter<'_>, id: u32, action_key: &str) -> Result<()>;
+}
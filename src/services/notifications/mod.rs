@@ -0,0 +1,318 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use crate::utils::app_icon::{AppIcon, app_icon, named_icon};
+use chrono::{DateTime, Local};
+use dbus::{CloseReason, NotificationServer};
+use iced::{
+    Subscription, Task,
+    futures::{
+        SinkExt, StreamExt,
+        channel::mpsc::{self, UnboundedReceiver},
+        stream::pending,
+    },
+    stream::channel,
+    widget::image,
+};
+use log::{error, info};
+use serde::Serialize;
+use std::{any::TypeId, collections::HashMap, time::Duration};
+use zbus::zvariant::{OwnedValue, Value};
+
+pub mod dbus;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub id: u32,
+    pub app_name: String,
+    pub summary: String,
+    pub body: String,
+    pub icon: Option<AppIcon>,
+    /// key and label of the actions, the `default` one is invoked by a click on the notification
+    pub actions: Vec<(String, String)>,
+    pub urgency: Urgency,
+    /// percentage of the `value` hint, shown as a progress bar
+    pub progress: Option<u8>,
+    /// `None` when the notification doesn't say, zero when it never expires
+    pub timeout: Option<Duration>,
+    pub received: DateTime<Local>,
+}
+
+/// A notification of the history, as exported with `ashell msg notifications`
+#[derive(Serialize, Debug, Clone)]
+pub struct NotificationRecord {
+    pub id: u32,
+    pub app_name: String,
+    pub summary: String,
+    pub body: String,
+    pub urgency: Urgency,
+    pub received: String,
+}
+
+fn string_hint<'a>(hints: &'a HashMap<String, OwnedValue>, name: &str) -> Option<&'a str> {
+    hints
+        .get(name)
+        .and_then(|value| match &**value {
+            Value::Str(value) => Some(value.as_str()),
+            _ => None,
+        })
+        .filter(|value| !value.is_empty())
+}
+
+/// The raw image of the `image-data` hint: width, height, rowstride, alpha,
+/// bits per sample, channels and the pixels
+fn image_data_hint(hints: &HashMap<String, OwnedValue>, name: &str) -> Option<AppIcon> {
+    let Value::Structure(structure) = &**hints.get(name)? else {
+        return None;
+    };
+    let [
+        Value::I32(width),
+        Value::I32(height),
+        Value::I32(rowstride),
+        Value::Bool(has_alpha),
+        Value::I32(8),
+        Value::I32(channels),
+        Value::Array(data),
+    ] = structure.fields()
+    else {
+        return None;
+    };
+    let data = data
+        .iter()
+        .map(|byte| match byte {
+            Value::U8(byte) => Some(*byte),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let (width, height, rowstride) = (
+        usize::try_from(*width).ok()?,
+        usize::try_from(*height).ok()?,
+        usize::try_from(*rowstride).ok()?,
+    );
+    let channels = usize::try_from(*channels)
+        .ok()
+        .filter(|channels| *channels >= if *has_alpha { 4 } else { 3 })?;
+
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let offset = y * rowstride + x * channels;
+            let pixel = data.get(offset..offset + channels)?;
+            pixels.extend_from_slice(&pixel[..3]);
+            pixels.push(if *has_alpha { pixel[3] } else { u8::MAX });
+        }
+    }
+
+    Some(AppIcon::Image(image::Handle::from_rgba(
+        width as u32,
+        height as u32,
+        pixels,
+    )))
+}
+
+/// Icon names, absolute paths and file URIs are accepted
+fn resolve_icon(icon: &str) -> Option<AppIcon> {
+    named_icon(icon.strip_prefix("file://").unwrap_or(icon))
+}
+
+impl Notification {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        app_name: String,
+        app_icon_name: &str,
+        summary: String,
+        body: String,
+        actions: Vec<String>,
+        hints: &HashMap<String, OwnedValue>,
+        expire_timeout: i32,
+    ) -> Self {
+        let urgency = match hints.get("urgency").map(|value| &**value) {
+            Some(Value::U8(0)) => Urgency::Low,
+            Some(Value::U8(2)) => Urgency::Critical,
+            _ => Urgency::Normal,
+        };
+
+        // the image of the notification comes before the icon of the application
+        let icon = image_data_hint(hints, "image-data")
+            .or_else(|| image_data_hint(hints, "image_data"))
+            .or_else(|| {
+                string_hint(hints, "image-path")
+                    .or_else(|| string_hint(hints, "image_path"))
+                    .and_then(resolve_icon)
+            })
+            .or_else(|| {
+                Some(app_icon_name)
+                    .filter(|name| !name.is_empty())
+                    .and_then(resolve_icon)
+            })
+            .or_else(|| image_data_hint(hints, "icon_data"))
+            .or_else(|| string_hint(hints, "desktop-entry").and_then(app_icon));
+
+        let progress = match hints.get("value").map(|value| &**value) {
+            Some(Value::I32(value)) => u8::try_from((*value).clamp(0, 100)).ok(),
+            Some(Value::U32(value)) => u8::try_from((*value).min(100)).ok(),
+            _ => None,
+        };
+
+        Self {
+            id,
+            app_name,
+            summary,
+            body,
+            icon,
+            actions: actions
+                .chunks_exact(2)
+                .map(|action| (action[0].clone(), action[1].clone()))
+                .collect(),
+            urgency,
+            progress,
+            timeout: u64::try_from(expire_timeout)
+                .ok()
+                .map(Duration::from_millis),
+            received: Local::now(),
+        }
+    }
+
+    pub fn has_default_action(&self) -> bool {
+        self.actions.iter().any(|(key, _)| key == "default")
+    }
+
+    /// Case insensitive search in the application, the summary and the body
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+
+        [&self.app_name, &self.summary, &self.body]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&search))
+    }
+
+    pub fn record(&self) -> NotificationRecord {
+        NotificationRecord {
+            id: self.id,
+            app_name: self.app_name.clone(),
+            summary: self.summary.clone(),
+            body: self.body.clone(),
+            urgency: self.urgency,
+            received: self.received.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    Received(Notification),
+    Closed(u32),
+}
+
+/// The `org.freedesktop.Notifications` daemon, newest notifications first
+#[derive(Debug, Clone)]
+pub struct NotificationsService {
+    pub notifications: Vec<Notification>,
+    connection: zbus::Connection,
+}
+
+impl NotificationsService {
+    pub fn get(&self, id: u32) -> Option<&Notification> {
+        self.notifications.iter().find(|n| n.id == id)
+    }
+
+    async fn listen(
+        connection: zbus::Connection,
+        mut events: UnboundedReceiver<NotificationEvent>,
+        output: &mut mpsc::Sender<ServiceEvent<Self>>,
+    ) {
+        info!("Notification daemon started");
+
+        let _ = output
+            .send(ServiceEvent::Init(NotificationsService {
+                notifications: Vec::new(),
+                connection,
+            }))
+            .await;
+
+        while let Some(event) = events.next().await {
+            let _ = output.send(ServiceEvent::Update(event)).await;
+        }
+    }
+}
+
+impl ReadOnlyService for NotificationsService {
+    type UpdateEvent = NotificationEvent;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        match event {
+            NotificationEvent::Received(notification) => {
+                match self
+                    .notifications
+                    .iter_mut()
+                    .find(|n| n.id == notification.id)
+                {
+                    Some(existing) => *existing = notification,
+                    None => self.notifications.insert(0, notification),
+                }
+            }
+            NotificationEvent::Closed(id) => {
+                self.notifications.retain(|n| n.id != id);
+            }
+        }
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, async |mut output| {
+                let (tx, rx) = mpsc::unbounded();
+
+                match NotificationServer::start_server(tx).await {
+                    Ok(connection) => Self::listen(connection, rx, &mut output).await,
+                    Err(e) => {
+                        error!("Failed to start the notification daemon: {e}");
+                        let _ = output.send(ServiceEvent::Error(())).await;
+                    }
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum NotificationsCommand {
+    Dismiss(u32),
+    InvokeAction(u32, String),
+}
+
+impl Service for NotificationsService {
+    type Command = NotificationsCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let connection = self.connection.clone();
+
+        match command {
+            NotificationsCommand::Dismiss(id) => Task::perform(
+                async move {
+                    NotificationServer::closed(&connection, id, CloseReason::Dismissed).await;
+                },
+                move |_| ServiceEvent::Update(NotificationEvent::Closed(id)),
+            ),
+            NotificationsCommand::InvokeAction(id, action_key) => Task::perform(
+                async move {
+                    NotificationServer::invoked(&connection, id, &action_key).await;
+                    NotificationServer::closed(&connection, id, CloseReason::Dismissed).await;
+                },
+                move |_| ServiceEvent::Update(NotificationEvent::Closed(id)),
+            ),
+        }
+    }
+}
@@ -18,12 +18,16 @@ const WEBCAM_DEVICE_PATH: &str = "/dev/video0";
 pub enum Media {
     Video,
     Audio,
+    /// source created by the backend of the screencast portal
+    Screencast,
 }
 
 #[derive(Debug, Clone)]
 pub struct ApplicationNode {
     pub id: u32,
     pub media: Media,
+    /// application owning the stream
+    pub name: String,
 }
 
 #[derive(Debug, Clone)]
@@ -52,8 +56,25 @@ impl PrivacyData {
         self.webcam_access > 0
     }
 
+    /// The video streams are screen shares when the webcam isn't open
     pub fn screenshare_access(&self) -> bool {
-        self.nodes.iter().any(|n| n.media == Media::Video)
+        self.nodes.iter().any(|n| {
+            n.media == Media::Screencast || n.media == Media::Video && self.webcam_access == 0
+        })
+    }
+
+    /// Names of the applications recording the microphone or a video, without duplicates
+    pub fn applications(&self) -> Vec<&str> {
+        let mut names = self
+            .nodes
+            .iter()
+            .filter(|n| n.media != Media::Screencast)
+            .map(|n| n.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+
+        names
     }
 }
 
@@ -85,21 +106,34 @@ impl PrivacyService {
                 .global({
                     let tx = tx.clone();
                     move |global| {
-                        if let Some(props) = global.props
-                            && let Some(media) = props.get("media.class").filter(|v| {
-                                v == &"Stream/Input/Video" || v == &"Stream/Input/Audio"
-                            })
-                        {
-                            debug!("New global: {global:?}");
-                            let _ = tx.send(PrivacyEvent::AddNode(ApplicationNode {
-                                id: global.id,
-                                media: if media == "Stream/Input/Video" {
-                                    Media::Video
-                                } else {
-                                    Media::Audio
-                                },
-                            }));
-                        }
+                        let Some(props) = global.props else {
+                            return;
+                        };
+                        let media = match props.get("media.class") {
+                            Some("Stream/Input/Video") => Media::Video,
+                            Some("Stream/Input/Audio") => Media::Audio,
+                            // the cameras are sources backed by a device
+                            Some("Video/Source") if props.get("device.api").is_none() => {
+                                Media::Screencast
+                            }
+                            _ => return,
+                        };
+
+                        debug!("New global: {global:?}");
+                        let name = [
+                            "application.name",
+                            "application.process.binary",
+                            "node.name",
+                        ]
+                        .into_iter()
+                        .find_map(|key| props.get(key))
+                        .unwrap_or("Unknown")
+                        .to_string();
+                        let _ = tx.send(PrivacyEvent::AddNode(ApplicationNode {
+                            id: global.id,
+                            media,
+                            name,
+                        }));
                     }
                 })
                 .global_remove({
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+/// Attributes used by the most common clients to store the user name
+const USERNAME_ATTRIBUTES: [&str; 4] = ["username", "user", "login", "username_value"];
+
+#[derive(Debug, Clone)]
+pub struct SecretEntry {
+    pub path: OwnedObjectPath,
+    pub label: String,
+    pub username: Option<String>,
+}
+
+/// Lists the items of the unlocked collections, locked items are skipped
+/// because unlocking them requires a prompt from the keyring
+pub async fn list_items() -> anyhow::Result<Vec<SecretEntry>> {
+    let conn = zbus::Connection::session().await?;
+    let service = SecretServiceProxy::new(&conn).await?;
+    let (unlocked, _) = service.search_items(HashMap::new()).await?;
+
+    let mut items = Vec::with_capacity(unlocked.len());
+    for path in unlocked {
+        let item = SecretItemProxy::builder(&conn)
+            .path(path.clone())?
+            .build()
+            .await?;
+        let attributes = item.attributes().await.unwrap_or_default();
+
+        items.push(SecretEntry {
+            label: item.label().await.unwrap_or_default(),
+            username: USERNAME_ATTRIBUTES
+                .iter()
+                .find_map(|attribute| attributes.get(*attribute).cloned()),
+            path,
+        });
+    }
+
+    Ok(items)
+}
+
+pub async fn secret(path: OwnedObjectPath) -> anyhow::Result<String> {
+    let conn = zbus::Connection::session().await?;
+    let service = SecretServiceProxy::new(&conn).await?;
+
+    // the secret travels on the session bus, which is private to the user
+    let (_, session) = service.open_session("plain", &Value::from("")).await?;
+
+    let item = SecretItemProxy::builder(&conn).path(path)?.build().await?;
+    let secret = item.get_secret(&session).await;
+
+    let _ = SecretSessionProxy::builder(&conn)
+        .path(session)?
+        .build()
+        .await?
+        .close()
+        .await;
+
+    let (_, _, value, _) = secret?;
+
+    Ok(String::from_utf8(value)?)
+}
+
+#[proxy(
+    default_service = "org.freedesktop.secrets",
+    default_path = "/org/freedesktop/secrets",
+    interface = "org.freedesktop.Secret.Service"
+)]
+trait SecretService {
+    fn open_session(
+        &self,
+        algorithm: &str,
+        input: &Value<'_>,
+    ) -> zbus::Result<(OwnedValue, OwnedObjectPath)>;
+
+    fn search_items(
+        &self,
+        attributes: HashMap<&str, &str>,
+    ) -> zbus::Result<(Vec<OwnedObjectPath>, Vec<OwnedObjectPath>)>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.secrets",
+    interface = "org.freedesktop.Secret.Item"
+)]
+trait SecretItem {
+    fn get_secret(
+        &self,
+        session: &OwnedObjectPath,
+    ) -> zbus::Result<(OwnedObjectPath, Vec<u8>, Vec<u8>, String)>;
+
+    #[zbus(property)]
+    fn label(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn attributes(&self) -> zbus::Result<HashMap<String, String>>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.secrets",
+    interface = "org.freedesktop.Secret.Session"
+)]
+trait SecretSession {
+    fn close(&self) -> zbus::Result<()>;
+}
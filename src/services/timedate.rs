@@ -0,0 +1,305 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use iced::{
+    Subscription, Task,
+    futures::{SinkExt, StreamExt, channel::mpsc::Sender, stream::pending},
+    stream::channel,
+};
+use log::{debug, error, info};
+use std::{any::TypeId, convert, ops::Deref, time::Duration};
+use tokio::time::sleep;
+use zbus::{fdo::DBusProxy, names::BusName, proxy};
+
+const TIMESYNCD: &str = "org.freedesktop.timesync1";
+
+#[derive(Debug, Clone, Default)]
+pub struct NtpStatus {
+    /// the NTP service is enabled
+    pub enabled: bool,
+    pub synchronized: bool,
+    /// only reported by systemd-timesyncd
+    pub server: Option<String>,
+    /// offset of the system clock from the last NTP response in microseconds,
+    /// only reported by systemd-timesyncd
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TimedateData {
+    pub timezone: String,
+    pub ntp: NtpStatus,
+}
+
+#[derive(Debug, Clone)]
+pub enum TimedateEvent {
+    Timezone(String),
+    Ntp(NtpStatus),
+}
+
+#[derive(Debug, Clone)]
+pub enum TimedateCommand {
+    Refresh,
+    /// Restarts the NTP service, enabling it if needed
+    Resync,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimedateService {
+    conn: zbus::Connection,
+    data: TimedateData,
+}
+
+impl Deref for TimedateService {
+    type Target = TimedateData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl TimedateService {
+    async fn ntp_status(conn: &zbus::Connection) -> anyhow::Result<NtpStatus> {
+        let timedate = TimedateProxy::new(conn).await?;
+        let enabled = timedate.ntp().await?;
+        let synchronized = timedate.ntp_synchronized().await?;
+
+        // timesyncd is bus activated, don't start it when another NTP service is used
+        let timesyncd_running = DBusProxy::new(conn)
+            .await?
+            .name_has_owner(BusName::try_from(TIMESYNCD)?)
+            .await?;
+
+        let (server, offset) = if timesyncd_running {
+            let timesync = TimesyncProxy::new(conn).await?;
+            let server = timesync
+                .server_name()
+                .await
+                .ok()
+                .filter(|server| !server.is_empty());
+            // the same offset computed by `timedatectl timesync-status`
+            let offset = timesync.ntp_message().await.ok().and_then(
+                |(_, _, _, _, _, _, _, _, origin, receive, transmit, destination, _, _, _)| {
+                    (destination > 0).then(|| {
+                        ((receive as i64 - origin as i64) + (transmit as i64 - destination as i64))
+                            / 2
+                    })
+                },
+            );
+
+            (server, offset)
+        } else {
+            (None, None)
+        };
+
+        Ok(NtpStatus {
+            enabled,
+            synchronized,
+            server,
+            offset,
+        })
+    }
+
+    async fn initialize_data(conn: &zbus::Connection) -> anyhow::Result<TimedateData> {
+        let timedate = TimedateProxy::new(conn).await?;
+        let timezone = timedate.timezone().await.unwrap_or_default();
+        debug!("Timezone: {timezone}");
+
+        let ntp = TimedateService::ntp_status(conn)
+            .await
+            .unwrap_or_else(|err| {
+                error!("Failed to read the NTP status: {err}");
+
+                NtpStatus::default()
+            });
+
+        Ok(TimedateData { timezone, ntp })
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => match TimedateService::initialize_data(&conn).await {
+                    Ok(data) => {
+                        let _ = output
+                            .send(ServiceEvent::Init(TimedateService {
+                                conn: conn.clone(),
+                                data,
+                            }))
+                            .await;
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to connect to timedated: {err}");
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to system bus for timedated: {err}");
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for timezone changes");
+
+                match TimedateProxy::new(&conn).await {
+                    Ok(timedate) => {
+                        // timedated is bus activated, the signal is received even
+                        // when it exits after being idle
+                        let mut changes = timedate.receive_timezone_changed().await;
+
+                        while let Some(change) = changes.next().await {
+                            if let Ok(timezone) = change.get().await {
+                                debug!("Timezone changed: {timezone}");
+                                let _ = output
+                                    .send(ServiceEvent::Update(TimedateEvent::Timezone(timezone)))
+                                    .await;
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for timezone changes: {err}");
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                let _ = pending::<u8>().next().await;
+
+                State::Error
+            }
+        }
+    }
+
+    fn refresh_after(
+        conn: zbus::Connection,
+        action: impl Future<Output = anyhow::Result<()>> + Send + 'static,
+    ) -> Task<ServiceEvent<Self>> {
+        Task::perform(
+            async move {
+                let res = action.await;
+
+                match (res, TimedateService::ntp_status(&conn).await) {
+                    (Err(err), _) => ServiceEvent::Error(err.to_string()),
+                    (Ok(_), Ok(ntp)) => ServiceEvent::Update(TimedateEvent::Ntp(ntp)),
+                    (Ok(_), Err(err)) => ServiceEvent::Error(err.to_string()),
+                }
+            },
+            convert::identity,
+        )
+    }
+}
+
+impl ReadOnlyService for TimedateService {
+    type UpdateEvent = TimedateEvent;
+    type Error = String;
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        match event {
+            TimedateEvent::Timezone(timezone) => self.data.timezone = timezone,
+            TimedateEvent::Ntp(ntp) => self.data.ntp = ntp,
+        }
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                let mut state = State::Init;
+
+                loop {
+                    state = TimedateService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+impl Service for TimedateService {
+    type Command = TimedateCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let conn = self.conn.clone();
+
+        match command {
+            TimedateCommand::Refresh => TimedateService::refresh_after(conn, async { Ok(()) }),
+            TimedateCommand::Resync => {
+                debug!("Restarting the NTP service");
+
+                TimedateService::refresh_after(conn.clone(), async move {
+                    let timedate = TimedateProxy::new(&conn).await?;
+                    // timedated restarts the NTP service unit
+                    timedate.set_ntp(false, true).await?;
+                    timedate.set_ntp(true, true).await?;
+
+                    // give the service the time to reach the server
+                    sleep(Duration::from_secs(3)).await;
+
+                    Ok(())
+                })
+            }
+        }
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.timedate1",
+    default_path = "/org/freedesktop/timedate1",
+    interface = "org.freedesktop.timedate1"
+)]
+trait Timedate {
+    #[zbus(name = "SetNTP")]
+    fn set_ntp(&self, use_ntp: bool, interactive: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn timezone(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "NTP")]
+    fn ntp(&self) -> zbus::Result<bool>;
+
+    #[zbus(property(emits_changed_signal = "false"), name = "NTPSynchronized")]
+    fn ntp_synchronized(&self) -> zbus::Result<bool>;
+}
+
+type NtpMessage = (
+    u32,
+    u32,
+    u32,
+    u32,
+    i32,
+    u64,
+    u64,
+    Vec<u8>,
+    u64,
+    u64,
+    u64,
+    u64,
+    bool,
+    u64,
+    u64,
+);
+
+#[proxy(
+    default_service = "org.freedesktop.timesync1",
+    default_path = "/org/freedesktop/timesync1",
+    interface = "org.freedesktop.timesync1.Manager"
+)]
+trait Timesync {
+    #[zbus(property)]
+    fn server_name(&self) -> zbus::Result<String>;
+
+    #[zbus(property(emits_changed_signal = "false"), name = "NTPMessage")]
+    fn ntp_message(&self) -> zbus::Result<NtpMessage>;
+}
@@ -16,7 +16,7 @@ use iced::{
     widget::{image, svg},
 };
 use linicon_theme::get_icon_theme;
-use log::{debug, error, info, trace};
+use log::{debug, error, info, trace, warn};
 use std::{any::TypeId, ops::Deref};
 
 pub mod dbus;
@@ -63,6 +63,8 @@ pub struct StatusNotifierItem {
     pub name: String,
     pub icon: Option<TrayIcon>,
     pub menu: Layout,
+    /// the left click opens the menu, the other items are activated
+    pub is_menu: bool,
     item_proxy: StatusNotifierItemProxy<'static>,
     menu_proxy: DBusMenuProxy<'static>,
 }
@@ -114,6 +116,9 @@ impl StatusNotifierItem {
                 .and_then(get_icon_from_name),
         };
 
+        // the property is missing from the items following the spec before it was added
+        let is_menu = item_proxy.item_is_menu().await.unwrap_or(false);
+
         let menu_path = item_proxy.menu().await?;
         let menu_proxy = dbus::DBusMenuProxy::builder(conn)
             .destination(dest.to_owned())?
@@ -127,6 +132,7 @@ impl StatusNotifierItem {
             name,
             icon,
             menu,
+            is_menu,
             item_proxy,
             menu_proxy,
         })
@@ -467,6 +473,7 @@ impl ReadOnlyService for TrayService {
 #[derive(Debug, Clone)]
 pub enum TrayCommand {
     MenuSelected(String, i32),
+    Activate(String),
 }
 
 impl Service for TrayService {
@@ -499,6 +506,23 @@ impl Service for TrayService {
                     Task::none()
                 }
             }
+            TrayCommand::Activate(name) => {
+                let Some(item) = self.data.iter().find(|item| item.name == name) else {
+                    return Task::none();
+                };
+                let proxy = item.item_proxy.clone();
+
+                Task::perform(
+                    async move {
+                        debug!("Activate tray item {name}");
+                        // the position of the click on the screen isn't known
+                        if let Err(e) = proxy.activate(0, 0).await {
+                            warn!("Failed to activate the tray item {name}: {e}");
+                        }
+                    },
+                    |_| ServiceEvent::Update(TrayEvent::None),
+                )
+            }
         }
     }
 }
@@ -0,0 +1,226 @@
+use super::{EncryptedVolume, VolumeKind};
+use log::warn;
+use std::collections::HashMap;
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+const ENCRYPTED_INTERFACE: &str = "org.freedesktop.UDisks2.Encrypted";
+const FILESYSTEM_INTERFACE: &str = "org.freedesktop.UDisks2.Filesystem";
+
+/// Converts a nul terminated byte array, as used by udisks for paths
+fn bytes_to_string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes.strip_suffix(&[0]).unwrap_or(bytes)).into_owned()
+}
+
+pub struct UDisksDbus<'a> {
+    pub manager: UDisksObjectManagerProxy<'a>,
+}
+
+impl UDisksDbus<'_> {
+    pub async fn new(conn: &zbus::Connection) -> anyhow::Result<Self> {
+        let manager = UDisksObjectManagerProxy::new(conn).await?;
+
+        Ok(Self { manager })
+    }
+
+    fn conn(&self) -> &zbus::Connection {
+        self.manager.inner().connection()
+    }
+
+    pub async fn volumes(&self) -> anyhow::Result<Vec<EncryptedVolume>> {
+        let objects = self.manager.get_managed_objects().await?;
+
+        let mut volumes = Vec::new();
+        for (path, interfaces) in &objects {
+            if !interfaces.contains_key(ENCRYPTED_INTERFACE) {
+                continue;
+            }
+
+            let block = BlockProxy::builder(self.conn())
+                .path(path.clone())?
+                .build()
+                .await?;
+
+            if block.hint_ignore().await.unwrap_or_default() {
+                continue;
+            }
+
+            let kind = match block.id_type().await.unwrap_or_default().as_str() {
+                "crypto_TCRYPT" => VolumeKind::VeraCrypt,
+                _ => VolumeKind::Luks,
+            };
+            let device = bytes_to_string(&block.preferred_device().await.unwrap_or_default());
+            let label = [block.id_label().await, block.hint_name().await]
+                .into_iter()
+                .flatten()
+                .find(|label| !label.is_empty())
+                .unwrap_or_else(|| device.clone());
+
+            let encrypted = EncryptedProxy::builder(self.conn())
+                .path(path.clone())?
+                .build()
+                .await?;
+            let cleartext = encrypted
+                .cleartext_device()
+                .await
+                .ok()
+                .filter(|cleartext| cleartext.as_str() != "/");
+
+            let mut mount_point = None;
+            if let Some(cleartext) = &cleartext
+                && objects
+                    .get(cleartext)
+                    .is_some_and(|interfaces| interfaces.contains_key(FILESYSTEM_INTERFACE))
+            {
+                let filesystem = FilesystemProxy::builder(self.conn())
+                    .path(cleartext.clone())?
+                    .build()
+                    .await?;
+
+                mount_point = filesystem
+                    .mount_points()
+                    .await
+                    .unwrap_or_default()
+                    .first()
+                    .map(|mount_point| bytes_to_string(mount_point));
+            }
+
+            volumes.push(EncryptedVolume {
+                path: path.clone(),
+                label,
+                device,
+                kind,
+                cleartext,
+                mount_point,
+            });
+        }
+
+        volumes.sort_by(|a, b| a.label.cmp(&b.label));
+
+        Ok(volumes)
+    }
+
+    /// Unlocks the volume and mounts the filesystem it contains
+    pub async fn unlock(&self, volume: &OwnedObjectPath, passphrase: &str) -> anyhow::Result<()> {
+        let encrypted = EncryptedProxy::builder(self.conn())
+            .path(volume.clone())?
+            .build()
+            .await?;
+
+        let cleartext = encrypted.unlock(passphrase, HashMap::new()).await?;
+
+        let filesystem = FilesystemProxy::builder(self.conn())
+            .path(cleartext)?
+            .build()
+            .await?;
+
+        // the volume could contain something else than a filesystem, like LVM
+        if let Err(err) = filesystem.mount(HashMap::new()).await {
+            warn!("Failed to mount unlocked volume {volume:?}: {err}");
+        }
+
+        Ok(())
+    }
+
+    /// Unmounts the filesystem of the volume, if any, and locks it
+    pub async fn lock(
+        &self,
+        volume: &OwnedObjectPath,
+        cleartext: Option<&OwnedObjectPath>,
+    ) -> anyhow::Result<()> {
+        if let Some(cleartext) = cleartext {
+            let filesystem = FilesystemProxy::builder(self.conn())
+                .path(cleartext.clone())?
+                .build()
+                .await?;
+
+            if !filesystem
+                .mount_points()
+                .await
+                .unwrap_or_default()
+                .is_empty()
+            {
+                filesystem.unmount(HashMap::new()).await?;
+            }
+        }
+
+        let encrypted = EncryptedProxy::builder(self.conn())
+            .path(volume.clone())?
+            .build()
+            .await?;
+
+        encrypted.lock(HashMap::new()).await?;
+
+        Ok(())
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    default_path = "/org/freedesktop/UDisks2",
+    interface = "org.freedesktop.DBus.ObjectManager"
+)]
+pub trait UDisksObjectManager {
+    fn get_managed_objects(&self) -> zbus::Result<ManagedObjects>;
+
+    #[zbus(signal)]
+    fn interfaces_added(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn interfaces_removed(&self) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Block"
+)]
+trait Block {
+    #[zbus(property)]
+    fn preferred_device(&self) -> zbus::Result<Vec<u8>>;
+
+    #[zbus(property)]
+    fn id_type(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn id_label(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn hint_name(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn hint_ignore(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Encrypted"
+)]
+trait Encrypted {
+    fn unlock(
+        &self,
+        passphrase: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    fn lock(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn cleartext_device(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Filesystem"
+)]
+trait Filesystem {
+    fn mount(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<String>;
+
+    fn unmount(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn mount_points(&self) -> zbus::Result<Vec<Vec<u8>>>;
+}
@@ -0,0 +1,250 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use crate::utils::secret::Secret;
+use dbus::UDisksDbus;
+use iced::{
+    Subscription, Task,
+    futures::{SinkExt, Stream, StreamExt, channel::mpsc::Sender, stream::pending, stream_select},
+    stream::channel,
+};
+use log::{debug, error, info};
+use std::{any::TypeId, convert, ops::Deref};
+use zbus::zvariant::OwnedObjectPath;
+
+mod dbus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeKind {
+    Luks,
+    VeraCrypt,
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptedVolume {
+    pub path: OwnedObjectPath,
+    pub label: String,
+    pub device: String,
+    pub kind: VolumeKind,
+    pub cleartext: Option<OwnedObjectPath>,
+    pub mount_point: Option<String>,
+}
+
+impl EncryptedVolume {
+    pub fn is_unlocked(&self) -> bool {
+        self.cleartext.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UDisksData {
+    pub volumes: Vec<EncryptedVolume>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UDisksService {
+    conn: zbus::Connection,
+    data: UDisksData,
+}
+
+impl Deref for UDisksService {
+    type Target = UDisksData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum UDisksCommand {
+    Unlock(OwnedObjectPath, Secret),
+    Lock(OwnedObjectPath),
+    LockAll,
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl UDisksService {
+    async fn initialize_data(conn: &zbus::Connection) -> anyhow::Result<UDisksData> {
+        let udisks = UDisksDbus::new(conn).await?;
+        let volumes = udisks.volumes().await?;
+
+        Ok(UDisksData { volumes })
+    }
+
+    async fn events(conn: &zbus::Connection) -> anyhow::Result<impl Stream<Item = ()> + use<>> {
+        let udisks = UDisksDbus::new(conn).await?;
+
+        // unlocking and locking a volume adds and removes its cleartext device
+        Ok(stream_select!(
+            udisks.manager.receive_interfaces_added().await?.map(|_| {}),
+            udisks
+                .manager
+                .receive_interfaces_removed()
+                .await?
+                .map(|_| {}),
+        ))
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => match UDisksService::initialize_data(&conn).await {
+                    Ok(data) => {
+                        info!("UDisks service initialized");
+
+                        let _ = output
+                            .send(ServiceEvent::Init(UDisksService {
+                                data,
+                                conn: conn.clone(),
+                            }))
+                            .await;
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to initialize udisks service: {err}");
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to system bus: {err}");
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for udisks events");
+
+                match UDisksService::events(&conn).await {
+                    Ok(mut events) => {
+                        while events.next().await.is_some() {
+                            if let Ok(data) = UDisksService::initialize_data(&conn).await {
+                                let _ = output.send(ServiceEvent::Update(data)).await;
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for udisks events: {err}");
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                error!("UDisks service error");
+
+                let _ = pending::<u8>().next().await;
+
+                State::Error
+            }
+        }
+    }
+
+    fn refresh_after(
+        conn: zbus::Connection,
+        action: impl Future<Output = anyhow::Result<()>> + Send + 'static,
+    ) -> Task<ServiceEvent<Self>> {
+        Task::perform(
+            async move {
+                let res = action.await;
+
+                match (res, UDisksService::initialize_data(&conn).await) {
+                    (Err(err), _) => ServiceEvent::Error(err.to_string()),
+                    (Ok(_), Ok(data)) => ServiceEvent::Update(data),
+                    (Ok(_), Err(err)) => ServiceEvent::Error(err.to_string()),
+                }
+            },
+            convert::identity,
+        )
+    }
+}
+
+impl ReadOnlyService for UDisksService {
+    type UpdateEvent = UDisksData;
+    type Error = String;
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                let mut state = State::Init;
+
+                loop {
+                    state = UDisksService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+impl Service for UDisksService {
+    type Command = UDisksCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let conn = self.conn.clone();
+
+        match command {
+            UDisksCommand::Unlock(path, passphrase) => {
+                debug!("Unlocking volume {path:?}");
+
+                UDisksService::refresh_after(conn.clone(), async move {
+                    UDisksDbus::new(&conn)
+                        .await?
+                        .unlock(&path, passphrase.expose())
+                        .await
+                })
+            }
+            UDisksCommand::Lock(path) => {
+                let cleartext = self
+                    .volumes
+                    .iter()
+                    .find(|volume| volume.path == path)
+                    .and_then(|volume| volume.cleartext.clone());
+                debug!("Locking volume {path:?}");
+
+                UDisksService::refresh_after(conn.clone(), async move {
+                    UDisksDbus::new(&conn)
+                        .await?
+                        .lock(&path, cleartext.as_ref())
+                        .await
+                })
+            }
+            UDisksCommand::LockAll => {
+                let volumes = self
+                    .volumes
+                    .iter()
+                    .filter(|volume| volume.is_unlocked())
+                    .map(|volume| (volume.path.clone(), volume.cleartext.clone()))
+                    .collect::<Vec<_>>();
+                debug!("Locking {} volumes", volumes.len());
+
+                UDisksService::refresh_after(conn.clone(), async move {
+                    let udisks = UDisksDbus::new(&conn).await?;
+
+                    // try every volume even if one of them is busy
+                    let mut res = Ok(());
+                    for (path, cleartext) in volumes {
+                        if let Err(err) = udisks.lock(&path, cleartext.as_ref()).await {
+                            error!("Failed to lock volume {path:?}: {err}");
+                            res = Err(err);
+                        }
+                    }
+
+                    res
+                })
+            }
+        }
+    }
+}
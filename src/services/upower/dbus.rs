@@ -340,3 +340,21 @@ pub trait PowerProfiles {
     #[zbus(property)]
     fn set_active_profile(&self, profile: &str) -> zbus::Result<()>;
 }
+
+/// The daemons before 0.20 only own the `net.hadess.PowerProfiles` name
+pub async fn power_profiles_proxy(
+    conn: &zbus::Connection,
+) -> zbus::Result<PowerProfilesProxy<'static>> {
+    let proxy = PowerProfilesProxy::new(conn).await?;
+    if proxy.active_profile().await.is_ok() {
+        return Ok(proxy);
+    }
+
+    debug!("Falling back to the net.hadess.PowerProfiles interface");
+    PowerProfilesProxy::builder(conn)
+        .destination("net.hadess.PowerProfiles")?
+        .path("/net/hadess/PowerProfiles")?
+        .interface("net.hadess.PowerProfiles")?
+        .build()
+        .await
+}
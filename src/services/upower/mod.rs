@@ -2,7 +2,9 @@ use super::{ReadOnlyService, Service, ServiceEvent};
 use crate::{
     components::icons::StaticIcon, services::throttle::ThrottleExt, utils::IndicatorState,
 };
-use dbus::{DeviceProxy, PowerProfilesProxy, SystemBattery, UPowerDbus, UPowerProxy, UpDeviceKind};
+use dbus::{
+    DeviceProxy, SystemBattery, UPowerDbus, UPowerProxy, UpDeviceKind, power_profiles_proxy,
+};
 use iced::{
     Subscription,
     futures::{
@@ -14,7 +16,8 @@ use iced::{
     stream::channel,
 };
 use log::{error, warn};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::{any::TypeId, fmt, time::Duration};
 use zbus::zvariant::ObjectPath;
 
@@ -162,7 +165,7 @@ impl Peripheral {
     }
 }
 
-#[derive(Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Debug)]
 pub enum PeripheralDeviceKind {
     Keyboard,
     Mouse,
@@ -227,6 +230,18 @@ impl From<String> for PowerProfile {
     }
 }
 
+impl PowerProfile {
+    /// The name used by power-profiles-daemon
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            PowerProfile::Balanced => Some("balanced"),
+            PowerProfile::Performance => Some("performance"),
+            PowerProfile::PowerSaver => Some("power-saver"),
+            PowerProfile::Unknown => None,
+        }
+    }
+}
+
 impl From<PowerProfile> for StaticIcon {
     fn from(profile: PowerProfile) -> Self {
         match profile {
@@ -333,7 +348,7 @@ impl UPowerService {
     async fn initialize_power_profile_data(
         conn: &zbus::Connection,
     ) -> anyhow::Result<PowerProfile> {
-        let powerprofiles = PowerProfilesProxy::new(conn).await?;
+        let powerprofiles = power_profiles_proxy(conn).await?;
 
         let profile = powerprofiles
             .active_profile()
@@ -588,7 +603,7 @@ impl UPowerService {
             })
             .boxed();
 
-        let powerprofiles = PowerProfilesProxy::new(conn).await?;
+        let powerprofiles = power_profiles_proxy(conn).await?;
         let power_profile_event =
             powerprofiles
                 .receive_active_profile_changed()
@@ -669,6 +684,7 @@ impl UPowerService {
 
 pub enum PowerProfileCommand {
     Toggle,
+    Set(PowerProfile),
 }
 
 impl Service for UPowerService {
@@ -680,7 +696,7 @@ impl Service for UPowerService {
                 let conn = self.conn.clone();
                 let power_profile = self.power_profile;
                 async move {
-                    let powerprofiles = PowerProfilesProxy::new(&conn)
+                    let powerprofiles = power_profiles_proxy(&conn)
                         .await
                         .expect("Failed to create PowerProfilesProxy");
 
@@ -706,6 +722,14 @@ impl Service for UPowerService {
                                 PowerProfile::Unknown => PowerProfile::Unknown,
                             }
                         }
+                        PowerProfileCommand::Set(profile) => match profile.name() {
+                            Some(name) => {
+                                let _ = powerprofiles.set_active_profile(name).await;
+
+                                profile
+                            }
+                            None => power_profile,
+                        },
                     }
                 }
             },
@@ -0,0 +1,260 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use iced::{
+    Subscription, Task,
+    futures::{SinkExt, Stream, StreamExt, channel::mpsc::Sender, stream::pending, stream_select},
+    stream::channel,
+};
+use log::{debug, error, info};
+use std::{any::TypeId, convert, ops::Deref};
+use zbus::proxy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsbTarget {
+    Allow,
+    Block,
+    Reject,
+}
+
+impl UsbTarget {
+    fn from_rule(rule: &str) -> Option<Self> {
+        match rule.split_whitespace().next()? {
+            "allow" => Some(Self::Allow),
+            "block" => Some(Self::Block),
+            "reject" => Some(Self::Reject),
+            _ => None,
+        }
+    }
+
+    fn as_dbus(self) -> u32 {
+        match self {
+            Self::Allow => 0,
+            Self::Block => 1,
+            Self::Reject => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UsbDevice {
+    pub id: u32,
+    pub name: String,
+    pub vendor_product: String,
+    pub target: UsbTarget,
+}
+
+/// Reads the value following the given key in a usbguard rule,
+/// e.g. `name "Keyboard"` or `id 046d:c52b`
+fn rule_value<'a>(rule: &'a str, key: &str) -> Option<&'a str> {
+    let start = rule.find(&format!(" {key} "))? + key.len() + 2;
+    let rest = &rule[start..];
+
+    match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next(),
+        None => rest.split_whitespace().next(),
+    }
+}
+
+impl UsbDevice {
+    fn from_rule(id: u32, rule: &str) -> Option<Self> {
+        let vendor_product = rule_value(rule, "id").unwrap_or_default().to_owned();
+        let name = rule_value(rule, "name")
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned)
+            .unwrap_or_else(|| vendor_product.clone());
+
+        Some(Self {
+            id,
+            name,
+            vendor_product,
+            target: UsbTarget::from_rule(rule)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UsbGuardData {
+    pub devices: Vec<UsbDevice>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UsbGuardService {
+    conn: zbus::Connection,
+    data: UsbGuardData,
+}
+
+impl Deref for UsbGuardService {
+    type Target = UsbGuardData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum UsbGuardCommand {
+    /// Applies the target to the device, the last field makes the decision permanent
+    ApplyPolicy(u32, UsbTarget, bool),
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl UsbGuardService {
+    async fn initialize_data(conn: &zbus::Connection) -> anyhow::Result<UsbGuardData> {
+        let devices = UsbGuardDevicesProxy::new(conn)
+            .await?
+            .list_devices("match")
+            .await?
+            .into_iter()
+            .filter_map(|(id, rule)| UsbDevice::from_rule(id, &rule))
+            .collect();
+
+        Ok(UsbGuardData { devices })
+    }
+
+    async fn events(conn: &zbus::Connection) -> anyhow::Result<impl Stream<Item = ()> + use<>> {
+        let devices = UsbGuardDevicesProxy::new(conn).await?;
+
+        Ok(stream_select!(
+            devices.receive_device_presence_changed().await?.map(|_| {}),
+            devices.receive_device_policy_changed().await?.map(|_| {}),
+        ))
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => match UsbGuardService::initialize_data(&conn).await {
+                    Ok(data) => {
+                        info!("USBGuard service initialized");
+
+                        let _ = output
+                            .send(ServiceEvent::Init(UsbGuardService {
+                                data,
+                                conn: conn.clone(),
+                            }))
+                            .await;
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to initialize usbguard service: {err}");
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to system bus: {err}");
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for usbguard events");
+
+                match UsbGuardService::events(&conn).await {
+                    Ok(mut events) => {
+                        while events.next().await.is_some() {
+                            if let Ok(data) = UsbGuardService::initialize_data(&conn).await {
+                                let _ = output.send(ServiceEvent::Update(data)).await;
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for usbguard events: {err}");
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                error!("USBGuard service error");
+
+                let _ = pending::<u8>().next().await;
+
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for UsbGuardService {
+    type UpdateEvent = UsbGuardData;
+    type Error = String;
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, async |mut output| {
+                let mut state = State::Init;
+
+                loop {
+                    state = UsbGuardService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+impl Service for UsbGuardService {
+    type Command = UsbGuardCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let conn = self.conn.clone();
+
+        match command {
+            UsbGuardCommand::ApplyPolicy(id, target, permanent) => Task::perform(
+                async move {
+                    debug!("Applying {target:?} to usb device {id}, permanent: {permanent}");
+
+                    let res = async {
+                        UsbGuardDevicesProxy::new(&conn)
+                            .await?
+                            .apply_device_policy(id, target.as_dbus(), permanent)
+                            .await
+                    }
+                    .await;
+
+                    match res {
+                        Ok(_) => match UsbGuardService::initialize_data(&conn).await {
+                            Ok(data) => ServiceEvent::Update(data),
+                            Err(err) => ServiceEvent::Error(err.to_string()),
+                        },
+                        Err(err) => ServiceEvent::Error(err.to_string()),
+                    }
+                },
+                convert::identity,
+            ),
+        }
+    }
+}
+
+#[proxy(
+    default_service = "org.usbguard1",
+    default_path = "/org/usbguard1/Devices",
+    interface = "org.usbguard.Devices1"
+)]
+trait UsbGuardDevices {
+    #[zbus(name = "listDevices")]
+    fn list_devices(&self, query: &str) -> zbus::Result<Vec<(u32, String)>>;
+
+    #[zbus(name = "applyDevicePolicy")]
+    fn apply_device_policy(&self, id: u32, target: u32, permanent: bool) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn device_presence_changed(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn device_policy_changed(&self) -> zbus::Result<()>;
+}
@@ -0,0 +1,165 @@
+use crate::config::SoundsConfig;
+use log::{debug, error, warn};
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// The themes inherit from it when they don't say otherwise
+const FALLBACK_THEME: &str = "freedesktop";
+const EXTENSIONS: [&str; 3] = ["oga", "ogg", "wav"];
+/// The outputs announced when the bar starts weren't just plugged
+const STARTUP_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+pub enum SoundEvent {
+    DeviceAdded,
+    DeviceRemoved,
+    BatteryCritical,
+    WorkspaceSwitch,
+    Notification,
+}
+
+impl SoundEvent {
+    /// Name of the sound in the freedesktop sound naming specification
+    fn name(self) -> &'static str {
+        match self {
+            SoundEvent::DeviceAdded => "device-added",
+            SoundEvent::DeviceRemoved => "device-removed",
+            SoundEvent::BatteryCritical => "battery-caution",
+            SoundEvent::WorkspaceSwitch => "desktop-switch-right",
+            SoundEvent::Notification => "message-new-instant",
+        }
+    }
+
+    fn enabled(self, config: &SoundsConfig) -> bool {
+        match self {
+            SoundEvent::DeviceAdded | SoundEvent::DeviceRemoved => config.device_plug,
+            SoundEvent::BatteryCritical => config.battery_critical,
+            SoundEvent::WorkspaceSwitch => config.workspace_switch,
+            SoundEvent::Notification => config.notification,
+        }
+    }
+}
+
+fn sounds_dirs() -> Vec<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    data_home
+        .into_iter()
+        .chain(env::split_paths(&data_dirs))
+        .map(|dir| dir.join("sounds"))
+        .collect()
+}
+
+/// The `Inherits` key of the theme index
+fn parents(theme_dir: &Path) -> Vec<String> {
+    fs::read_to_string(theme_dir.join("index.theme"))
+        .ok()
+        .and_then(|content| {
+            content.lines().find_map(|line| {
+                line.trim()
+                    .strip_prefix("Inherits=")
+                    .map(|parents| parents.split(',').map(|p| p.trim().to_string()).collect())
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Looks for the sound in the theme, then in the themes it inherits from
+fn find_sound(theme: &str, name: &str) -> Option<PathBuf> {
+    let dirs = sounds_dirs();
+    let mut themes = vec![theme.to_string()];
+    let mut visited = HashSet::new();
+
+    while let Some(theme) = themes.pop() {
+        if !visited.insert(theme.clone()) {
+            continue;
+        }
+
+        let mut inherits = Vec::new();
+        for theme_dir in dirs.iter().map(|dir| dir.join(&theme)) {
+            for subdir in ["stereo", ""] {
+                for extension in EXTENSIONS {
+                    let path = theme_dir.join(subdir).join(format!("{name}.{extension}"));
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+            }
+            if inherits.is_empty() {
+                inherits = parents(&theme_dir);
+            }
+        }
+
+        if inherits.is_empty() && theme != FALLBACK_THEME {
+            inherits.push(FALLBACK_THEME.to_string());
+        }
+        // the first parent is searched first
+        themes.extend(inherits.into_iter().rev());
+    }
+
+    None
+}
+
+/// Plays the sounds of the freedesktop sound theme on the events enabled in the configuration
+pub struct Sounds {
+    config: SoundsConfig,
+    started: Instant,
+}
+
+impl Sounds {
+    pub fn new(config: SoundsConfig) -> Self {
+        Self {
+            config,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn reload(&mut self, config: SoundsConfig) {
+        self.config = config;
+    }
+
+    pub fn play(&self, event: SoundEvent) {
+        if !event.enabled(&self.config)
+            || matches!(event, SoundEvent::DeviceAdded) && self.started.elapsed() < STARTUP_GRACE
+        {
+            return;
+        }
+
+        let Some(path) = find_sound(&self.config.theme, event.name()) else {
+            warn!(
+                "sound {} not found in the theme {}",
+                event.name(),
+                self.config.theme
+            );
+            return;
+        };
+
+        debug!("playing {}", path.display());
+        tokio::spawn(async move {
+            match tokio::process::Command::new("pw-play")
+                .arg(&path)
+                .status()
+                .await
+            {
+                Ok(status) if !status.success() => {
+                    error!(
+                        "failed to play {}: pw-play exited with {status}",
+                        path.display()
+                    );
+                }
+                Err(e) => error!("failed to run pw-play: {e}"),
+                _ => {}
+            }
+        });
+    }
+}
@@ -0,0 +1,80 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::{env, fs, path::PathBuf};
+
+/// Runtime settings persisted across restarts, changed from the bar
+/// instead of the configuration file
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct State {
+    /// names of the modules hidden at runtime, as written in the configuration
+    pub hidden_modules: Vec<String>,
+    /// names of the pinned popups
+    pub pinned_popups: Vec<String>,
+    /// the notifications don't show popups
+    pub do_not_disturb: bool,
+}
+
+fn state_path() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .map(|state| state.join("ashell").join("state.json"))
+}
+
+impl State {
+    pub fn load() -> Self {
+        let Some(path) = state_path() else {
+            return State::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+                warn!("Failed to parse the state file {path:?}: {err}");
+
+                State::default()
+            }),
+            Err(err) => {
+                debug!("No state file loaded from {path:?}: {err}");
+
+                State::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = state_path() {
+            let res = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| {
+                    serde_json::to_string_pretty(self)
+                        .map_err(std::io::Error::other)
+                        .and_then(|content| fs::write(&path, content))
+                });
+
+            if let Err(err) = res {
+                warn!("Failed to write the state file {path:?}: {err}");
+            }
+        }
+    }
+
+    pub fn is_hidden(&self, module: &str) -> bool {
+        self.hidden_modules.iter().any(|hidden| hidden == module)
+    }
+
+    /// Returns true when the visibility of the module changed
+    pub fn set_hidden(&mut self, module: &str, hidden: bool) -> bool {
+        if hidden == self.is_hidden(module) {
+            return false;
+        }
+
+        if hidden {
+            self.hidden_modules.push(module.to_string());
+        } else {
+            self.hidden_modules.retain(|name| name != module);
+        }
+
+        true
+    }
+}
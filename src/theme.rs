@@ -1,4 +1,6 @@
 use crate::config::{Appearance, AppearanceColor, AppearanceStyle, MenuAppearance, Position};
+#[cfg(feature = "wallpaper-colors")]
+use crate::wallpaper_colors::BarColors;
 use iced::{
     Background, Border, Color, Theme,
     theme::{Palette, palette},
@@ -92,6 +94,7 @@ pub struct AshellTheme {
     pub menu: MenuAppearance,
     pub workspace_colors: Vec<AppearanceColor>,
     pub special_workspace_colors: Option<Vec<AppearanceColor>>,
+    pub urgent_workspace_color: AppearanceColor,
     pub scale_factor: f64,
 }
 
@@ -107,6 +110,9 @@ impl AshellTheme {
             menu: appearance.menu,
             workspace_colors: appearance.workspace_colors.clone(),
             special_workspace_colors: appearance.special_workspace_colors.clone(),
+            urgent_workspace_color: appearance
+                .urgent_workspace_color
+                .unwrap_or(appearance.danger_color),
             scale_factor: appearance.scale_factor,
             iced_theme: Theme::custom_with_fn(
                 "local".to_string(),
@@ -216,6 +222,19 @@ impl AshellTheme {
         &self.iced_theme
     }
 
+    /// The theme of a bar colored after its wallpaper
+    #[cfg(feature = "wallpaper-colors")]
+    pub fn bar_theme(&self, colors: BarColors) -> Theme {
+        Theme::custom(
+            "wallpaper".to_string(),
+            Palette {
+                background: colors.background,
+                text: colors.text,
+                ..self.iced_theme.palette()
+            },
+        )
+    }
+
     pub fn ghost_button_style(&self) -> impl Fn(&Theme, Status) -> button::Style {
         move |theme, status| {
             let mut base = button::Style {
@@ -414,6 +433,7 @@ impl AshellTheme {
     pub fn workspace_button_style(
         &self,
         is_empty: bool,
+        is_focused: bool,
         colors: Option<Option<AppearanceColor>>,
     ) -> impl Fn(&Theme, Status) -> button::Style {
         move |theme: &Theme, status: Status| {
@@ -444,10 +464,19 @@ impl AshellTheme {
                 } else {
                     bg_color
                 })),
-                border: Border {
-                    width: if is_empty { 1.0 } else { 0.0 },
-                    color: bg_color,
-                    radius: self.radius.lg.into(),
+                // the workspace selected with the keyboard
+                border: if is_focused {
+                    Border {
+                        width: 2.0,
+                        color: theme.palette().text,
+                        radius: self.radius.lg.into(),
+                    }
+                } else {
+                    Border {
+                        width: if is_empty { 1.0 } else { 0.0 },
+                        color: bg_color,
+                        radius: self.radius.lg.into(),
+                    }
                 },
                 text_color: if is_empty {
                     theme.extended_palette().background.weak.text
@@ -642,6 +671,45 @@ impl AshellTheme {
             }
         }
     }
+
+    pub fn taskbar_button_style(&self, focused: bool) -> impl Fn(&Theme, Status) -> button::Style {
+        move |theme, status| {
+            let mut base = button::Style {
+                background: focused.then(|| {
+                    theme
+                        .extended_palette()
+                        .background
+                        .weak
+                        .color
+                        .scale_alpha(self.opacity)
+                        .into()
+                }),
+                border: Border {
+                    width: 0.0,
+                    radius: self.radius.lg.into(),
+                    color: Color::TRANSPARENT,
+                },
+                text_color: theme.palette().text,
+                ..button::Style::default()
+            };
+            match status {
+                Status::Active => base,
+                Status::Hovered => {
+                    base.background = Some(
+                        theme
+                            .extended_palette()
+                            .background
+                            .strong
+                            .color
+                            .scale_alpha(self.opacity)
+                            .into(),
+                    );
+                    base
+                }
+                _ => base,
+            }
+        }
+    }
 }
 
 pub fn backdrop_color(backdrop: f32) -> Color {
@@ -0,0 +1,196 @@
+use data_encoding::{BASE64, HEXLOWER};
+use iced::widget::image::Handle;
+use log::{debug, warn};
+use sha1::{Digest, Sha1};
+use std::{
+    env,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tokio::{process::Command, task};
+
+use super::percent_decode;
+
+/// Larger images are not downloaded
+const MAX_IMAGE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Side of the image blurred for the backdrop, it's scaled up when shown
+const BACKDROP_SIZE: u32 = 64;
+
+#[derive(Debug, Clone)]
+pub struct AlbumArt {
+    pub image: Handle,
+    pub backdrop: Option<Handle>,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|cache| cache.join("ashell").join("album_art"))
+}
+
+fn cache_path(url: &str) -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(HEXLOWER.encode(&Sha1::digest(url.as_bytes()))))
+}
+
+/// Removes the least recently used images until the cache fits the size limit
+fn prune_cache(max_size: u64) {
+    let Some(dir) = cache_dir() else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+
+    let mut files = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+
+            Some((entry.path(), metadata.len(), modified))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| b.2.cmp(&a.2));
+
+    let mut size = 0;
+    for (path, len, _) in files {
+        size += len;
+
+        if size > max_size {
+            debug!("Removing album art {path:?} from the cache");
+
+            if let Err(err) = fs::remove_file(&path) {
+                warn!("Failed to remove album art {path:?}: {err}");
+            }
+        }
+    }
+}
+
+/// Marks the cached image as recently used
+fn touch(path: &Path) {
+    if let Err(err) = File::options()
+        .append(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        debug!("Failed to update the album art {path:?}: {err}");
+    }
+}
+
+async fn download(url: &str, path: &Path) -> bool {
+    let tmp_path = path.with_extension("part");
+
+    let res = Command::new("curl")
+        .args(["--silent", "--fail", "--location", "--max-time", "10"])
+        .arg("--max-filesize")
+        .arg(MAX_IMAGE_SIZE.to_string())
+        .arg("--output")
+        .arg(&tmp_path)
+        .arg(url)
+        .status()
+        .await;
+
+    match res {
+        Ok(status) if status.success() => fs::rename(&tmp_path, path)
+            .inspect_err(|err| warn!("Failed to save album art {path:?}: {err}"))
+            .is_ok(),
+        Ok(status) => {
+            debug!("Failed to download album art {url}: curl exited with {status}");
+            let _ = fs::remove_file(&tmp_path);
+
+            false
+        }
+        Err(err) => {
+            warn!("Failed to run curl to download album art: {err}");
+
+            false
+        }
+    }
+}
+
+/// Finds the image of the `mpris:artUrl`, downloading or decoding it in the cache
+async fn resolve(url: &str, cache_size: u64) -> Option<PathBuf> {
+    if let Some(path) = url.strip_prefix("file://") {
+        let path = PathBuf::from(percent_decode(path));
+
+        return path.exists().then_some(path);
+    }
+
+    let path = cache_path(url)?;
+    if path.exists() {
+        touch(&path);
+
+        return Some(path);
+    }
+
+    fs::create_dir_all(path.parent()?)
+        .inspect_err(|err| warn!("Failed to create the album art cache: {err}"))
+        .ok()?;
+
+    if let Some(data) = url.strip_prefix("data:") {
+        let (_, encoded) = data.split_once(";base64,")?;
+        if encoded.len() as u64 > MAX_IMAGE_SIZE * 4 / 3 {
+            return None;
+        }
+
+        let decoded = BASE64
+            .decode(encoded.trim().as_bytes())
+            .inspect_err(|err| debug!("Invalid base64 album art: {err}"))
+            .ok()?;
+        fs::write(&path, decoded)
+            .inspect_err(|err| warn!("Failed to save album art {path:?}: {err}"))
+            .ok()?;
+    } else if url.starts_with("http://") || url.starts_with("https://") {
+        if !download(url, &path).await {
+            return None;
+        }
+    } else {
+        debug!("Unsupported album art url {url}");
+
+        return None;
+    }
+
+    prune_cache(cache_size);
+
+    Some(path)
+}
+
+fn blur(path: &Path) -> Option<Handle> {
+    let image = image::open(path)
+        .inspect_err(|err| debug!("Failed to decode album art {path:?}: {err}"))
+        .ok()?
+        .thumbnail(BACKDROP_SIZE, BACKDROP_SIZE)
+        .to_rgba8();
+    let blurred = image::imageops::blur(&image, 4.);
+
+    Some(Handle::from_rgba(
+        blurred.width(),
+        blurred.height(),
+        blurred.into_raw(),
+    ))
+}
+
+/// Loads the album art, `cache_size` is the size limit of the cache in bytes
+pub async fn load(url: String, backdrop: bool, cache_size: u64) -> Option<AlbumArt> {
+    let path = resolve(&url, cache_size).await?;
+
+    let backdrop = if backdrop {
+        let path = path.clone();
+
+        // decoding and blurring is too slow for the async runtime
+        task::spawn_blocking(move || blur(&path))
+            .await
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+
+    Some(AlbumArt {
+        image: Handle::from_path(path),
+        backdrop,
+    })
+}
@@ -0,0 +1,162 @@
+use freedesktop_icons::lookup;
+use iced::{
+    Element, Length,
+    widget::{Image, Svg, image, svg},
+};
+use linicon_theme::get_icon_theme;
+use log::debug;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+/// Icon of an application, resolved from its desktop entry
+#[derive(Debug, Clone)]
+pub enum AppIcon {
+    Image(image::Handle),
+    Svg(svg::Handle),
+}
+
+impl AppIcon {
+    fn from_path(path: PathBuf) -> Self {
+        if path.extension().is_some_and(|ext| ext == "svg") {
+            AppIcon::Svg(svg::Handle::from_path(path))
+        } else {
+            AppIcon::Image(image::Handle::from_path(path))
+        }
+    }
+
+    pub fn view<'a, Message: 'a>(&self, size: u16) -> Element<'a, Message> {
+        match self {
+            AppIcon::Image(handle) => Image::new(handle.clone())
+                .height(Length::Fixed(size as f32))
+                .into(),
+            AppIcon::Svg(handle) => Svg::new(handle.clone())
+                .height(Length::Fixed(size as f32))
+                .width(Length::Fixed(size as f32))
+                .into(),
+        }
+    }
+}
+
+/// The resolution reads the desktop entries, the result is kept for the next windows
+static CACHE: LazyLock<Mutex<HashMap<String, Option<AppIcon>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn applications_dirs() -> Vec<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    data_home
+        .into_iter()
+        .chain(env::split_paths(&data_dirs))
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// Reads the `Icon` and `StartupWMClass` keys of the main section
+fn read_entry(path: &Path) -> Option<(Option<String>, Option<String>)> {
+    let content = fs::read_to_string(path).ok()?;
+
+    let mut icon = None;
+    let mut wm_class = None;
+    let mut main_section = false;
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            main_section = line == "[Desktop Entry]";
+        } else if main_section {
+            if let Some(value) = line.strip_prefix("Icon=") {
+                icon = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("StartupWMClass=") {
+                wm_class = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    Some((icon, wm_class))
+}
+
+/// Finds the icon name in the desktop entry of the application, matching the
+/// file name, the last component of reverse DNS names or the `StartupWMClass`
+fn desktop_entry_icon(class: &str) -> Option<String> {
+    let class = class.to_lowercase();
+
+    for dir in applications_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|ext| ext != "desktop") {
+                continue;
+            }
+
+            let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_lowercase()) else {
+                continue;
+            };
+            let name_matches = stem == class || stem.rsplit('.').next() == Some(class.as_str());
+
+            if let Some((icon, wm_class)) = read_entry(&path)
+                && (name_matches
+                    || wm_class.is_some_and(|wm_class| wm_class.to_lowercase() == class))
+                && let Some(icon) = icon
+            {
+                debug!("found desktop entry {path:?} for {class}");
+
+                return Some(icon);
+            }
+        }
+    }
+
+    None
+}
+
+fn find_icon(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    if path.is_absolute() {
+        return path.exists().then(|| path.to_path_buf());
+    }
+
+    let lookup = lookup(name).with_cache();
+
+    match get_icon_theme() {
+        Some(theme) => lookup.with_theme(&theme).find(),
+        None => lookup.find(),
+    }
+}
+
+/// Resolves an icon name of the theme or an absolute image path
+pub fn named_icon(name: &str) -> Option<AppIcon> {
+    find_icon(name).map(AppIcon::from_path)
+}
+
+/// Resolves the icon of the window class or app_id
+pub fn app_icon(class: &str) -> Option<AppIcon> {
+    if class.is_empty() {
+        return None;
+    }
+
+    if let Some(icon) = CACHE.lock().ok()?.get(class) {
+        return icon.clone();
+    }
+
+    let icon = desktop_entry_icon(class)
+        .and_then(|name| find_icon(&name))
+        // some applications use the icon name as app_id
+        .or_else(|| find_icon(&class.to_lowercase()))
+        .map(AppIcon::from_path);
+
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.insert(class.to_string(), icon.clone());
+    }
+
+    icon
+}
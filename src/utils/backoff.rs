@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+/// a listener running for this long was healthy, its next failure starts over
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+/// failures in a row after which the listener is reported as down
+pub const MAX_RETRIES: u32 = 5;
+
+/// Delays between the restarts of a failing event listener, doubling up to a maximum
+pub struct Backoff {
+    failures: u32,
+    started: Instant,
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self {
+            failures: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Marks the start of the listener
+    pub fn start(&mut self) {
+        self.started = Instant::now();
+    }
+
+    /// Records the end of the listener, returns the delay before restarting it
+    pub fn fail(&mut self) -> Duration {
+        if self.started.elapsed() >= HEALTHY_RUN {
+            self.failures = 0;
+        }
+        self.failures = self.failures.saturating_add(1);
+
+        let delay = BASE_DELAY
+            .saturating_mul(2u32.saturating_pow(self.failures - 1))
+            .min(MAX_DELAY);
+
+        // the listeners of the modules don't restart all at once
+        delay + jitter(delay / 4)
+    }
+
+    /// The listener just reached the maximum number of failures in a row
+    pub fn just_down(&self) -> bool {
+        self.failures == MAX_RETRIES
+    }
+}
+
+/// A pseudo random duration up to `max`, the clock is random enough to spread the restarts
+fn jitter(max: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+
+    max.mul_f64(f64::from(nanos % 1000) / 1000.0)
+}
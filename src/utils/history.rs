@@ -0,0 +1,49 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Samples of a value over a sliding time window, the older ones are dropped
+pub struct History {
+    window: Duration,
+    samples: VecDeque<(Instant, f32)>,
+}
+
+impl History {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, value: f32) {
+        let now = Instant::now();
+
+        while self
+            .samples
+            .front()
+            .is_some_and(|(time, _)| now.duration_since(*time) > self.window)
+        {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, value));
+    }
+
+    /// The samples, the oldest first
+    pub fn values(&self) -> impl Iterator<Item = f32> + '_ {
+        self.samples.iter().map(|(_, value)| *value)
+    }
+
+    pub fn last(&self) -> Option<f32> {
+        self.samples.back().map(|(_, value)| *value)
+    }
+
+    pub fn max(&self) -> Option<f32> {
+        self.values().reduce(f32::max)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
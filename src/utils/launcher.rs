@@ -1,4 +1,11 @@
-use std::process::Command;
+use log::error;
+use std::process::{Command, Stdio};
+use tokio::io::AsyncWriteExt;
+
+/// Wraps the value in single quotes so that it can be used as a shell argument
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
 
 pub fn execute_command(command: String) {
     tokio::spawn(async move {
@@ -11,6 +18,74 @@ pub fn execute_command(command: String) {
     });
 }
 
+pub fn execute_command_with_env(command: String, envs: Vec<(&'static str, String)>) {
+    tokio::spawn(async move {
+        let child = tokio::process::Command::new("bash")
+            .arg("-c")
+            .arg(&command)
+            .envs(envs)
+            .spawn();
+
+        match child {
+            Ok(mut child) => {
+                let _ = child.wait().await;
+            }
+            Err(err) => {
+                error!("Failed to execute command {command}: {err}");
+            }
+        }
+    });
+}
+
+/// Runs the command and returns its stdout if it exits successfully
+pub async fn command_output(command: &str) -> Option<String> {
+    let output = tokio::process::Command::new("bash")
+        .arg("-c")
+        .arg(command)
+        .stdout(Stdio::piped())
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => {
+            error!("Command {command} exited with {}", output.status);
+            None
+        }
+        Err(err) => {
+            error!("Failed to execute command {command}: {err}");
+            None
+        }
+    }
+}
+
+/// Runs the command writing the input to its stdin
+pub async fn pipe_to_command(command: &str, input: &str) {
+    let child = tokio::process::Command::new("bash")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .spawn();
+
+    match child {
+        Ok(mut child) => {
+            // stdin is dropped at the end of the block, closing the pipe
+            if let Some(mut stdin) = child.stdin.take()
+                && let Err(err) = stdin.write_all(input.as_bytes()).await
+            {
+                error!("Failed to write to command {command}: {err}");
+            }
+
+            let _ = child.wait().await;
+        }
+        Err(err) => {
+            error!("Failed to execute command {command}: {err}");
+        }
+    }
+}
+
 pub fn suspend(cmd: String) {
     tokio::spawn(async move {
         let _ = Command::new("bash")
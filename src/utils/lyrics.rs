@@ -0,0 +1,176 @@
+use log::{debug, warn};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{fs, process::Command};
+
+use super::percent_decode;
+
+const LRCLIB_URL: &str = "https://lrclib.net/api/get";
+
+/// Lyrics of a track, the lines are sorted by time when they're synced
+#[derive(Debug, Clone)]
+pub struct Lyrics {
+    pub lines: Vec<(Option<Duration>, String)>,
+}
+
+impl Lyrics {
+    /// Parses the LRC format, the lines without a time tag are kept unsynced
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut lines = Vec::new();
+
+        for line in content.lines().map(str::trim) {
+            let mut rest = line;
+            let mut times = Vec::new();
+
+            while let Some((tag, tail)) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+                match parse_time(tag) {
+                    Some(time) => times.push(time),
+                    // metadata tags like [ar:...] or [length:...]
+                    None if times.is_empty() => break,
+                    None => {}
+                }
+                rest = tail;
+            }
+
+            if times.is_empty() {
+                if !line.starts_with('[') && !line.is_empty() {
+                    lines.push((None, line.to_string()));
+                }
+            } else {
+                lines.extend(
+                    times
+                        .into_iter()
+                        .map(|time| (Some(time), rest.trim().to_string())),
+                );
+            }
+        }
+
+        if lines.iter().all(|(_, text)| text.is_empty()) {
+            return None;
+        }
+
+        lines.sort_by_key(|(time, _)| *time);
+
+        Some(Self { lines })
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.lines.iter().any(|(time, _)| time.is_some())
+    }
+
+    /// Index of the line sung at the position
+    pub fn current_line(&self, position: Duration) -> Option<usize> {
+        if !self.is_synced() {
+            return None;
+        }
+
+        self.lines
+            .iter()
+            .rposition(|(time, _)| time.is_some_and(|time| time <= position))
+    }
+}
+
+/// Parses `mm:ss.xx` time tags
+fn parse_time(tag: &str) -> Option<Duration> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes = minutes.parse::<u64>().ok()?;
+    let seconds = seconds.parse::<f64>().ok()?;
+
+    Some(Duration::from_secs(minutes * 60) + Duration::from_secs_f64(seconds))
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackInfo {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    pub length: Option<Duration>,
+    /// `xesam:url` of the track, the `.lrc` file next to a local track is used first
+    pub url: Option<String>,
+}
+
+async fn read_lrc(path: &Path) -> Option<Lyrics> {
+    let content = fs::read_to_string(path).await.ok()?;
+    debug!("Found lyrics {path:?}");
+
+    Lyrics::parse(&content)
+}
+
+async fn fetch_lrclib(track: &TrackInfo) -> Option<Lyrics> {
+    let mut command = Command::new("curl");
+    command
+        .args([
+            "--silent",
+            "--fail",
+            "--location",
+            "--max-time",
+            "10",
+            "--get",
+        ])
+        .args([
+            "--user-agent",
+            "ashell (https://github.com/MalpenZibo/ashell)",
+        ])
+        .arg("--data-urlencode")
+        .arg(format!("artist_name={}", track.artist))
+        .arg("--data-urlencode")
+        .arg(format!("track_name={}", track.title));
+    if let Some(album) = &track.album {
+        command
+            .arg("--data-urlencode")
+            .arg(format!("album_name={album}"));
+    }
+    if let Some(length) = track.length {
+        command
+            .arg("--data-urlencode")
+            .arg(format!("duration={}", length.as_secs()));
+    }
+
+    let output = command
+        .arg(LRCLIB_URL)
+        .output()
+        .await
+        .inspect_err(|err| warn!("Failed to run curl to fetch the lyrics: {err}"))
+        .ok()?;
+    if !output.status.success() {
+        debug!(
+            "No lyrics found on LRCLIB for {} - {}",
+            track.artist, track.title
+        );
+
+        return None;
+    }
+
+    let response = serde_json::from_slice::<serde_json::Value>(&output.stdout).ok()?;
+
+    ["syncedLyrics", "plainLyrics"]
+        .iter()
+        .filter_map(|key| response.get(key).and_then(|value| value.as_str()))
+        .find_map(Lyrics::parse)
+}
+
+/// Looks for the lyrics of the track in the local `.lrc` files, then on LRCLIB
+pub async fn load(track: TrackInfo, dir: Option<PathBuf>, lrclib: bool) -> Option<Lyrics> {
+    let track_lrc = track
+        .url
+        .as_deref()
+        .and_then(|url| url.strip_prefix("file://"))
+        .map(|path| PathBuf::from(percent_decode(path)).with_extension("lrc"));
+
+    let dir_lrc = dir
+        .map(|dir| dir.join(format!("{} - {}.lrc", track.artist, track.title).replace('/', "_")));
+
+    for path in track_lrc.into_iter().chain(dir_lrc) {
+        if let Some(lyrics) = read_lrc(&path).await {
+            return Some(lyrics);
+        }
+    }
+
+    if lrclib {
+        fetch_lrclib(&track).await
+    } else {
+        None
+    }
+}
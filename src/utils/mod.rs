@@ -1,6 +1,19 @@
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
+#[cfg(feature = "media-player")]
+pub mod album_art;
+pub mod app_icon;
+pub mod backoff;
+#[cfg(feature = "dashboard")]
+pub mod history;
 pub mod launcher;
+#[cfg(feature = "media-player")]
+pub mod lyrics;
+#[cfg(any(feature = "passwords", feature = "encrypted-volumes", feature = "totp"))]
+pub mod secret;
+#[cfg(any(feature = "dashboard", feature = "metrics"))]
+pub mod stats;
+pub mod watchdog;
 
 pub enum IndicatorState {
     Normal,
@@ -19,15 +32,38 @@ pub fn format_duration(duration: &Duration) -> String {
     }
 }
 
-pub fn truncate_text(value: &str, max_length: u32) -> String {
+pub fn truncate_text(value: &str, max_length: u32) -> Cow<'_, str> {
     let length = value.len();
 
     if length > max_length as usize {
         let split = max_length as usize / 2;
         let first_part = value.chars().take(split).collect::<String>();
         let last_part = value.chars().skip(length - split).collect::<String>();
-        format!("{first_part}...{last_part}")
+        Cow::Owned(format!("{first_part}...{last_part}"))
     } else {
-        value.to_string()
+        Cow::Borrowed(value)
     }
 }
+
+/// Decodes the `%XX` escapes of an url
+pub fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
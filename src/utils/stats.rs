@@ -0,0 +1,88 @@
+use std::{fs, time::Instant};
+use sysinfo::{Components, Networks, System};
+
+/// Average charge of the batteries, from the kernel
+fn battery_percentage() -> Option<f32> {
+    let capacities = fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            fs::read_to_string(entry.path().join("type")).is_ok_and(|kind| kind.trim() == "Battery")
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path().join("capacity")).ok())
+        .filter_map(|capacity| capacity.trim().parse::<f32>().ok())
+        .collect::<Vec<_>>();
+
+    (!capacities.is_empty()).then(|| capacities.iter().sum::<f32>() / capacities.len() as f32)
+}
+
+/// The system stats at a point in time
+#[derive(Debug, Clone, Default)]
+pub struct Sample {
+    pub battery: Option<f32>,
+    pub cpu: f32,
+    /// throughput in KB/s since the previous sample
+    pub download: f32,
+    pub upload: f32,
+    /// temperature of the sensors by label
+    pub temperatures: Vec<(String, f32)>,
+}
+
+impl Sample {
+    pub fn temperature(&self, sensor: &str) -> Option<f32> {
+        self.temperatures
+            .iter()
+            .find(|(label, _)| label == sensor)
+            .map(|(_, temperature)| *temperature)
+    }
+}
+
+/// Reads the system stats, the CPU usage and the throughput are computed between two samples
+pub struct Sampler {
+    system: System,
+    components: Components,
+    networks: Networks,
+    last_sample: Instant,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        system.refresh_cpu_usage();
+
+        Self {
+            system,
+            components: Components::new_with_refreshed_list(),
+            networks: Networks::new_with_refreshed_list(),
+            last_sample: Instant::now(),
+        }
+    }
+
+    pub fn sample(&mut self) -> Sample {
+        self.system.refresh_cpu_usage();
+        self.components.refresh(true);
+        self.networks.refresh(true);
+
+        let elapsed = self.last_sample.elapsed().as_secs_f32().max(1.);
+        self.last_sample = Instant::now();
+
+        let (received, transmitted) = self.networks.iter().filter(|(name, _)| *name != "lo").fold(
+            (0, 0),
+            |(received, transmitted), (_, data)| {
+                (received + data.received(), transmitted + data.transmitted())
+            },
+        );
+
+        Sample {
+            battery: battery_percentage(),
+            cpu: self.system.global_cpu_usage(),
+            download: received as f32 / 1000. / elapsed,
+            upload: transmitted as f32 / 1000. / elapsed,
+            temperatures: self
+                .components
+                .iter()
+                .filter_map(|c| Some((c.label().to_string(), c.temperature()?)))
+                .collect(),
+        }
+    }
+}
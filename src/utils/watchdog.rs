@@ -0,0 +1,59 @@
+use log::warn;
+use std::time::{Duration, Instant};
+
+/// How often the backend state is compared with the one built from the events
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// The events received shortly before the check may not be applied yet
+const QUIET_PERIOD: Duration = Duration::from_secs(10);
+
+/// Restarts a listener that stopped reporting the changes of a backend still answering queries,
+/// e.g. the Hyprland socket open but no events coming
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    name: &'static str,
+    last_event: Instant,
+    restarts: u32,
+}
+
+impl Watchdog {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            last_event: Instant::now(),
+            restarts: 0,
+        }
+    }
+
+    /// An event came from the listener
+    pub fn feed(&mut self) {
+        self.last_event = Instant::now();
+    }
+
+    /// The listener is stuck when the queried state changed while it was quiet
+    pub fn check(&mut self, changed: bool) -> bool {
+        let stuck = changed && self.last_event.elapsed() >= QUIET_PERIOD;
+        if stuck {
+            self.restarts += 1;
+            self.last_event = Instant::now();
+            warn!(
+                "the {} listener missed the changes, restarting it ({} restarts)",
+                self.name, self.restarts
+            );
+        }
+
+        stuck
+    }
+
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// A restart changes the subscription id, iced drops the stuck listener and starts a new one
+    pub fn instance(&self, instance: &str) -> String {
+        if self.restarts == 0 {
+            instance.to_string()
+        } else {
+            format!("{instance}#{}", self.restarts)
+        }
+    }
+}
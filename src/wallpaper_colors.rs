@@ -0,0 +1,215 @@
+use crate::config::{Position, WallpaperColorsConfig};
+use iced::{Color, Subscription, Task, time::every};
+use log::{debug, warn};
+use std::{fs, path::PathBuf, time::Duration, time::SystemTime};
+use tokio::{process::Command, task};
+
+/// Share of the image height sampled under the bar
+const STRIP_HEIGHT: f32 = 0.05;
+
+/// Side of the image sampled, the strip is averaged on a thumbnail
+const THUMBNAIL_SIZE: u32 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarColors {
+    pub background: Color,
+    pub text: Color,
+}
+
+/// Colors derived from the wallpaper of an output
+#[derive(Debug, Clone)]
+pub struct Wallpaper {
+    /// part of the output name
+    output: String,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    colors: BarColors,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+    Loaded(Vec<Wallpaper>),
+}
+
+/// Derives the colors of the bars from the wallpapers of their outputs
+pub struct WallpaperColors {
+    config: WallpaperColorsConfig,
+    position: Position,
+    wallpapers: Vec<Wallpaper>,
+}
+
+impl WallpaperColors {
+    pub fn new(config: WallpaperColorsConfig, position: Position) -> (Self, Task<Message>) {
+        let mut wallpaper_colors = Self {
+            config,
+            position,
+            wallpapers: Vec::new(),
+        };
+        let task = wallpaper_colors.update(Message::Refresh);
+
+        (wallpaper_colors, task)
+    }
+
+    pub fn reload(&mut self, config: WallpaperColorsConfig, position: Position) -> Task<Message> {
+        // the strip under the bar changes with the position
+        if self.position != position {
+            self.wallpapers.clear();
+        }
+        self.config = config;
+        self.position = position;
+
+        self.update(Message::Refresh)
+    }
+
+    fn enabled(&self) -> bool {
+        !self.config.images.is_empty() || self.config.query_cmd.is_some()
+    }
+
+    /// Colors of the bar on the monitor, the wallpaper set for all the outputs is the fallback
+    pub fn colors(&self, monitor: Option<&str>) -> Option<BarColors> {
+        self.wallpapers
+            .iter()
+            .find(|w| w.output != "*" && monitor.is_some_and(|m| m.contains(&w.output)))
+            .or_else(|| self.wallpapers.iter().find(|w| w.output == "*"))
+            .map(|w| w.colors)
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Refresh => {
+                if !self.enabled() {
+                    self.wallpapers.clear();
+
+                    return Task::none();
+                }
+
+                Task::perform(
+                    load(self.config.clone(), self.position, self.wallpapers.clone()),
+                    Message::Loaded,
+                )
+            }
+            Message::Loaded(wallpapers) => {
+                self.wallpapers = wallpapers;
+
+                Task::none()
+            }
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.enabled() {
+            every(Duration::from_secs(self.config.interval.max(1))).map(|_| Message::Refresh)
+        } else {
+            Subscription::none()
+        }
+    }
+}
+
+/// Reads the `<output> <path>` lines printed by the query command
+async fn query(cmd: &str) -> Vec<(String, String)> {
+    match Command::new("bash").arg("-c").arg(cmd).output().await {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().split_once(char::is_whitespace))
+            .map(|(output, path)| (output.to_string(), path.trim().to_string()))
+            .collect(),
+        Ok(output) => {
+            warn!("Wallpaper query command exited with {}", output.status);
+            Vec::new()
+        }
+        Err(err) => {
+            warn!("Failed to run the wallpaper query command: {err}");
+            Vec::new()
+        }
+    }
+}
+
+/// Averages the strip of the image under the bar, the text is dark on the light colors
+fn sample(path: &PathBuf, position: Position) -> Option<BarColors> {
+    let image = image::open(path)
+        .inspect_err(|err| warn!("Failed to decode the wallpaper {path:?}: {err}"))
+        .ok()?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgb8();
+
+    let rows = ((image.height() as f32 * STRIP_HEIGHT).ceil() as u32).max(1);
+    let start = match position {
+        Position::Top => 0,
+        Position::Bottom => image.height().saturating_sub(rows),
+    };
+
+    let (mut sum, mut count) = ([0u64; 3], 0u64);
+    for y in start..(start + rows).min(image.height()) {
+        for x in 0..image.width() {
+            let pixel = image.get_pixel(x, y);
+            for (sum, value) in sum.iter_mut().zip(pixel.0) {
+                *sum += value as u64;
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return None;
+    }
+
+    let [r, g, b] = sum.map(|sum| sum as f32 / count as f32 / 255.);
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+    Some(BarColors {
+        background: Color::from_rgb(r, g, b),
+        text: if luminance > 0.5 {
+            Color::BLACK
+        } else {
+            Color::WHITE
+        },
+    })
+}
+
+/// Samples the wallpapers, the unchanged ones are reused from `previous`
+async fn load(
+    config: WallpaperColorsConfig,
+    position: Position,
+    previous: Vec<Wallpaper>,
+) -> Vec<Wallpaper> {
+    let mut sources = config.images.into_iter().collect::<Vec<_>>();
+    if let Some(cmd) = &config.query_cmd {
+        sources.extend(query(cmd).await);
+    }
+
+    let mut wallpapers = Vec::new();
+    for (output, path) in sources {
+        let path = PathBuf::from(shellexpand::tilde(&path).as_ref());
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+        if let Some(wallpaper) = previous
+            .iter()
+            .find(|w| w.output == output && w.path == path && w.modified == modified)
+        {
+            wallpapers.push(wallpaper.clone());
+            continue;
+        }
+
+        debug!("Sampling the wallpaper {path:?} of {output}");
+
+        // decoding a large image is too slow for the async runtime
+        let colors = {
+            let path = path.clone();
+            task::spawn_blocking(move || sample(&path, position))
+                .await
+                .ok()
+                .flatten()
+        };
+
+        if let Some(colors) = colors {
+            wallpapers.push(Wallpaper {
+                output,
+                path,
+                modified,
+                colors,
+            });
+        }
+    }
+
+    wallpapers
+}
@@ -0,0 +1,114 @@
+use crate::{
+    config::{ModuleName, ZenModeConfig},
+    ipc::Toggle,
+};
+use iced::{Subscription, window};
+use log::info;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Set(Toggle),
+    Frame(Instant),
+}
+
+/// Hides all the modules except a whitelist, fading them in and out
+pub struct ZenMode {
+    config: ZenModeConfig,
+    enabled: bool,
+    /// start of the running fade
+    transition: Option<Instant>,
+    /// the fade is skipped when disabled
+    animations: bool,
+}
+
+impl ZenMode {
+    pub fn new(config: ZenModeConfig) -> Self {
+        Self {
+            config,
+            enabled: false,
+            transition: None,
+            animations: true,
+        }
+    }
+
+    pub fn reload(&mut self, config: ZenModeConfig) {
+        self.config = config;
+    }
+
+    pub fn set_animations(&mut self, animations: bool) {
+        self.animations = animations;
+    }
+
+    fn duration(&self) -> Duration {
+        if self.animations {
+            Duration::from_millis(self.config.fade_duration)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// Progress of the running fade, from 0 to 1
+    fn progress(&self, now: Instant) -> Option<f32> {
+        self.transition.map(|start| {
+            let duration = self.duration();
+
+            if duration.is_zero() {
+                1.
+            } else {
+                (now.saturating_duration_since(start).as_secs_f32() / duration.as_secs_f32())
+                    .min(1.)
+            }
+        })
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Set(toggle) => {
+                let enabled = toggle.apply(self.enabled);
+
+                if enabled != self.enabled {
+                    info!("Zen mode {}", if enabled { "enabled" } else { "disabled" });
+
+                    // reverse a running fade from its current point
+                    let now = Instant::now();
+                    let remaining = self
+                        .transition
+                        .map(|start| self.duration().saturating_sub(now - start))
+                        .unwrap_or_default();
+
+                    self.enabled = enabled;
+                    self.transition = Some(now.checked_sub(remaining).unwrap_or(now));
+                }
+            }
+            Message::Frame(now) => {
+                if self.progress(now).is_some_and(|progress| progress >= 1.) {
+                    self.transition = None;
+                }
+            }
+        }
+    }
+
+    /// Opacity of the module, it's hidden at 0
+    pub fn module_opacity(&self, module: &ModuleName) -> f32 {
+        if self.config.modules.contains(module) {
+            return 1.;
+        }
+
+        let progress = self.progress(Instant::now()).unwrap_or(1.);
+
+        if self.enabled {
+            1. - progress
+        } else {
+            progress
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.transition.is_some() {
+            window::frames().map(Message::Frame)
+        } else {
+            Subscription::none()
+        }
+    }
+}